@@ -0,0 +1,142 @@
+use serde::{Serialize, ser::SerializeStruct};
+
+/// Represents a WiFi access point that is visible to the host.
+///
+/// `AccessPoint` is the common scan model of every network backend.
+/// The backends are responsible for converting their own scan results
+/// into this model, and the callers are responsible for rendering it.
+///
+/// To render a list of access points in a human-readable or terse format,
+/// check out [`format`].
+///
+/// [`format`]: crate::format
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessPoint {
+    /// The raw bytes of the SSID.
+    ///
+    /// SSIDs are not guaranteed to be valid UTF-8, hence the bytes.
+    /// An empty SSID represents a hidden network.
+    pub ssid: Vec<u8>,
+
+    /// The BSSID of the access point in the `XX:XX:XX:XX:XX:XX` format.
+    pub bssid: String,
+
+    /// The operation mode of the access point (e.g. `Infra`, `Ad-Hoc`, `Mesh`).
+    pub mode: String,
+
+    /// The channel that the access point operates on.
+    pub channel: u32,
+
+    /// The frequency that the access point operates on, in MHz.
+    pub frequency: u32,
+
+    /// The maximum bitrate of the access point, in Mbit/s.
+    pub rate: u32,
+
+    /// The signal strength of the access point, in percentage (0..=100).
+    pub signal: u8,
+
+    /// The security protocols of the access point, separated by spaces (e.g. `WPA1 WPA2`).
+    ///
+    /// An empty value represents an open network.
+    pub security: String,
+
+    /// Whether the host is connected to the access point or not.
+    pub in_use: bool,
+}
+
+impl AccessPoint {
+    /// Provides the signal strength as bars.
+    ///
+    /// The bars are calculated from [`signal`], which means that each network backend
+    /// shares the same representation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wl::AccessPoint;
+    ///
+    /// let ap = AccessPoint {
+    ///     signal: 60,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!("▂▄▆_", ap.bars());
+    /// ```
+    ///
+    /// [`signal`]: crate::AccessPoint::signal
+    pub fn bars(&self) -> &'static str {
+        bars(self.signal)
+    }
+
+    /// Provides whether the access point requires authentication or not.
+    pub fn is_secured(&self) -> bool {
+        !self.security.trim().is_empty()
+    }
+}
+
+impl Serialize for AccessPoint {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut ap = serializer.serialize_struct("AccessPoint", 10)?;
+        ap.serialize_field("in_use", &self.in_use)?;
+        ap.serialize_field("bssid", &self.bssid)?;
+        ap.serialize_field("ssid", &String::from_utf8_lossy(&self.ssid))?;
+        ap.serialize_field("mode", &self.mode)?;
+        ap.serialize_field("channel", &self.channel)?;
+        ap.serialize_field("frequency", &self.frequency)?;
+        ap.serialize_field("rate", &self.rate)?;
+        ap.serialize_field("signal", &self.signal)?;
+        ap.serialize_field("bars", self.bars())?;
+        ap.serialize_field("security", &self.security)?;
+        ap.end()
+    }
+}
+
+/// Converts the given signal strength percentage into bars.
+pub(crate) fn bars(signal: u8) -> &'static str {
+    match signal {
+        81.. => "▂▄▆█",
+        56..=80 => "▂▄▆_",
+        31..=55 => "▂▄__",
+        6..=30 => "▂___",
+        _ => "____",
+    }
+}
+
+/// Parses the given BSSID in the `XX:XX:XX:XX:XX:XX` format into its bytes.
+///
+/// It returns `None` if the BSSID is not in the format. The hex digits are case-insensitive.
+pub(crate) fn parse_bssid(bssid: &str) -> Option<[u8; 6]> {
+    let mut bytes = [0u8; 6];
+    let mut parts = bssid.split(':');
+
+    for byte in bytes.iter_mut() {
+        // `from_str_radix` accepts a leading sign (e.g. `+F`), so the digits are checked first.
+        let part = parts
+            .next()
+            .filter(|p| p.len() == 2 && p.bytes().all(|b| b.is_ascii_hexdigit()))?;
+        *byte = u8::from_str_radix(part, 16).ok()?;
+    }
+
+    parts.next().is_none().then_some(bytes)
+}
+
+/// Converts the given frequency (MHz) into its WiFi channel.
+///
+/// It returns 0 if the frequency does not belong to the 2.4, 5 or 6 GHz bands.
+pub(crate) fn channel_from_frequency(frequency: u32) -> u32 {
+    match frequency {
+        2484 => 14,
+        2412..=2472 => (frequency - 2407) / 5,
+        5955..=7115 => (frequency - 5950) / 5,
+        5000..=5925 => (frequency - 5000) / 5,
+        _ => 0,
+    }
+}
+
+/// Converts the given RSSI (dBm) into a signal strength percentage.
+///
+/// The conversion is linear between -100 dBm (0%) and -50 dBm (100%).
+pub(crate) fn signal_from_dbm(dbm: i32) -> u8 {
+    (2 * (dbm + 100)).clamp(0, 100) as u8
+}
@@ -1,6 +1,12 @@
-use std::{error, fmt, io};
+use std::{error, fmt, io, str::FromStr, sync::mpsc};
 
-use crate::{api::ScanArgs, nmcli};
+use clap::ValueEnum;
+
+use crate::{
+    AccessPoint, Band, EapCredentials, ExportedNetwork, Hotspot, Iwd, Network, NetworkManager,
+    NetworkPriority, Nmcli, Notification, Profile, ProfileEdit, Security, WifiStatus,
+    WpaSupplicant,
+};
 
 /// Represents the line feed byte that can be used to split
 /// a byte slice into lines.
@@ -36,49 +42,41 @@ pub const LOOPBACK_INTERFACE_NAME: &[u8] = b"lo";
 ///
 /// The implementors of `Wl` may or may not encode their Ok result - it depends on the functionality of each individual method.
 ///
-/// The callers of `Wl` should not assume anything about the return format other than being a byte stream. The format may differ for each method, and the implementors should document them wherever possible.
-///
-/// If a method returns a terse output for scripting purposes, then the implementor should mention it.
+/// Unless a method returns a typed model (e.g. [`WifiStatus`], [`Network`], [`AccessPoint`]), the callers of `Wl` should not assume anything about the return format other than being a byte stream. The format may differ for each method, and the implementors should document them wherever possible.
 ///
 /// To see the available Error's, check out [`Error`].
 ///
 /// [`Error`]: crate::adapter::Error
+/// [`WifiStatus`]: crate::WifiStatus
+/// [`Network`]: crate::Network
+/// [`AccessPoint`]: crate::AccessPoint
 pub trait Wl {
-    /// Provides the byte that is used for terse outputs.
-    ///
-    /// The byte that is returned from this method should be the one that is used in terse outputs that are returned from other methods to ensure that caller can rely on this method.
-    fn get_field_separator(&self) -> u8;
-
     /// Provides the WiFi status.
-    fn get_wifi_status(&self) -> Result<Vec<u8>, Error>;
+    fn get_wifi_status(&self) -> Result<WifiStatus, Error>;
 
-    /// Toggles the WiFi status.
-    fn toggle_wifi(&self) -> Result<Vec<u8>, Error>;
+    /// Toggles the WiFi status, and provides the updated one.
+    fn toggle_wifi(&self) -> Result<WifiStatus, Error>;
 
-    /// Lists the known networks on the host.
-    fn list_networks(&self, show_active: bool, show_ssid: bool) -> Result<Vec<u8>, Error>;
-
-    /// Provides a stream of SSID-Device pairs.
-    fn get_active_ssid_dev_pairs(&self) -> Result<Vec<u8>, Error>;
-
-    /// Provides a stream of active SSID's - aka. connected networks.
-    fn get_active_ssids(&self) -> Result<Vec<u8>, Error>;
+    /// Provides the known networks on the host.
+    ///
+    /// If `active_only` is set, then only the active (connected) networks are provided.
+    fn get_networks(&self, active_only: bool) -> Result<Vec<Network>, Error>;
 
     /// Disconnects the host from the given SSID.
     ///
     /// If `forget` is set, then this method removes the given SSID from the known network list of the host.
     fn disconnect(&self, ssid: &[u8], forget: bool) -> Result<Vec<u8>, Error>;
 
-    /// Provides a stream of networks that can be connected.
+    /// Provides the access points that are visible to the host.
     ///
-    /// The implementors of this method should be able to provide both
-    /// human-readable and terse output.
+    /// If `re_scan` is set, then the implementors should refresh their
+    /// list of access points before returning it.
     ///
-    /// The values of [`ScanArgs`] may differ between each network backend.
-    /// The implementors should document the available options for callers.
+    /// The implementors should not render the access points.
+    /// Rendering is handled by [`format`], which is shared by all network backends.
     ///
-    /// [`ScanArgs`]: crate::api::ScanArgs
-    fn scan(&self, args: &ScanArgs) -> Result<Vec<u8>, Error>;
+    /// [`format`]: crate::format
+    fn get_access_points(&self, re_scan: bool) -> Result<Vec<AccessPoint>, Error>;
 
     /// Provides whether the given SSID exists under the known network list
     /// of the host or not.
@@ -89,20 +87,520 @@ pub trait Wl {
     /// The implementors should validate whether the given SSID-password
     /// pair is valid or not.
     /// The callers are responsible from providing the SSID-password pair to the implementors.
+    ///
+    /// If `bssid` is provided (in the `XX:XX:XX:XX:XX:XX` format), then the implementors should connect to that access point only,
+    /// and pin the connection profile to it, so the later connections do not roam to another access point of the same SSID.
+    /// The pin is removed with [`Wl::clear_bssid`].
+    ///
+    /// [`Wl::clear_bssid`]: crate::Wl::clear_bssid
     fn connect(
         &self,
         ssid: &[u8],
         passwd: Option<&[u8]>,
+        bssid: Option<&str>,
         is_known_ssid: bool,
     ) -> Result<Vec<u8>, Error>;
+
+    /// Removes the BSSID that is pinned to the given known SSID by [`Wl::connect`].
+    ///
+    /// The known network can connect to any access point of the SSID afterwards.
+    /// The implementors should return an error with the exit code `10` if the given SSID is not a known one.
+    ///
+    /// The default implementation returns [`Error::CannotUpdateNetwork`] with [`io::ErrorKind::Unsupported`],
+    /// for the network backends that cannot pin a known network to an access point.
+    ///
+    /// [`Wl::connect`]: crate::Wl::connect
+    /// [`Error::CannotUpdateNetwork`]: crate::adapter::Error::CannotUpdateNetwork
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn clear_bssid(&self, ssid: &[u8]) -> Result<Vec<u8>, Error> {
+        let _ = ssid;
+        Err(Error::CannotUpdateNetwork(unsupported(
+            "pinning access points",
+        )))
+    }
+
+    /// Connects the host to the given hidden SSID, which does not appear on the scan list.
+    ///
+    /// The implementors should create a new connection profile with the given security type, and probe the SSID explicitly
+    /// since the access point does not broadcast it.
+    /// `passwd` is provided for the secured types.
+    /// If `is_known_ssid` is set, then the known network is replaced with the new connection profile.
+    ///
+    /// The default implementation returns [`Error::CannotConnect`] with [`io::ErrorKind::Unsupported`],
+    /// for the network backends that cannot connect to hidden networks.
+    ///
+    /// [`Error::CannotConnect`]: crate::adapter::Error::CannotConnect
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn connect_hidden(
+        &self,
+        ssid: &[u8],
+        security: Security,
+        passwd: Option<&[u8]>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let _ = (ssid, security, passwd, is_known_ssid);
+        Err(Error::CannotConnect(unsupported("hidden networks")))
+    }
+
+    /// Connects the host to the given enterprise (802.1X / WPA-EAP) SSID with the given credentials.
+    ///
+    /// The semantics of `is_known_ssid` are the same as [`Wl::connect`] with a password: the known network is replaced with the given credentials.
+    /// The implementors should let the network backend validate the credentials.
+    ///
+    /// The default implementation returns [`Error::CannotConnect`] with [`io::ErrorKind::Unsupported`],
+    /// for the network backends that cannot connect to enterprise networks.
+    ///
+    /// [`Wl::connect`]: crate::Wl::connect
+    /// [`Error::CannotConnect`]: crate::adapter::Error::CannotConnect
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn connect_enterprise(
+        &self,
+        ssid: &[u8],
+        credentials: &EapCredentials,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let _ = (ssid, credentials, is_known_ssid);
+        Err(Error::CannotConnect(unsupported("enterprise networks")))
+    }
+
+    /// Starts a WiFi hotspot that shares the connection of the host, and provides it.
+    ///
+    /// If `passwd` is provided, then the hotspot is secured with WPA-PSK. If not, then the hotspot is open.
+    /// If `band` or `channel` are not provided, then the implementors should let the network backend choose them.
+    ///
+    /// The implementors should replace the hotspot that is created by `wl` before, if there is any.
+    ///
+    /// The default implementation returns [`Error::CannotStartHotspot`] with [`io::ErrorKind::Unsupported`],
+    /// for the network backends that cannot create a hotspot.
+    ///
+    /// [`Error::CannotStartHotspot`]: crate::adapter::Error::CannotStartHotspot
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn start_hotspot(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        band: Option<Band>,
+        channel: Option<u32>,
+    ) -> Result<Hotspot, Error> {
+        let _ = (ssid, passwd, band, channel);
+        Err(Error::CannotStartHotspot(unsupported("hotspots")))
+    }
+
+    /// Stops the WiFi hotspot that is started by [`Wl::start_hotspot`], and provides it.
+    ///
+    /// The implementors should remove the hotspot from the host entirely (e.g. its connection profile), not only deactivate it.
+    ///
+    /// The default implementation returns [`Error::CannotStopHotspot`] with [`io::ErrorKind::Unsupported`].
+    ///
+    /// [`Wl::start_hotspot`]: crate::Wl::start_hotspot
+    /// [`Error::CannotStopHotspot`]: crate::adapter::Error::CannotStopHotspot
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn stop_hotspot(&self) -> Result<Hotspot, Error> {
+        Err(Error::CannotStopHotspot(unsupported("hotspots")))
+    }
+
+    /// Provides the WiFi hotspot that is started by [`Wl::start_hotspot`], if it is active.
+    ///
+    /// The default implementation returns [`Error::CannotGetHotspot`] with [`io::ErrorKind::Unsupported`].
+    ///
+    /// [`Wl::start_hotspot`]: crate::Wl::start_hotspot
+    /// [`Error::CannotGetHotspot`]: crate::adapter::Error::CannotGetHotspot
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn get_hotspot(&self) -> Result<Option<Hotspot>, Error> {
+        Err(Error::CannotGetHotspot(unsupported("hotspots")))
+    }
+
+    /// Provides the password of the given known SSID, as it is stored by the network backend.
+    ///
+    /// If the known network is open, then `None` is provided.
+    /// The implementors should return an error with the exit code `10` if the given SSID is not a known one.
+    ///
+    /// The default implementation returns [`Error::CannotGetPasswd`] with [`io::ErrorKind::Unsupported`],
+    /// for the network backends that do not expose their stored passwords.
+    ///
+    /// [`Error::CannotGetPasswd`]: crate::adapter::Error::CannotGetPasswd
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn get_passwd(&self, ssid: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let _ = ssid;
+        Err(Error::CannotGetPasswd(unsupported(
+            "reading stored passwords",
+        )))
+    }
+
+    /// Provides the settings of the given known SSID.
+    ///
+    /// The implementors should return an error with the exit code `10` if the given SSID is not a known one.
+    ///
+    /// The default implementation returns [`Error::CannotGetProfile`] with [`io::ErrorKind::Unsupported`],
+    /// for the network backends that do not expose the settings of their known networks.
+    ///
+    /// [`Error::CannotGetProfile`]: crate::adapter::Error::CannotGetProfile
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn get_profile(&self, ssid: &[u8]) -> Result<Profile, Error> {
+        let _ = ssid;
+        Err(Error::CannotGetProfile(unsupported(
+            "known network settings",
+        )))
+    }
+
+    /// Changes the settings of the given known SSID, and keeps the settings that are not set in `edit`.
+    ///
+    /// The implementors should return an error with the exit code `10` if the given SSID is not a known one.
+    /// The changes are applied on the next connection to the known network.
+    ///
+    /// The default implementation returns [`Error::CannotUpdateNetwork`] with [`io::ErrorKind::Unsupported`].
+    ///
+    /// [`Error::CannotUpdateNetwork`]: crate::adapter::Error::CannotUpdateNetwork
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn edit_profile(&self, ssid: &[u8], edit: &ProfileEdit) -> Result<Vec<u8>, Error> {
+        let _ = (ssid, edit);
+        Err(Error::CannotUpdateNetwork(unsupported(
+            "known network settings",
+        )))
+    }
+
+    /// Renames the given known SSID to `name`.
+    ///
+    /// Only the name (connection id) of the known network changes, its SSID is kept.
+    /// The implementors should return an error with the exit code `10` if the given SSID is not a known one.
+    ///
+    /// The default implementation returns [`Error::CannotUpdateNetwork`] with [`io::ErrorKind::Unsupported`].
+    ///
+    /// [`Error::CannotUpdateNetwork`]: crate::adapter::Error::CannotUpdateNetwork
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn rename_profile(&self, ssid: &[u8], name: &[u8]) -> Result<Vec<u8>, Error> {
+        let _ = (ssid, name);
+        Err(Error::CannotUpdateNetwork(unsupported(
+            "renaming known networks",
+        )))
+    }
+
+    /// Provides the autoconnect priorities of the known networks, in the order that is reported by the network backend.
+    ///
+    /// The default implementation returns [`Error::CannotListNetworks`] with [`io::ErrorKind::Unsupported`],
+    /// for the network backends that do not prioritize their known networks.
+    ///
+    /// [`Error::CannotListNetworks`]: crate::adapter::Error::CannotListNetworks
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn get_priorities(&self) -> Result<Vec<NetworkPriority>, Error> {
+        Err(Error::CannotListNetworks(unsupported(
+            "autoconnect priorities",
+        )))
+    }
+
+    /// Sets the autoconnect priority of the given known SSID.
+    ///
+    /// The implementors should return an error with the exit code `10` if the given SSID is not a known one.
+    ///
+    /// The default implementation returns [`Error::CannotUpdateNetwork`] with [`io::ErrorKind::Unsupported`].
+    ///
+    /// [`Error::CannotUpdateNetwork`]: crate::adapter::Error::CannotUpdateNetwork
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn set_priority(&self, ssid: &[u8], priority: i32) -> Result<Vec<u8>, Error> {
+        let _ = (ssid, priority);
+        Err(Error::CannotUpdateNetwork(unsupported(
+            "autoconnect priorities",
+        )))
+    }
+
+    /// Adds the given network as a known network, without connecting to it.
+    ///
+    /// If `is_known_ssid` is set, then the known network of the same SSID is replaced with the new one.
+    /// The secured networks without a secret are added as well, and their passwords are asked on the first connection wherever the network backend allows it.
+    ///
+    /// The default implementation returns [`Error::CannotAddNetwork`] with [`io::ErrorKind::Unsupported`].
+    ///
+    /// [`Error::CannotAddNetwork`]: crate::adapter::Error::CannotAddNetwork
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn add_network(
+        &self,
+        network: &ExportedNetwork,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let _ = (network, is_known_ssid);
+        Err(Error::CannotAddNetwork(unsupported(
+            "adding known networks",
+        )))
+    }
+
+    /// Subscribes to the changes that are reported by the network backend (e.g. `nmcli monitor`, D-Bus signals).
+    ///
+    /// Each [`Notification`] is sent as soon as the network backend reports it, until the receiver is dropped.
+    /// If the network backend stops reporting the changes, then the sender should be dropped.
+    ///
+    /// The default implementation provides `None`, for the network backends that cannot report their changes.
+    /// The callers should poll the state of the host instead.
+    ///
+    /// [`Notification`]: crate::Notification
+    fn subscribe(&self) -> Result<Option<mpsc::Receiver<Notification>>, Error> {
+        Ok(None)
+    }
+}
+
+pub(crate) fn unsupported(functionality: &str) -> (io::Error, i32) {
+    let err = io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("the network backend does not support {}", functionality),
+    );
+    (err, 1)
+}
+
+impl<W: Wl + ?Sized> Wl for Box<W> {
+    fn get_wifi_status(&self) -> Result<WifiStatus, Error> {
+        (**self).get_wifi_status()
+    }
+
+    fn toggle_wifi(&self) -> Result<WifiStatus, Error> {
+        (**self).toggle_wifi()
+    }
+
+    fn get_networks(&self, active_only: bool) -> Result<Vec<Network>, Error> {
+        (**self).get_networks(active_only)
+    }
+
+    fn disconnect(&self, ssid: &[u8], forget: bool) -> Result<Vec<u8>, Error> {
+        (**self).disconnect(ssid, forget)
+    }
+
+    fn get_access_points(&self, re_scan: bool) -> Result<Vec<AccessPoint>, Error> {
+        (**self).get_access_points(re_scan)
+    }
+
+    fn is_known_ssid(&self, ssid: &[u8]) -> Result<bool, Error> {
+        (**self).is_known_ssid(ssid)
+    }
+
+    fn connect(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        bssid: Option<&str>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        (**self).connect(ssid, passwd, bssid, is_known_ssid)
+    }
+
+    fn clear_bssid(&self, ssid: &[u8]) -> Result<Vec<u8>, Error> {
+        (**self).clear_bssid(ssid)
+    }
+
+    fn connect_hidden(
+        &self,
+        ssid: &[u8],
+        security: Security,
+        passwd: Option<&[u8]>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        (**self).connect_hidden(ssid, security, passwd, is_known_ssid)
+    }
+
+    fn connect_enterprise(
+        &self,
+        ssid: &[u8],
+        credentials: &EapCredentials,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        (**self).connect_enterprise(ssid, credentials, is_known_ssid)
+    }
+
+    fn start_hotspot(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        band: Option<Band>,
+        channel: Option<u32>,
+    ) -> Result<Hotspot, Error> {
+        (**self).start_hotspot(ssid, passwd, band, channel)
+    }
+
+    fn stop_hotspot(&self) -> Result<Hotspot, Error> {
+        (**self).stop_hotspot()
+    }
+
+    fn get_hotspot(&self) -> Result<Option<Hotspot>, Error> {
+        (**self).get_hotspot()
+    }
+
+    fn get_passwd(&self, ssid: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        (**self).get_passwd(ssid)
+    }
+
+    fn get_profile(&self, ssid: &[u8]) -> Result<Profile, Error> {
+        (**self).get_profile(ssid)
+    }
+
+    fn edit_profile(&self, ssid: &[u8], edit: &ProfileEdit) -> Result<Vec<u8>, Error> {
+        (**self).edit_profile(ssid, edit)
+    }
+
+    fn rename_profile(&self, ssid: &[u8], name: &[u8]) -> Result<Vec<u8>, Error> {
+        (**self).rename_profile(ssid, name)
+    }
+
+    fn get_priorities(&self) -> Result<Vec<NetworkPriority>, Error> {
+        (**self).get_priorities()
+    }
+
+    fn set_priority(&self, ssid: &[u8], priority: i32) -> Result<Vec<u8>, Error> {
+        (**self).set_priority(ssid, priority)
+    }
+
+    fn add_network(
+        &self,
+        network: &ExportedNetwork,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        (**self).add_network(network, is_known_ssid)
+    }
+
+    fn subscribe(&self) -> Result<Option<mpsc::Receiver<Notification>>, Error> {
+        (**self).subscribe()
+    }
+}
+
+/// Represents the name of the connection profile of the hotspot that is started by [`Wl::start_hotspot`].
+///
+/// The network backends that keep connection profiles use this name, so the hotspot can be found and torn down later.
+///
+/// [`Wl::start_hotspot`]: crate::Wl::start_hotspot
+pub const HOTSPOT_PROFILE: &str = "wl-hotspot";
+
+/// Represents the exit code that is returned when none of the network backends are available.
+///
+/// The value follows the shell convention of "command not found".
+pub const NO_BACKEND_EXIT_CODE: i32 = 127;
+
+/// Represents the exit code of a network backend failure, when the given SSID, connection profile or hotspot does not exist.
+///
+/// The value follows the exit code of `nmcli` for the same failure, so the failures of each backend are categorized as [`ErrorKind::SsidNotFound`].
+///
+/// [`ErrorKind::SsidNotFound`]: crate::ErrorKind::SsidNotFound
+pub const UNKNOWN_SSID_EXIT_CODE: i32 = 10;
+
+/// The order that is used to probe the network backends when the caller does not provide one.
+pub const DEFAULT_BACKEND_ORDER: [Backend; 4] = [
+    Backend::NetworkManager,
+    Backend::Nmcli,
+    Backend::Iwd,
+    Backend::WpaSupplicant,
+];
+
+/// Represents the network backends that are supported by `wl`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// NetworkManager, through the system D-Bus.
+    #[value(name = "networkmanager", alias = "nm")]
+    NetworkManager,
+
+    /// NetworkManager, through `nmcli`.
+    Nmcli,
+
+    /// iwd, through `iwctl`.
+    Iwd,
+
+    /// wpa_supplicant, through its control interface.
+    #[value(name = "wpa_supplicant", alias = "wpa-supplicant")]
+    WpaSupplicant,
 }
 
-/// Initializes a new network backend adapter to the caller.
+impl Backend {
+    /// Provides the name of the backend, as it is accepted by `--backend`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::NetworkManager => "networkmanager",
+            Backend::Nmcli => "nmcli",
+            Backend::Iwd => "iwd",
+            Backend::WpaSupplicant => "wpa_supplicant",
+        }
+    }
+
+    /// Provides whether the backend is available on the host or not.
+    ///
+    /// The backends that rely on an external program (`nmcli`, `iwctl`) are available
+    /// when the program is found under `PATH`.
+    /// NetworkManager is available when its service is running on the system bus.
+    /// `wpa_supplicant` is available when one of its control sockets exists.
+    pub fn is_available(&self) -> bool {
+        match self {
+            Backend::NetworkManager => NetworkManager::new().is_available(),
+            Backend::Nmcli => which::which("nmcli").is_ok(),
+            Backend::Iwd => which::which("iwctl").is_ok(),
+            Backend::WpaSupplicant => WpaSupplicant::new().is_available(),
+        }
+    }
+
+    fn init(&self) -> Box<dyn Wl> {
+        match self {
+            Backend::NetworkManager => Box::new(NetworkManager::new()),
+            Backend::Nmcli => Box::new(Nmcli::new()),
+            Backend::Iwd => Box::new(Iwd::new()),
+            Backend::WpaSupplicant => Box::new(WpaSupplicant::new()),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Backend as ValueEnum>::from_str(s.trim(), true)
+    }
+}
+
+/// Initializes the first available network backend adapter to the caller.
+///
+/// The backends are probed in the given `order`.
+/// If `order` is empty, then [`DEFAULT_BACKEND_ORDER`] is used instead.
+///
+/// To force a single backend, provide an `order` that only contains that backend.
+///
+/// # Errors
+///
+/// This function returns [`Error::NoBackendFound`] when none of the backends in `order` are available on the host.
+/// The error lists the backends that are tried, and holds [`NO_BACKEND_EXIT_CODE`] as its exit code.
+///
+/// # Examples
 ///
-/// If the network backend relies on an external program, this
-/// function does not validate the existence of that external program.
-pub fn new() -> impl Wl {
-    nmcli::Nmcli::new()
+/// ```no_run
+/// use wl::{Backend, Wl};
+///
+/// let process = wl::detect_backend(&[Backend::Iwd, Backend::Nmcli]).unwrap();
+/// let wifi_status = process.get_wifi_status().unwrap();
+/// ```
+///
+/// [`DEFAULT_BACKEND_ORDER`]: crate::DEFAULT_BACKEND_ORDER
+/// [`Error::NoBackendFound`]: crate::adapter::Error::NoBackendFound
+/// [`NO_BACKEND_EXIT_CODE`]: crate::NO_BACKEND_EXIT_CODE
+pub fn new(order: &[Backend]) -> Result<Box<dyn Wl>, Error> {
+    let order = if order.is_empty() {
+        DEFAULT_BACKEND_ORDER.as_slice()
+    } else {
+        order
+    };
+
+    order
+        .iter()
+        .find(|backend| backend.is_available())
+        .map(|backend| backend.init())
+        .ok_or_else(|| {
+            let tried = order
+                .iter()
+                .map(|backend| backend.name())
+                .collect::<Vec<&str>>()
+                .join(", ");
+
+            let err = io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("tried {}, none of them are available on the host", tried),
+            );
+            Error::NoBackendFound((err, NO_BACKEND_EXIT_CODE))
+        })
 }
 
 /// The main Error that is returned from the implementors of `Wl`.
@@ -121,9 +619,41 @@ pub enum Error {
     CannotDisconnect((io::Error, i32)),
     CannotScanWiFi((io::Error, i32)),
     CannotConnect((io::Error, i32)),
+    NoBackendFound((io::Error, i32)),
+    CannotStartHotspot((io::Error, i32)),
+    CannotStopHotspot((io::Error, i32)),
+    CannotGetHotspot((io::Error, i32)),
+    CannotGetPasswd((io::Error, i32)),
+    CannotWatch((io::Error, i32)),
+    CannotUpdateNetwork((io::Error, i32)),
+    CannotGetProfile((io::Error, i32)),
+    CannotAddNetwork((io::Error, i32)),
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::CannotGetWiFiStatus((err, _))
+            | Error::CannotToggleWiFi((err, _))
+            | Error::CannotListNetworks((err, _))
+            | Error::CannotGetActiveConnections((err, _))
+            | Error::CannotGetSSIDStatus((err, _))
+            | Error::CannotDisconnect((err, _))
+            | Error::CannotScanWiFi((err, _))
+            | Error::CannotConnect((err, _))
+            | Error::NoBackendFound((err, _))
+            | Error::CannotStartHotspot((err, _))
+            | Error::CannotStopHotspot((err, _))
+            | Error::CannotGetHotspot((err, _))
+            | Error::CannotGetPasswd((err, _))
+            | Error::CannotWatch((err, _))
+            | Error::CannotUpdateNetwork((err, _))
+            | Error::CannotGetProfile((err, _))
+            | Error::CannotAddNetwork((err, _)) => Some(err),
+        }
+    }
 }
 
-impl error::Error for Error {}
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -147,6 +677,37 @@ impl fmt::Display for Error {
             Error::CannotConnect((err, _)) => {
                 write!(f, "unable to connect to the network: {}", err)
             }
+            Error::NoBackendFound((err, _)) => {
+                write!(f, "unable to find a network backend: {}", err)
+            }
+            Error::CannotStartHotspot((err, _)) => {
+                write!(f, "unable to start the hotspot: {}", err)
+            }
+            Error::CannotStopHotspot((err, _)) => {
+                write!(f, "unable to stop the hotspot: {}", err)
+            }
+            Error::CannotGetHotspot((err, _)) => {
+                write!(f, "unable to get the hotspot: {}", err)
+            }
+            Error::CannotGetPasswd((err, _)) => {
+                write!(f, "unable to get the password of the network: {}", err)
+            }
+            Error::CannotWatch((err, _)) => {
+                write!(
+                    f,
+                    "unable to watch the changes of the network backend: {}",
+                    err
+                )
+            }
+            Error::CannotUpdateNetwork((err, _)) => {
+                write!(f, "unable to update the known network: {}", err)
+            }
+            Error::CannotGetProfile((err, _)) => {
+                write!(f, "unable to get the known network: {}", err)
+            }
+            Error::CannotAddNetwork((err, _)) => {
+                write!(f, "unable to add the known network: {}", err)
+            }
         }
     }
 }
@@ -1,13 +1,111 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum, builder::BoolishValueParser};
+
+use crate::{Backend, Band, EapMethod, IpMethod, MacRandomization, Metered, Phase2Auth, Security};
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct Args {
+    /// The output format of the subcommands.
+    ///
+    /// `json` and `ndjson` are meant for scripting purposes.
+    /// Lists are written as a single array in `json`, and as one object per line in `ndjson`.
+    #[arg(short, long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// The network backends to probe, in order.
+    ///
+    /// The first backend that is available on the host is used.
+    /// Provide a single backend to force it.
+    /// If not provided, the backends are probed in the order of `networkmanager,nmcli,iwd,wpa_supplicant`.
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_enum,
+        value_delimiter = ',',
+        env = "WL_BACKEND"
+    )]
+    pub backend: Vec<Backend>,
+
     #[command(subcommand)]
     pub wl_command: Option<WlCommand>,
 }
 
+/// The output formats that are supported by the subcommands.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable output.
+    #[default]
+    Text,
+
+    /// A single JSON value per subcommand.
+    Json,
+
+    /// Newline delimited JSON, one JSON value per line.
+    Ndjson,
+}
+
+/// The file formats of the known networks that are exported by `wl export`.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// A TOML document with a `[[networks]]` table per known network.
+    #[default]
+    Toml,
+
+    /// A JSON object with a `networks` array.
+    Json,
+}
+
+/// The ways of handling the imported networks that are already known to the host.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep the known network, and skip the imported one.
+    #[default]
+    Skip,
+
+    /// Replace the known network with the imported one.
+    Replace,
+
+    /// Fail before importing any network.
+    Fail,
+}
+
+/// The sources of the networks that are imported by `wl import`.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImportSource {
+    /// A TOML or JSON file that is exported by `wl export`.
+    #[default]
+    Wl,
+
+    /// The `network={...}` blocks of a `wpa_supplicant.conf` file.
+    WpaSupplicant,
+
+    /// An `iwd` profile (`.psk`, `.open`), or a directory of them (e.g. `/var/lib/iwd`).
+    Iwd,
+}
+
+/// The shells that are supported by `wl completions`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// The subcommands whose `-i/--ssid` are completed by `wl __complete-ssid`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SsidSource {
+    /// The SSIDs of the scan list.
+    Connect,
+
+    /// The active SSIDs, followed by the other known SSIDs.
+    Disconnect,
+}
+
 #[derive(Debug, Subcommand)]
+#[allow(clippy::large_enum_variant)]
 pub enum WlCommand {
     /// Show the overall status of WiFi (on/off, connected network if any)
     #[clap(visible_alias = "s")]
@@ -37,6 +135,31 @@ pub enum WlCommand {
         /// Re-enter the SSID password even if it is a known network.
         #[arg(short, long, default_value_t = false)]
         force_passwd: bool,
+
+        /// Connect to a hidden network (a network that does not broadcast its SSID).
+        ///
+        /// If the SSID is not provided, then it is asked instead of chosen from the scan list.
+        #[arg(long, default_value_t = false, conflicts_with_all = ["force_passwd", "eap"])]
+        hidden: bool,
+
+        /// Security type of the hidden network.
+        ///
+        /// If not provided, then it is asked.
+        #[arg(long, value_enum, requires = "hidden")]
+        security: Option<Security>,
+
+        /// BSSID of the access point to connect to (XX:XX:XX:XX:XX:XX).
+        ///
+        /// The known network is pinned to the access point. If the SSID is not provided,
+        /// then it is taken from the access point on the scan list.
+        #[arg(long, conflicts_with_all = ["hidden", "eap"])]
+        bssid: Option<String>,
+
+        #[command(flatten)]
+        eap: EapArgs,
+
+        #[command(flatten)]
+        passwd: PasswdArgs,
     },
 
     /// Disconnect from a WiFi network.
@@ -65,6 +188,377 @@ pub enum WlCommand {
         #[arg(short = 'i', long = "ssid", default_value_t = false)]
         show_ssid: bool,
     },
+
+    /// Watch the connectivity changes of the host, until interrupted.
+    #[clap(visible_alias = "w")]
+    Watch {
+        #[command(flatten)]
+        args: WatchArgs,
+    },
+
+    /// Remove the BSSID that a known network is pinned to by `connect --bssid`.
+    Unpin {
+        /// SSID of the known network.
+        #[arg(short = 'i', long)]
+        ssid: String,
+    },
+
+    /// Show and change the settings of the known networks.
+    #[clap(visible_alias = "p")]
+    Profile {
+        #[command(subcommand)]
+        profile_command: ProfileCommand,
+    },
+
+    /// List the known networks by their autoconnect priorities, and reorder them.
+    ///
+    /// When multiple known networks are visible, the one with the highest priority is connected automatically.
+    #[clap(visible_alias = "pr")]
+    Priority {
+        #[command(subcommand)]
+        priority_command: Option<PriorityCommand>,
+    },
+
+    /// Export the known WiFi networks into a portable file.
+    ///
+    /// The passwords are read from the stored profiles of the networks, which may require elevated privileges.
+    Export {
+        /// File format of the export.
+        #[arg(long, value_enum, default_value_t = ExportFormat::Toml)]
+        format: ExportFormat,
+
+        /// Leave the passwords out of the file.
+        #[arg(long, default_value_t = false)]
+        no_secrets: bool,
+
+        /// Path of the file, which is only readable by its owner.
+        ///
+        /// If not provided, then the file is written to stdout.
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+    },
+
+    /// Add the known networks of a file that is exported by `wl export`, without connecting to them.
+    ///
+    /// Use `--from` to migrate the networks of `wpa_supplicant` or `iwd` instead.
+    Import {
+        /// Path of the file, or `-` to read an exported file from stdin.
+        file: PathBuf,
+
+        /// The source of the networks.
+        #[arg(long, value_enum, default_value_t = ImportSource::Wl)]
+        from: ImportSource,
+
+        /// Ignore the passwords in the file, and ask them on the first connection instead.
+        #[arg(long, default_value_t = false)]
+        no_secrets: bool,
+
+        /// How to handle the networks that are already known.
+        #[arg(long, value_enum, default_value_t = ConflictPolicy::Skip)]
+        on_conflict: ConflictPolicy,
+    },
+
+    /// Back up the known WiFi networks and their passwords into a passphrase-encrypted archive.
+    ///
+    /// The passphrase is read from the `WL_BACKUP_PASSPHRASE` environment variable, or asked on the terminal.
+    /// The passwords are read from the stored profiles of the networks, which may require elevated privileges.
+    Backup {
+        /// Path of the archive, which is only readable by its owner.
+        file: PathBuf,
+    },
+
+    /// Restore the known networks of an archive that is written by `wl backup`, without connecting to them.
+    ///
+    /// The passphrase is read from the `WL_BACKUP_PASSPHRASE` environment variable, or asked on the terminal.
+    Restore {
+        /// Path of the archive, or `-` to read it from stdin.
+        file: PathBuf,
+
+        /// How to handle the networks that are already known.
+        #[arg(long, value_enum, default_value_t = ConflictPolicy::Skip)]
+        on_conflict: ConflictPolicy,
+    },
+
+    /// Share a known network as a WiFi QR code.
+    ///
+    /// The password is read from the stored profile of the network, which may require elevated privileges.
+    Share {
+        /// SSID of the known network.
+        #[arg(short = 'i', long)]
+        ssid: String,
+    },
+
+    /// Share the connection of the host through a WiFi hotspot.
+    #[clap(visible_alias = "hs")]
+    Hotspot {
+        #[command(subcommand)]
+        hotspot_command: HotspotCommand,
+    },
+
+    /// Print the completion script of a shell.
+    ///
+    /// The SSIDs of `connect -i` and `disconnect -i` are completed from the network backend.
+    Completions {
+        /// The shell of the completion script.
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Print the manual page in the roff format.
+    Man {
+        /// Write the manual pages of `wl` and each of its subcommands into the directory instead.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+
+    /// Print the SSIDs that complete `-i/--ssid`, one per line. Used by the completion scripts.
+    #[command(name = "__complete-ssid", hide = true)]
+    CompleteSsid {
+        /// The subcommand whose SSIDs are completed.
+        #[arg(value_enum)]
+        source: SsidSource,
+    },
+
+    /// Manage the configuration file (`$XDG_CONFIG_HOME/wl/config.toml`).
+    Config {
+        #[command(subcommand)]
+        config_command: ConfigCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Show the effective configuration, which merges the configuration file with the defaults and the arguments.
+    Show,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum HotspotCommand {
+    /// Start a hotspot, replacing the one that is started before.
+    Start {
+        #[command(flatten)]
+        args: HotspotArgs,
+    },
+
+    /// Stop the hotspot and remove it from the host.
+    Stop,
+
+    /// Show the hotspot and its connected clients.
+    Status,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProfileCommand {
+    /// Show the settings of a known network.
+    Show {
+        /// SSID of the known network.
+        #[arg(short = 'i', long)]
+        ssid: String,
+    },
+
+    /// Change the settings of a known network.
+    ///
+    /// The settings that are not provided are kept. The changes are applied on the next connection.
+    Edit {
+        /// SSID of the known network.
+        #[arg(short = 'i', long)]
+        ssid: String,
+
+        #[command(flatten)]
+        args: ProfileArgs,
+    },
+
+    /// Rename a known network, keeping its SSID.
+    ///
+    /// The known network is referred by its new name afterwards.
+    Rename {
+        /// SSID of the known network.
+        #[arg(short = 'i', long)]
+        ssid: String,
+
+        /// New name of the known network.
+        #[arg(short, long)]
+        name: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PriorityCommand {
+    /// List the known networks, starting from the highest priority (default).
+    List,
+
+    /// Set the autoconnect priority of a known network.
+    Set {
+        /// SSID of the known network.
+        ssid: String,
+
+        /// Priority of the known network. The known networks with higher priorities are preferred.
+        #[arg(allow_negative_numbers = true)]
+        priority: i32,
+    },
+
+    /// Move a known network one place up, above the known network before it.
+    Up {
+        /// SSID of the known network.
+        ssid: String,
+    },
+
+    /// Move a known network one place down, below the known network after it.
+    Down {
+        /// SSID of the known network.
+        ssid: String,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+#[group(required = true, multiple = true)]
+pub struct ProfileArgs {
+    /// Security type of the known network.
+    ///
+    /// The stored password is kept, so a secured type can only be selected for a known network that has a password.
+    #[arg(long, value_enum)]
+    pub security: Option<Security>,
+
+    /// Connect to the known network automatically when it is visible (yes/no).
+    #[arg(short, long, value_parser = BoolishValueParser::new())]
+    pub autoconnect: Option<bool>,
+
+    /// Priority of the known network when it is connected automatically.
+    ///
+    /// The known networks with higher priorities are preferred.
+    #[arg(short, long, allow_negative_numbers = true)]
+    pub priority: Option<i32>,
+
+    /// Treat the known network as metered (e.g. a mobile hotspot with a data cap).
+    #[arg(short, long, value_enum)]
+    pub metered: Option<Metered>,
+
+    /// Randomize the MAC address of the host on the known network.
+    #[arg(long, value_enum)]
+    pub mac_randomization: Option<MacRandomization>,
+
+    /// How the known network obtains its IPv4 address.
+    #[arg(long, value_enum)]
+    pub ip_method: Option<IpMethod>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct HotspotArgs {
+    /// SSID of the hotspot.
+    #[arg(short = 'i', long)]
+    pub ssid: String,
+
+    #[command(flatten)]
+    pub passwd: PasswdArgs,
+
+    /// Start an open hotspot without a password.
+    ///
+    /// If not provided, then the password (8 to 63 characters) is asked on the terminal, or read from the given password source.
+    #[arg(long, default_value_t = false, conflicts_with = "PasswdArgs")]
+    pub open: bool,
+
+    /// Frequency band of the hotspot.
+    ///
+    /// If not provided, then the band is chosen by the network backend.
+    #[arg(long, value_enum)]
+    pub band: Option<Band>,
+
+    /// Channel of the hotspot.
+    ///
+    /// If not provided, then the channel is chosen by the network backend.
+    #[arg(short, long)]
+    pub channel: Option<u32>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct EapArgs {
+    /// EAP method of an enterprise (802.1X / WPA-EAP) network.
+    ///
+    /// If provided, then the network is connected with the enterprise credentials below instead of a password.
+    /// The password of PEAP and TTLS is asked for the identity.
+    #[arg(
+        long,
+        value_enum,
+        requires = "identity",
+        conflicts_with = "force_passwd"
+    )]
+    pub eap: Option<EapMethod>,
+
+    /// Identity (username) of the enterprise network.
+    #[arg(long, requires = "eap")]
+    pub identity: Option<String>,
+
+    /// Identity that is sent before the TLS tunnel is established.
+    ///
+    /// If not provided, then `--identity` is sent instead.
+    #[arg(long, requires = "eap")]
+    pub anonymous_identity: Option<String>,
+
+    /// Inner authentication of PEAP and TTLS.
+    ///
+    /// If not provided, then the inner authentication is chosen by the network backend.
+    #[arg(long, value_enum, requires = "eap")]
+    pub phase2_auth: Option<Phase2Auth>,
+
+    /// CA certificate to verify the authentication server.
+    ///
+    /// If not provided, then the authentication server is not verified.
+    #[arg(long, requires = "eap")]
+    pub ca_cert: Option<PathBuf>,
+
+    /// Client certificate of EAP-TLS.
+    #[arg(long, requires = "eap", required_if_eq("eap", "tls"))]
+    pub client_cert: Option<PathBuf>,
+
+    /// Private key of the client certificate.
+    ///
+    /// Its password is asked, which can be left empty if the private key is not encrypted.
+    #[arg(long, requires = "eap", required_if_eq("eap", "tls"))]
+    pub private_key: Option<PathBuf>,
+
+    /// Suffix that should match the domain name of the authentication server certificate.
+    #[arg(long, requires = "eap")]
+    pub domain_suffix_match: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+#[group(multiple = false)]
+pub struct PasswdArgs {
+    /// Read the password from stdin instead of asking it on the terminal.
+    ///
+    /// If the SSID of `connect` is not provided, then the password is read after the SSID selection.
+    #[arg(long, default_value_t = false)]
+    pub passwd_stdin: bool,
+
+    /// Read the password from the first line of the given file.
+    #[arg(long, value_name = "PATH")]
+    pub passwd_file: Option<PathBuf>,
+
+    /// Read the password from the given environment variable.
+    #[arg(long, value_name = "VAR")]
+    pub passwd_env: Option<String>,
+
+    /// The password itself, which is refused since the arguments are visible to the other users of the host.
+    #[arg(long, alias = "passwd", hide = true)]
+    pub password: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct WatchArgs {
+    /// Exit after the given number of events.
+    #[arg(short = 'n', long)]
+    pub count: Option<usize>,
+
+    /// The interval in seconds to re-read the state of the host.
+    ///
+    /// The state is also re-read as soon as the network backend reports a change.
+    /// For the network backends that cannot report their changes, this is the polling interval.
+    #[arg(long, default_value_t = 2.0)]
+    pub interval: f64,
+
+    /// The signal strengths that emit an event when the signal of the connected network crosses them.
+    #[arg(short = 't', long, value_delimiter = ',', default_values_t = [25, 50, 75])]
+    pub thresholds: Vec<u8>,
 }
 
 #[derive(clap::Args, Debug)]
@@ -0,0 +1,317 @@
+use std::{error, fmt, io};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng, Payload, rand_core::RngCore},
+};
+
+use crate::{
+    ExportReport, ImportedNetwork,
+    adapter::Wl,
+    api::{ConflictPolicy, OutputFormat},
+    export::{self, EXPORT_VERSION, ExportFile},
+    write_bytes,
+};
+
+/// Represents the version of the archive format that is written by [`backup`].
+///
+/// [`restore`] rejects the archives of the other versions.
+///
+/// [`backup`]: crate::backup
+/// [`restore`]: crate::restore
+pub const BACKUP_VERSION: u8 = 1;
+
+const MAGIC: &[u8; 8] = b"WLBACKUP";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 3 * 4 + SALT_LEN + NONCE_LEN;
+
+// The archives that ask for more than 1 GiB of memory, 16 passes or 16 lanes to derive their keys are rejected,
+// so that a crafted archive cannot exhaust the memory of the host or hang `restore`.
+const MAX_MEMORY_COST: u32 = 1024 * 1024;
+const MAX_TIME_COST: u32 = 16;
+const MAX_PARALLELISM: u32 = 16;
+
+/// Defines [`Error`] variants that may return whilst backing up or restoring the known networks.
+///
+/// [`Error`]: std::error::Error
+#[derive(Debug)]
+pub enum Error {
+    /// Represents an empty passphrase, which cannot protect the archive.
+    EmptyPassphrase,
+
+    /// Represents a passphrase that does not match its confirmation.
+    PassphraseMismatch,
+
+    /// Represents a file that is not an archive that is written by [`backup`].
+    ///
+    /// [`backup`]: crate::backup
+    InvalidArchive,
+
+    /// Represents an archive that is written by another version of `wl`.
+    UnsupportedVersion(u8),
+
+    /// Represents an archive that cannot be decrypted, either due to a wrong passphrase or a modified archive.
+    CannotDecrypt,
+
+    /// Represents a failure of the key derivation.
+    ///
+    /// It holds the details of the underlying failure.
+    CannotDeriveKey(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::EmptyPassphrase => write!(f, "the passphrase cannot be empty"),
+            Error::PassphraseMismatch => write!(f, "the passphrases do not match"),
+            Error::InvalidArchive => write!(f, "the file is not a wl backup"),
+            Error::UnsupportedVersion(version) => write!(
+                f,
+                "the backup version {} is not supported, the supported version is {}",
+                version, BACKUP_VERSION
+            ),
+            Error::CannotDecrypt => write!(
+                f,
+                "the backup cannot be decrypted, the passphrase is wrong or the backup is modified"
+            ),
+            Error::CannotDeriveKey(err) => write!(f, "unable to derive the key: {}", err),
+        }
+    }
+}
+impl error::Error for Error {}
+
+/// Backs up the known WiFi networks along with their secrets by using the given [`Wl`] implementation, and writes them on the given [`io::Write`] as an encrypted archive.
+///
+/// The known networks are collected in the same way as [`export`], and they are encrypted with a key that is derived from the given passphrase.
+/// The key is derived by Argon2id with a random salt, and the networks are encrypted by ChaCha20-Poly1305 with a random nonce.
+/// The archive is authenticated as a whole, so [`restore`] detects a wrong passphrase or a modified archive before re-creating any network.
+///
+/// The archive is a binary file with the following layout:
+///
+/// ```text
+/// "WLBACKUP" | version (1 byte) | Argon2id memory, iterations and parallelism (3 x 4 bytes, little endian) | salt (16 bytes) | nonce (12 bytes) | ciphertext
+/// ```
+///
+/// The known networks that cannot be recreated by [`restore`] (e.g. `wpa-eap`, `wep`) are skipped, and their names are returned in [`ExportReport::skipped`].
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backup`] with:
+///
+/// - [`BackupError::EmptyPassphrase`] if the given passphrase is empty.
+/// - [`BackupError::CannotDeriveKey`] if the key cannot be derived from the passphrase.
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails (e.g. the network backend does not expose the settings of its known networks),
+/// and [`Error::Io`] when the archive cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::FakeWl;
+///
+/// let fake = FakeWl::new().with_known_network(b"Home", Some(b"secret"));
+///
+/// let mut archive = Vec::new();
+/// let report = wl::backup(&fake, &mut archive, b"passphrase").unwrap();
+///
+/// assert_eq!(1, report.networks.len());
+/// assert!(archive.starts_with(b"WLBACKUP"));
+/// assert!(!archive.windows(6).any(|w| w == b"secret"));
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`export`]: crate::export
+/// [`restore`]: crate::restore
+/// [`ExportReport::skipped`]: crate::ExportReport::skipped
+/// [`Error::Backup`]: crate::Error::Backup
+/// [`BackupError::EmptyPassphrase`]: crate::BackupError::EmptyPassphrase
+/// [`BackupError::CannotDeriveKey`]: crate::BackupError::CannotDeriveKey
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn backup(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    passphrase: &[u8],
+) -> Result<ExportReport, crate::Error> {
+    if passphrase.is_empty() {
+        Err(Error::EmptyPassphrase)?;
+    }
+
+    let report = export::export_networks(process, false)?;
+
+    let file = ExportFile {
+        version: EXPORT_VERSION,
+        networks: report.networks,
+    };
+    let plaintext = serde_json::to_vec(&file).map_err(io::Error::from)?;
+
+    let params = Params::default();
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.push(BACKUP_VERSION);
+    header.extend_from_slice(&params.m_cost().to_le_bytes());
+    header.extend_from_slice(&params.t_cost().to_le_bytes());
+    header.extend_from_slice(&params.p_cost().to_le_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let cipher = new_cipher(passphrase, &salt, params)?;
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &plaintext,
+                aad: &header,
+            },
+        )
+        .map_err(|_| io::Error::other("unable to encrypt the backup"))?;
+
+    write_bytes(f, &[header, ciphertext].concat())?;
+
+    Ok(ExportReport {
+        networks: file.networks,
+        skipped: report.skipped,
+    })
+}
+
+/// Restores the known networks of an archive that is written by [`backup`] by using the given [`Wl`] implementation, and writes the outcomes on the given [`io::Write`].
+///
+/// The archive is read from the given [`io::Read`], and it is decrypted with the given passphrase before any network is re-created.
+/// The networks are re-created along with their secrets, without connecting to them.
+///
+/// The networks that are already known are handled by `on_conflict` in the same way as [`import`], and the outcomes are written in the same format as [`import`].
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backup`] with:
+///
+/// - [`BackupError::InvalidArchive`] if the file is not an archive that is written by [`backup`], or its key derivation costs are too high to be restored.
+/// - [`BackupError::UnsupportedVersion`] if the archive is written by another version of `wl`.
+/// - [`BackupError::CannotDecrypt`] if the passphrase is wrong, or the archive is modified.
+/// - [`BackupError::CannotDeriveKey`] if the key cannot be derived from the passphrase.
+///
+/// This function can also return [`Error::Import`] with [`ImportError::AlreadyKnown`] if a network is already known, and [`ConflictPolicy::Fail`] is selected.
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails to add a network, in which case the networks before it are kept,
+/// and [`Error::Io`] when the archive cannot be read or the outcomes cannot be written.
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::{ConflictPolicy, OutputFormat}};
+///
+/// let fake = FakeWl::new().with_known_network(b"Home", Some(b"secret"));
+/// let mut archive = Vec::new();
+/// wl::backup(&fake, &mut archive, b"passphrase").unwrap();
+///
+/// let target = FakeWl::new();
+/// let mut out = Vec::new();
+/// wl::restore(&target, &mut out, &mut archive.as_slice(), b"passphrase", ConflictPolicy::Skip, OutputFormat::Text).unwrap();
+///
+/// assert_eq!(b"Home: added\n".as_slice(), out);
+///
+/// let err = wl::restore(&target, &mut out, &mut archive.as_slice(), b"wrong", ConflictPolicy::Skip, OutputFormat::Text).unwrap_err();
+/// assert!(matches!(err, wl::Error::Backup(wl::BackupError::CannotDecrypt)));
+/// ```
+///
+/// [`backup`]: crate::backup
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`io::Read`]: std::io::Read
+/// [`import`]: crate::import
+/// [`Error::Backup`]: crate::Error::Backup
+/// [`BackupError::InvalidArchive`]: crate::BackupError::InvalidArchive
+/// [`BackupError::UnsupportedVersion`]: crate::BackupError::UnsupportedVersion
+/// [`BackupError::CannotDecrypt`]: crate::BackupError::CannotDecrypt
+/// [`BackupError::CannotDeriveKey`]: crate::BackupError::CannotDeriveKey
+/// [`Error::Import`]: crate::Error::Import
+/// [`ImportError::AlreadyKnown`]: crate::ImportError::AlreadyKnown
+/// [`ConflictPolicy::Fail`]: crate::api::ConflictPolicy::Fail
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn restore(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    r: &mut impl io::Read,
+    passphrase: &[u8],
+    on_conflict: ConflictPolicy,
+    output_format: OutputFormat,
+) -> Result<Vec<ImportedNetwork>, crate::Error> {
+    let mut archive = Vec::new();
+    r.read_to_end(&mut archive)?;
+
+    if archive.len() < HEADER_LEN || !archive.starts_with(MAGIC) {
+        Err(Error::InvalidArchive)?;
+    }
+    let (header, ciphertext) = archive.split_at(HEADER_LEN);
+
+    let version = header[MAGIC.len()];
+    if version != BACKUP_VERSION {
+        Err(Error::UnsupportedVersion(version))?;
+    }
+
+    let field = |idx: usize| {
+        let start = MAGIC.len() + 1 + idx * 4;
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(&header[start..start + 4]);
+        u32::from_le_bytes(buf)
+    };
+    let (m_cost, t_cost, p_cost) = (field(0), field(1), field(2));
+    if m_cost > MAX_MEMORY_COST || t_cost > MAX_TIME_COST || p_cost > MAX_PARALLELISM {
+        Err(Error::InvalidArchive)?;
+    }
+    let params = Params::new(m_cost, t_cost, p_cost, Some(KEY_LEN))
+        .map_err(|err| Error::CannotDeriveKey(err.to_string()))?;
+
+    let salt_start = HEADER_LEN - SALT_LEN - NONCE_LEN;
+    let salt = &header[salt_start..salt_start + SALT_LEN];
+    let nonce = Nonce::from_slice(&header[salt_start + SALT_LEN..]);
+
+    let cipher = new_cipher(passphrase, salt, params)?;
+    let plaintext = cipher
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| Error::CannotDecrypt)?;
+
+    let file: ExportFile = serde_json::from_slice(&plaintext).map_err(|_| Error::InvalidArchive)?;
+
+    export::import_networks(
+        process,
+        f,
+        file,
+        Vec::new(),
+        false,
+        on_conflict,
+        output_format,
+    )
+}
+
+fn new_cipher(passphrase: &[u8], salt: &[u8], params: Params) -> Result<ChaCha20Poly1305, Error> {
+    let mut key = [0u8; KEY_LEN];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase, salt, &mut key)
+        .map_err(|err| Error::CannotDeriveKey(err.to_string()))?;
+
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
@@ -0,0 +1,272 @@
+use std::{io, path::Path};
+
+use clap::CommandFactory;
+
+use crate::{
+    adapter::{HOTSPOT_PROFILE, LINE_FEED, Wl},
+    api::{self, Shell, SsidSource},
+    write_bytes,
+};
+
+const PROGRAM: &str = "wl";
+
+// The generated `_wl` of zsh is renamed, so that the `_wl` below can complete the SSIDs before falling back to it.
+const ZSH_GENERATED_FN: &str = "\n_wl() {\n";
+const ZSH_RENAMED_FN: &str = "\n_wl_clap() {\n";
+const ZSH_REGISTRATION: &str = "\nif [ \"$funcstack[1]\" = \"_wl\" ]; then";
+
+const BASH_SSIDS: &str = r#"
+_wl_ssids() {
+    local prev="${COMP_WORDS[COMP_CWORD-1]}" source="" word
+    if [[ "$prev" == "-i" || "$prev" == "--ssid" ]]; then
+        for word in "${COMP_WORDS[@]:1:COMP_CWORD-2}"; do
+            case "$word" in
+                connect|c) source=connect; break ;;
+                disconnect|d) source=disconnect; break ;;
+            esac
+        done
+    fi
+
+    if [[ -z "$source" ]]; then
+        _wl "$@"
+        return
+    fi
+
+    local cur="${COMP_WORDS[COMP_CWORD]}" ssid
+    COMPREPLY=()
+    while IFS= read -r ssid; do
+        [[ "$ssid" == "$cur"* ]] && COMPREPLY+=("$(printf '%q' "$ssid")")
+    done < <(wl __complete-ssid "$source" 2>/dev/null)
+}
+
+complete -F _wl_ssids -o bashdefault -o default wl
+"#;
+
+const ZSH_SSIDS: &str = r#"
+_wl() {
+    local source word
+    if [[ "${words[CURRENT-1]}" == (-i|--ssid) ]]; then
+        for word in "${(@)words[2,CURRENT-2]}"; do
+            case "$word" in
+                (connect|c) source=connect; break ;;
+                (disconnect|d) source=disconnect; break ;;
+            esac
+        done
+    fi
+
+    if [[ -z "$source" ]]; then
+        _wl_clap "$@"
+        return
+    fi
+
+    local -a ssids
+    ssids=(${(f)"$(wl __complete-ssid "$source" 2>/dev/null)"})
+    compadd -a ssids
+}
+"#;
+
+const FISH_SSIDS: &str = r#"
+complete -c wl -n "__fish_wl_using_subcommand connect" -s i -l ssid -f -a "(wl __complete-ssid connect 2>/dev/null)"
+complete -c wl -n "__fish_wl_using_subcommand c" -s i -l ssid -f -a "(wl __complete-ssid connect 2>/dev/null)"
+complete -c wl -n "__fish_wl_using_subcommand disconnect" -s i -l ssid -f -a "(wl __complete-ssid disconnect 2>/dev/null)"
+complete -c wl -n "__fish_wl_using_subcommand d" -s i -l ssid -f -a "(wl __complete-ssid disconnect 2>/dev/null)"
+"#;
+
+/// Writes the completion script of the given [`Shell`] on the given [`io::Write`].
+///
+/// The script completes the subcommands and the options of [`Args`].
+/// In addition, `-i/--ssid` of `connect` completes the SSIDs of the scan list, and `-i/--ssid` of `disconnect` completes the active and known SSIDs.
+/// These SSIDs are queried from the network backend at completion time, by the hidden `wl __complete-ssid` subcommand (see [`complete_ssids`]).
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Io`] when the script cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::api::Shell;
+///
+/// let mut out = Vec::new();
+/// wl::completions(&mut out, Shell::Fish).unwrap();
+///
+/// assert!(String::from_utf8(out).unwrap().contains("(wl __complete-ssid connect 2>/dev/null)"));
+/// ```
+///
+/// [`Shell`]: crate::api::Shell
+/// [`io::Write`]: std::io::Write
+/// [`Args`]: crate::api::Args
+/// [`complete_ssids`]: crate::complete_ssids
+/// [`Error::Io`]: crate::Error::Io
+pub fn completions(f: &mut impl io::Write, shell: Shell) -> Result<(), crate::Error> {
+    let mut cmd = api::Args::command();
+    let mut script = Vec::new();
+
+    let script = match shell {
+        Shell::Bash => {
+            clap_complete::generate(clap_complete::Shell::Bash, &mut cmd, PROGRAM, &mut script);
+            [script, BASH_SSIDS.as_bytes().to_vec()].concat()
+        }
+        Shell::Zsh => {
+            clap_complete::generate(clap_complete::Shell::Zsh, &mut cmd, PROGRAM, &mut script);
+            let script =
+                String::from_utf8_lossy(&script).replacen(ZSH_GENERATED_FN, ZSH_RENAMED_FN, 1);
+
+            // The wrapper is defined before the registration, since the registration calls `_wl` when the script is autoloaded.
+            match script.find(ZSH_REGISTRATION) {
+                Some(idx) => [&script[..idx], ZSH_SSIDS, &script[idx..]].concat(),
+                None => [&script, ZSH_SSIDS, "\ncompdef _wl wl\n"].concat(),
+            }
+            .into_bytes()
+        }
+        Shell::Fish => {
+            clap_complete::generate(clap_complete::Shell::Fish, &mut cmd, PROGRAM, &mut script);
+            [script, FISH_SSIDS.as_bytes().to_vec()].concat()
+        }
+    };
+
+    write_bytes(f, &script)?;
+
+    Ok(())
+}
+
+/// Writes the manual page of `wl` on the given [`io::Write`] in the `roff` format.
+///
+/// The manual page documents the options of [`Args`] and lists its subcommands.
+/// Use [`man_to`] to write a manual page for each subcommand as well.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Io`] when the manual page cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// let mut out = Vec::new();
+/// wl::man(&mut out).unwrap();
+///
+/// assert!(String::from_utf8(out).unwrap().starts_with(".ie \\n(.g .ds Aq \\(aq"));
+/// ```
+///
+/// [`io::Write`]: std::io::Write
+/// [`Args`]: crate::api::Args
+/// [`man_to`]: crate::man_to
+/// [`Error::Io`]: crate::Error::Io
+pub fn man(f: &mut impl io::Write) -> Result<(), crate::Error> {
+    let mut out_buf = Vec::new();
+    clap_mangen::Man::new(api::Args::command()).render(&mut out_buf)?;
+
+    write_bytes(f, &out_buf)?;
+
+    Ok(())
+}
+
+/// Writes the manual pages of `wl` and each of its subcommands into the given directory (e.g. `wl.1`, `wl-connect.1`).
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Io`] when a manual page cannot be written into the given directory.
+///
+/// [`Error::Io`]: crate::Error::Io
+pub fn man_to(dir: &Path) -> Result<(), crate::Error> {
+    clap_mangen::generate_to(api::Args::command(), dir)?;
+
+    Ok(())
+}
+
+/// Writes the SSIDs that complete `-i/--ssid` of the given [`SsidSource`] on the given [`io::Write`], one SSID per line.
+///
+/// - [`SsidSource::Connect`] provides the SSIDs of the scan list, in the order that is reported by the network backend.
+/// - [`SsidSource::Disconnect`] provides the active SSIDs, followed by the other known SSIDs.
+///
+/// The hidden networks, the hotspot and the duplicate SSIDs are left out. The SSIDs that contain a line feed are left out as well, since they cannot be written on a single line.
+/// This function is used by the completion scripts of [`completions`] through the hidden `wl __complete-ssid` subcommand.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails,
+/// and [`Error::Io`] when the SSIDs cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{AccessPoint, FakeWl, api::SsidSource};
+///
+/// let fake = FakeWl::new()
+///     .with_access_point(AccessPoint { ssid: b"Home".to_vec(), signal: 80, ..Default::default() })
+///     .with_access_point(AccessPoint { ssid: b"Home".to_vec(), signal: 40, ..Default::default() });
+///
+/// let mut out = Vec::new();
+/// let ssids = wl::complete_ssids(&fake, &mut out, SsidSource::Connect).unwrap();
+///
+/// assert_eq!(vec![b"Home".to_vec()], ssids);
+/// assert_eq!(b"Home\n".as_slice(), out);
+/// ```
+///
+/// [`SsidSource`]: crate::api::SsidSource
+/// [`io::Write`]: std::io::Write
+/// [`SsidSource::Connect`]: crate::api::SsidSource::Connect
+/// [`SsidSource::Disconnect`]: crate::api::SsidSource::Disconnect
+/// [`completions`]: crate::completions
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Wl`]: crate::Wl
+/// [`Error::Io`]: crate::Error::Io
+pub fn complete_ssids(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    source: SsidSource,
+) -> Result<Vec<Vec<u8>>, crate::Error> {
+    let candidates = match source {
+        SsidSource::Connect => process
+            .get_access_points(false)?
+            .into_iter()
+            .map(|ap| ap.ssid)
+            .collect::<Vec<_>>(),
+        SsidSource::Disconnect => {
+            let active = process.get_networks(true)?;
+            let known = process.get_networks(false)?;
+            active
+                .into_iter()
+                .chain(known)
+                .filter(|n| n.kind == "wifi")
+                .map(|n| n.ssid)
+                .collect()
+        }
+    };
+
+    let mut ssids: Vec<Vec<u8>> = Vec::with_capacity(candidates.len());
+    for ssid in candidates {
+        if ssid.is_empty()
+            || ssid == HOTSPOT_PROFILE.as_bytes()
+            || ssid.contains(&LINE_FEED)
+            || ssids.contains(&ssid)
+        {
+            continue;
+        }
+        ssids.push(ssid);
+    }
+
+    let out_buf = ssids
+        .iter()
+        .flat_map(|ssid| ssid.iter().copied().chain([LINE_FEED]))
+        .collect::<Vec<_>>();
+    write_bytes(f, &out_buf)?;
+
+    Ok(ssids)
+}
@@ -0,0 +1,509 @@
+use std::{
+    collections::BTreeMap,
+    env, error,
+    ffi::OsString,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use clap::{CommandFactory, ValueEnum, builder::ArgPredicate};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::{
+    adapter::{Backend, DEFAULT_BACKEND_ORDER},
+    api::{self, OutputFormat},
+    output, write_bytes,
+};
+
+/// Represents the environment variable that overrides the path of the configuration file.
+pub const CONFIG_ENV: &str = "WL_CONFIG";
+
+// The global options that take a value, which are skipped whilst looking for an alias.
+const VALUE_OPTIONS: [&str; 4] = ["-o", "--output", "-b", "--backend"];
+
+/// Defines [`Error`] variants that may return whilst loading the configuration file.
+///
+/// [`Error`]: std::error::Error
+#[derive(Debug)]
+pub enum Error {
+    /// Represents a configuration file that cannot be parsed.
+    ///
+    /// It holds the path of the file and the details of the failure.
+    InvalidFile(PathBuf, String),
+
+    /// Represents an alias that has the name of a subcommand, or expands to nothing.
+    ///
+    /// It holds the name of the alias.
+    InvalidAlias(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidFile(path, err) if path.as_os_str().is_empty() => {
+                write!(f, "invalid configuration: {}", err)
+            }
+            Error::InvalidFile(path, err) => {
+                write!(f, "invalid configuration {}: {}", path.display(), err)
+            }
+            Error::InvalidAlias(name) => write!(
+                f,
+                "the alias '{}' must not be empty or have the name of a subcommand",
+                name
+            ),
+        }
+    }
+}
+impl error::Error for Error {}
+
+/// Represents the configuration file of `wl`.
+///
+/// The configuration file is read from `$XDG_CONFIG_HOME/wl/config.toml` (or `~/.config/wl/config.toml`), unless [`CONFIG_ENV`] points to another path.
+/// Each setting is optional:
+///
+/// ```toml
+/// # The defaults of --backend and --output.
+/// backend = ["nmcli", "wpa_supplicant"]
+/// output = "text"
+///
+/// # The defaults of `wl scan`.
+/// [scan]
+/// min-strength = 40
+/// re-scan = false
+/// columns = "ssid,signal,security"
+///
+/// # The nicknames of the SSIDs, which are accepted wherever an SSID is expected.
+/// [nicknames]
+/// office = "ACME Corp 5G"
+///
+/// # The custom subcommands, which expand to the given arguments.
+/// [aliases]
+/// home = "connect -i Home"
+/// strong = ["scan", "--min-strength", "70"]
+/// ```
+///
+/// The settings are only defaults: the arguments and the environment variables (e.g. `WL_BACKEND`) take precedence over them.
+///
+/// [`CONFIG_ENV`]: crate::CONFIG_ENV
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The path of the loaded configuration file, if any.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+
+    /// The default network backends to probe, in order.
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_backends",
+        deserialize_with = "deserialize_backends"
+    )]
+    pub backend: Vec<Backend>,
+
+    /// The default output format.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_output",
+        deserialize_with = "deserialize_output"
+    )]
+    pub output: Option<OutputFormat>,
+
+    /// The defaults of `wl scan`.
+    pub scan: ScanDefaults,
+
+    /// The nicknames of the SSIDs, which map a nickname to its SSID.
+    pub nicknames: BTreeMap<String, String>,
+
+    /// The custom subcommands, which map an alias to the arguments that it expands to.
+    pub aliases: BTreeMap<String, Alias>,
+}
+
+/// Represents the defaults of [`ScanArgs`].
+///
+/// [`ScanArgs`]: crate::api::ScanArgs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScanDefaults {
+    /// The default of `--min-strength`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_strength: Option<u8>,
+
+    /// The default of `--re-scan`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub re_scan: Option<bool>,
+
+    /// The default of `--columns`, which is not used when `--get-values` is provided.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<String>,
+
+    /// The default of `--get-values`, which is not used when `--columns` is provided.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub get_values: Option<String>,
+}
+
+/// Represents the arguments that an alias expands to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Alias {
+    /// The arguments that are separated by whitespace (e.g. `"connect -i Home"`).
+    Line(String),
+
+    /// The arguments as they are, which can contain whitespace (e.g. `["connect", "-i", "Home Office"]`).
+    Args(Vec<String>),
+}
+
+impl Alias {
+    /// Provides the arguments that the alias expands to.
+    pub fn args(&self) -> Vec<String> {
+        match self {
+            Alias::Line(line) => line.split_whitespace().map(String::from).collect(),
+            Alias::Args(args) => args.clone(),
+        }
+    }
+}
+
+impl Config {
+    /// Provides the path of the configuration file.
+    ///
+    /// The path is read from [`CONFIG_ENV`] if it is set, then from `$XDG_CONFIG_HOME/wl/config.toml`, and then from `$HOME/.config/wl/config.toml`.
+    /// It returns [`None`] when none of these variables are set.
+    ///
+    /// [`CONFIG_ENV`]: crate::CONFIG_ENV
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os(CONFIG_ENV) {
+            return Some(PathBuf::from(path));
+        }
+
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+        Some(config_home.join("wl").join("config.toml"))
+    }
+
+    /// Loads the configuration file at [`Config::path`].
+    ///
+    /// A missing configuration file is not an error, and it results in the default [`Config`].
+    ///
+    /// # Panics
+    ///
+    /// This function does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::Config`] when the configuration file cannot be parsed (see [`Config::parse`]),
+    /// and [`Error::Io`] when the configuration file exists but cannot be read.
+    ///
+    /// [`Config::path`]: crate::Config::path
+    /// [`Config`]: crate::Config
+    /// [`Error::Config`]: crate::Error::Config
+    /// [`Config::parse`]: crate::Config::parse
+    /// [`Error::Io`]: crate::Error::Io
+    pub fn load() -> Result<Config, crate::Error> {
+        let Some(path) = Config::path() else {
+            return Ok(Config::default());
+        };
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut config = Config::parse(&content).map_err(|err| match err {
+            Error::InvalidFile(_, err) => Error::InvalidFile(path.clone(), err),
+            err => err,
+        })?;
+        config.path = Some(path);
+
+        Ok(config)
+    }
+
+    /// Parses the given content of a configuration file.
+    ///
+    /// # Panics
+    ///
+    /// This function does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`ConfigError::InvalidFile`] if the content is not a valid TOML document, has an unknown setting, or has an invalid value (e.g. an unknown backend),
+    /// and [`ConfigError::InvalidAlias`] if an alias has the name of a subcommand, or expands to nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wl::{Backend, Config, api::OutputFormat};
+    ///
+    /// let config = Config::parse("backend = \"nmcli\"\noutput = \"json\"\n").unwrap();
+    ///
+    /// assert_eq!(vec![Backend::Nmcli], config.backend);
+    /// assert_eq!(Some(OutputFormat::Json), config.output);
+    /// ```
+    ///
+    /// [`ConfigError::InvalidFile`]: crate::ConfigError::InvalidFile
+    /// [`ConfigError::InvalidAlias`]: crate::ConfigError::InvalidAlias
+    pub fn parse(content: &str) -> Result<Config, Error> {
+        let config: Config = toml::from_str(content)
+            .map_err(|err| Error::InvalidFile(PathBuf::new(), err.message().to_string()))?;
+
+        let cmd = api::Args::command();
+        for (name, alias) in &config.aliases {
+            if alias.args().is_empty() || name == "help" || cmd.find_subcommand(name).is_some() {
+                return Err(Error::InvalidAlias(name.clone()));
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Provides the [`clap::Command`] of [`Args`], whose defaults are replaced with the settings of the configuration.
+    ///
+    /// [`clap::Command`]: clap::Command
+    /// [`Args`]: crate::api::Args
+    pub fn command(&self) -> clap::Command {
+        let mut cmd = api::Args::command();
+
+        if !self.backend.is_empty() {
+            let backends = self.backend.iter().map(|b| b.name()).collect::<Vec<_>>();
+            cmd = cmd.mut_arg("backend", |arg| arg.default_values(backends));
+        }
+
+        if let Some(output) = self.output {
+            cmd = cmd.mut_arg("output", |arg| arg.default_value(output_name(output)));
+        }
+
+        let scan = self.scan.clone();
+        cmd.mut_subcommand("scan", |mut sc| {
+            if let Some(min_strength) = scan.min_strength {
+                sc = sc.mut_arg("min_strength", |arg| {
+                    arg.default_value(min_strength.to_string())
+                });
+            }
+            if let Some(re_scan) = scan.re_scan {
+                sc = sc.mut_arg("re_scan", |arg| arg.default_value(re_scan.to_string()));
+            }
+            // A default of `--columns` or `--get-values` is dropped when the other one is provided, since they conflict.
+            if let Some(columns) = scan.columns {
+                sc = sc.mut_arg("columns", |arg| {
+                    arg.default_value(columns).default_value_if(
+                        "get_values",
+                        ArgPredicate::IsPresent,
+                        None,
+                    )
+                });
+            }
+            if let Some(get_values) = scan.get_values {
+                sc = sc.mut_arg("get_values", |arg| {
+                    arg.default_value(get_values).default_value_if(
+                        "columns",
+                        ArgPredicate::IsPresent,
+                        None,
+                    )
+                });
+            }
+            sc
+        })
+    }
+
+    /// Expands the alias of the given command line arguments, if any.
+    ///
+    /// The first argument is the program name, and the alias is the first argument that is neither an option nor the value of a global option (e.g. `wl -o json home`).
+    /// The alias is replaced with the arguments that it expands to, and the rest of the arguments are kept after them.
+    /// The aliases are expanded once, so an alias cannot expand to another alias.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ffi::OsString;
+    ///
+    /// use wl::Config;
+    ///
+    /// let config = Config::parse("[aliases]\nhome = \"connect -i Home\"\n").unwrap();
+    ///
+    /// let args = config.expand_alias(["wl", "-o", "json", "home", "-f"].map(OsString::from));
+    ///
+    /// assert_eq!(
+    ///     ["wl", "-o", "json", "connect", "-i", "Home", "-f"].map(OsString::from).to_vec(),
+    ///     args
+    /// );
+    /// ```
+    pub fn expand_alias(&self, args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+        let mut args = args.into_iter().collect::<Vec<_>>();
+
+        let mut idx = 1;
+        while let Some(arg) = args.get(idx).and_then(|arg| arg.to_str()) {
+            if VALUE_OPTIONS.contains(&arg) {
+                idx += 2;
+            } else if arg.starts_with('-') {
+                idx += 1;
+            } else {
+                if let Some(alias) = self.aliases.get(arg) {
+                    let expanded = alias.args().into_iter().map(OsString::from);
+                    args.splice(idx..=idx, expanded);
+                }
+                break;
+            }
+        }
+
+        args
+    }
+
+    /// Resolves the given SSID from its nickname, or provides it as is if it is not a nickname.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wl::Config;
+    ///
+    /// let config = Config::parse("[nicknames]\noffice = \"ACME Corp 5G\"\n").unwrap();
+    ///
+    /// assert_eq!("ACME Corp 5G", config.resolve_ssid(String::from("office")));
+    /// assert_eq!("Home", config.resolve_ssid(String::from("Home")));
+    /// ```
+    pub fn resolve_ssid(&self, ssid: String) -> String {
+        self.nicknames.get(&ssid).cloned().unwrap_or(ssid)
+    }
+}
+
+/// Writes the effective configuration of `wl` on the given [`io::Write`], and returns it.
+///
+/// The effective configuration merges the given [`Config`] with the built-in defaults and the given arguments:
+///
+/// - `backend` is the given backends (e.g. `--backend`, `WL_BACKEND`, or the configuration), or [`DEFAULT_BACKEND_ORDER`] if they are empty.
+/// - `output` is the given [`OutputFormat`].
+/// - `scan` has the defaults of [`ScanArgs`] for the settings that are not configured.
+///
+/// In [`OutputFormat::Text`], the effective configuration is written as a TOML document, which starts with a comment that shows the path of the loaded configuration file.
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], it is written as a JSON object.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Io`] when the configuration cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{Config, api::OutputFormat};
+///
+/// let config = Config::parse("[scan]\nmin-strength = 40\n").unwrap();
+///
+/// let mut out = Vec::new();
+/// let effective = wl::config_show(&mut out, &config, &[], OutputFormat::Text).unwrap();
+///
+/// assert_eq!(Some(40), effective.scan.min_strength);
+/// assert_eq!(Some(false), effective.scan.re_scan);
+/// assert!(String::from_utf8(out).unwrap().contains("backend = [\"networkmanager\", \"nmcli\", \"iwd\", \"wpa_supplicant\"]\n"));
+/// ```
+///
+/// [`io::Write`]: std::io::Write
+/// [`Config`]: crate::Config
+/// [`DEFAULT_BACKEND_ORDER`]: crate::DEFAULT_BACKEND_ORDER
+/// [`OutputFormat`]: crate::api::OutputFormat
+/// [`ScanArgs`]: crate::api::ScanArgs
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Io`]: crate::Error::Io
+pub fn config_show(
+    f: &mut impl io::Write,
+    config: &Config,
+    backend: &[Backend],
+    output_format: OutputFormat,
+) -> Result<Config, crate::Error> {
+    let effective = Config {
+        path: config.path.clone(),
+        backend: match backend {
+            [] => DEFAULT_BACKEND_ORDER.to_vec(),
+            backend => backend.to_vec(),
+        },
+        output: Some(output_format),
+        scan: ScanDefaults {
+            min_strength: Some(config.scan.min_strength.unwrap_or(0)),
+            re_scan: Some(config.scan.re_scan.unwrap_or(false)),
+            columns: config.scan.columns.clone(),
+            get_values: config.scan.get_values.clone(),
+        },
+        nicknames: config.nicknames.clone(),
+        aliases: config.aliases.clone(),
+    };
+
+    match output_format {
+        OutputFormat::Text => {
+            let header = match &effective.path {
+                Some(path) => format!("# Loaded from {}\n\n", path.display()),
+                None => String::from("# No configuration file is loaded.\n\n"),
+            };
+            let document = toml::to_string(&effective).map_err(io::Error::other)?;
+            write_bytes(f, [header, document].concat().as_bytes())?;
+        }
+        _ => output::write_value(f, &effective)?,
+    }
+
+    Ok(effective)
+}
+
+fn output_name(output: OutputFormat) -> &'static str {
+    match output {
+        OutputFormat::Text => "text",
+        OutputFormat::Json => "json",
+        OutputFormat::Ndjson => "ndjson",
+    }
+}
+
+fn serialize_backends<S: Serializer>(
+    backend: &[Backend],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(backend.iter().map(|b| b.name()))
+}
+
+/// Deserializes the backends from a list (`["nmcli", "iwd"]`), or from a comma separated string as in `WL_BACKEND` (`"nmcli,iwd"`).
+fn deserialize_backends<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Backend>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Backends {
+        List(Vec<String>),
+        Line(String),
+    }
+
+    let names = match Backends::deserialize(deserializer)? {
+        Backends::List(names) => names,
+        Backends::Line(line) => line.split(',').map(String::from).collect(),
+    };
+
+    names
+        .iter()
+        .map(|name| {
+            Backend::from_str(name.trim(), true)
+                .map_err(|_| de::Error::custom(format!("unknown backend '{}'", name)))
+        })
+        .collect()
+}
+
+fn serialize_output<S: Serializer>(
+    output: &Option<OutputFormat>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match output {
+        Some(output) => serializer.serialize_str(output_name(*output)),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_output<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<OutputFormat>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+
+    OutputFormat::from_str(&name, true)
+        .map(Some)
+        .map_err(|_| de::Error::custom(format!("unknown output format '{}'", name)))
+}
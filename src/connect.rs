@@ -1,17 +1,148 @@
 use std::{
     collections::HashMap,
-    error, fmt,
+    env, error, fmt, fs,
     io::{self},
+    os::{fd::AsFd, unix::ffi::OsStrExt},
+    path::PathBuf,
 };
 
+use clap::ValueEnum;
+use serde::Serialize;
 use termion::input::TermRead;
 
 use crate::{
-    adapter::{self, LINE_FEED, Wl},
-    api::ScanArgs,
+    EapCredentials, EapMethod, Security,
+    access_point::parse_bssid,
+    adapter::Wl,
+    api::OutputFormat,
+    output,
+    picker::{self, Entry, Selection},
     write_bytes,
 };
 
+/// The label of the picker row that switches to the connection flow of the hidden networks.
+const OTHER_NETWORK: &str = "Other network…";
+
+/// Represents the result of [`connect_with`].
+///
+/// [`connect_with`]: crate::connect_with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConnectReport {
+    /// The SSID (name) of the network that is connected.
+    #[serde(serialize_with = "output::serialize_ssid")]
+    pub ssid: Vec<u8>,
+
+    /// The BSSID of the access point that the network is pinned to, if it is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bssid: Option<String>,
+
+    /// Whether the connection attempt is successful.
+    pub connected: bool,
+
+    /// The result of the connection attempt that is provided by the [`Wl`] implementation.
+    ///
+    /// It is only used in [`OutputFormat::Text`].
+    ///
+    /// [`Wl`]: crate::Wl
+    /// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+    #[serde(skip)]
+    pub message: Vec<u8>,
+}
+
+/// Represents where the password of a connection attempt is read from.
+///
+/// The password is only read when it is needed, which is the same as when it is asked by [`PasswdSource::Prompt`].
+/// Therefore, a known network is re-used as is unless the password is forced.
+///
+/// [`PasswdSource::File`] and [`PasswdSource::Env`] provide the same password each time it is needed,
+/// whereas [`PasswdSource::Stdin`] reads a new line each time.
+///
+/// There is no variant to provide the password directly from an argument of `wl`,
+/// since the arguments of a process are visible to the other users of the host.
+///
+/// [`PasswdSource::Prompt`]: crate::PasswdSource::Prompt
+/// [`PasswdSource::File`]: crate::PasswdSource::File
+/// [`PasswdSource::Env`]: crate::PasswdSource::Env
+/// [`PasswdSource::Stdin`]: crate::PasswdSource::Stdin
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PasswdSource {
+    /// The password is asked on the terminal, and it is not echoed.
+    #[default]
+    Prompt,
+
+    /// The password is read as a line from stdin, without a prompt.
+    ///
+    /// If the SSID is selected from a numbered list, then the password is read after the selection.
+    Stdin,
+
+    /// The password is the first line of the file at the given path.
+    File(PathBuf),
+
+    /// The password is the value of the environment variable with the given name.
+    Env(String),
+}
+
+/// Represents how a connection attempt of [`connect`] and [`connect_with`] is authenticated.
+///
+/// The default is [`Auth::Psk`], which asks the password of a network that is not known, and does not pin an access point.
+///
+/// [`connect`]: crate::connect
+/// [`connect_with`]: crate::connect_with
+/// [`Auth::Psk`]: crate::Auth::Psk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Auth {
+    /// A password (pre-shared key), which is asked if `force_passwd` is set or the network is not known.
+    ///
+    /// If `bssid` is provided, then the connection is made to the access point with that BSSID only, and the known network is pinned to it. Use [`unpin`] to remove the pin.
+    /// If an SSID is not given, then it is obtained from the access point on the scan list, instead of a list to choose from. The BSSID is case-insensitive.
+    ///
+    /// [`unpin`]: crate::unpin
+    Psk {
+        force_passwd: bool,
+        bssid: Option<String>,
+    },
+
+    /// The credentials of an enterprise (802.1X / WPA-EAP) network.
+    ///
+    /// The network is always connected with the credentials, even if it is a known network. The known network is replaced with the new credentials.
+    /// If the password of a tunneled method (PEAP or TTLS) is not provided, then it is asked for the identity of the credentials.
+    /// If the private key of EAP-TLS is not encrypted, then its password can be left empty when it is asked.
+    /// The relative paths of the certificates and the private key are resolved against the current directory, since the network backends read them by themselves.
+    Enterprise(EapCredentials),
+
+    /// The security type of a hidden network, which is asked as one of `open`, `wpa-psk` or `sae` if it is not provided.
+    ///
+    /// A hidden network does not broadcast its SSID, so it is not on the scan list and its security type cannot be detected.
+    /// Therefore, the network is always connected with a new profile that probes the SSID, even if it is a known network,
+    /// and its SSID is asked instead of selected from a list.
+    Hidden { security: Option<Security> },
+}
+
+impl Default for Auth {
+    fn default() -> Self {
+        Auth::Psk {
+            force_passwd: false,
+            bssid: None,
+        }
+    }
+}
+
+impl Auth {
+    /// Validates the BSSID of [`Auth::Psk`], and provides it in upper case.
+    fn normalize(self) -> Result<Self, Error> {
+        match self {
+            Auth::Psk {
+                force_passwd,
+                bssid: Some(bssid),
+            } => Ok(Auth::Psk {
+                force_passwd,
+                bssid: Some(normalize_bssid(&bssid)?),
+            }),
+            auth => Ok(auth),
+        }
+    }
+}
+
 /// Defines [`Error`] variants that may return during a connection attempt.
 ///
 /// [`Error`]: `std::error::Error`
@@ -19,52 +150,150 @@ use crate::{
 pub enum Error {
     /// Represents a read failure whilst trying to obtain the SSID password.
     ///
-    /// This failure can only come from the underlying stream (e.g. stdin).
+    /// This failure can only come from the underlying stream (e.g. stdin, or the given [`io::BufRead`]).
     /// It holds the details of the underlying [`io::Error`].
     ///
     /// [`io::Error`]: std::io::Error
+    /// [`io::BufRead`]: std::io::BufRead
     CannotReadPasswd(io::Error),
 
-    /// Represents a read faliure whilst trying to obtain the SSID.
+    /// Represents a read failure whilst trying to obtain the SSID password from the file of [`PasswdSource::File`].
+    ///
+    /// It holds the path of the file and the details of the underlying [`io::Error`].
+    ///
+    /// [`PasswdSource::File`]: crate::PasswdSource::File
+    /// [`io::Error`]: std::io::Error
+    CannotReadPasswdFile(PathBuf, io::Error),
+
+    /// Represents a failure whilst trying to obtain the SSID password from the environment variable of [`PasswdSource::Env`].
+    ///
+    /// It holds the name of the environment variable and the details of the underlying [`env::VarError`] (e.g. the variable is not set).
+    ///
+    /// [`PasswdSource::Env`]: crate::PasswdSource::Env
+    /// [`env::VarError`]: std::env::VarError
+    CannotReadPasswdEnv(String, env::VarError),
+
+    /// Represents a read failure whilst trying to obtain the SSID selection.
+    ///
+    /// This failure can only come from the underlying stream (e.g. stdin, or the given [`io::BufRead`]).
+    /// It holds the details of the underlying [`io::Error`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    /// [`io::BufRead`]: std::io::BufRead
+    CannotReadSSID(io::Error),
+
+    /// Represents an SSID selection that is not a number, or does not exist on the given list of SSID's.
+    ///
+    /// It holds the invalid SSID selection.
+    InvalidSSIDSelection(String),
+
+    /// Represents an enterprise connection attempt without a credential that is required by its EAP method (e.g. the client certificate of EAP-TLS).
+    ///
+    /// It holds the EAP method and the name of the missing credential.
+    MissingEapCredential(EapMethod, &'static str),
+
+    /// Represents a security type selection of a hidden network that is not one of [`Security`].
     ///
-    /// This error can happen in three ways:
+    /// It holds the invalid security type selection.
     ///
-    /// - From the underlying stdin stream.
-    /// - By providing an invalid SSID selection.
-    /// - By providing an SSID that does not exist on the given list of SSID's.
+    /// [`Security`]: crate::Security
+    InvalidSecurity(String),
+
+    /// Represents a BSSID that is not in the `XX:XX:XX:XX:XX:XX` format.
     ///
-    /// Based on the error case, it holds:
+    /// It holds the invalid BSSID.
+    InvalidBssid(String),
+
+    /// Represents a BSSID that is not on the scan list, whilst trying to obtain the SSID of the access point.
     ///
-    /// - [`io::Error`] coming from the stream (stdin).
-    /// - The invalid SSID selection.
-    /// - None.
-    CannotReadSSID(Option<String>),
+    /// It holds the BSSID.
+    BssidNotFound(String),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::CannotReadPasswd(err) => write!(f, "cannot read passwd from stdin: {}", err),
-            Error::CannotReadSSID(err) => match err {
-                Some(err) => write!(f, "unable to get the SSID: {}", err),
-                None => write!(f, "the given SSID does not exist on the list"),
-            },
+            Error::CannotReadPasswdFile(path, err) => write!(
+                f,
+                "cannot read passwd from the file '{}': {}",
+                path.display(),
+                err
+            ),
+            Error::CannotReadPasswdEnv(name, err) => write!(
+                f,
+                "cannot read passwd from the environment variable '{}': {}",
+                name, err
+            ),
+            Error::CannotReadSSID(err) => write!(f, "unable to get the SSID: {}", err),
+            Error::InvalidSSIDSelection(selection) => write!(
+                f,
+                "the given SSID selection '{}' does not exist on the list",
+                selection
+            ),
+            Error::MissingEapCredential(method, credential) => {
+                write!(f, "the EAP method '{}' requires a {}", method, credential)
+            }
+            Error::InvalidSecurity(security) => write!(
+                f,
+                "the given security type '{}' is not one of open, wpa-psk or sae",
+                security
+            ),
+            Error::InvalidBssid(bssid) => write!(
+                f,
+                "the given BSSID '{}' is not in the XX:XX:XX:XX:XX:XX format",
+                bssid
+            ),
+            Error::BssidNotFound(bssid) => {
+                write!(f, "the given BSSID '{}' is not on the scan list", bssid)
+            }
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::CannotReadPasswd(err)
+            | Error::CannotReadPasswdFile(_, err)
+            | Error::CannotReadSSID(err) => Some(err),
+            Error::CannotReadPasswdEnv(_, err) => Some(err),
+            Error::InvalidSSIDSelection(_)
+            | Error::MissingEapCredential(..)
+            | Error::InvalidSecurity(_)
+            | Error::InvalidBssid(_)
+            | Error::BssidNotFound(_) => None,
         }
     }
 }
-impl error::Error for Error {}
 
-/// Connects to a given WiFi network by using a [`Wl`] implementation.
+/// Connects to a given WiFi network with the given [`Auth`] by using the given [`Wl`] implementation.
 ///
 /// If an SSID is not given by the caller, then `connect` shows a list of available networks to choose from.
+/// When stdin is a terminal, the list is shown on the full-screen [`picker`], which marks the secured and known networks, and can search and re-scan the networks.
+/// The "Other network…" row of the picker switches to the flow of [`Auth::Hidden`].
+/// Otherwise, the list is numbered and the index of the network is read from stdin.
+///
+/// The network is authenticated as described by each variant of [`Auth`]:
 ///
-/// If `force_passwd` is set to `true`, the caller is asked to provide a password for the SSID, even if the SSID is a known network.
-/// If not, then the password is asked when the provided SSID is not in the known network list of the host.
+/// - [`Auth::Psk`] asks the password when it is forced, or when the SSID is not in the known network list of the host. If a BSSID is given, then the connection is made to that access point only, and the known network is pinned to it.
+/// - [`Auth::Enterprise`] always connects with the given credentials, and asks the missing passwords of them.
+/// - [`Auth::Hidden`] always connects with a new profile that probes the SSID, and asks the SSID instead of selecting it from a list.
+///
+/// The passwords are read from the given [`PasswdSource`], which asks them on the terminal by default.
+/// The other sources do not write a prompt, so that a connection attempt can be made without a terminal.
 ///
 /// The validity of SSID-password pair is delegated to the [`Wl`] implementation. `connect` does not validate the pair.
 /// The success result of a connection attempt depends on the [`Wl`] implementation.
 ///
-/// The SSID selection and password are both retrieved from stdin, and the result of the connection attempt is written to stdout.
+/// The SSID selection is retrieved from stdin, and the result of the connection attempt is written to stdout.
+///
+/// In [`OutputFormat::Text`], the result is the one that is provided by the [`Wl`] implementation.
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], the result is a single JSON object, and the prompts are written to stderr to keep stdout machine-readable.
+/// The BSSID of [`Auth::Psk`] is also written, if it is given:
+///
+/// ```json
+/// {"ssid":"SSID1","bssid":"AA:BB:CC:DD:EE:FF","connected":true}
+/// ```
 ///
 /// # Panics
 ///
@@ -72,121 +301,604 @@ impl error::Error for Error {}
 ///
 /// # Errors
 ///
-/// This function returns [`Error::CannotReadSSID`] if the provided SSID cannot be read, and [`Error::CannotReadPasswd`] if the provided password cannot be read.
+/// This function returns [`Error::Connect`] with [`ConnectError::CannotReadSSID`] or [`ConnectError::InvalidSSIDSelection`] if the provided SSID cannot be read or does not exist, and [`ConnectError::CannotReadPasswd`] if the provided password cannot be read.
+/// The password of [`PasswdSource::File`] and [`PasswdSource::Env`] is read before the SSID, and the failures are returned as [`ConnectError::CannotReadPasswdFile`] and [`ConnectError::CannotReadPasswdEnv`] respectively.
+/// If a prompt is aborted (e.g. Ctrl-D), or stdin is closed before the password of [`PasswdSource::Stdin`] is read, then it returns [`Error::UserAborted`].
 ///
-/// This function can also return an [`NetworkAdapterError`] when the underlying [`Wl`] implementation fails or [`io::Error`] when the successful connection result cannot be written on the stdout stream.
+/// The errors of each [`Auth`] are:
+///
+/// - [`ConnectError::InvalidBssid`] if the BSSID of [`Auth::Psk`] is not in the `XX:XX:XX:XX:XX:XX` format, and [`ConnectError::BssidNotFound`] if an SSID is not given and the BSSID is not on the scan list.
+/// - [`ConnectError::MissingEapCredential`] if [`Auth::Enterprise`] uses EAP-TLS without a client certificate or a private key.
+/// - [`ConnectError::InvalidSecurity`] if the security type that is asked by [`Auth::Hidden`] is not valid.
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the successful connection result cannot be written on the stdout stream.
 ///
 /// [`Wl`]: crate::Wl
-/// [`Error::CannotReadSSID`]: crate::ConnectError::CannotReadSSID
-/// [`Error::CannotReadPasswd`]: crate::ConnectError::CannotReadPasswd
-/// [`NetworkAdapterError`]: crate::adapter::Error
-/// [`io::Error`]: std::io::Error
-pub fn connect(ssid: Option<Vec<u8>>, force_passwd: bool) -> Result<(), Box<dyn error::Error>> {
-    let process = adapter::new();
+/// [`Auth`]: crate::Auth
+/// [`Auth::Psk`]: crate::Auth::Psk
+/// [`Auth::Enterprise`]: crate::Auth::Enterprise
+/// [`Auth::Hidden`]: crate::Auth::Hidden
+/// [`PasswdSource`]: crate::PasswdSource
+/// [`PasswdSource::File`]: crate::PasswdSource::File
+/// [`PasswdSource::Env`]: crate::PasswdSource::Env
+/// [`PasswdSource::Stdin`]: crate::PasswdSource::Stdin
+/// [`ConnectError::CannotReadPasswdFile`]: crate::ConnectError::CannotReadPasswdFile
+/// [`ConnectError::CannotReadPasswdEnv`]: crate::ConnectError::CannotReadPasswdEnv
+/// [`picker`]: crate::picker
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Connect`]: crate::Error::Connect
+/// [`ConnectError::CannotReadSSID`]: crate::ConnectError::CannotReadSSID
+/// [`ConnectError::InvalidSSIDSelection`]: crate::ConnectError::InvalidSSIDSelection
+/// [`ConnectError::CannotReadPasswd`]: crate::ConnectError::CannotReadPasswd
+/// [`ConnectError::InvalidBssid`]: crate::ConnectError::InvalidBssid
+/// [`ConnectError::BssidNotFound`]: crate::ConnectError::BssidNotFound
+/// [`ConnectError::MissingEapCredential`]: crate::ConnectError::MissingEapCredential
+/// [`ConnectError::InvalidSecurity`]: crate::ConnectError::InvalidSecurity
+/// [`Error::UserAborted`]: crate::Error::UserAborted
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn connect(
+    process: &impl Wl,
+    ssid: Option<Vec<u8>>,
+    auth: Auth,
+    passwd_source: PasswdSource,
+    output_format: OutputFormat,
+) -> Result<(), crate::Error> {
+    let auth = auth.normalize()?;
+    connect_stdin(process, ssid, auth, passwd_source, output_format)
+}
+
+/// Connects to a given WiFi network with the given [`Auth`] by using the given [`Wl`] implementation, the given [`io::Write`] for the output and prompts, and the given [`io::BufRead`] for the answers.
+///
+/// The behavior and output are the same as [`connect`], except:
+///
+/// - The prompts are written to `prompt` instead of stdout/stderr.
+/// - The SSID selection and password are read line by line from `input` instead of stdin, and the SSID is always selected from a numbered list. The password is not hidden, since `input` is not required to be a terminal.
+/// - The result of the connection attempt is written to `f` and also returned as a [`ConnectReport`].
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// The errors are the same as [`connect`], except that the passwords are always read from `input`.
+/// If `input` is closed before an answer is read, then it returns [`Error::UserAborted`], and [`Error::Io`] is also returned when the prompts cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{AccessPoint, Auth, FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new()
+///     .with_access_point(AccessPoint {
+///         ssid: b"Home".to_vec(),
+///         security: String::from("WPA2"),
+///         ..Default::default()
+///     })
+///     .with_secret(b"Home", b"secret");
+///
+/// let (mut out, mut prompt) = (Vec::new(), Vec::new());
+/// let mut input = &b"0\nsecret\n"[..];
+///
+/// let report = wl::connect_with(&fake, &mut out, &mut prompt, &mut input, None, Auth::default(), OutputFormat::Json).unwrap();
+///
+/// assert!(report.connected);
+/// assert_eq!(b"Home".as_slice(), report.ssid);
+/// assert_eq!(b"{\"ssid\":\"Home\",\"connected\":true}\n".as_slice(), out);
+/// ```
+///
+/// An access point is pinned with [`Auth::Psk`], and a hidden network is connected with [`Auth::Hidden`]:
+///
+/// ```
+/// use wl::{AccessPoint, Auth, FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new()
+///     .with_access_point(AccessPoint {
+///         ssid: b"Office".to_vec(),
+///         bssid: String::from("AA:BB:CC:DD:EE:02"),
+///         ..Default::default()
+///     })
+///     .with_hidden_network(AccessPoint {
+///         ssid: b"Attic".to_vec(),
+///         security: String::from("WPA2"),
+///         ..Default::default()
+///     })
+///     .with_known_network(b"Office", None)
+///     .with_secret(b"Attic", b"secret");
+///
+/// let auth = Auth::Psk { force_passwd: false, bssid: Some(String::from("aa:bb:cc:dd:ee:02")) };
+/// let mut out = Vec::new();
+/// let report = wl::connect_with(&fake, &mut out, &mut Vec::new(), &mut &b""[..], None, auth, OutputFormat::Json).unwrap();
+///
+/// assert_eq!(Some("AA:BB:CC:DD:EE:02"), fake.pinned_bssid(b"Office").as_deref());
+/// assert_eq!(b"{\"ssid\":\"Office\",\"bssid\":\"AA:BB:CC:DD:EE:02\",\"connected\":true}\n".as_slice(), out);
+///
+/// let auth = Auth::Hidden { security: None };
+/// let mut input = &b"Attic\nwpa-psk\nsecret\n"[..];
+/// let report = wl::connect_with(&fake, &mut Vec::new(), &mut Vec::new(), &mut input, None, auth, OutputFormat::Json).unwrap();
+///
+/// assert_eq!(b"Attic".as_slice(), report.ssid);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`Auth`]: crate::Auth
+/// [`Auth::Psk`]: crate::Auth::Psk
+/// [`Auth::Hidden`]: crate::Auth::Hidden
+/// [`io::Write`]: std::io::Write
+/// [`io::BufRead`]: std::io::BufRead
+/// [`connect`]: crate::connect
+/// [`ConnectReport`]: crate::ConnectReport
+/// [`Error::UserAborted`]: crate::Error::UserAborted
+/// [`Error::Io`]: crate::Error::Io
+pub fn connect_with(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    prompt: &mut impl io::Write,
+    input: &mut impl io::BufRead,
+    ssid: Option<Vec<u8>>,
+    auth: Auth,
+    output_format: OutputFormat,
+) -> Result<ConnectReport, crate::Error> {
+    let auth = auth.normalize()?;
+    let report = connect_to(process, prompt, input, ssid, auth, read_visible_passwd)?;
+
+    write_report(f, &report, output_format)?;
+
+    Ok(report)
+}
+
+/// Reads the password of the given network name from the given [`PasswdSource`], the same way [`connect`] does.
+///
+/// This is meant for the commands other than `connect` that need a password, such as `hotspot start`.
+/// The prompt of [`PasswdSource::Prompt`] is written to stdout when `output_format` is [`OutputFormat::Text`], and to stderr otherwise.
+/// The surrounding whitespace of the password is trimmed.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Connect`] with [`ConnectError::CannotReadPasswdFile`] or [`ConnectError::CannotReadPasswdEnv`] if the source cannot be read,
+/// and [`ConnectError::CannotReadPasswd`] if the password cannot be read from the terminal or stdin.
+/// It returns [`Error::UserAborted`] if stdin is closed before a password is read.
+///
+/// # Examples
+///
+/// ```
+/// use wl::{PasswdSource, api::OutputFormat};
+///
+/// // SAFETY: The doctest is single threaded.
+/// unsafe { std::env::set_var("HOTSPOT_PASSWD", "password\n") };
+///
+/// let passwd = wl::read_passwd(b"Field", PasswdSource::Env(String::from("HOTSPOT_PASSWD")), OutputFormat::Text).unwrap();
+///
+/// assert_eq!(b"password".as_slice(), passwd);
+/// ```
+///
+/// [`PasswdSource`]: crate::PasswdSource
+/// [`PasswdSource::Prompt`]: crate::PasswdSource::Prompt
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`connect`]: crate::connect
+/// [`Error::Connect`]: crate::Error::Connect
+/// [`Error::UserAborted`]: crate::Error::UserAborted
+/// [`ConnectError::CannotReadPasswd`]: crate::ConnectError::CannotReadPasswd
+/// [`ConnectError::CannotReadPasswdFile`]: crate::ConnectError::CannotReadPasswdFile
+/// [`ConnectError::CannotReadPasswdEnv`]: crate::ConnectError::CannotReadPasswdEnv
+pub fn read_passwd(
+    name: &[u8],
+    passwd_source: PasswdSource,
+    output_format: OutputFormat,
+) -> Result<Vec<u8>, crate::Error> {
+    let passwd = Passwd::resolve(passwd_source)?;
+    let mut stdin = io::stdin().lock();
+
+    match output_format {
+        OutputFormat::Text => ask_passwd(
+            &mut io::stdout(),
+            &mut stdin,
+            |prompt, input, question| passwd.read(prompt, input, question),
+            name,
+        ),
+        _ => ask_passwd(
+            &mut io::stderr(),
+            &mut stdin,
+            |prompt, input, question| passwd.read(prompt, input, question),
+            name,
+        ),
+    }
+}
+
+/// The resolved [`PasswdSource`] of [`connect_stdin`].
+enum Passwd {
+    /// The password is asked on the terminal.
+    Hidden,
+
+    /// The password is read as a line from stdin.
+    Line,
+
+    /// The password that is read from a file or an environment variable.
+    Value(String),
+}
+
+impl Passwd {
+    fn resolve(source: PasswdSource) -> Result<Self, Error> {
+        match source {
+            PasswdSource::Prompt => Ok(Passwd::Hidden),
+            PasswdSource::Stdin => Ok(Passwd::Line),
+            PasswdSource::File(path) => match fs::read_to_string(&path) {
+                Ok(content) => Ok(Passwd::Value(
+                    content.lines().next().unwrap_or_default().to_string(),
+                )),
+                Err(err) => Err(Error::CannotReadPasswdFile(path, err)),
+            },
+            PasswdSource::Env(name) => match env::var(&name) {
+                Ok(value) => Ok(Passwd::Value(value)),
+                Err(err) => Err(Error::CannotReadPasswdEnv(name, err)),
+            },
+        }
+    }
+
+    fn read<P, I>(
+        &self,
+        prompt: &mut P,
+        input: &mut I,
+        question: &[u8],
+    ) -> Result<Option<String>, io::Error>
+    where
+        P: io::Write + AsFd,
+        I: io::BufRead,
+    {
+        match self {
+            Passwd::Hidden => read_hidden_passwd(prompt, input, question),
+            Passwd::Line => read_passwd_line(input),
+            Passwd::Value(passwd) => Ok(Some(passwd.clone())),
+        }
+    }
+}
+
+fn connect_stdin(
+    process: &impl Wl,
+    ssid: Option<Vec<u8>>,
+    mut auth: Auth,
+    passwd_source: PasswdSource,
+    output_format: OutputFormat,
+) -> Result<(), crate::Error> {
+    let passwd = Passwd::resolve(passwd_source)?;
+    let hidden = matches!(auth, Auth::Hidden { .. });
+    let pinned = matches!(auth, Auth::Psk { bssid: Some(_), .. });
+    let other = matches!(auth, Auth::Psk { .. }).then_some(OTHER_NETWORK);
 
     let ssid = match ssid {
-        Some(v) => Ok(v),
-        None => ask_ssid(&process),
+        None if !hidden && !pinned && termion::is_tty(&io::stdin()) => {
+            let picked = picker::pick("Select the SSID to connect", other, |re_scan| {
+                access_point_entries(process, re_scan)
+            })?;
+
+            match picked {
+                Selection::Ssid(ssid) => Some(ssid),
+                Selection::Other => {
+                    auth = Auth::Hidden { security: None };
+                    None
+                }
+            }
+        }
+        ssid => ssid,
+    };
+
+    let mut stdin = io::stdin().lock();
+
+    let report = match output_format {
+        OutputFormat::Text => connect_to(
+            process,
+            &mut io::stdout(),
+            &mut stdin,
+            ssid,
+            auth,
+            |prompt, input, question| passwd.read(prompt, input, question),
+        ),
+        _ => connect_to(
+            process,
+            &mut io::stderr(),
+            &mut stdin,
+            ssid,
+            auth,
+            |prompt, input, question| passwd.read(prompt, input, question),
+        ),
+    }?;
+
+    write_report(&mut io::stdout(), &report, output_format)?;
+
+    Ok(())
+}
+
+fn write_report(
+    f: &mut impl io::Write,
+    report: &ConnectReport,
+    output_format: OutputFormat,
+) -> Result<(), io::Error> {
+    match output_format {
+        OutputFormat::Text => write_bytes(f, &report.message),
+        _ => output::write_value(f, report),
+    }
+}
+
+fn read_visible_passwd(
+    prompt: &mut impl io::Write,
+    input: &mut impl io::BufRead,
+    question: &[u8],
+) -> Result<Option<String>, io::Error> {
+    write_bytes(prompt, question)?;
+    read_passwd_line(input)
+}
+
+fn read_hidden_passwd(
+    prompt: &mut (impl io::Write + AsFd),
+    input: &mut impl io::Read,
+    question: &[u8],
+) -> Result<Option<String>, io::Error> {
+    write_bytes(prompt, question)?;
+    input.read_passwd(prompt)
+}
+
+fn read_passwd_line(input: &mut impl io::BufRead) -> Result<Option<String>, io::Error> {
+    let mut passwd = String::new();
+    match input.read_line(&mut passwd)? {
+        0 => Ok(None),
+        _ => Ok(Some(passwd)),
+    }
+}
+
+fn connect_to<P, I>(
+    process: &impl Wl,
+    prompt: &mut P,
+    input: &mut I,
+    ssid: Option<Vec<u8>>,
+    auth: Auth,
+    read_passwd: impl Fn(&mut P, &mut I, &[u8]) -> Result<Option<String>, io::Error>,
+) -> Result<ConnectReport, crate::Error>
+where
+    P: io::Write,
+    I: io::BufRead,
+{
+    let ssid = match (ssid, &auth) {
+        (Some(v), _) => Ok(v),
+        (None, Auth::Hidden { .. }) => ask_hidden_ssid(prompt, input),
+        (
+            None,
+            Auth::Psk {
+                bssid: Some(bssid), ..
+            },
+        ) => bssid_ssid(process, bssid),
+        (None, _) => ask_ssid(process, prompt, input),
     }?;
 
     let is_known_ssid = process.is_known_ssid(&ssid)?;
+    let mut pinned_bssid = None;
 
-    let password = match force_passwd {
-        true => get_ssid_password(&ssid),
-        false => {
-            if is_known_ssid {
-                Ok(None)
-            } else {
-                get_ssid_password(&ssid)
+    let message = match auth {
+        Auth::Psk {
+            force_passwd,
+            bssid,
+        } => {
+            let password = match force_passwd || !is_known_ssid {
+                true => Some(ask_passwd(prompt, input, &read_passwd, &ssid)?),
+                false => None,
+            };
+
+            let message =
+                process.connect(&ssid, password.as_deref(), bssid.as_deref(), is_known_ssid)?;
+            pinned_bssid = bssid;
+
+            message
+        }
+        Auth::Enterprise(mut credentials) => {
+            if credentials.method.is_tunneled() && credentials.passwd.is_none() {
+                credentials.passwd = Some(ask_passwd(
+                    prompt,
+                    input,
+                    &read_passwd,
+                    &credentials.identity,
+                )?);
             }
+
+            if !credentials.method.is_tunneled() {
+                let missing = match (&credentials.client_cert, &credentials.private_key) {
+                    (None, _) => Some("client certificate"),
+                    (_, None) => Some("private key"),
+                    _ => None,
+                };
+                if let Some(credential) = missing {
+                    Err(Error::MissingEapCredential(credentials.method, credential))?;
+                }
+            }
+
+            // The private key is only used by EAP-TLS, so the password of a tunneled method is never taken as the one of the key.
+            if let (false, Some(key), None) = (
+                credentials.method.is_tunneled(),
+                &credentials.private_key,
+                &credentials.private_key_passwd,
+            ) {
+                // An empty answer means that the private key is not encrypted.
+                let name = [b"the private key ", key.as_os_str().as_bytes()].concat();
+                let passwd = ask_passwd(prompt, input, &read_passwd, &name)?;
+                credentials.private_key_passwd = (!passwd.is_empty()).then_some(passwd);
+            }
+
+            for path in [
+                &mut credentials.ca_cert,
+                &mut credentials.client_cert,
+                &mut credentials.private_key,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                *path = std::path::absolute(&*path).map_err(crate::Error::Io)?;
+            }
+
+            process.connect_enterprise(&ssid, &credentials, is_known_ssid)?
         }
-    }?;
+        Auth::Hidden { security } => {
+            let security = match security {
+                Some(security) => security,
+                None => ask_security(prompt, input)?,
+            };
 
-    let result = process.connect(&ssid, password.as_deref(), is_known_ssid)?;
+            let password = match security.is_secured() {
+                true => Some(ask_passwd(prompt, input, &read_passwd, &ssid)?),
+                false => None,
+            };
 
-    let mut out_buf = io::stdout();
-    write_bytes(&mut out_buf, &result)?;
+            process.connect_hidden(&ssid, security, password.as_deref(), is_known_ssid)?
+        }
+    };
 
-    Ok(())
+    Ok(ConnectReport {
+        ssid,
+        bssid: pinned_bssid,
+        connected: true,
+        message,
+    })
 }
 
-fn ask_ssid(process: &impl Wl) -> Result<Vec<u8>, Box<dyn error::Error>> {
-    let scan_args = ScanArgs {
-        min_strength: 0,
-        re_scan: true,
-        columns: None,
-        get_values: Some(String::from("SSID,SIGNAL")),
-    };
-    let scan_result = process.scan(&scan_args)?;
-
-    let separator = process.get_field_separator();
-    let parsed_scan_result = scan_result
-        .split(|b| b == &LINE_FEED)
-        .enumerate()
-        .filter_map(|(idx, l)| {
-            if l.is_empty() {
-                None
-            } else {
-                let fields = l
-                    .split(|b| b == &separator)
-                    .filter(|b| !b.is_empty())
-                    .collect::<Vec<&[u8]>>();
-
-                Some((idx, fields[0], fields[1]))
-            }
-        });
+fn ask_passwd<P, I>(
+    prompt: &mut P,
+    input: &mut I,
+    read_passwd: impl Fn(&mut P, &mut I, &[u8]) -> Result<Option<String>, io::Error>,
+    name: &[u8],
+) -> Result<Vec<u8>, crate::Error>
+where
+    P: io::Write,
+{
+    let question = [b"Enter the password for ", name, b": "].concat();
+
+    let passwd = read_passwd(prompt, input, &question)
+        .map_err(Error::CannotReadPasswd)?
+        .ok_or(crate::Error::UserAborted)?;
+
+    Ok(String::from(passwd.trim()).into_bytes())
+}
+
+fn normalize_bssid(bssid: &str) -> Result<String, Error> {
+    match parse_bssid(bssid) {
+        Some(_) => Ok(bssid.to_uppercase()),
+        None => Err(Error::InvalidBssid(bssid.to_string())),
+    }
+}
+
+fn bssid_ssid(process: &impl Wl, bssid: &str) -> Result<Vec<u8>, crate::Error> {
+    let ssid = process
+        .get_access_points(true)?
+        .into_iter()
+        .find(|ap| ap.bssid.eq_ignore_ascii_case(bssid) && !ap.ssid.is_empty())
+        .map(|ap| ap.ssid)
+        .ok_or_else(|| Error::BssidNotFound(bssid.to_string()))?;
+
+    Ok(ssid)
+}
+
+fn ask_hidden_ssid(
+    prompt: &mut impl io::Write,
+    input: &mut impl io::BufRead,
+) -> Result<Vec<u8>, crate::Error> {
+    write_bytes(prompt, b"Enter the SSID of the hidden network: ")?;
+
+    let mut answer = String::new();
+    let read = input
+        .read_line(&mut answer)
+        .map_err(Error::CannotReadSSID)?;
+
+    if read == 0 {
+        return Err(crate::Error::UserAborted);
+    }
+
+    // Only the line ending is removed, since the spaces are valid in an SSID.
+    Ok(answer.trim_end_matches(['\r', '\n']).as_bytes().to_vec())
+}
+
+fn ask_security(
+    prompt: &mut impl io::Write,
+    input: &mut impl io::BufRead,
+) -> Result<Security, crate::Error> {
+    write_bytes(
+        prompt,
+        b"Select the security of the hidden network (open, wpa-psk, sae): ",
+    )?;
+
+    let mut answer = String::new();
+    if input.read_line(&mut answer)? == 0 {
+        return Err(crate::Error::UserAborted);
+    }
+
+    let answer = answer.trim();
+    let security =
+        Security::from_str(answer, true).map_err(|_| Error::InvalidSecurity(answer.to_string()))?;
+
+    Ok(security)
+}
+
+fn access_point_entries(process: &impl Wl, re_scan: bool) -> Result<Vec<Entry>, crate::Error> {
+    let aps = process.get_access_points(re_scan)?;
+    let known = process
+        .get_networks(false)?
+        .into_iter()
+        .map(|n| n.ssid)
+        .collect::<Vec<Vec<u8>>>();
+
+    Ok(Entry::from_access_points(aps, &known))
+}
+
+fn ask_ssid(
+    process: &impl Wl,
+    prompt: &mut impl io::Write,
+    input: &mut impl io::BufRead,
+) -> Result<Vec<u8>, crate::Error> {
+    let aps = process.get_access_points(true)?;
 
     let mut ssids = HashMap::new();
     let mut ssid_lines = Vec::new();
-    for (idx, ssid, signal) in parsed_scan_result.into_iter() {
-        ssids.insert(idx, ssid);
-
+    for (idx, ap) in aps.into_iter().filter(|ap| !ap.ssid.is_empty()).enumerate() {
         let line = [
             b"(",
             idx.to_string().as_bytes(),
             b") ",
-            ssid,
+            &ap.ssid[..],
             b" (sig: ",
-            signal,
+            ap.signal.to_string().as_bytes(),
             b")\n",
         ]
         .concat();
         ssid_lines.push(line);
+
+        ssids.insert(idx, ap.ssid);
     }
 
-    let prompt = [
+    let prompt_buf = [
         &ssid_lines.into_iter().flatten().collect::<Vec<u8>>()[..],
         b"Select the SSID to connect: ",
     ]
     .concat();
 
-    write_bytes(&mut io::stdout(), &prompt)?;
+    write_bytes(prompt, &prompt_buf)?;
 
     let mut answer = String::new();
-    io::stdin()
+    let read = input
         .read_line(&mut answer)
-        .map_err(|err| Error::CannotReadSSID(Some(err.to_string())))?;
-
-    let answer = answer
-        .trim()
-        .parse::<usize>()
-        .map_err(|err| Error::CannotReadSSID(Some(err.to_string())))?;
+        .map_err(Error::CannotReadSSID)?;
 
-    let ssid = ssids.remove(&answer).ok_or(Error::CannotReadSSID(None))?;
-
-    Ok(ssid.to_vec())
-}
-
-fn get_ssid_password(ssid: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn error::Error>> {
-    let mut stdin = io::stdin();
-    let mut writer = io::stdout();
-
-    let out_buf = [b"Enter the password for ", ssid, b": "].concat();
-    write_bytes(&mut writer, &out_buf)?;
+    if read == 0 {
+        return Err(crate::Error::UserAborted);
+    }
 
-    let passwd = stdin
-        .read_passwd(&mut writer)
-        .map_err(Error::CannotReadPasswd)?;
+    let answer = answer.trim();
+    let ssid = answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|idx| ssids.remove(&idx))
+        .ok_or_else(|| Error::InvalidSSIDSelection(answer.to_string()))?;
 
-    Ok(passwd.map(|pw| String::from(pw.trim()).into_bytes()))
+    Ok(ssid)
 }
@@ -4,45 +4,88 @@ use std::{
     io::{self},
 };
 
+use serde::Serialize;
+
 use crate::{
-    adapter::{self, CARRIAGE_RETURN, LINE_FEED, LOOPBACK_INTERFACE_NAME, Wl},
+    adapter::{LOOPBACK_INTERFACE_NAME, Wl},
+    api::OutputFormat,
+    output,
+    picker::{self, Entry, Selection},
     write_bytes,
 };
 
+/// Represents the result of [`disconnect_with`].
+///
+/// [`disconnect_with`]: crate::disconnect_with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DisconnectReport {
+    /// The SSID (name) of the network that is disconnected.
+    #[serde(serialize_with = "output::serialize_ssid")]
+    pub ssid: Vec<u8>,
+
+    /// Whether the network is disconnected.
+    pub disconnected: bool,
+
+    /// Whether the network is removed from the known network list.
+    pub forgotten: bool,
+
+    /// The result of the disconnect that is provided by the [`Wl`] implementation.
+    ///
+    /// It is only used in [`OutputFormat::Text`].
+    ///
+    /// [`Wl`]: crate::Wl
+    /// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+    #[serde(skip)]
+    pub message: Vec<u8>,
+}
+
 /// Defines [`Error`] variants that may return during a disconnect process.
 ///
 /// [`Error`]: std::error::Error
 #[derive(Debug)]
 pub enum Error {
-    /// Represents a read faliure whilst trying to obtain the SSID.
+    /// Represents a read failure whilst trying to obtain the active SSID selection.
     ///
-    /// This read failure can happen in three ways:
+    /// This failure can only come from the underlying stream (e.g. stdin, or the given [`io::BufRead`]).
+    /// It holds the details of the underlying [`io::Error`].
     ///
-    /// 1 - From the underlying stream (stdin).
-    /// 2 - By providing an input in the wrong format.
-    /// 3 - By providing an SSID that does not exist on the given list of SSID's.
-    /// Based on the error case, it holds:
+    /// [`io::Error`]: std::io::Error
+    /// [`io::BufRead`]: std::io::BufRead
+    CannotReadActiveSSID(io::Error),
+
+    /// Represents an active SSID selection that is not a number, or does not exist on the given list of SSID's.
     ///
-    /// - [`io::Error`] coming from the stream (stdin).
-    /// - The invalid SSID format.
-    /// - None.
-    InvalidActiveSSID(Option<String>),
+    /// It holds the invalid SSID selection.
+    InvalidActiveSSID(String),
 }
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Error::InvalidActiveSSID(err) => match err {
-                Some(err) => write!(f, "unable to get the active SSID: {}", err),
-                None => write!(f, "unable to get the active SSID"),
-            },
+            Error::CannotReadActiveSSID(err) => {
+                write!(f, "unable to get the active SSID: {}", err)
+            }
+            Error::InvalidActiveSSID(selection) => write!(
+                f,
+                "the given SSID selection '{}' does not exist on the list",
+                selection
+            ),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::CannotReadActiveSSID(err) => Some(err),
+            Error::InvalidActiveSSID(_) => None,
         }
     }
 }
-impl error::Error for Error {}
 
-/// Disconnects from a WiFi network by using a [`Wl`] implementation.
+/// Disconnects from a WiFi network by using the given [`Wl`] implementation.
 ///
 /// If an SSID is not given by the caller, then `disconnect` shows a list of active networks to choose from.
+/// When stdin is a terminal, the list is shown on the full-screen [`picker`].
+/// Otherwise, the list is numbered and the index of the network is read from stdin.
 ///
 /// If `forget` is set to `true`, then the selected SSID is disconnected and removed from the known network list.
 /// If `forget` is set to `false`, then the selected SSID is only disconnected.
@@ -50,48 +93,194 @@ impl error::Error for Error {}
 ///
 /// The SSID selection is retrieved from stdin, and the result of the disconnect is written to stdout.
 ///
+/// In [`OutputFormat::Text`], the result is the one that is provided by the [`Wl`] implementation.
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], the result is a single JSON object, and the prompts are written to stderr to keep stdout machine-readable:
+///
+/// ```json
+/// {"ssid":"SSID1","disconnected":true,"forgotten":false}
+/// ```
+///
 /// # Panics
 ///
 /// This function does not panic.
 ///
 /// # Errors
 ///
-/// This function returns [`Error::InvalidActiveSSID`] if the provided SSID cannot be read.
+/// This function returns [`Error::Disconnect`] with [`DisconnectError::CannotReadActiveSSID`] or [`DisconnectError::InvalidActiveSSID`] if the provided SSID cannot be read or does not exist.
+/// If the prompt is aborted (e.g. Ctrl-D), then it returns [`Error::UserAborted`].
 ///
-/// This function can also return an [`NetworkAdapterError`] when the underlying [`Wl`] implementation fails or [`io::Error`] when the successful disconnection result cannot be written on the stdout stream.
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the successful disconnection result cannot be written on the stdout stream.
 ///
 /// [`Wl`]: crate::Wl
-/// [`Error::InvalidActiveSSID`]: crate::DisconnectError::InvalidActiveSSID
-/// [`NetworkAdapterError`]: crate::adapter::Error
-/// [`io::Error`]: std::io::Error
-pub fn disconnect(ssid: Option<Vec<u8>>, forget: bool) -> Result<(), Box<dyn error::Error>> {
+/// [`picker`]: crate::picker
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Disconnect`]: crate::Error::Disconnect
+/// [`DisconnectError::CannotReadActiveSSID`]: crate::DisconnectError::CannotReadActiveSSID
+/// [`DisconnectError::InvalidActiveSSID`]: crate::DisconnectError::InvalidActiveSSID
+/// [`Error::UserAborted`]: crate::Error::UserAborted
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn disconnect(
+    process: &impl Wl,
+    ssid: Option<Vec<u8>>,
+    forget: bool,
+    output_format: OutputFormat,
+) -> Result<(), crate::Error> {
     let ssid = match ssid {
-        Some(val) => val,
-        None => select_active_ssid()?,
+        None if termion::is_tty(&io::stdin()) => {
+            let picked = picker::pick("Select the SSID to disconnect", None, |re_scan| {
+                active_entries(process, re_scan)
+            })?;
+
+            match picked {
+                Selection::Ssid(ssid) => Some(ssid),
+                // The picker of disconnect does not have an "other" row.
+                Selection::Other => None,
+            }
+        }
+        ssid => ssid,
     };
 
-    let process = adapter::new();
-    let result = process.disconnect(&ssid, forget)?;
+    let mut stdout = io::stdout();
+    let mut stdin = io::stdin().lock();
 
-    let mut out_buf = io::stdout();
-    write_bytes(&mut out_buf, &result)?;
+    match output_format {
+        OutputFormat::Text => disconnect_with(
+            process,
+            &mut stdout,
+            &mut io::stdout(),
+            &mut stdin,
+            ssid,
+            forget,
+            output_format,
+        ),
+        _ => disconnect_with(
+            process,
+            &mut stdout,
+            &mut io::stderr(),
+            &mut stdin,
+            ssid,
+            forget,
+            output_format,
+        ),
+    }?;
 
     Ok(())
 }
 
-fn select_active_ssid() -> Result<Vec<u8>, Box<dyn error::Error>> {
-    let process = adapter::new();
+/// Disconnects from a WiFi network by using the given [`Wl`] implementation, the given [`io::Write`] for the output and prompts, and the given [`io::BufRead`] for the answers.
+///
+/// The behavior and output are the same as [`disconnect`], except:
+///
+/// - The prompts are written to `prompt` instead of stdout/stderr.
+/// - The SSID selection is read from `input` instead of stdin, and it is always selected from a numbered list.
+/// - The result of the disconnect is written to `f` and also returned as a [`DisconnectReport`].
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Disconnect`] with [`DisconnectError::CannotReadActiveSSID`] or [`DisconnectError::InvalidActiveSSID`] if the provided SSID cannot be read or does not exist.
+/// If the prompt is aborted (e.g. Ctrl-D), then it returns [`Error::UserAborted`].
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the prompts or the disconnection result cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new().with_active_network(b"Home");
+///
+/// let (mut out, mut prompt) = (Vec::new(), Vec::new());
+/// let mut input = &b"0\n"[..];
+///
+/// let report = wl::disconnect_with(&fake, &mut out, &mut prompt, &mut input, None, false, OutputFormat::Json).unwrap();
+///
+/// assert!(report.disconnected);
+/// assert_eq!(b"(0) Home\nSelect the SSID to disconnect: ".as_slice(), prompt);
+/// assert_eq!(b"{\"ssid\":\"Home\",\"disconnected\":true,\"forgotten\":false}\n".as_slice(), out);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`io::BufRead`]: std::io::BufRead
+/// [`disconnect`]: crate::disconnect
+/// [`DisconnectReport`]: crate::DisconnectReport
+/// [`Error::Disconnect`]: crate::Error::Disconnect
+/// [`DisconnectError::CannotReadActiveSSID`]: crate::DisconnectError::CannotReadActiveSSID
+/// [`DisconnectError::InvalidActiveSSID`]: crate::DisconnectError::InvalidActiveSSID
+/// [`Error::UserAborted`]: crate::Error::UserAborted
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn disconnect_with(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    prompt: &mut impl io::Write,
+    input: &mut impl io::BufRead,
+    ssid: Option<Vec<u8>>,
+    forget: bool,
+    output_format: OutputFormat,
+) -> Result<DisconnectReport, crate::Error> {
+    let ssid = match ssid {
+        Some(val) => val,
+        None => select_active_ssid(process, prompt, input)?,
+    };
 
-    let active_ssids = process.get_active_ssids()?;
-    let active_ssids_iter = active_ssids.split(|b| b == &LINE_FEED).filter_map(|s| {
-        let line = s.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(s);
+    let message = process.disconnect(&ssid, forget)?;
 
-        if line.is_empty() || line == LOOPBACK_INTERFACE_NAME {
-            None
-        } else {
-            Some(line)
-        }
-    });
+    let report = DisconnectReport {
+        ssid,
+        disconnected: true,
+        forgotten: forget,
+        message,
+    };
+
+    match output_format {
+        OutputFormat::Text => write_bytes(f, &report.message)?,
+        _ => output::write_value(f, &report)?,
+    }
+
+    Ok(report)
+}
+
+fn active_entries(process: &impl Wl, re_scan: bool) -> Result<Vec<Entry>, crate::Error> {
+    // The signal strength is only decorative, so a failing scan does not prevent the disconnect.
+    let aps = process.get_access_points(re_scan).unwrap_or_default();
+
+    let entries = process
+        .get_networks(true)?
+        .into_iter()
+        .filter(|n| !n.ssid.is_empty() && n.ssid != LOOPBACK_INTERFACE_NAME)
+        .map(|n| {
+            let ap = aps.iter().find(|ap| ap.in_use && ap.ssid == n.ssid);
+            Entry {
+                signal: ap.map(|ap| ap.signal),
+                secured: ap.is_some_and(|ap| ap.is_secured()),
+                known: true,
+                in_use: true,
+                ssid: n.ssid,
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+fn select_active_ssid(
+    process: &impl Wl,
+    prompt: &mut impl io::Write,
+    input: &mut impl io::BufRead,
+) -> Result<Vec<u8>, crate::Error> {
+    let active_ssids = process.get_networks(true)?;
+    let active_ssids_iter = active_ssids
+        .iter()
+        .map(|n| &n.ssid[..])
+        .filter(|ssid| !ssid.is_empty() && ssid != &LOOPBACK_INTERFACE_NAME);
 
     let mut ssid_lines = Vec::with_capacity(30);
     let mut ssids = HashMap::new();
@@ -109,24 +298,24 @@ fn select_active_ssid() -> Result<Vec<u8>, Box<dyn error::Error>> {
         ssids.insert(idx, ssid);
     }
 
-    let mut stdout = io::stdout();
-
     let out_buf = &[&ssid_lines[..], b"Select the SSID to disconnect: "].concat();
-    write_bytes(&mut stdout, out_buf)?;
+    write_bytes(prompt, out_buf)?;
 
     let mut answer_buf = String::new();
-    io::stdin()
+    let read = input
         .read_line(&mut answer_buf)
-        .map_err(|err| Error::InvalidActiveSSID(Some(err.to_string())))?;
+        .map_err(Error::CannotReadActiveSSID)?;
 
-    let answer = answer_buf
-        .trim()
-        .parse::<usize>()
-        .map_err(|err| Error::InvalidActiveSSID(Some(err.to_string())))?;
+    if read == 0 {
+        return Err(crate::Error::UserAborted);
+    }
 
-    let ssid = ssids
-        .remove(&answer)
-        .ok_or(Error::InvalidActiveSSID(None))?;
+    let answer = answer_buf.trim();
+    let ssid = answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|idx| ssids.remove(&idx))
+        .ok_or_else(|| Error::InvalidActiveSSID(answer.to_string()))?;
 
     Ok(ssid.to_vec())
 }
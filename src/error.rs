@@ -0,0 +1,417 @@
+use std::{error, fmt, io};
+
+use crate::{
+    BackupError, ConfigError, ConnectError, DisconnectError, FormatError, HotspotError,
+    ImportError, NetworkAdapterError, PriorityError, ProfileError, ScanError, ShareError,
+    UNKNOWN_SSID_EXIT_CODE, WatchError,
+};
+
+/// Represents the categories of [`Error`].
+///
+/// Each category has a distinct exit code, which is stable across the network backends and releases:
+///
+/// | Kind                 | Exit code |
+/// |----------------------|-----------|
+/// | [`Other`]            | 1         |
+/// | [`InvalidInput`]     | 2         |
+/// | [`AuthFailed`]       | 3         |
+/// | [`SsidNotFound`]     | 4         |
+/// | [`Timeout`]          | 5         |
+/// | [`PermissionDenied`] | 6         |
+/// | [`Unsupported`]      | 69        |
+/// | [`Io`]               | 74        |
+/// | [`BackendMissing`]   | 127       |
+/// | [`UserAborted`]      | 130       |
+///
+/// [`Error`]: crate::Error
+/// [`Other`]: crate::ErrorKind::Other
+/// [`InvalidInput`]: crate::ErrorKind::InvalidInput
+/// [`AuthFailed`]: crate::ErrorKind::AuthFailed
+/// [`SsidNotFound`]: crate::ErrorKind::SsidNotFound
+/// [`Timeout`]: crate::ErrorKind::Timeout
+/// [`PermissionDenied`]: crate::ErrorKind::PermissionDenied
+/// [`Unsupported`]: crate::ErrorKind::Unsupported
+/// [`Io`]: crate::ErrorKind::Io
+/// [`BackendMissing`]: crate::ErrorKind::BackendMissing
+/// [`UserAborted`]: crate::ErrorKind::UserAborted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Represents a failure that does not fit into the other categories.
+    Other,
+
+    /// Represents an input that is rejected by `wl` or the network backend (e.g. an invalid SSID selection or scan field).
+    InvalidInput,
+
+    /// Represents a connection attempt that is rejected due to a wrong or missing password.
+    AuthFailed,
+
+    /// Represents an SSID that is not visible, known or active on the host.
+    SsidNotFound,
+
+    /// Represents an operation that is not completed in time by the network backend.
+    Timeout,
+
+    /// Represents an operation that is not authorized by the network backend.
+    PermissionDenied,
+
+    /// Represents a functionality that is not supported by the network backend.
+    Unsupported,
+
+    /// Represents a read or write failure of `wl` itself (e.g. stdin, stdout).
+    Io,
+
+    /// Represents a network backend that is not available or not running on the host.
+    BackendMissing,
+
+    /// Represents a prompt that is aborted by the user (e.g. Ctrl-C, Ctrl-D).
+    UserAborted,
+}
+
+impl ErrorKind {
+    /// Provides the exit code of the category.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            ErrorKind::Other => 1,
+            ErrorKind::InvalidInput => 2,
+            ErrorKind::AuthFailed => 3,
+            ErrorKind::SsidNotFound => 4,
+            ErrorKind::Timeout => 5,
+            ErrorKind::PermissionDenied => 6,
+            ErrorKind::Unsupported => 69,
+            ErrorKind::Io => 74,
+            ErrorKind::BackendMissing => 127,
+            ErrorKind::UserAborted => 130,
+        }
+    }
+}
+
+/// The crate-level Error that is returned from the public functions of `wl`.
+///
+/// It wraps the errors of each functionality without changing their messages, and categorizes them into an [`ErrorKind`].
+/// The wrapped error can be reached by matching on the variants, and its underlying cause by [`Error::source`].
+///
+/// The failures of the network backends are categorized by their messages and exit codes.
+/// The messages are checked first, since they are more specific than the exit codes (e.g. `nmcli` uses the same exit code for a wrong password and a failed activation).
+///
+/// [`ErrorKind`]: crate::ErrorKind
+/// [`Error::source`]: std::error::Error::source
+#[derive(Debug)]
+pub enum Error {
+    /// Represents a failure of the network backend.
+    ///
+    /// It holds the category of the failure and the [`NetworkAdapterError`] itself.
+    ///
+    /// [`NetworkAdapterError`]: crate::adapter::Error
+    Backend(ErrorKind, NetworkAdapterError),
+
+    /// Represents a failure of [`connect`].
+    ///
+    /// [`connect`]: crate::connect
+    Connect(ConnectError),
+
+    /// Represents a failure of [`disconnect`].
+    ///
+    /// [`disconnect`]: crate::disconnect
+    Disconnect(DisconnectError),
+
+    /// Represents a failure of [`scan`].
+    ///
+    /// [`scan`]: crate::scan
+    Scan(ScanError),
+
+    /// Represents a failure of [`hotspot_start`].
+    ///
+    /// [`hotspot_start`]: crate::hotspot_start
+    Hotspot(HotspotError),
+
+    /// Represents a failure of [`share`].
+    ///
+    /// [`share`]: crate::share
+    Share(ShareError),
+
+    /// Represents an invalid argument of [`profile_edit`] and [`profile_rename`].
+    ///
+    /// [`profile_edit`]: crate::profile_edit
+    /// [`profile_rename`]: crate::profile_rename
+    Profile(ProfileError),
+
+    /// Represents an unknown SSID that is given to [`priority_up`] and [`priority_down`].
+    ///
+    /// [`priority_up`]: crate::priority_up
+    /// [`priority_down`]: crate::priority_down
+    Priority(PriorityError),
+
+    /// Represents an invalid file of [`import`].
+    ///
+    /// [`import`]: crate::import
+    Import(ImportError),
+
+    /// Represents an invalid passphrase or archive of [`backup`] and [`restore`].
+    ///
+    /// [`backup`]: crate::backup
+    /// [`restore`]: crate::restore
+    Backup(BackupError),
+
+    /// Represents an invalid configuration file of [`Config::load`].
+    ///
+    /// [`Config::load`]: crate::Config::load
+    Config(ConfigError),
+
+    /// Represents an invalid argument of [`watch`].
+    ///
+    /// [`watch`]: crate::watch
+    Watch(WatchError),
+
+    /// Represents an invalid field of [`format`].
+    ///
+    /// [`format`]: crate::format
+    Format(FormatError),
+
+    /// Represents a read or write failure of `wl` itself.
+    Io(io::Error),
+
+    /// Represents a prompt that is aborted by the user.
+    UserAborted,
+}
+
+impl Error {
+    /// Provides the category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Backend(kind, _) => *kind,
+            Error::Connect(err) => match err {
+                ConnectError::CannotReadPasswd(_)
+                | ConnectError::CannotReadPasswdFile(..)
+                | ConnectError::CannotReadSSID(_) => ErrorKind::Io,
+                ConnectError::CannotReadPasswdEnv(..)
+                | ConnectError::InvalidSSIDSelection(_)
+                | ConnectError::MissingEapCredential(..)
+                | ConnectError::InvalidSecurity(_)
+                | ConnectError::InvalidBssid(_) => ErrorKind::InvalidInput,
+                ConnectError::BssidNotFound(_) => ErrorKind::SsidNotFound,
+            },
+            Error::Disconnect(err) => match err {
+                DisconnectError::CannotReadActiveSSID(_) => ErrorKind::Io,
+                DisconnectError::InvalidActiveSSID(_) => ErrorKind::InvalidInput,
+            },
+            Error::Priority(_) => ErrorKind::SsidNotFound,
+            Error::Scan(_)
+            | Error::Hotspot(_)
+            | Error::Share(_)
+            | Error::Profile(_)
+            | Error::Import(_)
+            | Error::Backup(_)
+            | Error::Config(_)
+            | Error::Watch(_)
+            | Error::Format(_) => ErrorKind::InvalidInput,
+            Error::Io(_) => ErrorKind::Io,
+            Error::UserAborted => ErrorKind::UserAborted,
+        }
+    }
+
+    /// Provides the exit code of the error, which is determined by its [`ErrorKind`].
+    ///
+    /// [`ErrorKind`]: crate::ErrorKind
+    pub fn exit_code(&self) -> u8 {
+        self.kind().exit_code()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Backend(_, err) => err.fmt(f),
+            Error::Connect(err) => err.fmt(f),
+            Error::Disconnect(err) => err.fmt(f),
+            Error::Scan(err) => err.fmt(f),
+            Error::Hotspot(err) => err.fmt(f),
+            Error::Share(err) => err.fmt(f),
+            Error::Profile(err) => err.fmt(f),
+            Error::Priority(err) => err.fmt(f),
+            Error::Import(err) => err.fmt(f),
+            Error::Backup(err) => err.fmt(f),
+            Error::Config(err) => err.fmt(f),
+            Error::Watch(err) => err.fmt(f),
+            Error::Format(err) => err.fmt(f),
+            Error::Io(err) => err.fmt(f),
+            Error::UserAborted => write!(f, "aborted by the user"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Backend(_, err) => err.source(),
+            Error::Connect(err) => err.source(),
+            Error::Disconnect(err) => err.source(),
+            Error::Scan(err) => err.source(),
+            Error::Hotspot(err) => err.source(),
+            Error::Share(err) => err.source(),
+            Error::Profile(err) => err.source(),
+            Error::Priority(err) => err.source(),
+            Error::Import(err) => err.source(),
+            Error::Backup(err) => err.source(),
+            Error::Config(err) => err.source(),
+            Error::Watch(err) => err.source(),
+            Error::Format(err) => err.source(),
+            Error::Io(err) => err.source(),
+            Error::UserAborted => None,
+        }
+    }
+}
+
+impl From<NetworkAdapterError> for Error {
+    fn from(err: NetworkAdapterError) -> Self {
+        Error::Backend(classify(&err), err)
+    }
+}
+
+impl From<ConnectError> for Error {
+    fn from(err: ConnectError) -> Self {
+        Error::Connect(err)
+    }
+}
+
+impl From<DisconnectError> for Error {
+    fn from(err: DisconnectError) -> Self {
+        Error::Disconnect(err)
+    }
+}
+
+impl From<ScanError> for Error {
+    fn from(err: ScanError) -> Self {
+        Error::Scan(err)
+    }
+}
+
+impl From<HotspotError> for Error {
+    fn from(err: HotspotError) -> Self {
+        Error::Hotspot(err)
+    }
+}
+
+impl From<ShareError> for Error {
+    fn from(err: ShareError) -> Self {
+        Error::Share(err)
+    }
+}
+
+impl From<ProfileError> for Error {
+    fn from(err: ProfileError) -> Self {
+        Error::Profile(err)
+    }
+}
+
+impl From<PriorityError> for Error {
+    fn from(err: PriorityError) -> Self {
+        Error::Priority(err)
+    }
+}
+
+impl From<ImportError> for Error {
+    fn from(err: ImportError) -> Self {
+        Error::Import(err)
+    }
+}
+
+impl From<BackupError> for Error {
+    fn from(err: BackupError) -> Self {
+        Error::Backup(err)
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        Error::Config(err)
+    }
+}
+
+impl From<WatchError> for Error {
+    fn from(err: WatchError) -> Self {
+        Error::Watch(err)
+    }
+}
+
+impl From<FormatError> for Error {
+    fn from(err: FormatError) -> Self {
+        Error::Format(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// The messages of the network backends that are used to categorize their failures.
+///
+/// They are matched case-insensitively, in order.
+/// Most of them are the messages of `nmcli`, which are also used by the other network backends wherever possible.
+const MESSAGE_KINDS: [(&str, ErrorKind); 14] = [
+    ("secrets were required", ErrorKind::AuthFailed),
+    ("802-11-wireless-security.psk", ErrorKind::AuthFailed),
+    ("invalid passphrase", ErrorKind::AuthFailed),
+    ("wrong password", ErrorKind::AuthFailed),
+    ("not authorized", ErrorKind::PermissionDenied),
+    ("insufficient privileges", ErrorKind::PermissionDenied),
+    ("permission denied", ErrorKind::PermissionDenied),
+    ("timeout", ErrorKind::Timeout),
+    ("timed out", ErrorKind::Timeout),
+    ("networkmanager is not running", ErrorKind::BackendMissing),
+    ("no network with ssid", ErrorKind::SsidNotFound),
+    ("is not an active connection", ErrorKind::SsidNotFound),
+    ("unknown connection", ErrorKind::SsidNotFound),
+    ("no such connection", ErrorKind::SsidNotFound),
+];
+
+/// Categorizes the given [`NetworkAdapterError`] by its message first, and then by its exit code.
+///
+/// The exit codes follow the ones of `nmcli`, which are also used by the other network backends.
+///
+/// [`NetworkAdapterError`]: crate::adapter::Error
+fn classify(err: &NetworkAdapterError) -> ErrorKind {
+    let (err, ecode) = match err {
+        NetworkAdapterError::NoBackendFound(_) => return ErrorKind::BackendMissing,
+        NetworkAdapterError::CannotGetWiFiStatus((err, ecode))
+        | NetworkAdapterError::CannotToggleWiFi((err, ecode))
+        | NetworkAdapterError::CannotListNetworks((err, ecode))
+        | NetworkAdapterError::CannotGetActiveConnections((err, ecode))
+        | NetworkAdapterError::CannotGetSSIDStatus((err, ecode))
+        | NetworkAdapterError::CannotDisconnect((err, ecode))
+        | NetworkAdapterError::CannotScanWiFi((err, ecode))
+        | NetworkAdapterError::CannotConnect((err, ecode))
+        | NetworkAdapterError::CannotStartHotspot((err, ecode))
+        | NetworkAdapterError::CannotStopHotspot((err, ecode))
+        | NetworkAdapterError::CannotGetHotspot((err, ecode))
+        | NetworkAdapterError::CannotGetPasswd((err, ecode))
+        | NetworkAdapterError::CannotWatch((err, ecode))
+        | NetworkAdapterError::CannotUpdateNetwork((err, ecode))
+        | NetworkAdapterError::CannotGetProfile((err, ecode))
+        | NetworkAdapterError::CannotAddNetwork((err, ecode)) => (err, *ecode),
+    };
+
+    match err.kind() {
+        io::ErrorKind::PermissionDenied => return ErrorKind::PermissionDenied,
+        io::ErrorKind::TimedOut => return ErrorKind::Timeout,
+        io::ErrorKind::Unsupported => return ErrorKind::Unsupported,
+        _ => {}
+    }
+
+    let message = err.to_string().to_lowercase();
+    if let Some((_, kind)) = MESSAGE_KINDS
+        .iter()
+        .find(|(pattern, _)| message.contains(pattern))
+    {
+        return *kind;
+    }
+
+    match ecode {
+        2 => ErrorKind::InvalidInput,
+        3 => ErrorKind::Timeout,
+        8 => ErrorKind::BackendMissing,
+        UNKNOWN_SSID_EXIT_CODE => ErrorKind::SsidNotFound,
+        _ => ErrorKind::Other,
+    }
+}
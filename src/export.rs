@@ -0,0 +1,445 @@
+use std::{error, fmt, io};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ExportedNetwork, NetworkAdapterError, Security,
+    adapter::{HOTSPOT_PROFILE, Wl},
+    api::{ConflictPolicy, ExportFormat, OutputFormat},
+    output, write_bytes,
+};
+
+/// Represents the version of the file format that is written by [`export`].
+///
+/// [`import`] rejects the files of the newer versions.
+///
+/// [`export`]: crate::export
+/// [`import`]: crate::import
+pub const EXPORT_VERSION: u32 = 1;
+
+const TOML_HEADER: &str = "# The known WiFi networks that are exported by `wl export`.\n# Use `wl import` to add them to another host.\n\n";
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ExportFile {
+    pub(crate) version: u32,
+    #[serde(default)]
+    pub(crate) networks: Vec<ExportedNetwork>,
+}
+
+/// Represents the result of [`export`].
+///
+/// [`export`]: crate::export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportReport {
+    /// The known networks that are written in the file.
+    pub networks: Vec<ExportedNetwork>,
+
+    /// The names of the known networks that are skipped, since their security types cannot be imported (e.g. `wpa-eap`, `wep`).
+    pub skipped: Vec<Vec<u8>>,
+}
+
+/// Represents the outcomes of the networks that are imported by [`import`].
+///
+/// [`import`]: crate::import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStatus {
+    /// The network is added as a new known network.
+    Added,
+
+    /// The known network is replaced with the imported one.
+    Replaced,
+
+    /// The network is already known, and it is kept as it is.
+    Skipped,
+
+    /// The network is not imported, since its security type cannot be recreated (e.g. `wpa-eap`, `wep`).
+    Unsupported,
+}
+
+impl fmt::Display for ImportStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportStatus::Added => write!(f, "added"),
+            ImportStatus::Replaced => write!(f, "replaced"),
+            ImportStatus::Skipped => write!(f, "skipped"),
+            ImportStatus::Unsupported => write!(f, "unsupported"),
+        }
+    }
+}
+
+/// Represents a network that is imported by [`import`].
+///
+/// [`import`]: crate::import
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportedNetwork {
+    /// The SSID of the network.
+    #[serde(serialize_with = "output::serialize_ssid")]
+    pub ssid: Vec<u8>,
+
+    /// The outcome of the network.
+    pub status: ImportStatus,
+}
+
+/// Defines [`Error`] variants that may return whilst importing the known networks.
+///
+/// [`Error`]: std::error::Error
+#[derive(Debug)]
+pub enum Error {
+    /// Represents a file that is neither a valid TOML nor a valid JSON export.
+    InvalidFile(String),
+
+    /// Represents a file that is exported by a newer version of `wl`.
+    UnsupportedVersion(u32),
+
+    /// Represents an imported network that is already known, when [`ConflictPolicy::Fail`] is selected.
+    ///
+    /// [`ConflictPolicy::Fail`]: crate::api::ConflictPolicy::Fail
+    AlreadyKnown(Vec<u8>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidFile(err) => write!(f, "the file cannot be imported: {}", err),
+            Error::UnsupportedVersion(version) => write!(
+                f,
+                "the file version {} is not supported, the latest supported version is {}",
+                version, EXPORT_VERSION
+            ),
+            Error::AlreadyKnown(ssid) => write!(
+                f,
+                "{} is already a known network",
+                String::from_utf8_lossy(ssid)
+            ),
+        }
+    }
+}
+impl error::Error for Error {}
+
+/// Exports the known WiFi networks by using the given [`Wl`] implementation, and writes them on the given [`io::Write`] as a portable file.
+///
+/// Each known network is exported with its SSID, security type, secret, hidden flag, autoconnect priority and autoconnect setting.
+/// The secrets are read from the known networks, which may require elevated privileges. Set `no_secrets` to leave them out of the file.
+/// A secret that cannot be read (e.g. one that is kept by the secret agent of a user session) is left out, and its network is still exported.
+///
+/// In [`ExportFormat::Toml`], the file is a TOML document with a `[[networks]]` table per known network:
+///
+/// ```toml
+/// version = 1
+///
+/// [[networks]]
+/// ssid = "Home"
+/// security = "wpa-psk"
+/// secret = "secret"
+/// hidden = false
+/// priority = 0
+/// autoconnect = true
+/// ```
+///
+/// In [`ExportFormat::Json`], the file is a JSON object with the same fields:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "networks": [
+///     {
+///       "ssid": "Home",
+///       "security": "wpa-psk",
+///       "secret": "secret",
+///       "hidden": false,
+///       "priority": 0,
+///       "autoconnect": true
+///     }
+///   ]
+/// }
+/// ```
+///
+/// The known networks that cannot be recreated by [`import`] (e.g. `wpa-eap`, `wep`) are skipped, and their names are returned in [`ExportReport::skipped`].
+/// The renamed known networks are exported by their SSIDs.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails (e.g. the network backend does not expose the settings of its known networks),
+/// and [`Error::Io`] when the file cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::ExportFormat};
+///
+/// let fake = FakeWl::new().with_known_network(b"Home", Some(b"secret"));
+///
+/// let mut out = Vec::new();
+/// let report = wl::export(&fake, &mut out, ExportFormat::Toml, false).unwrap();
+///
+/// assert_eq!(Some(b"secret".to_vec()), report.networks[0].secret);
+/// assert!(String::from_utf8(out).unwrap().contains("[[networks]]\nssid = \"Home\"\n"));
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`ExportFormat::Toml`]: crate::api::ExportFormat::Toml
+/// [`ExportFormat::Json`]: crate::api::ExportFormat::Json
+/// [`import`]: crate::import
+/// [`ExportReport::skipped`]: crate::ExportReport::skipped
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn export(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    format: ExportFormat,
+    no_secrets: bool,
+) -> Result<ExportReport, crate::Error> {
+    let ExportReport { networks, skipped } = export_networks(process, no_secrets)?;
+
+    let file = ExportFile {
+        version: EXPORT_VERSION,
+        networks,
+    };
+    let out_buf = match format {
+        ExportFormat::Toml => {
+            let document = toml::to_string(&file).map_err(io::Error::other)?;
+            [TOML_HEADER, &document].concat().into_bytes()
+        }
+        ExportFormat::Json => {
+            let mut out_buf = serde_json::to_vec_pretty(&file).map_err(io::Error::from)?;
+            out_buf.push(b'\n');
+            out_buf
+        }
+    };
+    write_bytes(f, &out_buf)?;
+
+    Ok(ExportReport {
+        networks: file.networks,
+        skipped,
+    })
+}
+
+/// Imports the known networks of a file that is written by [`export`] by using the given [`Wl`] implementation, and writes the outcomes on the given [`io::Write`].
+///
+/// The file is read from the given [`io::Read`], and its format (TOML or JSON) is detected from its content.
+/// The networks are added without connecting to them. Set `no_secrets` to ignore the secrets in the file, in which case the passwords are asked on the first connection.
+///
+/// The networks that are already known are handled by `on_conflict`:
+///
+/// - [`ConflictPolicy::Skip`] keeps the known network.
+/// - [`ConflictPolicy::Replace`] replaces the known network with the imported one.
+/// - [`ConflictPolicy::Fail`] fails before importing any network.
+///
+/// In [`OutputFormat::Text`], the outcome of each network is written on its own line (e.g. `Home: added`).
+/// In [`OutputFormat::Json`], the outcomes are a JSON array, and in [`OutputFormat::Ndjson`], each outcome is a JSON object on its own line:
+///
+/// ```json
+/// [{"ssid":"Home","status":"added"},{"ssid":"Office","status":"skipped"}]
+/// ```
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Import`] with:
+///
+/// - [`ImportError::InvalidFile`] if the file is neither a valid TOML nor a valid JSON export.
+/// - [`ImportError::UnsupportedVersion`] if the file is exported by a newer version of `wl`.
+/// - [`ImportError::AlreadyKnown`] if a network is already known, and [`ConflictPolicy::Fail`] is selected.
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails to add a network, in which case the networks before it are kept,
+/// and [`Error::Io`] when the file cannot be read or the outcomes cannot be written.
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::{ConflictPolicy, OutputFormat}};
+///
+/// let file = r#"
+/// version = 1
+///
+/// [[networks]]
+/// ssid = "Home"
+/// security = "wpa-psk"
+/// secret = "secret"
+/// priority = 10
+/// "#;
+///
+/// let fake = FakeWl::new();
+/// let mut out = Vec::new();
+/// wl::import(&fake, &mut out, &mut file.as_bytes(), false, ConflictPolicy::Skip, OutputFormat::Text).unwrap();
+///
+/// assert_eq!(vec![b"Home".to_vec()], fake.known_ssids());
+/// assert_eq!(b"Home: added\n".as_slice(), out);
+/// ```
+///
+/// [`export`]: crate::export
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`io::Read`]: std::io::Read
+/// [`ConflictPolicy::Skip`]: crate::api::ConflictPolicy::Skip
+/// [`ConflictPolicy::Replace`]: crate::api::ConflictPolicy::Replace
+/// [`ConflictPolicy::Fail`]: crate::api::ConflictPolicy::Fail
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Import`]: crate::Error::Import
+/// [`ImportError::InvalidFile`]: crate::ImportError::InvalidFile
+/// [`ImportError::UnsupportedVersion`]: crate::ImportError::UnsupportedVersion
+/// [`ImportError::AlreadyKnown`]: crate::ImportError::AlreadyKnown
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn import(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    r: &mut impl io::Read,
+    no_secrets: bool,
+    on_conflict: ConflictPolicy,
+    output_format: OutputFormat,
+) -> Result<Vec<ImportedNetwork>, crate::Error> {
+    let mut content = String::new();
+    r.read_to_string(&mut content)?;
+
+    let file = parse_file(&content)?;
+
+    import_networks(
+        process,
+        f,
+        file,
+        Vec::new(),
+        no_secrets,
+        on_conflict,
+        output_format,
+    )
+}
+
+/// Reads the known networks that can be recreated by [`Wl::add_network`], along with their secrets unless `no_secrets` is set.
+///
+/// The secret of a network is left out if it cannot be read, so a single unreadable secret does not abort the export.
+///
+/// [`Wl::add_network`]: crate::Wl::add_network
+pub(crate) fn export_networks(
+    process: &impl Wl,
+    no_secrets: bool,
+) -> Result<ExportReport, crate::Error> {
+    let known_networks = process
+        .get_networks(false)?
+        .into_iter()
+        .filter(|n| n.kind == "wifi" && n.ssid != HOTSPOT_PROFILE.as_bytes());
+
+    let mut networks = Vec::new();
+    let mut skipped = Vec::new();
+    for known_network in known_networks {
+        let profile = process.get_profile(&known_network.ssid)?;
+        let Ok(security) = Security::from_str(&profile.security, true) else {
+            skipped.push(known_network.ssid);
+            continue;
+        };
+
+        let secret = match security.is_secured() && !no_secrets {
+            true => match process.get_passwd(&known_network.ssid) {
+                Ok(secret) => secret,
+                Err(NetworkAdapterError::CannotGetPasswd(_)) => None,
+                Err(err) => return Err(err.into()),
+            },
+            false => None,
+        };
+
+        networks.push(ExportedNetwork {
+            ssid: profile.ssid,
+            security,
+            secret,
+            hidden: profile.hidden,
+            priority: profile.priority,
+            autoconnect: profile.autoconnect,
+        });
+    }
+
+    Ok(ExportReport { networks, skipped })
+}
+
+/// Adds the networks of the given [`ExportFile`] by handling the known ones with `on_conflict`, and writes their outcomes on the given [`io::Write`].
+///
+/// The `unsupported` networks are not added, and they are written as [`ImportStatus::Unsupported`] after the others.
+///
+/// [`io::Write`]: std::io::Write
+pub(crate) fn import_networks(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    file: ExportFile,
+    unsupported: Vec<Vec<u8>>,
+    no_secrets: bool,
+    on_conflict: ConflictPolicy,
+    output_format: OutputFormat,
+) -> Result<Vec<ImportedNetwork>, crate::Error> {
+    if file.version > EXPORT_VERSION {
+        Err(Error::UnsupportedVersion(file.version))?;
+    }
+
+    let mut networks = Vec::with_capacity(file.networks.len());
+    for network in file.networks {
+        let is_known_ssid = process.is_known_ssid(&network.ssid)?;
+        if is_known_ssid && on_conflict == ConflictPolicy::Fail {
+            Err(Error::AlreadyKnown(network.ssid.clone()))?;
+        }
+        networks.push((network, is_known_ssid));
+    }
+
+    let mut imported = Vec::with_capacity(networks.len());
+    for (mut network, is_known_ssid) in networks {
+        let status = match (is_known_ssid, on_conflict) {
+            (true, ConflictPolicy::Skip) => ImportStatus::Skipped,
+            (true, _) => ImportStatus::Replaced,
+            (false, _) => ImportStatus::Added,
+        };
+
+        if status != ImportStatus::Skipped {
+            if no_secrets {
+                network.secret = None;
+            }
+            process.add_network(&network, is_known_ssid)?;
+        }
+
+        imported.push(ImportedNetwork {
+            ssid: network.ssid,
+            status,
+        });
+    }
+    imported.extend(unsupported.into_iter().map(|ssid| ImportedNetwork {
+        ssid,
+        status: ImportStatus::Unsupported,
+    }));
+
+    match output_format {
+        OutputFormat::Text => {
+            let out_buf = imported
+                .iter()
+                .flat_map(|n| {
+                    [
+                        n.ssid.as_slice(),
+                        b": ",
+                        n.status.to_string().as_bytes(),
+                        b"\n",
+                    ]
+                    .concat()
+                })
+                .collect::<Vec<u8>>();
+            write_bytes(f, &out_buf)?;
+        }
+        _ => output::write_list(f, output_format, &imported)?,
+    }
+
+    Ok(imported)
+}
+
+fn parse_file(content: &str) -> Result<ExportFile, Error> {
+    if content.trim_start().starts_with('{') {
+        serde_json::from_str(content).map_err(|err| Error::InvalidFile(err.to_string()))
+    } else {
+        toml::from_str(content).map_err(|err| Error::InvalidFile(err.message().to_string()))
+    }
+}
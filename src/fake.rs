@@ -0,0 +1,865 @@
+//! An in-memory network backend that simulates a host, designed for testing.
+//!
+//! [`FakeWl`] implements [`Wl`] without touching the host. Its state (radio, visible access points,
+//! known networks) is programmable, and each [`Operation`] can be set up to fail with a given exit code.
+//!
+//! Since the public functions of `wl` accept any [`Wl`] implementation, [`FakeWl`] can be plugged into them directly:
+//!
+//! ```
+//! use wl::{AccessPoint, Auth, FakeWl, PasswdSource, api::OutputFormat};
+//!
+//! let fake = FakeWl::new()
+//!     .with_access_point(AccessPoint {
+//!         ssid: b"Home".to_vec(),
+//!         security: String::from("WPA2"),
+//!         signal: 80,
+//!         ..Default::default()
+//!     })
+//!     .with_known_network(b"Home", Some(b"secret"));
+//!
+//! wl::connect(&fake, Some(b"Home".to_vec()), Auth::default(), PasswdSource::Prompt, OutputFormat::Json).unwrap();
+//! assert_eq!(Some(b"Home".to_vec()), fake.active_ssid());
+//! ```
+//!
+//! [`FakeWl`]: crate::FakeWl
+//! [`Wl`]: crate::Wl
+//! [`Operation`]: crate::fake::Operation
+
+use std::{collections::HashMap, io, sync::Mutex};
+
+use crate::{
+    AccessPoint, Band, EapCredentials, ExportedNetwork, Hotspot, HotspotClient, IpMethod,
+    MacRandomization, Metered, Network, NetworkPriority, Profile, ProfileEdit, Security,
+    WifiStatus,
+    adapter::{Error, UNKNOWN_SSID_EXIT_CODE, Wl},
+};
+
+/// The device name that [`FakeWl`] reports for its active network.
+///
+/// [`FakeWl`]: crate::FakeWl
+pub const FAKE_DEVICE: &str = "wlan0";
+
+/// Represents the methods of [`Wl`] that [`FakeWl`] can be set up to fail.
+///
+/// [`Wl`]: crate::Wl
+/// [`FakeWl`]: crate::FakeWl
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    GetWifiStatus,
+    ToggleWifi,
+    GetNetworks,
+    Disconnect,
+    GetAccessPoints,
+    IsKnownSsid,
+    Connect,
+    StartHotspot,
+    StopHotspot,
+    GetHotspot,
+    GetPasswd,
+    UpdateNetwork,
+    GetProfile,
+    AddNetwork,
+}
+
+/// Represents a known network of [`FakeWl`].
+///
+/// The known network is looked up by its `name`, which is its SSID unless it is renamed.
+#[derive(Debug, Clone)]
+struct KnownNetwork {
+    name: Vec<u8>,
+    ssid: Vec<u8>,
+    uuid: String,
+    passwd: Option<Vec<u8>>,
+    bssid: Option<String>,
+    security: Option<Security>,
+    hidden: bool,
+    autoconnect: bool,
+    priority: i32,
+    metered: Metered,
+    mac_randomization: MacRandomization,
+    ip_method: IpMethod,
+}
+
+#[derive(Debug)]
+struct State {
+    wifi: WifiStatus,
+    access_points: Vec<AccessPoint>,
+    hidden_access_points: Vec<AccessPoint>,
+    secrets: HashMap<Vec<u8>, Vec<u8>>,
+    profiles: Vec<KnownNetwork>,
+    active_ssid: Option<Vec<u8>>,
+    hotspot: Option<Hotspot>,
+    hotspot_clients: Vec<HotspotClient>,
+    failures: HashMap<Operation, i32>,
+    calls: HashMap<Operation, usize>,
+    next_uuid: u32,
+}
+
+/// The in-memory network backend that implements [`Wl`].
+///
+/// A new `FakeWl` simulates a host that has its WiFi enabled, and does not see any access points.
+/// Use the `with_*` methods to program its state, and the accessors to inspect the state after a call.
+///
+/// The simulation follows the semantics of the real network backends:
+///
+/// - The secured access points can only be connected to with their passwords, which are set by [`FakeWl::with_secret`]. If a secret is not set, then any password is accepted.
+/// - The hidden access points appear in the scans without their SSIDs, and the security type of a hidden connection should match the one of the access point.
+/// - The enterprise connections of PEAP and TTLS check their passwords the same way, and EAP-TLS only requires a client certificate and a private key.
+/// - A successful connection adds the SSID to the known networks, and makes it the active network.
+/// - A connection with a BSSID requires an access point of the SSID with that BSSID, and pins the known network to it. A pinned known network only connects to its access point.
+/// - A renamed known network is referred by its new name, and it is still connected to its own SSID. A secured known network requires a password, and the manual IP method is rejected since the fake does not keep addresses.
+/// - Disabling WiFi disconnects the active network, and the disabled WiFi fails the scans and the connection attempts.
+/// - The host has a single WiFi device, so starting a hotspot disconnects the active network, and connecting to a network stops the hotspot.
+///
+/// The failures of the programmed [`Operation`]s are returned with the same [`NetworkAdapterError`] variants and exit codes as the real backends.
+///
+/// [`Wl`]: crate::Wl
+/// [`FakeWl::with_secret`]: crate::FakeWl::with_secret
+/// [`Operation`]: crate::fake::Operation
+/// [`NetworkAdapterError`]: crate::NetworkAdapterError
+#[derive(Debug)]
+pub struct FakeWl {
+    state: Mutex<State>,
+}
+
+impl Default for FakeWl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeWl {
+    /// Creates a new `FakeWl` with WiFi enabled, without any access points or known networks.
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(State {
+                wifi: WifiStatus::Enabled,
+                access_points: Vec::new(),
+                hidden_access_points: Vec::new(),
+                secrets: HashMap::new(),
+                profiles: Vec::new(),
+                active_ssid: None,
+                hotspot: None,
+                hotspot_clients: Vec::new(),
+                failures: HashMap::new(),
+                calls: HashMap::new(),
+                next_uuid: 1,
+            }),
+        }
+    }
+
+    /// Sets the WiFi status.
+    pub fn with_wifi(self, wifi: WifiStatus) -> Self {
+        self.update(|state| state.wifi = wifi);
+        self
+    }
+
+    /// Adds an access point that is visible to the host.
+    ///
+    /// The `in_use` field is ignored, it is derived from the active network instead.
+    pub fn with_access_point(self, ap: AccessPoint) -> Self {
+        self.update(|state| state.access_points.push(ap));
+        self
+    }
+
+    /// Adds an access point that does not broadcast its SSID.
+    ///
+    /// It is visible in the scans with an empty SSID, and it can only be connected with [`Wl::connect_hidden`],
+    /// or with [`Wl::connect`] once it is a known network.
+    ///
+    /// [`Wl::connect_hidden`]: crate::Wl::connect_hidden
+    /// [`Wl::connect`]: crate::Wl::connect
+    pub fn with_hidden_network(self, ap: AccessPoint) -> Self {
+        self.update(|state| state.hidden_access_points.push(ap));
+        self
+    }
+
+    /// Sets the password that is required to connect to the given SSID.
+    pub fn with_secret(self, ssid: &[u8], passwd: &[u8]) -> Self {
+        self.update(|state| {
+            state.secrets.insert(ssid.to_vec(), passwd.to_vec());
+        });
+        self
+    }
+
+    /// Adds the given SSID to the known networks, with its saved password if any.
+    pub fn with_known_network(self, ssid: &[u8], passwd: Option<&[u8]>) -> Self {
+        self.update(|state| state.add_profile(ssid, passwd));
+        self
+    }
+
+    /// Sets the active network.
+    ///
+    /// The SSID is added to the known networks if it is not known already.
+    pub fn with_active_network(self, ssid: &[u8]) -> Self {
+        self.update(|state| {
+            if !state.profiles.iter().any(|p| p.name == ssid) {
+                state.add_profile(ssid, None);
+            }
+            state.active_ssid = Some(ssid.to_vec());
+        });
+        self
+    }
+
+    /// Adds a client that is connected to the hotspot, once it is started.
+    pub fn with_hotspot_client(self, client: HotspotClient) -> Self {
+        self.update(|state| state.hotspot_clients.push(client));
+        self
+    }
+
+    /// Makes each call of the given operation fail with the given exit code.
+    pub fn with_failure(self, operation: Operation, ecode: i32) -> Self {
+        self.update(|state| {
+            state.failures.insert(operation, ecode);
+        });
+        self
+    }
+
+    /// Changes the signal strength of the given access point (e.g. to simulate a host that moves away from it).
+    ///
+    /// Unlike the `with_*` methods, it can be called while the `FakeWl` is in use.
+    pub fn set_signal(&self, ssid: &[u8], signal: u8) {
+        self.update(|state| {
+            state
+                .access_points
+                .iter_mut()
+                .filter(|ap| ap.ssid == ssid)
+                .for_each(|ap| ap.signal = signal);
+        });
+    }
+
+    /// Provides the current WiFi status.
+    pub fn wifi_status(&self) -> WifiStatus {
+        self.read(|state| state.wifi)
+    }
+
+    /// Provides the SSID of the active network, if any.
+    pub fn active_ssid(&self) -> Option<Vec<u8>> {
+        self.read(|state| state.active_ssid.clone())
+    }
+
+    /// Provides the names of the known networks, in the order they are added.
+    ///
+    /// The names are the SSIDs of the known networks, unless they are renamed.
+    pub fn known_ssids(&self) -> Vec<Vec<u8>> {
+        self.read(|state| state.profiles.iter().map(|p| p.name.clone()).collect())
+    }
+
+    /// Provides the BSSID that the given known SSID is pinned to, if any.
+    pub fn pinned_bssid(&self, ssid: &[u8]) -> Option<String> {
+        self.read(|state| {
+            state
+                .profiles
+                .iter()
+                .find(|p| p.name == ssid)
+                .and_then(|p| p.bssid.clone())
+        })
+    }
+
+    /// Provides the active hotspot, if any.
+    pub fn hotspot(&self) -> Option<Hotspot> {
+        self.read(|state| state.hotspot.clone())
+    }
+
+    /// Provides the number of the completed calls of the given operation, including the failed ones.
+    ///
+    /// It can be used to wait for a caller on another thread (e.g. [`watch`]) to reach a given point.
+    ///
+    /// [`watch`]: crate::watch
+    pub fn call_count(&self, operation: Operation) -> usize {
+        self.read(|state| state.calls.get(&operation).copied().unwrap_or_default())
+    }
+
+    fn update(&self, f: impl FnOnce(&mut State)) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        f(&mut state);
+    }
+
+    fn read<T>(&self, f: impl FnOnce(&State) -> T) -> T {
+        let state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        f(&state)
+    }
+
+    /// Runs the given operation on the state, unless the operation is set up to fail.
+    fn exec<T>(
+        &self,
+        operation: Operation,
+        f: impl FnOnce(&mut State) -> Result<T, (io::Error, i32)>,
+    ) -> Result<T, (io::Error, i32)> {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        // The state stays locked until the call completes, so the count is only observed after it.
+        *state.calls.entry(operation).or_default() += 1;
+
+        if let Some(ecode) = state.failures.get(&operation) {
+            let err = io::Error::other(format!("injected failure of {:?}", operation));
+            return Err((err, *ecode));
+        }
+
+        f(&mut state)
+    }
+}
+
+impl State {
+    fn add_profile(&mut self, ssid: &[u8], passwd: Option<&[u8]>) {
+        self.profiles.push(KnownNetwork {
+            name: ssid.to_vec(),
+            ssid: ssid.to_vec(),
+            uuid: format!("00000000-0000-0000-0000-{:012}", self.next_uuid),
+            passwd: passwd.map(|pw| pw.to_vec()),
+            bssid: None,
+            security: None,
+            hidden: false,
+            autoconnect: true,
+            priority: 0,
+            metered: Metered::default(),
+            mac_randomization: MacRandomization::default(),
+            ip_method: IpMethod::default(),
+        });
+        self.next_uuid += 1;
+    }
+
+    fn find_profile_mut(&mut self, name: &[u8]) -> Result<&mut KnownNetwork, (io::Error, i32)> {
+        self.profiles
+            .iter_mut()
+            .find(|p| p.name == name)
+            .ok_or_else(|| {
+                let err = io::Error::other(format!(
+                    "unknown connection '{}'",
+                    String::from_utf8_lossy(name)
+                ));
+                (err, UNKNOWN_SSID_EXIT_CODE)
+            })
+    }
+
+    fn ensure_wifi_enabled(&self) -> Result<(), (io::Error, i32)> {
+        match self.wifi {
+            WifiStatus::Enabled => Ok(()),
+            WifiStatus::Disabled => Err((io::Error::other("WiFi is disabled"), 10)),
+        }
+    }
+}
+
+impl Wl for FakeWl {
+    fn get_wifi_status(&self) -> Result<WifiStatus, Error> {
+        self.exec(Operation::GetWifiStatus, |state| Ok(state.wifi))
+            .map_err(Error::CannotGetWiFiStatus)
+    }
+
+    fn toggle_wifi(&self) -> Result<WifiStatus, Error> {
+        self.exec(Operation::ToggleWifi, |state| {
+            state.wifi = state.wifi.toggled();
+            if state.wifi == WifiStatus::Disabled {
+                state.active_ssid = None;
+                state.hotspot = None;
+            }
+
+            Ok(state.wifi)
+        })
+        .map_err(Error::CannotToggleWiFi)
+    }
+
+    fn get_networks(&self, active_only: bool) -> Result<Vec<Network>, Error> {
+        let map_err = if active_only {
+            Error::CannotGetActiveConnections
+        } else {
+            Error::CannotListNetworks
+        };
+
+        self.exec(Operation::GetNetworks, |state| {
+            let networks = state
+                .profiles
+                .iter()
+                .map(|p| Network {
+                    ssid: p.name.clone(),
+                    uuid: p.uuid.clone(),
+                    kind: String::from("wifi"),
+                    device: (state.active_ssid.as_ref() == Some(&p.ssid))
+                        .then(|| FAKE_DEVICE.to_string()),
+                })
+                .filter(|n| !active_only || n.is_active())
+                .collect();
+
+            Ok(networks)
+        })
+        .map_err(map_err)
+    }
+
+    fn disconnect(&self, ssid: &[u8], forget: bool) -> Result<Vec<u8>, Error> {
+        self.exec(Operation::Disconnect, |state| {
+            let profile = state.profiles.iter().find(|p| p.name == ssid);
+            let is_known = profile.is_some();
+            let network_ssid = profile.map_or(ssid, |p| &p.ssid);
+            let is_active = state.active_ssid.as_deref() == Some(network_ssid);
+
+            if (forget && !is_known) || (!forget && !is_active) {
+                let err = io::Error::other(format!(
+                    "'{}' is not an active connection",
+                    String::from_utf8_lossy(ssid)
+                ));
+                return Err((err, UNKNOWN_SSID_EXIT_CODE));
+            }
+
+            if is_active {
+                state.active_ssid = None;
+            }
+            if forget {
+                state.profiles.retain(|p| p.name != ssid);
+            }
+
+            let action = if forget { "deleted" } else { "deactivated" };
+            Ok([
+                b"Connection '",
+                ssid,
+                b"' successfully ",
+                action.as_bytes(),
+                b".\n",
+            ]
+            .concat())
+        })
+        .map_err(Error::CannotDisconnect)
+    }
+
+    fn get_access_points(&self, _re_scan: bool) -> Result<Vec<AccessPoint>, Error> {
+        self.exec(Operation::GetAccessPoints, |state| {
+            state.ensure_wifi_enabled()?;
+
+            let aps = state
+                .access_points
+                .iter()
+                .map(|ap| AccessPoint {
+                    in_use: state.active_ssid.as_ref() == Some(&ap.ssid),
+                    ..ap.clone()
+                })
+                .chain(state.hidden_access_points.iter().map(|ap| AccessPoint {
+                    ssid: Vec::new(),
+                    in_use: state.active_ssid.as_ref() == Some(&ap.ssid),
+                    ..ap.clone()
+                }))
+                .collect();
+
+            Ok(aps)
+        })
+        .map_err(Error::CannotScanWiFi)
+    }
+
+    fn is_known_ssid(&self, ssid: &[u8]) -> Result<bool, Error> {
+        self.exec(Operation::IsKnownSsid, |state| {
+            Ok(state.profiles.iter().any(|p| p.name == ssid))
+        })
+        .map_err(Error::CannotGetSSIDStatus)
+    }
+
+    fn connect(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        bssid: Option<&str>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        self.exec(Operation::Connect, |state| {
+            state.ensure_wifi_enabled()?;
+
+            let profile = state.profiles.iter().find(|p| p.name == ssid);
+            let pinned_bssid = profile.and_then(|p| p.bssid.clone());
+            let bssid = bssid.map(String::from).or(pinned_bssid);
+            // A renamed known network is connected to its own SSID.
+            let network_ssid = profile.map_or(ssid.to_vec(), |p| p.ssid.clone());
+
+            let ap = state
+                .access_points
+                .iter()
+                .chain(
+                    state
+                        .hidden_access_points
+                        .iter()
+                        .filter(|_| profile.is_some()),
+                )
+                .filter(|ap| {
+                    bssid
+                        .as_ref()
+                        .is_none_or(|bssid| ap.bssid.eq_ignore_ascii_case(bssid))
+                })
+                .find(|ap| ap.ssid == network_ssid)
+                .ok_or_else(|| {
+                    let err = match &bssid {
+                        Some(bssid) => io::Error::other(format!(
+                            "No network with SSID '{}' and BSSID '{}' found.",
+                            String::from_utf8_lossy(ssid),
+                            bssid
+                        )),
+                        None => io::Error::other(format!(
+                            "No network with SSID '{}' found.",
+                            String::from_utf8_lossy(ssid)
+                        )),
+                    };
+                    (err, UNKNOWN_SSID_EXIT_CODE)
+                })?;
+
+            let saved_passwd = state
+                .profiles
+                .iter()
+                .find(|p| p.name == ssid)
+                .and_then(|p| p.passwd.clone());
+            let passwd = match passwd {
+                Some(passwd) => Some(passwd.to_vec()),
+                None if is_known_ssid => saved_passwd,
+                None => None,
+            };
+
+            if ap.is_secured() {
+                let is_valid = match (state.secrets.get(&network_ssid), &passwd) {
+                    (_, None) => false,
+                    (Some(secret), Some(passwd)) => secret == passwd,
+                    (None, Some(_)) => true,
+                };
+
+                if !is_valid {
+                    let err = io::Error::other(
+                        "Connection activation failed: Secrets were required, but not provided.",
+                    );
+                    return Err((err, 4));
+                }
+            }
+
+            if !state.profiles.iter().any(|p| p.name == ssid) {
+                state.add_profile(ssid, None);
+            }
+            if let Some(profile) = state.profiles.iter_mut().find(|p| p.name == ssid) {
+                profile.passwd = passwd;
+                profile.bssid = bssid;
+            }
+            state.active_ssid = Some(network_ssid);
+            state.hotspot = None;
+
+            Ok([
+                b"Device '",
+                FAKE_DEVICE.as_bytes(),
+                b"' successfully activated with '",
+                ssid,
+                b"'.\n",
+            ]
+            .concat())
+        })
+        .map_err(Error::CannotConnect)
+    }
+
+    fn clear_bssid(&self, ssid: &[u8]) -> Result<Vec<u8>, Error> {
+        self.exec(Operation::UpdateNetwork, |state| {
+            let profile = state
+                .profiles
+                .iter_mut()
+                .find(|p| p.name == ssid)
+                .ok_or_else(|| {
+                    let err = io::Error::other(format!(
+                        "unknown connection '{}'",
+                        String::from_utf8_lossy(ssid)
+                    ));
+                    (err, UNKNOWN_SSID_EXIT_CODE)
+                })?;
+            profile.bssid = None;
+
+            Ok([
+                b"Connection '",
+                ssid,
+                b"' is no longer pinned to a BSSID.\n",
+            ]
+            .concat())
+        })
+        .map_err(Error::CannotUpdateNetwork)
+    }
+
+    fn get_profile(&self, ssid: &[u8]) -> Result<Profile, Error> {
+        self.exec(Operation::GetProfile, |state| {
+            let profile = state.find_profile_mut(ssid)?;
+            let security = match (profile.security, &profile.passwd) {
+                (Some(security), _) => security.name(),
+                (None, Some(_)) => Security::WpaPsk.name(),
+                (None, None) => Security::Open.name(),
+            };
+
+            Ok(Profile {
+                name: profile.name.clone(),
+                ssid: profile.ssid.clone(),
+                security: security.to_string(),
+                hidden: profile.hidden,
+                autoconnect: profile.autoconnect,
+                priority: profile.priority,
+                metered: profile.metered,
+                mac_randomization: profile.mac_randomization,
+                ip_method: profile.ip_method,
+            })
+        })
+        .map_err(Error::CannotGetProfile)
+    }
+
+    fn edit_profile(&self, ssid: &[u8], edit: &ProfileEdit) -> Result<Vec<u8>, Error> {
+        self.exec(Operation::UpdateNetwork, |state| {
+            let profile = state.find_profile_mut(ssid)?;
+
+            if edit.security.is_some_and(|s| s.is_secured()) && profile.passwd.is_none() {
+                let err = io::Error::other("802-11-wireless-security.psk: property is missing");
+                return Err((err, 2));
+            }
+            if edit.ip_method == Some(IpMethod::Manual) {
+                let err = io::Error::other(
+                    "ipv4.addresses: this property cannot be empty for 'method=manual'",
+                );
+                return Err((err, 2));
+            }
+
+            if let Some(security) = edit.security {
+                if !security.is_secured() {
+                    profile.passwd = None;
+                }
+                profile.security = Some(security);
+            }
+            profile.autoconnect = edit.autoconnect.unwrap_or(profile.autoconnect);
+            profile.priority = edit.priority.unwrap_or(profile.priority);
+            profile.metered = edit.metered.unwrap_or(profile.metered);
+            profile.mac_randomization = edit.mac_randomization.unwrap_or(profile.mac_randomization);
+            profile.ip_method = edit.ip_method.unwrap_or(profile.ip_method);
+
+            Ok([b"Connection '", ssid, b"' successfully modified.\n"].concat())
+        })
+        .map_err(Error::CannotUpdateNetwork)
+    }
+
+    fn rename_profile(&self, ssid: &[u8], name: &[u8]) -> Result<Vec<u8>, Error> {
+        self.exec(Operation::UpdateNetwork, |state| {
+            state.find_profile_mut(ssid)?.name = name.to_vec();
+
+            Ok([b"Connection '", ssid, b"' is renamed to '", name, b"'.\n"].concat())
+        })
+        .map_err(Error::CannotUpdateNetwork)
+    }
+
+    fn add_network(
+        &self,
+        network: &ExportedNetwork,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let ssid = network.ssid.as_slice();
+        self.exec(Operation::AddNetwork, |state| {
+            if is_known_ssid {
+                state.find_profile_mut(ssid)?;
+                state.profiles.retain(|p| p.name != ssid);
+            }
+
+            let secret = network.secret.as_deref();
+            state.add_profile(ssid, secret.filter(|_| network.security.is_secured()));
+
+            if let Some(profile) = state.profiles.last_mut() {
+                profile.security = Some(network.security);
+                profile.hidden = network.hidden;
+                profile.priority = network.priority;
+                profile.autoconnect = network.autoconnect;
+            }
+
+            Ok([b"Connection '", ssid, b"' successfully added.\n"].concat())
+        })
+        .map_err(Error::CannotAddNetwork)
+    }
+
+    fn get_priorities(&self) -> Result<Vec<NetworkPriority>, Error> {
+        self.exec(Operation::GetNetworks, |state| {
+            let priorities = state
+                .profiles
+                .iter()
+                .map(|p| NetworkPriority {
+                    ssid: p.name.clone(),
+                    priority: p.priority,
+                    autoconnect: p.autoconnect,
+                })
+                .collect();
+
+            Ok(priorities)
+        })
+        .map_err(Error::CannotListNetworks)
+    }
+
+    fn set_priority(&self, ssid: &[u8], priority: i32) -> Result<Vec<u8>, Error> {
+        self.exec(Operation::UpdateNetwork, |state| {
+            state.find_profile_mut(ssid)?.priority = priority;
+
+            Ok([b"Connection '", ssid, b"' successfully modified.\n"].concat())
+        })
+        .map_err(Error::CannotUpdateNetwork)
+    }
+
+    fn connect_hidden(
+        &self,
+        ssid: &[u8],
+        security: Security,
+        passwd: Option<&[u8]>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let _ = is_known_ssid;
+        self.exec(Operation::Connect, |state| {
+            state.ensure_wifi_enabled()?;
+
+            let ap = state
+                .hidden_access_points
+                .iter()
+                .chain(state.access_points.iter())
+                .find(|ap| ap.ssid == ssid)
+                .ok_or_else(|| {
+                    let err = io::Error::other(format!(
+                        "No network with SSID '{}' found.",
+                        String::from_utf8_lossy(ssid)
+                    ));
+                    (err, UNKNOWN_SSID_EXIT_CODE)
+                })?;
+
+            let is_valid = match (ap.is_secured(), security.is_secured(), passwd) {
+                (false, false, _) => true,
+                (true, true, Some(passwd)) => state
+                    .secrets
+                    .get(ssid)
+                    .is_none_or(|secret| secret == passwd),
+                _ => false,
+            };
+
+            if !is_valid {
+                let err = io::Error::other(
+                    "Connection activation failed: Secrets were required, but not provided.",
+                );
+                return Err((err, 4));
+            }
+
+            let passwd = passwd.filter(|_| security.is_secured());
+            match state.profiles.iter_mut().find(|p| p.name == ssid) {
+                Some(profile) => profile.passwd = passwd.map(|pw| pw.to_vec()),
+                None => state.add_profile(ssid, passwd),
+            }
+            state.find_profile_mut(ssid)?.hidden = true;
+            state.active_ssid = Some(ssid.to_vec());
+            state.hotspot = None;
+
+            Ok([
+                b"Device '",
+                FAKE_DEVICE.as_bytes(),
+                b"' successfully activated with '",
+                ssid,
+                b"'.\n",
+            ]
+            .concat())
+        })
+        .map_err(Error::CannotConnect)
+    }
+
+    fn connect_enterprise(
+        &self,
+        ssid: &[u8],
+        credentials: &EapCredentials,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let _ = is_known_ssid;
+        self.exec(Operation::Connect, |state| {
+            state.ensure_wifi_enabled()?;
+
+            if !state.access_points.iter().any(|ap| ap.ssid == ssid) {
+                let err = io::Error::other(format!(
+                    "No network with SSID '{}' found.",
+                    String::from_utf8_lossy(ssid)
+                ));
+                return Err((err, UNKNOWN_SSID_EXIT_CODE));
+            }
+
+            let is_valid = match credentials.method.is_tunneled() {
+                true => match (state.secrets.get(ssid), &credentials.passwd) {
+                    (_, None) => false,
+                    (Some(secret), Some(passwd)) => secret == passwd,
+                    (None, Some(_)) => true,
+                },
+                false => credentials.client_cert.is_some() && credentials.private_key.is_some(),
+            };
+
+            if !is_valid {
+                let err = io::Error::other(
+                    "Connection activation failed: Secrets were required, but not provided.",
+                );
+                return Err((err, 4));
+            }
+
+            // The EAP password is not a pre-shared key, so it is not stored as the password of the profile.
+            match state.profiles.iter_mut().find(|p| p.name == ssid) {
+                Some(profile) => profile.passwd = None,
+                None => state.add_profile(ssid, None),
+            }
+            state.active_ssid = Some(ssid.to_vec());
+            state.hotspot = None;
+
+            Ok([
+                b"Device '",
+                FAKE_DEVICE.as_bytes(),
+                b"' successfully activated with '",
+                ssid,
+                b"'.\n",
+            ]
+            .concat())
+        })
+        .map_err(Error::CannotConnect)
+    }
+
+    fn start_hotspot(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        band: Option<Band>,
+        channel: Option<u32>,
+    ) -> Result<Hotspot, Error> {
+        self.exec(Operation::StartHotspot, |state| {
+            state.ensure_wifi_enabled()?;
+
+            let hotspot = Hotspot {
+                ssid: ssid.to_vec(),
+                device: FAKE_DEVICE.to_string(),
+                band: band.or(channel.map(Band::from_channel)),
+                channel,
+                secured: passwd.is_some(),
+                clients: Some(state.hotspot_clients.clone()),
+            };
+
+            state.active_ssid = None;
+            state.hotspot = Some(hotspot.clone());
+
+            Ok(hotspot)
+        })
+        .map_err(Error::CannotStartHotspot)
+    }
+
+    fn stop_hotspot(&self) -> Result<Hotspot, Error> {
+        self.exec(Operation::StopHotspot, |state| {
+            state.hotspot.take().ok_or_else(|| {
+                (
+                    io::Error::other("no hotspot is active"),
+                    UNKNOWN_SSID_EXIT_CODE,
+                )
+            })
+        })
+        .map_err(Error::CannotStopHotspot)
+    }
+
+    fn get_hotspot(&self) -> Result<Option<Hotspot>, Error> {
+        self.exec(Operation::GetHotspot, |state| Ok(state.hotspot.clone()))
+            .map_err(Error::CannotGetHotspot)
+    }
+
+    fn get_passwd(&self, ssid: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        self.exec(Operation::GetPasswd, |state| {
+            state
+                .profiles
+                .iter()
+                .find(|p| p.name == ssid)
+                .map(|p| p.passwd.clone())
+                .ok_or_else(|| {
+                    let err = io::Error::other(format!(
+                        "unknown connection '{}'",
+                        String::from_utf8_lossy(ssid)
+                    ));
+                    (err, UNKNOWN_SSID_EXIT_CODE)
+                })
+        })
+        .map_err(Error::CannotGetPasswd)
+    }
+}
@@ -0,0 +1,368 @@
+//! The formatting layer of the typed results of network backends.
+//!
+//! Network backends provide a list of [`AccessPoint`]s, and this module renders
+//! that list either in a **human-readable format** (table) or in a **terse format**.
+//! The known [`Network`]s and their [`NetworkPriority`]s are rendered as tables.
+//!
+//! Both formats of the access points use the same field names:
+//!
+//! `IN-USE`, `BSSID`, `SSID`, `MODE`, `CHAN`, `FREQ`, `RATE`, `SIGNAL`, `BARS`, `SECURITY`
+//!
+//! The field names are case-insensitive.
+//!
+//! [`AccessPoint`]: crate::AccessPoint
+//! [`Network`]: crate::Network
+//! [`NetworkPriority`]: crate::NetworkPriority
+
+use std::{error, fmt, io, str::FromStr};
+
+use crate::{AccessPoint, Network, NetworkPriority, adapter::LINE_FEED, write_bytes};
+
+/// Represents the byte that is used to separate the fields of a terse output.
+///
+/// If a field value contains the separator itself or a backslash, then it is
+/// escaped with a backslash.
+pub const TERSE_FIELD_SEPARATOR: u8 = b':';
+
+/// The fields that are shown when the caller does not specify any.
+pub const DEFAULT_FIELDS: [Field; 9] = [
+    Field::InUse,
+    Field::Bssid,
+    Field::Ssid,
+    Field::Mode,
+    Field::Chan,
+    Field::Rate,
+    Field::Signal,
+    Field::Bars,
+    Field::Security,
+];
+
+/// Defines [`Error`] variants that may return during formatting.
+///
+/// [`Error`]: std::error::Error
+#[derive(Debug)]
+pub enum Error {
+    /// Represents a field name that does not exist on [`AccessPoint`].
+    ///
+    /// It holds the invalid field name.
+    ///
+    /// [`AccessPoint`]: crate::AccessPoint
+    InvalidField(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidField(field) => write!(f, "invalid field '{}'", field),
+        }
+    }
+}
+impl error::Error for Error {}
+
+/// Represents a single field (column) of an [`AccessPoint`].
+///
+/// [`AccessPoint`]: crate::AccessPoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    InUse,
+    Bssid,
+    Ssid,
+    Mode,
+    Chan,
+    Freq,
+    Rate,
+    Signal,
+    Bars,
+    Security,
+}
+
+impl Field {
+    /// Provides the name of the field, which is also used as the column name of the table.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::InUse => "IN-USE",
+            Field::Bssid => "BSSID",
+            Field::Ssid => "SSID",
+            Field::Mode => "MODE",
+            Field::Chan => "CHAN",
+            Field::Freq => "FREQ",
+            Field::Rate => "RATE",
+            Field::Signal => "SIGNAL",
+            Field::Bars => "BARS",
+            Field::Security => "SECURITY",
+        }
+    }
+
+    /// Provides the value of the field for the given [`AccessPoint`].
+    ///
+    /// If `terse` is set, then the empty values are kept as is.
+    /// Otherwise, they are replaced with `--` to keep the table readable.
+    ///
+    /// [`AccessPoint`]: crate::AccessPoint
+    pub fn value(&self, ap: &AccessPoint, terse: bool) -> Vec<u8> {
+        let value = match self {
+            Field::InUse => {
+                let in_use = match (ap.in_use, terse) {
+                    (true, _) => "*",
+                    (false, true) => "",
+                    (false, false) => " ",
+                };
+                return in_use.as_bytes().to_vec();
+            }
+            Field::Bssid => ap.bssid.as_bytes().to_vec(),
+            Field::Ssid => ap.ssid.clone(),
+            Field::Mode => ap.mode.as_bytes().to_vec(),
+            Field::Chan => ap.channel.to_string().into_bytes(),
+            Field::Freq => format!("{} MHz", ap.frequency).into_bytes(),
+            Field::Rate => format!("{} Mbit/s", ap.rate).into_bytes(),
+            Field::Signal => ap.signal.to_string().into_bytes(),
+            Field::Bars => ap.bars().as_bytes().to_vec(),
+            Field::Security => ap.security.as_bytes().to_vec(),
+        };
+
+        if value.is_empty() && !terse {
+            b"--".to_vec()
+        } else {
+            value
+        }
+    }
+
+    /// Parses a comma separated list of field names.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidField`] if one of the field names does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wl::format::Field;
+    ///
+    /// let fields = Field::parse_list("SSID,signal").unwrap();
+    /// assert_eq!(vec![Field::Ssid, Field::Signal], fields);
+    ///
+    /// assert!(Field::parse_list("SSID,INVALID").is_err());
+    /// ```
+    ///
+    /// [`Error::InvalidField`]: crate::format::Error::InvalidField
+    pub fn parse_list(fields: &str) -> Result<Vec<Field>, Error> {
+        fields
+            .split(',')
+            .map(|f| f.trim())
+            .filter(|f| !f.is_empty())
+            .map(Field::from_str)
+            .collect()
+    }
+}
+
+impl FromStr for Field {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let field = match s.to_ascii_uppercase().as_str() {
+            "IN-USE" => Field::InUse,
+            "BSSID" => Field::Bssid,
+            "SSID" => Field::Ssid,
+            "MODE" => Field::Mode,
+            "CHAN" => Field::Chan,
+            "FREQ" => Field::Freq,
+            "RATE" => Field::Rate,
+            "SIGNAL" => Field::Signal,
+            "BARS" => Field::Bars,
+            "SECURITY" => Field::Security,
+            _ => return Err(Error::InvalidField(s.to_string())),
+        };
+
+        Ok(field)
+    }
+}
+
+/// Writes the given access points as a table.
+///
+/// The first line of the output contains the column names.
+/// Each column is padded by the widest value it contains.
+///
+/// # Errors
+///
+/// This function returns an [`io::Error`] when the table cannot be written on the given [`io::Write`].
+///
+/// [`io::Error`]: std::io::Error
+/// [`io::Write`]: std::io::Write
+pub fn write_table(
+    f: &mut impl io::Write,
+    aps: &[AccessPoint],
+    fields: &[Field],
+) -> Result<(), io::Error> {
+    let header = fields.iter().map(|fd| fd.name()).collect::<Vec<&str>>();
+    let rows = aps
+        .iter()
+        .map(|ap| fields.iter().map(|fd| fd.value(ap, false)).collect())
+        .collect();
+
+    write_rows(f, &header, rows)
+}
+
+/// Writes the given known networks as a table.
+///
+/// The table contains the `NAME`, `UUID`, `TYPE` and `DEVICE` columns.
+/// If `ssid_only` is set, then the table only contains the `NAME` column.
+///
+/// # Errors
+///
+/// This function returns an [`io::Error`] when the table cannot be written on the given [`io::Write`].
+///
+/// [`io::Error`]: std::io::Error
+/// [`io::Write`]: std::io::Write
+pub fn write_network_table(
+    f: &mut impl io::Write,
+    networks: &[Network],
+    ssid_only: bool,
+) -> Result<(), io::Error> {
+    let header = if ssid_only {
+        vec!["NAME"]
+    } else {
+        vec!["NAME", "UUID", "TYPE", "DEVICE"]
+    };
+
+    let rows = networks
+        .iter()
+        .map(|n| {
+            let mut row = vec![n.ssid.clone()];
+            if !ssid_only {
+                row.push(match n.uuid.as_str() {
+                    "" => b"--".to_vec(),
+                    uuid => uuid.as_bytes().to_vec(),
+                });
+                row.push(n.kind.as_bytes().to_vec());
+                row.push(n.device.as_deref().unwrap_or("--").as_bytes().to_vec());
+            }
+            row
+        })
+        .collect();
+
+    write_rows(f, &header, rows)
+}
+
+/// Writes the given autoconnect priorities of the known networks as a table.
+///
+/// The table contains the `PRIORITY`, `AUTOCONNECT` and `NAME` columns, and the rows are written in the given order.
+///
+/// # Errors
+///
+/// This function returns an [`io::Error`] when the table cannot be written on the given [`io::Write`].
+///
+/// [`io::Error`]: std::io::Error
+/// [`io::Write`]: std::io::Write
+pub fn write_priority_table(
+    f: &mut impl io::Write,
+    priorities: &[NetworkPriority],
+) -> Result<(), io::Error> {
+    let header = vec!["PRIORITY", "AUTOCONNECT", "NAME"];
+
+    let rows = priorities
+        .iter()
+        .map(|p| {
+            let autoconnect = if p.autoconnect { "yes" } else { "no" };
+            vec![
+                p.priority.to_string().into_bytes(),
+                autoconnect.as_bytes().to_vec(),
+                p.ssid.clone(),
+            ]
+        })
+        .collect();
+
+    write_rows(f, &header, rows)
+}
+
+/// Writes the given access points in a terse format.
+///
+/// Each line represents an access point, and its fields are separated by [`TERSE_FIELD_SEPARATOR`].
+///
+/// # Errors
+///
+/// This function returns an [`io::Error`] when the output cannot be written on the given [`io::Write`].
+///
+/// # Example
+///
+/// ```
+/// use wl::{AccessPoint, format::{self, Field}};
+///
+/// let ap = AccessPoint {
+///     ssid: b"SSID1".to_vec(),
+///     bssid: String::from("AA:BB:CC:DD:EE:FF"),
+///     signal: 55,
+///     ..Default::default()
+/// };
+///
+/// let mut out = Vec::new();
+/// format::write_terse(&mut out, &[ap], &[Field::Ssid, Field::Bssid, Field::Signal]).unwrap();
+///
+/// assert_eq!(b"SSID1:AA\\:BB\\:CC\\:DD\\:EE\\:FF:55\n".as_slice(), out);
+/// ```
+///
+/// [`TERSE_FIELD_SEPARATOR`]: crate::format::TERSE_FIELD_SEPARATOR
+/// [`io::Error`]: std::io::Error
+/// [`io::Write`]: std::io::Write
+pub fn write_terse(
+    f: &mut impl io::Write,
+    aps: &[AccessPoint],
+    fields: &[Field],
+) -> Result<(), io::Error> {
+    let mut out_buf = Vec::new();
+
+    for ap in aps {
+        for (idx, field) in fields.iter().enumerate() {
+            if idx > 0 {
+                out_buf.push(TERSE_FIELD_SEPARATOR);
+            }
+
+            for b in field.value(ap, true) {
+                if b == TERSE_FIELD_SEPARATOR || b == b'\\' {
+                    out_buf.push(b'\\');
+                }
+                out_buf.push(b);
+            }
+        }
+        out_buf.push(LINE_FEED);
+    }
+
+    write_bytes(f, &out_buf)
+}
+
+fn write_rows(
+    f: &mut impl io::Write,
+    header: &[&str],
+    rows: Vec<Vec<Vec<u8>>>,
+) -> Result<(), io::Error> {
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            rows.iter()
+                .map(|r| display_width(&r[idx]))
+                .fold(name.len(), usize::max)
+        })
+        .collect::<Vec<usize>>();
+
+    let header = header.iter().map(|name| name.as_bytes().to_vec()).collect();
+
+    let mut out_buf = Vec::new();
+    for row in std::iter::once(header).chain(rows) {
+        let mut line = Vec::new();
+        for (value, width) in row.iter().zip(&widths) {
+            let padding = width - display_width(value) + 2;
+            line.extend_from_slice(value);
+            line.extend(std::iter::repeat_n(b' ', padding));
+        }
+
+        let line = line.trim_ascii_end();
+        out_buf.extend_from_slice(line);
+        out_buf.push(LINE_FEED);
+    }
+
+    write_bytes(f, &out_buf)
+}
+
+fn display_width(value: &[u8]) -> usize {
+    String::from_utf8_lossy(value).chars().count()
+}
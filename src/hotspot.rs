@@ -0,0 +1,323 @@
+use std::{error, fmt, io};
+
+use serde::Serialize;
+
+use crate::{Band, Hotspot, adapter::Wl, api::OutputFormat, output, write_bytes};
+
+/// The length limits of a WPA-PSK passphrase.
+const PASSWD_LEN: std::ops::RangeInclusive<usize> = 8..=63;
+
+#[derive(Serialize)]
+struct HotspotStatus<'a> {
+    active: bool,
+    #[serde(flatten)]
+    hotspot: Option<&'a Hotspot>,
+}
+
+#[derive(Serialize)]
+struct StoppedHotspot<'a> {
+    #[serde(serialize_with = "output::serialize_ssid")]
+    ssid: &'a [u8],
+    device: &'a str,
+    stopped: bool,
+}
+
+/// Defines [`Error`] variants that may return whilst starting a hotspot.
+///
+/// [`Error`]: std::error::Error
+#[derive(Debug)]
+pub enum Error {
+    /// Represents a password that cannot be used to secure the hotspot.
+    ///
+    /// WPA-PSK requires a password between 8 and 63 characters. It holds the length of the given password.
+    InvalidPassword(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidPassword(len) => write!(
+                f,
+                "the given password has {} characters, it should have between 8 and 63 characters",
+                len
+            ),
+        }
+    }
+}
+impl error::Error for Error {}
+
+/// Starts a WiFi hotspot that shares the connection of the host by using the given [`Wl`] implementation, and writes it on the given [`io::Write`].
+///
+/// If `passwd` is provided, then the hotspot is secured with WPA-PSK. If not, then the hotspot is open.
+/// If `band` or `channel` are not provided, then they are chosen by the network backend.
+/// The hotspot that is started by `wl` before is replaced.
+///
+/// The started hotspot is written in the same format as [`hotspot_status`], and it is also returned.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Hotspot`] with [`HotspotError::InvalidPassword`] if the given password is not between 8 and 63 characters.
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the hotspot cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{Band, FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new();
+///
+/// let mut out = Vec::new();
+/// let hotspot = wl::hotspot_start(
+///     &fake,
+///     &mut out,
+///     b"Field".to_vec(),
+///     Some(b"password".to_vec()),
+///     Some(Band::Bg),
+///     Some(6),
+///     OutputFormat::Text,
+/// )
+/// .unwrap();
+///
+/// assert!(hotspot.secured);
+/// assert!(out.starts_with(b"hotspot: active\nssid: Field\n"));
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`hotspot_status`]: crate::hotspot_status
+/// [`Error::Hotspot`]: crate::Error::Hotspot
+/// [`HotspotError::InvalidPassword`]: crate::HotspotError::InvalidPassword
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn hotspot_start(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    ssid: Vec<u8>,
+    passwd: Option<Vec<u8>>,
+    band: Option<Band>,
+    channel: Option<u32>,
+    output_format: OutputFormat,
+) -> Result<Hotspot, crate::Error> {
+    if let Some(passwd) = &passwd {
+        let len = String::from_utf8_lossy(passwd).chars().count();
+        if !PASSWD_LEN.contains(&len) {
+            Err(Error::InvalidPassword(len))?;
+        }
+    }
+
+    let hotspot = process.start_hotspot(&ssid, passwd.as_deref(), band, channel)?;
+
+    write_hotspot(f, Some(&hotspot), output_format)?;
+
+    Ok(hotspot)
+}
+
+/// Stops the WiFi hotspot that is started by [`hotspot_start`] by using the given [`Wl`] implementation, and writes the result on the given [`io::Write`].
+///
+/// The hotspot is removed from the host entirely, it is not only deactivated.
+///
+/// In [`OutputFormat::Text`], the result is a single line that contains the SSID and the device of the hotspot.
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], the result is a single JSON object:
+///
+/// ```json
+/// {"ssid":"Field","device":"wlan0","stopped":true}
+/// ```
+///
+/// The stopped hotspot is also returned.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails or the hotspot is not active, and [`Error::Io`] when the result cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, Wl, api::OutputFormat};
+///
+/// let fake = FakeWl::new();
+/// fake.start_hotspot(b"Field", None, None, None).unwrap();
+///
+/// let mut out = Vec::new();
+/// wl::hotspot_stop(&fake, &mut out, OutputFormat::Text).unwrap();
+///
+/// assert_eq!(b"hotspot: stopped Field/wlan0\n".as_slice(), out);
+/// ```
+///
+/// [`hotspot_start`]: crate::hotspot_start
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn hotspot_stop(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    output_format: OutputFormat,
+) -> Result<Hotspot, crate::Error> {
+    let hotspot = process.stop_hotspot()?;
+
+    match output_format {
+        OutputFormat::Text => {
+            let out_buf = [
+                b"hotspot: stopped ",
+                &hotspot.ssid[..],
+                b"/",
+                hotspot.device.as_bytes(),
+                b"\n",
+            ]
+            .concat();
+            write_bytes(f, &out_buf)?;
+        }
+        _ => output::write_value(
+            f,
+            &StoppedHotspot {
+                ssid: &hotspot.ssid,
+                device: &hotspot.device,
+                stopped: true,
+            },
+        )?,
+    }
+
+    Ok(hotspot)
+}
+
+/// Provides the WiFi hotspot that is started by [`hotspot_start`] by using the given [`Wl`] implementation, and writes it on the given [`io::Write`].
+///
+/// In [`OutputFormat::Text`], each field of the hotspot is written on its own line:
+///
+/// ```text
+/// hotspot: active
+/// ssid: Field
+/// device: wlan0
+/// band: bg
+/// channel: 6
+/// security: wpa-psk
+/// clients: aa:bb:cc:dd:ee:ff/10.42.0.23 (phone)
+/// ```
+///
+/// The band and the channel are `auto` when they are chosen by the network backend, and the clients are `unknown` when the network backend does not expose them.
+/// If the hotspot is not active, then only `hotspot: inactive` is written.
+///
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], the hotspot is a single JSON object:
+///
+/// ```json
+/// {"active":true,"ssid":"Field","device":"wlan0","band":"bg","channel":6,"secured":true,"clients":[{"mac":"aa:bb:cc:dd:ee:ff","ip":"10.42.0.23","hostname":"phone"}]}
+/// ```
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the hotspot cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new();
+///
+/// let mut out = Vec::new();
+/// let hotspot = wl::hotspot_status(&fake, &mut out, OutputFormat::Json).unwrap();
+///
+/// assert_eq!(None, hotspot);
+/// assert_eq!(b"{\"active\":false}\n".as_slice(), out);
+/// ```
+///
+/// [`hotspot_start`]: crate::hotspot_start
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn hotspot_status(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    output_format: OutputFormat,
+) -> Result<Option<Hotspot>, crate::Error> {
+    let hotspot = process.get_hotspot()?;
+
+    write_hotspot(f, hotspot.as_ref(), output_format)?;
+
+    Ok(hotspot)
+}
+
+fn write_hotspot(
+    f: &mut impl io::Write,
+    hotspot: Option<&Hotspot>,
+    output_format: OutputFormat,
+) -> Result<(), io::Error> {
+    if output_format != OutputFormat::Text {
+        let status = HotspotStatus {
+            active: hotspot.is_some(),
+            hotspot,
+        };
+        return output::write_value(f, &status);
+    }
+
+    let Some(hotspot) = hotspot else {
+        return write_bytes(f, b"hotspot: inactive\n");
+    };
+
+    let band = hotspot
+        .band
+        .map(|b| b.to_string())
+        .unwrap_or(String::from("auto"));
+    let channel = hotspot
+        .channel
+        .map(|c| c.to_string())
+        .unwrap_or(String::from("auto"));
+    let security = if hotspot.secured { "wpa-psk" } else { "open" };
+    let clients = match &hotspot.clients {
+        None => String::from("unknown"),
+        Some(clients) if clients.is_empty() => String::from("none"),
+        Some(clients) => clients
+            .iter()
+            .map(|c| {
+                let mut client = c.mac.clone();
+                if let Some(ip) = &c.ip {
+                    client = format!("{}/{}", client, ip);
+                }
+                if let Some(hostname) = &c.hostname {
+                    client = format!("{} ({})", client, hostname);
+                }
+                client
+            })
+            .collect::<Vec<String>>()
+            .join(", "),
+    };
+
+    let out_buf = [
+        b"hotspot: active\nssid: ",
+        &hotspot.ssid[..],
+        b"\ndevice: ",
+        hotspot.device.as_bytes(),
+        b"\nband: ",
+        band.as_bytes(),
+        b"\nchannel: ",
+        channel.as_bytes(),
+        b"\nsecurity: ",
+        security.as_bytes(),
+        b"\nclients: ",
+        clients.as_bytes(),
+        b"\n",
+    ]
+    .concat();
+
+    write_bytes(f, &out_buf)
+}
@@ -0,0 +1,920 @@
+use std::{
+    collections::HashMap,
+    io::{self},
+    str, thread,
+    time::Duration,
+};
+
+use zbus::{
+    blocking::{Connection, Proxy, proxy::Builder},
+    interface,
+    proxy::CacheProperties,
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue},
+};
+
+use crate::{
+    AccessPoint, Network, Security, WifiStatus,
+    access_point::{channel_from_frequency, signal_from_dbm},
+    adapter::{Error, UNKNOWN_SSID_EXIT_CODE, Wl, unsupported},
+    runner::{Runner, SystemRunner},
+};
+
+/// The escape byte that starts the color sequences of `iwctl`.
+const ESCAPE: u8 = 0x1B;
+
+/// The maximum number of `station list` polls to wait for a scan to complete.
+const MAX_SCAN_POLLS: usize = 20;
+
+/// The interval between two `station list` polls.
+const SCAN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The well-known bus name of iwd.
+const IWD_BUS_NAME: &str = "net.connman.iwd";
+
+const IWD_PATH: &str = "/net/connman/iwd";
+const AGENT_MANAGER_IFACE: &str = "net.connman.iwd.AgentManager";
+const DEVICE_IFACE: &str = "net.connman.iwd.Device";
+const STATION_IFACE: &str = "net.connman.iwd.Station";
+const NETWORK_IFACE: &str = "net.connman.iwd.Network";
+const OBJECT_MANAGER_IFACE: &str = "org.freedesktop.DBus.ObjectManager";
+
+/// The object path of the agent that provides the passphrase of a new network to iwd.
+const AGENT_PATH: &str = "/org/wl/iwd/agent";
+
+/// The interfaces and their properties of the objects of iwd, as provided by `GetManagedObjects`.
+type ManagedObjects = HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>>;
+
+/// The adapter struct that implements [`Wl`] by using `iwctl` (iwd).
+///
+/// `iwctl` requires a device for most of its commands.
+/// By default, the first device in station mode is used.
+/// To use a specific device, initialize the struct with [`Iwd::with_device`].
+///
+/// `iwctl` is run through a [`Runner`], which is [`SystemRunner`] by default.
+/// To replace `iwctl` with canned outputs (e.g. in tests), initialize the struct with [`Iwd::with_runner`].
+///
+/// `iwctl` can only read a passphrase from its arguments or from a terminal prompt, so the new secured networks are connected over D-Bus instead,
+/// and their passphrases are provided by an agent that is registered on the same connection. The system bus is used by default.
+/// To use another D-Bus connection, set it with [`Iwd::with_connection`].
+///
+/// Since the struct can be changed in future versions, always
+/// prefer to initialize it by using [`Iwd::new`], [`Iwd::with_device`] or [`Iwd::with_runner`] instead.
+///
+/// [`Wl`]: crate::Wl
+/// [`Runner`]: crate::runner::Runner
+/// [`SystemRunner`]: crate::runner::SystemRunner
+/// [`Iwd::new`]: crate::Iwd::new
+/// [`Iwd::with_device`]: crate::Iwd::with_device
+/// [`Iwd::with_runner`]: crate::Iwd::with_runner
+/// [`Iwd::with_connection`]: crate::Iwd::with_connection
+#[derive(Clone, Default)]
+pub struct Iwd<R = SystemRunner> {
+    device: Option<String>,
+    runner: R,
+    connection: Option<Connection>,
+}
+
+/// The `net.connman.iwd.Agent` that provides the passphrase of a new network to iwd.
+struct PassphraseAgent {
+    passphrase: String,
+}
+
+#[interface(name = "net.connman.iwd.Agent")]
+impl PassphraseAgent {
+    fn release(&self) {}
+
+    fn request_passphrase(&self, _network: ObjectPath<'_>) -> String {
+        self.passphrase.clone()
+    }
+
+    fn cancel(&self, _reason: &str) {}
+}
+
+/// Represents a row of the `iwctl device list` output.
+struct Device {
+    name: String,
+    powered: bool,
+}
+
+impl Iwd {
+    /// Creates a new `Iwd` instance that uses the first device in station mode.
+    ///
+    /// The instance created by `new` can be reused multiple times
+    /// in a given context. It can also be cloned freely.
+    pub fn new() -> Self {
+        Self {
+            device: None,
+            runner: SystemRunner,
+            connection: None,
+        }
+    }
+
+    /// Creates a new `Iwd` instance that uses the given device (e.g. `wlan0`).
+    pub fn with_device(device: &str) -> Self {
+        Self {
+            device: Some(device.to_string()),
+            runner: SystemRunner,
+            connection: None,
+        }
+    }
+}
+
+impl<R: Runner> Iwd<R> {
+    /// Creates a new `Iwd` instance that runs `iwctl` through the given [`Runner`], and uses the first device in station mode.
+    ///
+    /// [`Runner`]: crate::runner::Runner
+    pub fn with_runner(runner: R) -> Self {
+        Self {
+            device: None,
+            runner,
+            connection: None,
+        }
+    }
+
+    /// Sets the D-Bus connection that connects to the new secured networks, instead of the system bus.
+    ///
+    /// This is useful to talk to an iwd service that is not on the system bus.
+    pub fn with_connection(mut self, connection: Connection) -> Self {
+        self.connection = Some(connection);
+        self
+    }
+
+    /// Provides the [`Runner`] of the instance.
+    ///
+    /// [`Runner`]: crate::runner::Runner
+    pub fn runner(&self) -> &R {
+        &self.runner
+    }
+
+    fn exec(&self, args: &[&[u8]]) -> Result<Vec<u8>, (io::Error, i32)> {
+        let default_ecode = 1i32;
+        let cmd = self
+            .runner
+            .run("iwctl", args)
+            .map_err(|err| (err, default_ecode))?;
+
+        if !cmd.status.success() {
+            // `iwctl` writes some of its errors to stdout.
+            let iwctl_err = [&cmd.stderr[..], &cmd.stdout[..]].concat();
+            let iwctl_err = String::from_utf8_lossy(&strip_colors(&iwctl_err))
+                .trim()
+                .to_string();
+            let ecode = cmd.status.code().unwrap_or(default_ecode);
+            return Err((io::Error::other(iwctl_err), ecode));
+        }
+
+        Ok(strip_colors(&cmd.stdout))
+    }
+
+    fn get_device(&self) -> Result<Device, (io::Error, i32)> {
+        let result = self.exec(&[b"device", b"list"])?;
+
+        parse_table(&result, &["Name", "Address", "Powered", "Adapter", "Mode"])
+            .into_iter()
+            .filter(|row| match &self.device {
+                Some(device) => &row[0] == device,
+                None => row[4] == "station",
+            })
+            .map(|row| Device {
+                powered: row[2] == "on",
+                name: row[0].clone(),
+            })
+            .next()
+            .ok_or((
+                io::Error::other("no WiFi device in station mode is found"),
+                1,
+            ))
+    }
+
+    fn get_station_properties(
+        &self,
+        device: &str,
+    ) -> Result<Vec<(String, String)>, (io::Error, i32)> {
+        let result = self.exec(&[b"station", device.as_bytes(), b"show"])?;
+
+        let properties = parse_table(&result, &["Settable", "Property", "Value"])
+            .into_iter()
+            .map(|mut row| (row.remove(1), row.remove(1)))
+            .collect();
+
+        Ok(properties)
+    }
+
+    fn get_connected_ssid(&self, device: &str) -> Result<Option<Vec<u8>>, (io::Error, i32)> {
+        let ssid = self
+            .get_station_properties(device)?
+            .into_iter()
+            .find(|(key, _)| key == "Connected network")
+            .map(|(_, value)| value.into_bytes());
+
+        Ok(ssid)
+    }
+
+    fn connection(&self) -> Result<Connection, (io::Error, i32)> {
+        match &self.connection {
+            Some(conn) => Ok(conn.clone()),
+            None => Connection::system().map_err(dbus_err),
+        }
+    }
+
+    fn proxy<'a>(
+        &self,
+        conn: &Connection,
+        path: &'a str,
+        iface: &'a str,
+    ) -> Result<Proxy<'a>, (io::Error, i32)> {
+        Builder::<Proxy>::new(conn)
+            .destination(IWD_BUS_NAME)
+            .and_then(|b| b.path(path))
+            .and_then(|b| b.interface(iface))
+            .map(|b| b.cache_properties(CacheProperties::No))
+            .and_then(|b| b.build())
+            .map_err(dbus_err)
+    }
+
+    /// Connects the given device to the given SSID over D-Bus, and answers the passphrase requests of iwd with `passwd`.
+    ///
+    /// An agent is registered on the connection while the network is connected, so iwd asks it instead of the agents of the other clients.
+    /// If `hidden` is set, then the SSID is connected with `ConnectHiddenNetwork`. If not, then the network of the SSID is connected with `Connect`.
+    fn connect_with_agent(
+        &self,
+        device: &str,
+        ssid: &[u8],
+        passwd: &[u8],
+        hidden: bool,
+    ) -> Result<(), (io::Error, i32)> {
+        let invalid = |what| {
+            let err = io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("iwd only supports UTF-8 {what}"),
+            );
+            (err, 2)
+        };
+        let ssid = str::from_utf8(ssid).map_err(|_| invalid("SSIDs"))?;
+        let passphrase = str::from_utf8(passwd).map_err(|_| invalid("passphrases"))?;
+
+        let conn = self.connection()?;
+        let objects: ManagedObjects = self
+            .proxy(&conn, "/", OBJECT_MANAGER_IFACE)?
+            .call("GetManagedObjects", &())
+            .map_err(dbus_err)?;
+
+        let property = |ifaces: &HashMap<String, HashMap<String, OwnedValue>>, iface, key| {
+            ifaces
+                .get(iface)
+                .and_then(|props| props.get(key))
+                .and_then(|v| v.try_clone().ok())
+        };
+        let station = objects
+            .iter()
+            .find(|(_, ifaces)| {
+                property(ifaces, DEVICE_IFACE, "Name")
+                    .and_then(|v| String::try_from(v).ok())
+                    .is_some_and(|name| name == device)
+            })
+            .map(|(path, _)| path.clone())
+            .ok_or_else(|| {
+                let err = io::Error::other(format!("device '{device}' is not found"));
+                (err, 1)
+            })?;
+
+        let (path, iface, method) = match hidden {
+            true => (station, STATION_IFACE, "ConnectHiddenNetwork"),
+            false => {
+                let network = objects
+                    .iter()
+                    .find(|(_, ifaces)| {
+                        let name = property(ifaces, NETWORK_IFACE, "Name")
+                            .and_then(|v| String::try_from(v).ok());
+                        let dev = property(ifaces, NETWORK_IFACE, "Device")
+                            .and_then(|v| OwnedObjectPath::try_from(v).ok());
+                        name.as_deref() == Some(ssid) && dev.as_ref() == Some(&station)
+                    })
+                    .map(|(path, _)| path.clone())
+                    .ok_or_else(|| {
+                        let err = io::Error::other(format!("No network with SSID '{ssid}' found."));
+                        (err, UNKNOWN_SSID_EXIT_CODE)
+                    })?;
+
+                (network, NETWORK_IFACE, "Connect")
+            }
+        };
+
+        let target = self.proxy(&conn, path.as_str(), iface)?;
+        let agent_manager = self.proxy(&conn, IWD_PATH, AGENT_MANAGER_IFACE)?;
+        let agent_path = ObjectPath::from_static_str_unchecked(AGENT_PATH);
+        let agent = PassphraseAgent {
+            passphrase: passphrase.to_string(),
+        };
+        conn.object_server()
+            .at(AGENT_PATH, agent)
+            .map_err(dbus_err)?;
+
+        let result = agent_manager
+            .call::<_, _, ()>("RegisterAgent", &(&agent_path,))
+            .and_then(|()| {
+                let result = match hidden {
+                    true => target.call::<_, _, ()>(method, &(ssid,)),
+                    false => target.call::<_, _, ()>(method, &()),
+                };
+                let _ = agent_manager.call::<_, _, ()>("UnregisterAgent", &(&agent_path,));
+                result
+            });
+        let _ = conn
+            .object_server()
+            .remove::<PassphraseAgent, _>(AGENT_PATH);
+
+        result.map_err(dbus_err)
+    }
+
+    fn wait_for_scan(&self, device: &str) -> Result<(), (io::Error, i32)> {
+        for _ in 0..MAX_SCAN_POLLS {
+            thread::sleep(SCAN_POLL_INTERVAL);
+
+            let result = self.exec(&[b"station", b"list"])?;
+            let is_scanning = parse_table(&result, &["Name", "State", "Scanning"])
+                .into_iter()
+                .any(|row| row[0] == device && row[2] == "scanning");
+
+            if !is_scanning {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Runner> Wl for Iwd<R> {
+    /// Provides the WiFi status.
+    ///
+    /// The WiFi status is the `Powered` property of the device.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetWiFiStatus`] when it fails
+    /// to obtain the WiFi status.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Iwd, Wl};
+    ///
+    /// let iwd = Iwd::new();
+    /// let status = iwd.get_wifi_status().unwrap();
+    /// println!("wifi: {}", status);
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetWiFiStatus`]: crate::NetworkAdapterError::CannotGetWiFiStatus
+    fn get_wifi_status(&self) -> Result<WifiStatus, Error> {
+        let device = self.get_device().map_err(Error::CannotGetWiFiStatus)?;
+
+        Ok(if device.powered {
+            WifiStatus::Enabled
+        } else {
+            WifiStatus::Disabled
+        })
+    }
+
+    /// Toggles the WiFi status.
+    ///
+    /// The WiFi status is toggled by setting the `Powered` property of the device.
+    /// It returns the updated WiFi status.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method can return either a [`NetworkAdapterError::CannotGetWiFiStatus`] or a [`NetworkAdapterError::CannotToggleWiFi`] when it fails to toggle WiFi.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Iwd, Wl};
+    ///
+    /// let iwd = Iwd::new();
+    /// let updated_status = iwd.toggle_wifi().unwrap();
+    /// println!("wifi: {}", updated_status);
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetWiFiStatus`]: crate::NetworkAdapterError::CannotGetWiFiStatus
+    /// [`NetworkAdapterError::CannotToggleWiFi`]: crate::NetworkAdapterError::CannotToggleWiFi
+    fn toggle_wifi(&self) -> Result<WifiStatus, Error> {
+        let device = self.get_device().map_err(Error::CannotGetWiFiStatus)?;
+
+        let (powered, new_status) = if device.powered {
+            ("off", WifiStatus::Disabled)
+        } else {
+            ("on", WifiStatus::Enabled)
+        };
+
+        let args = ["device", &device.name, "set-property", "Powered", powered];
+
+        let _ = self
+            .exec(&args.map(|a| a.as_bytes()))
+            .map_err(Error::CannotToggleWiFi)?;
+
+        Ok(new_status)
+    }
+
+    /// Provides the known networks on the host.
+    ///
+    /// The known networks are obtained with `iwctl known-networks list`.
+    /// Since iwd does not assign identifiers to its known networks, the `uuid` of each [`Network`] is empty.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`NetworkAdapterError::CannotListNetworks`] when it fails to retrieve the networks, or a [`NetworkAdapterError::CannotGetActiveConnections`] if `active_only` is set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Iwd, Wl};
+    ///
+    /// let iwd = Iwd::new();
+    /// let networks = iwd.get_networks(false).unwrap();
+    ///
+    /// for network in networks {
+    ///     println!("{}", String::from_utf8_lossy(&network.ssid));
+    /// }
+    /// ```
+    ///
+    /// [`Network`]: crate::Network
+    /// [`NetworkAdapterError::CannotListNetworks`]: crate::NetworkAdapterError::CannotListNetworks
+    /// [`NetworkAdapterError::CannotGetActiveConnections`]: crate::NetworkAdapterError::CannotGetActiveConnections
+    fn get_networks(&self, active_only: bool) -> Result<Vec<Network>, Error> {
+        let map_err = if active_only {
+            Error::CannotGetActiveConnections
+        } else {
+            Error::CannotListNetworks
+        };
+
+        let device = self.get_device().map_err(map_err)?;
+        let connected_ssid = if device.powered {
+            self.get_connected_ssid(&device.name).map_err(map_err)?
+        } else {
+            None
+        };
+
+        let result = self.exec(&[b"known-networks", b"list"]).map_err(map_err)?;
+
+        let networks = parse_table(&result, &["Name", "Security", "Hidden", "Last connected"])
+            .into_iter()
+            .map(|row| {
+                let ssid = row[0].as_bytes().to_vec();
+                let is_active = connected_ssid.as_ref() == Some(&ssid);
+
+                Network {
+                    ssid,
+                    uuid: String::new(),
+                    kind: String::from("wifi"),
+                    device: is_active.then(|| device.name.clone()),
+                }
+            })
+            .filter(|n| !active_only || n.is_active())
+            .collect();
+
+        Ok(networks)
+    }
+
+    /// Disconnects from the given SSID.
+    ///
+    /// Since iwd connects a device to a single network at a time, the device is disconnected only if it is connected to the given SSID.
+    /// If `forget` is set, then the SSID is removed from the known networks, regardless of the connection.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`NetworkAdapterError::CannotDisconnect`] when it fails to disconnect, or when the given SSID is not active and `forget` is not set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Iwd, Wl};
+    /// use std::io::{self, Write};
+    ///
+    /// let iwd = Iwd::new();
+    /// let result = iwd.disconnect(b"SSID", false).unwrap();
+    /// io::stdout().write_all(&result).unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotDisconnect`]: crate::NetworkAdapterError::CannotDisconnect
+    fn disconnect(&self, ssid: &[u8], forget: bool) -> Result<Vec<u8>, Error> {
+        let device = self.get_device().map_err(Error::CannotDisconnect)?;
+        let connected_ssid = self
+            .get_connected_ssid(&device.name)
+            .map_err(Error::CannotDisconnect)?;
+
+        let is_active = connected_ssid.as_deref() == Some(ssid);
+
+        if is_active {
+            let args = [b"station", device.name.as_bytes(), b"disconnect"];
+            self.exec(&args).map_err(Error::CannotDisconnect)?;
+        } else if !forget {
+            let err = io::Error::other(format!(
+                "{} is not an active network",
+                String::from_utf8_lossy(ssid)
+            ));
+            return Err(Error::CannotDisconnect((err, UNKNOWN_SSID_EXIT_CODE)));
+        }
+
+        if forget {
+            let args = [b"known-networks", ssid, b"forget"];
+            self.exec(&args).map_err(Error::CannotDisconnect)?;
+        }
+
+        let action = if forget { "forgotten" } else { "disconnected" };
+        let result = [
+            b"Network '",
+            ssid,
+            b"' is successfully ",
+            action.as_bytes(),
+            b".\n",
+        ]
+        .concat();
+
+        Ok(result)
+    }
+
+    /// Provides the access points that are visible to the host.
+    ///
+    /// The access points are obtained with `iwctl station DEVICE get-networks rssi-dbms`.
+    /// iwd groups the access points by their SSID, which means that each SSID is represented by a single [`AccessPoint`].
+    ///
+    /// iwd only provides the BSSID, frequency and rate of the connected access point.
+    /// For the rest of them, these fields are left empty.
+    ///
+    /// If `re_scan` is set, then `get_access_points` triggers a scan and waits for it to complete.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotScanWiFi`] if it fails to scan the available networks.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Iwd, Wl};
+    ///
+    /// let iwd = Iwd::new();
+    /// let aps = iwd.get_access_points(true).unwrap();
+    ///
+    /// for ap in aps {
+    ///     println!("{} ({})", String::from_utf8_lossy(&ap.ssid), ap.signal);
+    /// }
+    /// ```
+    ///
+    /// [`AccessPoint`]: crate::AccessPoint
+    /// [`NetworkAdapterError::CannotScanWiFi`]: crate::NetworkAdapterError::CannotScanWiFi
+    fn get_access_points(&self, re_scan: bool) -> Result<Vec<AccessPoint>, Error> {
+        let device = self.get_device().map_err(Error::CannotScanWiFi)?;
+        let dev = device.name.as_bytes();
+
+        if re_scan {
+            self.exec(&[b"station", dev, b"scan"])
+                .map_err(Error::CannotScanWiFi)?;
+            self.wait_for_scan(&device.name)
+                .map_err(Error::CannotScanWiFi)?;
+        }
+
+        let result = self
+            .exec(&[b"station", dev, b"get-networks", b"rssi-dbms"])
+            .map_err(Error::CannotScanWiFi)?;
+
+        let connected = self
+            .get_station_properties(&device.name)
+            .map_err(Error::CannotScanWiFi)?;
+        let connected_property = |key: &str| {
+            connected
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+
+        let aps = parse_table_with_marker(&result, &["Network name", "Security", "Signal"])
+            .into_iter()
+            .map(|(in_use, row)| {
+                let mut ap = AccessPoint {
+                    ssid: row[0].as_bytes().to_vec(),
+                    mode: String::from("Infra"),
+                    signal: signal_from_dbm(parse_dbm(&row[2])),
+                    security: security_from_iwd(&row[1]).to_string(),
+                    in_use,
+                    ..Default::default()
+                };
+
+                if in_use {
+                    ap.bssid = connected_property("ConnectedBss")
+                        .unwrap_or_default()
+                        .to_ascii_uppercase();
+                    ap.frequency = connected_property("Frequency")
+                        .and_then(|f| f.split_whitespace().next()?.parse().ok())
+                        .unwrap_or_default();
+                    ap.channel = channel_from_frequency(ap.frequency);
+                    ap.rate = connected_property("TxBitrate")
+                        .and_then(|r| r.split_whitespace().next()?.parse::<u32>().ok())
+                        .map(|kbps| kbps / 1000)
+                        .unwrap_or_default();
+                }
+
+                ap
+            })
+            .collect();
+
+        Ok(aps)
+    }
+
+    /// Checks whether the given SSID is a known one or not.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetSSIDStatus`] if it fails to check the SSID.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Iwd, Wl};
+    ///
+    /// let iwd = Iwd::new();
+    /// let is_known_ssid = iwd.is_known_ssid(b"SSID").unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetSSIDStatus`]: crate::NetworkAdapterError::CannotGetSSIDStatus
+    fn is_known_ssid(&self, ssid: &[u8]) -> Result<bool, Error> {
+        let result = self
+            .exec(&[b"known-networks", b"list"])
+            .map_err(Error::CannotGetSSIDStatus)?;
+
+        let exists = parse_table(&result, &["Name", "Security", "Hidden", "Last connected"])
+            .into_iter()
+            .any(|row| row[0].as_bytes() == ssid);
+
+        Ok(exists)
+    }
+
+    /// Connects to the given SSID.
+    ///
+    /// The semantics are the same as [`Nmcli::connect`]:
+    ///
+    /// - To establish a new connection, provide both `ssid` and `passwd`.
+    /// - To re-use a known network, only provide `ssid`.
+    /// - To "update" a known network, provide all the arguments. The known network is forgotten before the new connection attempt.
+    ///
+    /// `iwctl` is called with `--dont-ask`, so it never prompts for the missing credentials.
+    ///
+    /// If `passwd` is provided, then the network is connected over D-Bus, and `passwd` is provided to iwd by an agent, so it never appears on a command line.
+    ///
+    /// `iwd` chooses the access point of the SSID by itself, and its known networks cannot be pinned to an access point.
+    /// Therefore, `bssid` is not supported.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotConnect`] if it fails to connect to the the SSID, or `bssid` is provided.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Iwd, Wl};
+    /// use std::io::{self, Write};
+    ///
+    /// let iwd = Iwd::new();
+    /// let result = iwd.connect(b"SSID", Some(b"PASS"), None, false).unwrap();
+    /// io::stdout().write_all(&result).unwrap();
+    /// ```
+    ///
+    /// [`Nmcli::connect`]: crate::Nmcli::connect
+    /// [`NetworkAdapterError::CannotConnect`]: crate::NetworkAdapterError::CannotConnect
+    fn connect(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        bssid: Option<&str>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        if bssid.is_some() {
+            return Err(Error::CannotConnect(unsupported("pinning access points")));
+        }
+
+        if is_known_ssid && passwd.is_some() {
+            self.exec(&[b"known-networks", ssid, b"forget"])
+                .map_err(Error::CannotConnect)?;
+        }
+
+        let device = self.get_device().map_err(Error::CannotConnect)?;
+        let dev = device.name.as_bytes();
+
+        match passwd {
+            Some(passwd) => self.connect_with_agent(&device.name, ssid, passwd, false),
+            None => self
+                .exec(&[b"--dont-ask", b"station", dev, b"connect", ssid])
+                .map(|_| ()),
+        }
+        .map_err(Error::CannotConnect)?;
+
+        let result = [
+            b"Device '",
+            dev,
+            b"' is successfully connected to '",
+            ssid,
+            b"'.\n",
+        ]
+        .concat();
+
+        Ok(result)
+    }
+
+    /// Connects to the given hidden SSID.
+    ///
+    /// The SSID is connected with `iwctl station <device> connect-hidden`, and the known network is forgotten before if `is_known_ssid` is set.
+    ///
+    /// `iwd` reads the security type from the probe response of the access point, so `security` only determines whether `passwd` is passed as the passphrase or not.
+    /// Like [`Iwd::connect`], a secured SSID is connected over D-Bus, and `passwd` is provided to iwd by an agent.
+    /// The hidden network is remembered by `iwd` as a known network once it is connected.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotConnect`] if it fails to connect to the the SSID.
+    ///
+    /// [`Iwd::connect`]: crate::Iwd::connect
+    /// [`NetworkAdapterError::CannotConnect`]: crate::NetworkAdapterError::CannotConnect
+    fn connect_hidden(
+        &self,
+        ssid: &[u8],
+        security: Security,
+        passwd: Option<&[u8]>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        if is_known_ssid {
+            self.exec(&[b"known-networks", ssid, b"forget"])
+                .map_err(Error::CannotConnect)?;
+        }
+
+        let device = self.get_device().map_err(Error::CannotConnect)?;
+        let dev = device.name.as_bytes();
+
+        match (security.is_secured(), passwd) {
+            (true, Some(passwd)) => self.connect_with_agent(&device.name, ssid, passwd, true),
+            _ => self
+                .exec(&[b"--dont-ask", b"station", dev, b"connect-hidden", ssid])
+                .map(|_| ()),
+        }
+        .map_err(Error::CannotConnect)?;
+
+        let result = [
+            b"Device '",
+            dev,
+            b"' is successfully connected to '",
+            ssid,
+            b"'.\n",
+        ]
+        .concat();
+
+        Ok(result)
+    }
+}
+
+fn dbus_err(err: zbus::Error) -> (io::Error, i32) {
+    (io::Error::other(err), 1)
+}
+
+/// Removes the color sequences (`ESC [ ... m`) from the given `iwctl` output.
+fn strip_colors(out: &[u8]) -> Vec<u8> {
+    let mut stripped = Vec::with_capacity(out.len());
+    let mut bytes = out.iter().peekable();
+
+    while let Some(b) = bytes.next() {
+        if *b == ESCAPE && bytes.peek() == Some(&&b'[') {
+            for b in bytes.by_ref() {
+                if (0x40..=0x7E).contains(b) && *b != b'[' {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(*b);
+        }
+    }
+
+    stripped
+}
+
+/// Parses a table of an `iwctl` output.
+///
+/// `iwctl` tables consist of a title, a header and rows, which are separated by dashed lines.
+/// The columns are aligned with the header, so each value is sliced by the position of its column.
+fn parse_table(out: &[u8], columns: &[&str]) -> Vec<Vec<String>> {
+    parse_table_with_marker(out, columns)
+        .into_iter()
+        .map(|(_, row)| row)
+        .collect()
+}
+
+/// Parses a table of an `iwctl` output, along with the `>` markers that are put
+/// in front of the rows (e.g. the connected network).
+fn parse_table_with_marker(out: &[u8], columns: &[&str]) -> Vec<(bool, Vec<String>)> {
+    let out = String::from_utf8_lossy(out);
+    let mut lines = out
+        .lines()
+        .skip_while(|l| !l.trim_start().starts_with(columns[0]));
+
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+
+    let positions = columns
+        .iter()
+        .map(|c| header.find(c).map(|idx| header[..idx].chars().count()))
+        .collect::<Option<Vec<usize>>>();
+
+    let Some(positions) = positions else {
+        return Vec::new();
+    };
+
+    lines
+        .filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with("---"))
+        .map(|l| {
+            let chars = l.chars().collect::<Vec<char>>();
+            let slice = |start: usize, end: usize| {
+                let start = start.min(chars.len());
+                let end = end.min(chars.len());
+                chars[start..end]
+                    .iter()
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            };
+
+            let marker = slice(0, positions[0]).contains('>');
+            let row = positions
+                .iter()
+                .enumerate()
+                .map(|(idx, start)| {
+                    let end = positions.get(idx + 1).copied().unwrap_or(chars.len());
+                    slice(*start, end)
+                })
+                .collect();
+
+            (marker, row)
+        })
+        .collect()
+}
+
+/// Parses the RSSI of `get-networks rssi-dbms`.
+///
+/// Depending on the iwd version, the value is either in dBm or in 100 * dBm.
+fn parse_dbm(value: &str) -> i32 {
+    let dbm = value
+        .split_whitespace()
+        .next()
+        .and_then(|v| v.parse::<i32>().ok())
+        .unwrap_or(-100);
+
+    if dbm < -1000 { dbm / 100 } else { dbm }
+}
+
+/// Converts the iwd security types into the ones that are used by [`AccessPoint`].
+///
+/// [`AccessPoint`]: crate::AccessPoint
+fn security_from_iwd(security: &str) -> &str {
+    match security {
+        "open" => "",
+        "psk" => "WPA2",
+        "8021x" => "802.1X",
+        "wep" => "WEP",
+        "owe" => "OWE",
+        security => security,
+    }
+}
@@ -3,9 +3,15 @@
 //! The WiFi functionality it exposes are executed by the network backends.
 //! Here is a list of network backends that are supported:
 //!
+//! - [`NetworkManager`] (D-Bus)
 //! - [`nmcli`] (NetworkManager)
+//! - [`iwd`] (`iwctl`)
+//! - [`wpa_supplicant`] (control interface)
 //!
 //! To see the interface for each network backend, check out the [`Wl`] trait.
+//! The network backend is detected at runtime by [`detect_backend`], which probes the backends in a configurable order.
+//! For testing purposes, [`FakeWl`] simulates a host in memory.
+//! The results of each network backend are represented by typed models such as [`WifiStatus`], [`Network`] and [`AccessPoint`], and rendered by [`format`].
 //! To see the available functionality, check out the corresponding functions below:
 //!
 //! - [`status`]
@@ -14,36 +20,145 @@
 //! - [`scan`]
 //! - [`connect`]
 //! - [`disconnect`]
+//! - [`unpin`]
+//! - [`profile_show`], [`profile_edit`] and [`profile_rename`]
+//! - [`priority_list`], [`priority_set`], [`priority_up`] and [`priority_down`]
+//! - [`export`], [`import`] and [`import_from`]
+//! - [`backup`] and [`restore`]
+//! - [`hotspot_start`], [`hotspot_stop`] and [`hotspot_status`]
+//! - [`share`]
+//! - [`watch`]
+//! - [`config_show`]
+//! - [`completions`], [`man`] and [`complete_ssids`]
 //!
+//! When stdin is a terminal, [`connect`] and [`disconnect`] select the SSID on the full-screen [`picker`].
+//!
+//! These functions use stdin and stdout/stderr. To embed `wl` into another program, use their `_with` variants instead (e.g. [`status_with`]).
+//! They take an [`io::Write`] for the output and an [`io::BufRead`] for the prompt answers, and return the results as typed reports such as [`StatusReport`].
+//!
+//! The failures of each function are represented by [`Error`], which categorizes them into an [`ErrorKind`] with a stable exit code.
+//!
+//! [`NetworkManager`]: crate::NetworkManager
 //! [`nmcli`]: crate::Nmcli
+//! [`iwd`]: crate::Iwd
+//! [`wpa_supplicant`]: crate::WpaSupplicant
 //! [`Wl`]: crate::Wl
+//! [`detect_backend`]: crate::detect_backend
+//! [`FakeWl`]: crate::FakeWl
+//! [`WifiStatus`]: crate::WifiStatus
+//! [`Network`]: crate::Network
+//! [`AccessPoint`]: crate::AccessPoint
+//! [`format`]: crate::format
 //! [`status`]: crate::status
 //! [`toggle`]: crate::toggle
 //! [`list_networks`]: crate::list_networks
 //! [`scan`]: crate::scan
 //! [`connect`]: crate::connect
 //! [`disconnect`]: crate::disconnect
+//! [`unpin`]: crate::unpin
+//! [`profile_show`]: crate::profile_show
+//! [`profile_edit`]: crate::profile_edit
+//! [`profile_rename`]: crate::profile_rename
+//! [`priority_list`]: crate::priority_list
+//! [`priority_set`]: crate::priority_set
+//! [`priority_up`]: crate::priority_up
+//! [`priority_down`]: crate::priority_down
+//! [`export`]: crate::export
+//! [`import`]: crate::import
+//! [`import_from`]: crate::import_from
+//! [`backup`]: crate::backup
+//! [`restore`]: crate::restore
+//! [`hotspot_start`]: crate::hotspot_start
+//! [`hotspot_stop`]: crate::hotspot_stop
+//! [`hotspot_status`]: crate::hotspot_status
+//! [`share`]: crate::share
+//! [`watch`]: crate::watch
+//! [`config_show`]: crate::config_show
+//! [`completions`]: crate::completions
+//! [`man`]: crate::man
+//! [`complete_ssids`]: crate::complete_ssids
+//! [`picker`]: crate::picker
+//! [`status_with`]: crate::status_with
+//! [`io::Write`]: std::io::Write
+//! [`io::BufRead`]: std::io::BufRead
+//! [`StatusReport`]: crate::StatusReport
+//! [`Error`]: crate::Error
+//! [`ErrorKind`]: crate::ErrorKind
 
+mod access_point;
 mod adapter;
 pub mod api;
+mod backup;
+mod completions;
+mod config;
 mod connect;
 mod disconnect;
+mod error;
+mod export;
+pub mod fake;
+pub mod format;
+mod hotspot;
+mod iwd;
 mod list_networks;
+mod migrate;
+mod network;
+mod network_manager;
 mod nmcli;
+mod output;
+pub mod picker;
+mod priority;
+mod profile;
+pub mod runner;
 mod scan;
+mod share;
 mod status;
 mod toggle;
+mod unpin;
+mod watch;
+mod wpa_supplicant;
 
+pub use access_point::AccessPoint;
 pub use adapter::{
-    CARRIAGE_RETURN, Decimal, Error as NetworkAdapterError, LINE_FEED, LOOPBACK_INTERFACE_NAME, Wl,
+    Backend, CARRIAGE_RETURN, DEFAULT_BACKEND_ORDER, Decimal, Error as NetworkAdapterError,
+    HOTSPOT_PROFILE, LINE_FEED, LOOPBACK_INTERFACE_NAME, NO_BACKEND_EXIT_CODE,
+    UNKNOWN_SSID_EXIT_CODE, Wl, new as detect_backend,
+};
+pub use backup::{BACKUP_VERSION, Error as BackupError, backup, restore};
+pub use completions::{complete_ssids, completions, man, man_to};
+pub use config::{Alias, CONFIG_ENV, Config, Error as ConfigError, ScanDefaults, config_show};
+pub use connect::{
+    Auth, ConnectReport, Error as ConnectError, PasswdSource, connect, connect_with, read_passwd,
 };
-pub use connect::{Error as ConnectError, connect};
-pub use disconnect::{Error as DisconnectError, disconnect};
-pub use list_networks::list_networks;
+pub use disconnect::{DisconnectReport, Error as DisconnectError, disconnect, disconnect_with};
+pub use error::{Error, ErrorKind};
+pub use export::{
+    EXPORT_VERSION, Error as ImportError, ExportReport, ImportStatus, ImportedNetwork, export,
+    import,
+};
+pub use fake::FakeWl;
+pub use format::Error as FormatError;
+pub use hotspot::{Error as HotspotError, hotspot_start, hotspot_status, hotspot_stop};
+pub use iwd::Iwd;
+pub use list_networks::{list_networks, list_networks_with};
+pub use migrate::{import_from, parse_iwd_profile, parse_wpa_supplicant_conf};
+pub use network::{
+    Band, EapCredentials, EapMethod, ExportedNetwork, Hotspot, HotspotClient, IpMethod,
+    MacRandomization, Metered, Network, NetworkPriority, Phase2Auth, Profile, ProfileEdit,
+    Security, WifiStatus,
+};
+pub use network_manager::NetworkManager;
 pub use nmcli::Nmcli;
+pub use priority::{
+    Error as PriorityError, priority_down, priority_list, priority_set, priority_up,
+};
+pub use profile::{Error as ProfileError, profile_edit, profile_rename, profile_show};
 pub use scan::{Error as ScanError, scan};
-pub use status::status;
-pub use toggle::toggle;
+pub use share::{Error as ShareError, share, share_payload};
+pub use status::{ActiveConnection, StatusReport, status, status_with};
+pub use toggle::{toggle, toggle_with};
+pub use unpin::unpin;
+pub use watch::{Direction, Error as WatchError, Event, Notification, watch};
+pub use wpa_supplicant::WpaSupplicant;
 
 use std::io;
 
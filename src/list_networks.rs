@@ -1,36 +1,123 @@
-use std::{error, io};
+use std::io;
 
-use crate::{
-    adapter::{self, Wl},
-    write_bytes,
-};
+use serde::Serialize;
 
-/// Provides the list of known WiFi networks by using a [`Wl`] implementation.
+use crate::{Error, Network, adapter::Wl, api::OutputFormat, format, output};
+
+#[derive(Serialize)]
+struct Ssid {
+    #[serde(serialize_with = "output::serialize_ssid")]
+    ssid: Vec<u8>,
+}
+
+/// Provides the list of known WiFi networks by using the given [`Wl`] implementation.
 /// To see the available networks to connect, please refer to [`scan`] instead.
 ///
 /// The list is written to stdout stream.
 ///
-/// The default list format depends on the [`Wl`] implementation.
 /// If desired, the list can be filtered in two different ways:
 ///
 /// - By showing the SSIDs only,
 /// - By showing the active network connections only.
 ///
+/// In [`OutputFormat::Text`], the list is written as a table (see [`format::write_network_table`]).
+///
+/// In [`OutputFormat::Json`], the list is written as a JSON array of objects, and in [`OutputFormat::Ndjson`] each object is written on its own line:
+///
+/// ```json
+/// {"ssid":"SSID1","uuid":"xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx","type":"wifi","device":"wlan0","active":true}
+/// ```
+///
+/// If the SSIDs are requested only, then the objects only contain the `ssid` key.
+///
 /// # Panics
 ///
 /// This function does not panic.
 ///
 /// # Errors
 ///
-/// This function can return an [`adapter::Error`] when the underlying [`Wl`] implementation fails or [`io::Error`] when the information cannot be written on the stdout stream.
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the information cannot be written on the stdout stream.
 ///
-/// [`adapter::Error`]: crate::adapter::Error
-/// [`io::Error`]: std::io::Error
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`format::write_network_table`]: crate::format::write_network_table
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
 /// [`scan`]: crate::scan
-pub fn list_networks(show_active: bool, show_ssid: bool) -> Result<(), Box<dyn error::Error>> {
-    let process = adapter::new();
-    let networks = process.list_networks(show_active, show_ssid)?;
+pub fn list_networks(
+    process: &impl Wl,
+    show_active: bool,
+    show_ssid: bool,
+    output_format: OutputFormat,
+) -> Result<(), Error> {
+    list_networks_with(
+        process,
+        &mut io::stdout(),
+        show_active,
+        show_ssid,
+        output_format,
+    )?;
 
-    write_bytes(&mut io::stdout(), &networks)?;
     Ok(())
 }
+
+/// Provides the list of known WiFi networks by using the given [`Wl`] implementation, and writes it on the given [`io::Write`].
+///
+/// The output is the same as [`list_networks`], and the list is also returned.
+/// The returned list is only filtered by `show_active`, `show_ssid` only changes the output.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the information cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new()
+///     .with_known_network(b"SSID1", None)
+///     .with_active_network(b"SSID2");
+///
+/// let mut out = Vec::new();
+/// let networks = wl::list_networks_with(&fake, &mut out, true, true, OutputFormat::Text).unwrap();
+///
+/// assert_eq!(1, networks.len());
+/// assert_eq!(b"NAME\nSSID2\n".as_slice(), out);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`list_networks`]: crate::list_networks
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn list_networks_with(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    show_active: bool,
+    show_ssid: bool,
+    output_format: OutputFormat,
+) -> Result<Vec<Network>, Error> {
+    let networks = process.get_networks(show_active)?;
+
+    match output_format {
+        OutputFormat::Text => format::write_network_table(f, &networks, show_ssid)?,
+        _ if show_ssid => {
+            let ssids = networks
+                .iter()
+                .map(|n| Ssid {
+                    ssid: n.ssid.clone(),
+                })
+                .collect::<Vec<Ssid>>();
+            output::write_list(f, output_format, &ssids)?
+        }
+        _ => output::write_list(f, output_format, &networks)?,
+    }
+
+    Ok(networks)
+}
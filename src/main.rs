@@ -1,58 +1,374 @@
-use std::{error, io, process::ExitCode};
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, Write},
+    os::unix::{ffi::OsStringExt, fs::OpenOptionsExt},
+    path::Path,
+    process::ExitCode,
+};
 
-use clap::Parser;
-use wl::{NetworkAdapterError, api};
+use clap::FromArgMatches;
+use termion::input::TermRead;
+use wl::api;
 
 const PROGRAM: &str = "wl";
+const PASSPHRASE_ENV: &str = "WL_BACKUP_PASSPHRASE";
 
 fn main() -> ExitCode {
-    which::which("nmcli").expect("The underlying network backend should be installed on the host");
-
     match run() {
         Ok(_) => ExitCode::SUCCESS,
         Err(err) => {
             eprintln!("{PROGRAM}: {err}");
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
 
-            if let Some(err) = err.downcast_ref::<wl::NetworkAdapterError>() {
-                let ecode = match err {
-                    NetworkAdapterError::CannotGetWiFiStatus((_, ecode)) => ecode,
-                    NetworkAdapterError::CannotToggleWiFi((_, ecode)) => ecode,
-
-                    NetworkAdapterError::CannotListNetworks((_, ecode)) => ecode,
-                    NetworkAdapterError::CannotGetActiveConnections((_, ecode)) => ecode,
-                    NetworkAdapterError::CannotGetSSIDStatus((_, ecode)) => ecode,
-                    NetworkAdapterError::CannotDisconnect((_, ecode)) => ecode,
-                    NetworkAdapterError::CannotScanWiFi((_, ecode)) => ecode,
-                    NetworkAdapterError::CannotConnect((_, ecode)) => ecode,
-                };
+fn run() -> Result<(), wl::Error> {
+    let config = wl::Config::load()?;
+    let matches = config
+        .command()
+        .get_matches_from(config.expand_alias(env::args_os()));
+    let args = api::Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
 
-                ExitCode::from(*ecode as u8)
-            } else {
-                ExitCode::from(1u8)
-            }
+    let output = args.output;
+    match args.wl_command {
+        Some(api::WlCommand::Config { config_command }) => {
+            return match config_command {
+                api::ConfigCommand::Show => {
+                    wl::config_show(&mut io::stdout(), &config, &args.backend, output).map(|_| ())
+                }
+            };
         }
+        Some(api::WlCommand::Completions { shell }) => {
+            return wl::completions(&mut io::stdout(), shell);
+        }
+        Some(api::WlCommand::Man { dir }) => {
+            return match dir {
+                Some(dir) => wl::man_to(&dir),
+                None => wl::man(&mut io::stdout()),
+            };
+        }
+        // The password is refused before anything else, since it is already visible to the other users of the host.
+        Some(
+            api::WlCommand::Connect { ref passwd, .. }
+            | api::WlCommand::Hotspot {
+                hotspot_command:
+                    api::HotspotCommand::Start {
+                        args: api::HotspotArgs { ref passwd, .. },
+                    },
+            },
+        ) if passwd.password.is_some() => {
+            config
+                .command()
+                .error(
+                    clap::error::ErrorKind::ArgumentConflict,
+                    "the password cannot be given as an argument, since the arguments are visible to the other users of the host; use --passwd-stdin, --passwd-file or --passwd-env instead",
+                )
+                .exit();
+        }
+        _ => {}
     }
-}
 
-fn run() -> Result<(), Box<dyn error::Error>> {
-    let args = api::Args::parse();
+    let process = wl::detect_backend(&args.backend)?;
 
     let wl_cmd = args.wl_command.unwrap_or(api::WlCommand::Status);
     match wl_cmd {
-        api::WlCommand::Status => wl::status(),
-        api::WlCommand::Toggle => wl::toggle(),
-        api::WlCommand::Scan { args } => wl::scan(&mut io::stdout(), args),
-        api::WlCommand::Connect { ssid, force_passwd } => {
-            wl::connect(ssid.map(|i| i.into_bytes()), force_passwd)
-        }
-        api::WlCommand::Disconnect { ssid, forget } => {
-            wl::disconnect(ssid.map(|i| i.into_bytes()), forget)
+        api::WlCommand::Status => wl::status(&process, output),
+        api::WlCommand::Toggle => wl::toggle(&process, output),
+        api::WlCommand::Scan { args } => wl::scan(&process, &mut io::stdout(), args, output),
+        api::WlCommand::Connect {
+            ssid,
+            force_passwd,
+            hidden,
+            security,
+            bssid,
+            eap,
+            passwd,
+        } => {
+            let ssid = ssid.map(|i| config.resolve_ssid(i).into_bytes());
+            let passwd = passwd_source(passwd);
+            let auth = match eap_credentials(eap) {
+                Some(credentials) => wl::Auth::Enterprise(credentials),
+                None if hidden => wl::Auth::Hidden { security },
+                None => wl::Auth::Psk {
+                    force_passwd,
+                    bssid,
+                },
+            };
+            wl::connect(&process, ssid, auth, passwd, output)
         }
+        api::WlCommand::Disconnect { ssid, forget } => wl::disconnect(
+            &process,
+            ssid.map(|i| config.resolve_ssid(i).into_bytes()),
+            forget,
+            output,
+        ),
         api::WlCommand::ListNetworks {
             show_active,
             show_ssid,
-        } => wl::list_networks(show_active, show_ssid),
+        } => wl::list_networks(&process, show_active, show_ssid, output),
+        api::WlCommand::Watch { args } => wl::watch(&process, &mut io::stdout(), args, output),
+        api::WlCommand::Unpin { ssid } => wl::unpin(
+            &process,
+            &mut io::stdout(),
+            config.resolve_ssid(ssid).into_bytes(),
+            output,
+        ),
+        api::WlCommand::Profile { profile_command } => {
+            let mut stdout = io::stdout();
+            match profile_command {
+                api::ProfileCommand::Show { ssid } => wl::profile_show(
+                    &process,
+                    &mut stdout,
+                    config.resolve_ssid(ssid).into_bytes(),
+                    output,
+                )
+                .map(|_| ()),
+                api::ProfileCommand::Edit { ssid, args } => wl::profile_edit(
+                    &process,
+                    &mut stdout,
+                    config.resolve_ssid(ssid).into_bytes(),
+                    &profile_edit(args),
+                    output,
+                )
+                .map(|_| ()),
+                api::ProfileCommand::Rename { ssid, name } => wl::profile_rename(
+                    &process,
+                    &mut stdout,
+                    config.resolve_ssid(ssid).into_bytes(),
+                    name.into_bytes(),
+                    output,
+                ),
+            }
+        }
+        api::WlCommand::Priority { priority_command } => {
+            let mut stdout = io::stdout();
+            match priority_command.unwrap_or(api::PriorityCommand::List) {
+                api::PriorityCommand::List => wl::priority_list(&process, &mut stdout, output),
+                api::PriorityCommand::Set { ssid, priority } => wl::priority_set(
+                    &process,
+                    &mut stdout,
+                    config.resolve_ssid(ssid).into_bytes(),
+                    priority,
+                    output,
+                ),
+                api::PriorityCommand::Up { ssid } => wl::priority_up(
+                    &process,
+                    &mut stdout,
+                    config.resolve_ssid(ssid).into_bytes(),
+                    output,
+                ),
+                api::PriorityCommand::Down { ssid } => wl::priority_down(
+                    &process,
+                    &mut stdout,
+                    config.resolve_ssid(ssid).into_bytes(),
+                    output,
+                ),
+            }
+            .map(|_| ())
+        }
+        api::WlCommand::Export {
+            format,
+            no_secrets,
+            file,
+        } => {
+            let report = match file {
+                Some(path) => wl::export(&process, &mut create_private(&path)?, format, no_secrets),
+                None => wl::export(&process, &mut io::stdout(), format, no_secrets),
+            }?;
+
+            for ssid in report.skipped {
+                eprintln!(
+                    "{PROGRAM}: skipped {}, its security type cannot be exported",
+                    String::from_utf8_lossy(&ssid)
+                );
+            }
+            Ok(())
+        }
+        api::WlCommand::Import {
+            file,
+            from,
+            no_secrets,
+            on_conflict,
+        } => {
+            let mut stdout = io::stdout();
+            if from == api::ImportSource::Wl && file.as_os_str() == "-" {
+                wl::import(
+                    &process,
+                    &mut stdout,
+                    &mut io::stdin(),
+                    no_secrets,
+                    on_conflict,
+                    output,
+                )
+            } else {
+                wl::import_from(
+                    &process,
+                    &mut stdout,
+                    from,
+                    &file,
+                    no_secrets,
+                    on_conflict,
+                    output,
+                )
+            }
+            .map(|_| ())
+        }
+        api::WlCommand::Backup { file } => {
+            let passphrase = read_passphrase(true)?;
+            let report = wl::backup(&process, &mut create_private(&file)?, &passphrase)?;
+
+            for ssid in report.skipped {
+                eprintln!(
+                    "{PROGRAM}: skipped {}, its security type cannot be backed up",
+                    String::from_utf8_lossy(&ssid)
+                );
+            }
+            Ok(())
+        }
+        api::WlCommand::Restore { file, on_conflict } => {
+            let passphrase = read_passphrase(false)?;
+            let mut stdout = io::stdout();
+            if file.as_os_str() == "-" {
+                wl::restore(
+                    &process,
+                    &mut stdout,
+                    &mut io::stdin(),
+                    &passphrase,
+                    on_conflict,
+                    output,
+                )
+            } else {
+                wl::restore(
+                    &process,
+                    &mut stdout,
+                    &mut File::open(file)?,
+                    &passphrase,
+                    on_conflict,
+                    output,
+                )
+            }
+            .map(|_| ())
+        }
+        api::WlCommand::Share { ssid } => wl::share(
+            &process,
+            &mut io::stdout(),
+            config.resolve_ssid(ssid).into_bytes(),
+            output,
+        )
+        .map(|_| ()),
+        api::WlCommand::CompleteSsid { source } => {
+            wl::complete_ssids(&process, &mut io::stdout(), source).map(|_| ())
+        }
+        api::WlCommand::Config { .. }
+        | api::WlCommand::Completions { .. }
+        | api::WlCommand::Man { .. } => {
+            unreachable!("handled before the backend detection")
+        }
+        api::WlCommand::Hotspot { hotspot_command } => {
+            let mut stdout = io::stdout();
+            match hotspot_command {
+                api::HotspotCommand::Start { args } => {
+                    let ssid = args.ssid.into_bytes();
+                    let passwd = match args.open {
+                        true => None,
+                        false => Some(wl::read_passwd(&ssid, passwd_source(args.passwd), output)?),
+                    };
+
+                    wl::hotspot_start(
+                        &process,
+                        &mut stdout,
+                        ssid,
+                        passwd,
+                        args.band,
+                        args.channel,
+                        output,
+                    )
+                    .map(|_| ())
+                }
+                api::HotspotCommand::Stop => {
+                    wl::hotspot_stop(&process, &mut stdout, output).map(|_| ())
+                }
+                api::HotspotCommand::Status => {
+                    wl::hotspot_status(&process, &mut stdout, output).map(|_| ())
+                }
+            }
+        }
     }?;
 
     Ok(())
 }
+
+fn eap_credentials(args: api::EapArgs) -> Option<wl::EapCredentials> {
+    Some(wl::EapCredentials {
+        method: args.eap?,
+        identity: args.identity.unwrap_or_default().into_bytes(),
+        anonymous_identity: args.anonymous_identity.map(|i| i.into_bytes()),
+        passwd: None,
+        phase2_auth: args.phase2_auth,
+        ca_cert: args.ca_cert,
+        client_cert: args.client_cert,
+        private_key: args.private_key,
+        private_key_passwd: None,
+        domain_suffix_match: args.domain_suffix_match,
+    })
+}
+
+fn passwd_source(args: api::PasswdArgs) -> wl::PasswdSource {
+    match (args.passwd_stdin, args.passwd_file, args.passwd_env) {
+        (true, _, _) => wl::PasswdSource::Stdin,
+        (_, Some(path), _) => wl::PasswdSource::File(path),
+        (_, _, Some(name)) => wl::PasswdSource::Env(name),
+        _ => wl::PasswdSource::Prompt,
+    }
+}
+
+fn profile_edit(args: api::ProfileArgs) -> wl::ProfileEdit {
+    wl::ProfileEdit {
+        security: args.security,
+        autoconnect: args.autoconnect,
+        priority: args.priority,
+        metered: args.metered,
+        mac_randomization: args.mac_randomization,
+        ip_method: args.ip_method,
+    }
+}
+
+/// Creates the file at the given path, which is only readable and writable by its owner.
+fn create_private(path: &Path) -> Result<File, io::Error> {
+    fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+}
+
+/// Reads the passphrase of `wl backup` and `wl restore` from `WL_BACKUP_PASSPHRASE`, or asks it on the terminal.
+///
+/// The terminal is used instead of stdin, so that the archive of `wl restore` can be read from stdin.
+fn read_passphrase(confirm: bool) -> Result<Vec<u8>, wl::Error> {
+    if let Some(passphrase) = env::var_os(PASSPHRASE_ENV) {
+        return Ok(passphrase.into_vec());
+    }
+
+    let mut tty = termion::get_tty()?;
+    let mut ask = |prompt: &[u8]| -> Result<Vec<u8>, wl::Error> {
+        tty.write_all(prompt)?;
+        tty.flush()?;
+        let passphrase = tty.try_clone()?.read_passwd(&mut tty)?;
+        tty.write_all(b"\n")?;
+
+        passphrase
+            .map(String::into_bytes)
+            .ok_or(wl::Error::UserAborted)
+    };
+
+    let passphrase = ask(b"Enter the backup passphrase: ")?;
+    if confirm && ask(b"Confirm the backup passphrase: ")? != passphrase {
+        Err(wl::BackupError::PassphraseMismatch)?;
+    }
+
+    Ok(passphrase)
+}
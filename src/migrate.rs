@@ -0,0 +1,470 @@
+use std::{fs, io, path::Path};
+
+use crate::{
+    ExportReport, ExportedNetwork, ImportError, ImportedNetwork, Security,
+    adapter::Wl,
+    api::{ConflictPolicy, ImportSource, OutputFormat},
+    export::{self, EXPORT_VERSION, ExportFile},
+    wpa_supplicant,
+};
+
+// `wpa_supplicant` uses both PSK and EAP when `key_mgmt` is not set.
+const DEFAULT_KEY_MGMT: &str = "WPA-PSK WPA-EAP";
+
+/// Imports the known networks of the given [`ImportSource`] at the given path by using the given [`Wl`] implementation, and writes the outcomes on the given [`io::Write`].
+///
+/// The path is read based on the source:
+///
+/// - [`ImportSource::Wl`] reads a file that is written by [`export`], in the same way as [`import`].
+/// - [`ImportSource::WpaSupplicant`] reads the `network={...}` blocks of a `wpa_supplicant.conf` file (see [`parse_wpa_supplicant_conf`]).
+/// - [`ImportSource::Iwd`] reads a single `iwd` profile, or every `.psk`, `.open` and `.8021x` profile of a directory (e.g. `/var/lib/iwd`, see [`parse_iwd_profile`]).
+///
+/// The networks are added without connecting to them, so they do not need to be in range.
+/// Set `no_secrets` to ignore the secrets of the networks, in which case the passwords are asked on the first connection.
+///
+/// The networks that are already known are handled by `on_conflict` in the same way as [`import`], and the outcomes are written in the same format as [`import`].
+/// The networks that cannot be recreated (e.g. enterprise or WEP networks) are not imported, and their outcomes are [`ImportStatus::Unsupported`].
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Import`] with:
+///
+/// - [`ImportError::InvalidFile`] if a file cannot be parsed.
+/// - [`ImportError::AlreadyKnown`] if a network is already known, and [`ConflictPolicy::Fail`] is selected.
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails to add a network, in which case the networks before it are kept,
+/// and [`Error::Io`] when the path cannot be read or the outcomes cannot be written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{io, path::Path};
+///
+/// use wl::{FakeWl, api::{ConflictPolicy, ImportSource, OutputFormat}};
+///
+/// let fake = FakeWl::new();
+/// wl::import_from(
+///     &fake,
+///     &mut io::stdout(),
+///     ImportSource::Iwd,
+///     Path::new("/var/lib/iwd"),
+///     false,
+///     ConflictPolicy::Skip,
+///     OutputFormat::Text,
+/// )
+/// .unwrap();
+/// ```
+///
+/// [`ImportSource`]: crate::api::ImportSource
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`ImportSource::Wl`]: crate::api::ImportSource::Wl
+/// [`ImportSource::WpaSupplicant`]: crate::api::ImportSource::WpaSupplicant
+/// [`ImportSource::Iwd`]: crate::api::ImportSource::Iwd
+/// [`export`]: crate::export
+/// [`import`]: crate::import
+/// [`parse_wpa_supplicant_conf`]: crate::parse_wpa_supplicant_conf
+/// [`parse_iwd_profile`]: crate::parse_iwd_profile
+/// [`ImportStatus::Unsupported`]: crate::ImportStatus::Unsupported
+/// [`Error::Import`]: crate::Error::Import
+/// [`ImportError::InvalidFile`]: crate::ImportError::InvalidFile
+/// [`ImportError::AlreadyKnown`]: crate::ImportError::AlreadyKnown
+/// [`ConflictPolicy::Fail`]: crate::api::ConflictPolicy::Fail
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn import_from(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    source: ImportSource,
+    path: &Path,
+    no_secrets: bool,
+    on_conflict: ConflictPolicy,
+    output_format: OutputFormat,
+) -> Result<Vec<ImportedNetwork>, crate::Error> {
+    let report = match source {
+        ImportSource::Wl => {
+            return export::import(
+                process,
+                f,
+                &mut fs::File::open(path)?,
+                no_secrets,
+                on_conflict,
+                output_format,
+            );
+        }
+        ImportSource::WpaSupplicant => parse_wpa_supplicant_conf(&fs::read_to_string(path)?)?,
+        ImportSource::Iwd => read_iwd_profiles(path)?,
+    };
+
+    let file = ExportFile {
+        version: EXPORT_VERSION,
+        networks: report.networks,
+    };
+    export::import_networks(
+        process,
+        f,
+        file,
+        report.skipped,
+        no_secrets,
+        on_conflict,
+        output_format,
+    )
+}
+
+/// Parses the `network={...}` blocks of a `wpa_supplicant.conf` file into the networks that can be imported.
+///
+/// The SSIDs can be quoted (`ssid="Home"`), hex-encoded (`ssid=486f6d65`) or escaped (`ssid=P"Home\n"`).
+/// The passwords can be quoted passphrases (`psk="secret"`, `sae_password="secret"`) or PSK hashes of 64 hex digits (`psk=1a2b...`), which are imported as is.
+///
+/// The security types are determined by `key_mgmt`:
+///
+/// - `WPA-PSK` is imported as [`Security::WpaPsk`].
+/// - `SAE` without `WPA-PSK` is imported as [`Security::Sae`].
+/// - `NONE` without WEP keys is imported as [`Security::Open`].
+///
+/// The other networks (e.g. `WPA-EAP`, WEP) cannot be recreated, and their SSIDs are returned in [`ExportReport::skipped`].
+/// `scan_ssid=1` marks a network as hidden, `disabled=1` turns its autoconnect off, and `priority` is kept as is.
+/// The global settings and the comments are ignored.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`ImportError::InvalidFile`] if a `network` block is not closed, has no `ssid`, or has a line or a value that cannot be parsed.
+///
+/// # Examples
+///
+/// ```
+/// use wl::Security;
+///
+/// let conf = r#"
+/// ctrl_interface=/run/wpa_supplicant
+///
+/// network={
+///     ssid="Home"
+///     psk="secret"
+///     priority=5
+/// }
+///
+/// network={
+///     ssid=436f7270
+///     key_mgmt=WPA-EAP
+///     eap=PEAP
+/// }
+/// "#;
+///
+/// let report = wl::parse_wpa_supplicant_conf(conf).unwrap();
+///
+/// assert_eq!(b"Home".as_slice(), report.networks[0].ssid);
+/// assert_eq!(Security::WpaPsk, report.networks[0].security);
+/// assert_eq!(Some(b"secret".to_vec()), report.networks[0].secret);
+/// assert_eq!(5, report.networks[0].priority);
+/// assert_eq!(vec![b"Corp".to_vec()], report.skipped);
+/// ```
+///
+/// [`Security::WpaPsk`]: crate::Security::WpaPsk
+/// [`Security::Sae`]: crate::Security::Sae
+/// [`Security::Open`]: crate::Security::Open
+/// [`ExportReport::skipped`]: crate::ExportReport::skipped
+/// [`ImportError::InvalidFile`]: crate::ImportError::InvalidFile
+pub fn parse_wpa_supplicant_conf(content: &str) -> Result<ExportReport, ImportError> {
+    let mut report = ExportReport {
+        networks: Vec::new(),
+        skipped: Vec::new(),
+    };
+
+    let mut block: Option<(usize, Vec<(&str, &str)>)> = None;
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match block.as_mut() {
+            None if line.split_whitespace().collect::<String>() == "network={" => {
+                block = Some((idx + 1, Vec::new()))
+            }
+            None => {}
+            Some((start, fields)) if line == "}" => {
+                let network = parse_wpa_supplicant_network(*start, fields)?;
+                match network {
+                    Ok(network) => report.networks.push(network),
+                    Err(ssid) => report.skipped.push(ssid),
+                }
+                block = None;
+            }
+            Some((_, fields)) => {
+                let (key, value) = line.split_once('=').ok_or_else(|| {
+                    invalid_line(idx + 1, "the line is not in the key=value format")
+                })?;
+                fields.push((key.trim(), value.trim()));
+            }
+        }
+    }
+
+    if let Some((start, _)) = block {
+        return Err(invalid_line(start, "the network block is not closed"));
+    }
+
+    Ok(report)
+}
+
+/// Parses an `iwd` network profile (e.g. `/var/lib/iwd/Home.psk`) into the network that can be imported.
+///
+/// The SSID is read from the file name, which is hex-encoded and prefixed by `=` for the SSIDs that are not made of alphanumeric characters, spaces, `-` and `_` only (e.g. `=436166c3a9.psk`).
+/// The security type is read from the file extension:
+///
+/// - `.psk` is imported as [`Security::WpaPsk`], with the `Passphrase` or `PreSharedKey` (a PSK hash of 64 hex digits) of its `[Security]` section.
+/// - `.open` is imported as [`Security::Open`].
+/// - `.8021x` cannot be recreated, and its SSID is returned in [`ExportReport::skipped`].
+///
+/// `Hidden` and `AutoConnect` of the `[Settings]` section are kept as they are.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`ImportError::InvalidFile`] if the file name is not a valid `iwd` profile name, or a line of the file cannot be parsed.
+///
+/// # Examples
+///
+/// ```
+/// use wl::Security;
+///
+/// let profile = "[Security]\nPassphrase=secret\n\n[Settings]\nAutoConnect=false\n";
+///
+/// let report = wl::parse_iwd_profile("Home.psk", profile).unwrap();
+///
+/// assert_eq!(b"Home".as_slice(), report.networks[0].ssid);
+/// assert_eq!(Security::WpaPsk, report.networks[0].security);
+/// assert_eq!(Some(b"secret".to_vec()), report.networks[0].secret);
+/// assert!(!report.networks[0].autoconnect);
+/// ```
+///
+/// [`Security::WpaPsk`]: crate::Security::WpaPsk
+/// [`Security::Open`]: crate::Security::Open
+/// [`ExportReport::skipped`]: crate::ExportReport::skipped
+/// [`ImportError::InvalidFile`]: crate::ImportError::InvalidFile
+pub fn parse_iwd_profile(file_name: &str, content: &str) -> Result<ExportReport, ImportError> {
+    let invalid_name = || ImportError::InvalidFile(format!("{}: not an iwd profile", file_name));
+
+    let (name, extension) = file_name.rsplit_once('.').ok_or_else(invalid_name)?;
+    let ssid = match name.strip_prefix('=') {
+        Some(hex) => decode_hex(hex).ok_or_else(invalid_name)?,
+        None => name.as_bytes().to_vec(),
+    };
+    if ssid.is_empty() {
+        return Err(invalid_name());
+    }
+
+    let security = match extension {
+        "psk" => Security::WpaPsk,
+        "open" => Security::Open,
+        "8021x" => {
+            return Ok(ExportReport {
+                networks: Vec::new(),
+                skipped: vec![ssid],
+            });
+        }
+        _ => return Err(invalid_name()),
+    };
+
+    let mut section = "";
+    let mut passphrase = None;
+    let mut psk_hash = None;
+    let mut hidden = false;
+    let mut autoconnect = true;
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name;
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or_else(|| {
+            ImportError::InvalidFile(format!(
+                "{}: line {}: the line is not in the key=value format",
+                file_name,
+                idx + 1
+            ))
+        })?;
+        match (section, key.trim()) {
+            ("Security", "Passphrase") => passphrase = Some(value.to_string()),
+            ("Security", "PreSharedKey") => psk_hash = Some(value.trim().to_lowercase()),
+            ("Settings", "Hidden") => hidden = value.trim() == "true",
+            ("Settings", "AutoConnect") => autoconnect = value.trim() != "false",
+            _ => {}
+        }
+    }
+
+    let secret = match security {
+        Security::Open => None,
+        _ => passphrase.or(psk_hash),
+    };
+
+    Ok(ExportReport {
+        networks: vec![ExportedNetwork {
+            ssid,
+            security,
+            secret: secret.map(String::into_bytes),
+            hidden,
+            priority: 0,
+            autoconnect,
+        }],
+        skipped: Vec::new(),
+    })
+}
+
+fn read_iwd_profiles(path: &Path) -> Result<ExportReport, crate::Error> {
+    let mut paths = if path.is_dir() {
+        fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, io::Error>>()?
+            .into_iter()
+            .filter(|p| {
+                p.is_file()
+                    && p.extension()
+                        .is_some_and(|ext| ext == "psk" || ext == "open" || ext == "8021x")
+            })
+            .collect()
+    } else {
+        vec![path.to_path_buf()]
+    };
+    paths.sort();
+
+    let mut report = ExportReport {
+        networks: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for path in paths {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let profile = parse_iwd_profile(&file_name, &fs::read_to_string(&path)?)?;
+
+        report.networks.extend(profile.networks);
+        report.skipped.extend(profile.skipped);
+    }
+
+    Ok(report)
+}
+
+/// Converts the fields of a `network` block that starts at the given line into a network, or into its SSID if it cannot be recreated.
+fn parse_wpa_supplicant_network(
+    start: usize,
+    fields: &[(&str, &str)],
+) -> Result<Result<ExportedNetwork, Vec<u8>>, ImportError> {
+    // The last occurrence of a field wins, as in `wpa_supplicant`.
+    let field = |key: &str| {
+        fields
+            .iter()
+            .rev()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+    };
+
+    let ssid = field("ssid").ok_or_else(|| invalid_line(start, "the network block has no ssid"))?;
+    let ssid = decode_wpa_supplicant_ssid(ssid)
+        .ok_or_else(|| invalid_line(start, "the ssid cannot be decoded"))?;
+
+    let psk = match field("psk") {
+        Some(psk) => {
+            Some(decode_psk(psk).ok_or_else(|| invalid_line(start, "the psk cannot be decoded"))?)
+        }
+        None => None,
+    };
+    let sae_password = field("sae_password").and_then(unquote);
+
+    let key_mgmt = field("key_mgmt").unwrap_or(DEFAULT_KEY_MGMT);
+    let key_mgmt = key_mgmt.split_whitespace().collect::<Vec<_>>();
+    let has_wep_key = (0..4).any(|i| field(&format!("wep_key{}", i)).is_some());
+    let is_eap = key_mgmt
+        .iter()
+        .any(|k| k.contains("EAP") || *k == "IEEE8021X");
+
+    let (security, secret) = if key_mgmt.contains(&"WPA-PSK") && (psk.is_some() || !is_eap) {
+        (Security::WpaPsk, psk)
+    } else if key_mgmt.contains(&"SAE") {
+        // SAE requires the passphrase, since it cannot use a PSK hash.
+        match sae_password.map(String::from).or(psk) {
+            Some(passwd) if !is_psk_hash(&passwd) => (Security::Sae, Some(passwd)),
+            _ => return Ok(Err(ssid)),
+        }
+    } else if key_mgmt == ["NONE"] && !has_wep_key {
+        (Security::Open, None)
+    } else {
+        return Ok(Err(ssid));
+    };
+
+    let priority = match field("priority") {
+        Some(priority) => priority
+            .parse()
+            .map_err(|_| invalid_line(start, "the priority is not a number"))?,
+        None => 0,
+    };
+
+    Ok(Ok(ExportedNetwork {
+        ssid,
+        security,
+        secret: secret.map(String::into_bytes),
+        hidden: field("scan_ssid") == Some("1"),
+        priority,
+        autoconnect: field("disabled").is_none_or(|d| d == "0"),
+    }))
+}
+
+fn decode_wpa_supplicant_ssid(ssid: &str) -> Option<Vec<u8>> {
+    if let Some(escaped) = ssid.strip_prefix('P').and_then(unquote) {
+        Some(wpa_supplicant::decode_ssid(escaped.as_bytes()))
+    } else if let Some(ssid) = unquote(ssid) {
+        Some(ssid.as_bytes().to_vec())
+    } else {
+        decode_hex(ssid)
+    }
+}
+
+/// Provides a quoted passphrase without its quotes, or a PSK hash as is.
+fn decode_psk(psk: &str) -> Option<String> {
+    match unquote(psk) {
+        Some(passphrase) => Some(passphrase.to_string()),
+        None if is_psk_hash(psk) => Some(psk.to_lowercase()),
+        None => None,
+    }
+}
+
+fn is_psk_hash(psk: &str) -> bool {
+    psk.len() == 64 && psk.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn unquote(value: &str) -> Option<&str> {
+    value.strip_prefix('"')?.strip_suffix('"')
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.is_empty() || !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit())
+    {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn invalid_line(line: usize, reason: &str) -> ImportError {
+    ImportError::InvalidFile(format!("line {}: {}", line, reason))
+}
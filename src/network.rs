@@ -0,0 +1,567 @@
+use std::{fmt, path::PathBuf};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize, ser::SerializeStruct};
+
+use crate::output;
+
+/// Represents the status of the WiFi radio of the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WifiStatus {
+    Enabled,
+    Disabled,
+}
+
+impl WifiStatus {
+    /// Provides the opposite of the current status.
+    pub fn toggled(self) -> Self {
+        match self {
+            WifiStatus::Enabled => WifiStatus::Disabled,
+            WifiStatus::Disabled => WifiStatus::Enabled,
+        }
+    }
+}
+
+impl fmt::Display for WifiStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WifiStatus::Enabled => write!(f, "enabled"),
+            WifiStatus::Disabled => write!(f, "disabled"),
+        }
+    }
+}
+
+/// Represents a known network (connection profile) of the host.
+///
+/// The known networks are not limited to WiFi. Depending on the network backend,
+/// the known network list may also contain other types of networks, such as loopback or ethernet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Network {
+    /// The name of the network, which is the SSID for WiFi networks.
+    pub ssid: Vec<u8>,
+
+    /// The unique identifier of the network, as provided by the network backend.
+    pub uuid: String,
+
+    /// The type of the network (e.g. `wifi`, `ethernet`, `loopback`).
+    pub kind: String,
+
+    /// The device that the network is active on.
+    ///
+    /// It is `None` when the network is not active.
+    pub device: Option<String>,
+}
+
+impl Network {
+    /// Provides whether the network is active (connected) or not.
+    pub fn is_active(&self) -> bool {
+        self.device.is_some()
+    }
+}
+
+impl Serialize for Network {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut network = serializer.serialize_struct("Network", 5)?;
+        network.serialize_field("ssid", &String::from_utf8_lossy(&self.ssid))?;
+        network.serialize_field("uuid", &self.uuid)?;
+        network.serialize_field("type", &self.kind)?;
+        network.serialize_field("device", &self.device)?;
+        network.serialize_field("active", &self.is_active())?;
+        network.end()
+    }
+}
+
+/// Represents the autoconnect priority of a known WiFi network.
+///
+/// When multiple known networks are visible, the network backends connect to the one with the highest priority.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NetworkPriority {
+    /// The name of the known network, which is its SSID unless it is renamed.
+    #[serde(serialize_with = "output::serialize_ssid")]
+    pub ssid: Vec<u8>,
+
+    /// The autoconnect priority of the known network.
+    pub priority: i32,
+
+    /// Whether the known network is connected automatically or not.
+    ///
+    /// The known networks that are not connected automatically are not affected by their priorities.
+    pub autoconnect: bool,
+}
+
+/// Represents the frequency bands of a WiFi hotspot.
+///
+/// The names follow the ones of NetworkManager.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Band {
+    /// 2.4 GHz.
+    #[value(name = "bg", alias = "2.4ghz")]
+    Bg,
+
+    /// 5 GHz.
+    #[value(name = "a", alias = "5ghz")]
+    A,
+}
+
+impl Band {
+    /// Provides the band of the given channel.
+    ///
+    /// The channels between 1 and 14 are on the 2.4 GHz band, the rest are on the 5 GHz band.
+    pub fn from_channel(channel: u32) -> Self {
+        match channel {
+            1..=14 => Band::Bg,
+            _ => Band::A,
+        }
+    }
+
+    /// Provides the name of the band, as it is accepted by `--band`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Band::Bg => "bg",
+            Band::A => "a",
+        }
+    }
+}
+
+impl fmt::Display for Band {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Represents a WiFi hotspot (access point) that shares the connection of the host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Hotspot {
+    /// The SSID (name) of the hotspot.
+    #[serde(serialize_with = "output::serialize_ssid")]
+    pub ssid: Vec<u8>,
+
+    /// The device that the hotspot is running on.
+    pub device: String,
+
+    /// The frequency band of the hotspot.
+    ///
+    /// It is `None` when the band is chosen by the network backend.
+    pub band: Option<Band>,
+
+    /// The channel of the hotspot.
+    ///
+    /// It is `None` when the channel is chosen by the network backend.
+    pub channel: Option<u32>,
+
+    /// Whether the hotspot is secured with a password or not.
+    pub secured: bool,
+
+    /// The clients that are connected to the hotspot.
+    ///
+    /// It is `None` when the network backend does not expose the clients.
+    pub clients: Option<Vec<HotspotClient>>,
+}
+
+/// Represents a client that is connected to a [`Hotspot`].
+///
+/// [`Hotspot`]: crate::Hotspot
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct HotspotClient {
+    /// The MAC address of the client.
+    pub mac: String,
+
+    /// The IP address that is leased to the client, if any.
+    pub ip: Option<String>,
+
+    /// The hostname of the client, if it is provided by the client.
+    pub hostname: Option<String>,
+}
+
+/// Represents the security types that can be selected for a hidden network.
+///
+/// The security of a hidden network cannot be read from a scan, hence it is selected explicitly.
+/// The names follow the key management names of NetworkManager.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Security {
+    /// An open network without a password.
+    #[value(alias = "none")]
+    Open,
+
+    /// WPA/WPA2 with a pre-shared key.
+    #[value(name = "wpa-psk", alias = "wpa2")]
+    WpaPsk,
+
+    /// WPA3 with SAE (simultaneous authentication of equals).
+    #[value(alias = "wpa3")]
+    Sae,
+}
+
+impl Security {
+    /// Provides the name of the security type, as it is accepted by `--security`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Security::Open => "open",
+            Security::WpaPsk => "wpa-psk",
+            Security::Sae => "sae",
+        }
+    }
+
+    /// Provides whether the security type requires a password or not.
+    pub fn is_secured(&self) -> bool {
+        !matches!(self, Security::Open)
+    }
+}
+
+impl fmt::Display for Security {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Represents the EAP methods of an enterprise (802.1X / WPA-EAP) network.
+///
+/// The names follow the ones of NetworkManager.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EapMethod {
+    /// Protected EAP, which authenticates with a password inside a TLS tunnel.
+    #[default]
+    Peap,
+
+    /// Tunneled TLS, which authenticates with a password inside a TLS tunnel.
+    Ttls,
+
+    /// EAP-TLS, which authenticates with a client certificate.
+    Tls,
+}
+
+impl EapMethod {
+    /// Provides the name of the method, as it is accepted by `--eap`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EapMethod::Peap => "peap",
+            EapMethod::Ttls => "ttls",
+            EapMethod::Tls => "tls",
+        }
+    }
+
+    /// Provides whether the method authenticates with a password inside a TLS tunnel (PEAP and TTLS) or not.
+    pub fn is_tunneled(&self) -> bool {
+        matches!(self, EapMethod::Peap | EapMethod::Ttls)
+    }
+}
+
+impl fmt::Display for EapMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Represents the inner (phase 2) authentication of the tunneled EAP methods.
+///
+/// The names follow the ones of NetworkManager.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Phase2Auth {
+    /// MSCHAPv2, the common inner authentication of PEAP.
+    #[default]
+    Mschapv2,
+
+    /// PAP, the common inner authentication of TTLS.
+    Pap,
+
+    /// GTC (generic token card).
+    Gtc,
+}
+
+impl Phase2Auth {
+    /// Provides the name of the inner authentication, as it is accepted by `--phase2-auth`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase2Auth::Mschapv2 => "mschapv2",
+            Phase2Auth::Pap => "pap",
+            Phase2Auth::Gtc => "gtc",
+        }
+    }
+}
+
+impl fmt::Display for Phase2Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Represents the credentials of an enterprise (802.1X / WPA-EAP) network.
+///
+/// The tunneled methods (PEAP and TTLS) authenticate with `identity` and `passwd`,
+/// and EAP-TLS authenticates with `identity`, `client_cert` and `private_key`.
+///
+/// The certificates and keys are paths on the host, since the network backends read them by themselves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EapCredentials {
+    /// The EAP method of the network.
+    pub method: EapMethod,
+
+    /// The identity (username) of the user.
+    pub identity: Vec<u8>,
+
+    /// The identity that is sent in clear text before the TLS tunnel is established.
+    ///
+    /// If not provided, then `identity` is sent instead.
+    pub anonymous_identity: Option<Vec<u8>>,
+
+    /// The password of the user, which is used by the tunneled methods.
+    pub passwd: Option<Vec<u8>>,
+
+    /// The inner authentication of the tunneled methods.
+    ///
+    /// If not provided, then it is chosen by the network backend.
+    pub phase2_auth: Option<Phase2Auth>,
+
+    /// The CA certificate that is used to verify the authentication server.
+    ///
+    /// If not provided, then the server is not verified.
+    pub ca_cert: Option<PathBuf>,
+
+    /// The client certificate of EAP-TLS.
+    pub client_cert: Option<PathBuf>,
+
+    /// The private key of the client certificate.
+    pub private_key: Option<PathBuf>,
+
+    /// The password of the private key, if it is encrypted.
+    pub private_key_passwd: Option<Vec<u8>>,
+
+    /// The suffix that should match the domain name of the authentication server certificate.
+    pub domain_suffix_match: Option<String>,
+}
+
+/// Represents whether a known network is treated as metered (e.g. a mobile hotspot with a data cap) or not.
+///
+/// The names follow the ones of NetworkManager.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Metered {
+    /// The network backend decides whether the network is metered or not.
+    #[default]
+    Unknown,
+
+    /// The network is metered.
+    Yes,
+
+    /// The network is not metered.
+    No,
+}
+
+impl Metered {
+    /// Provides the name of the value, as it is accepted by `--metered`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Metered::Unknown => "unknown",
+            Metered::Yes => "yes",
+            Metered::No => "no",
+        }
+    }
+}
+
+impl fmt::Display for Metered {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Represents whether the MAC address of the host is randomized while it is connected to a known network.
+///
+/// The names follow the ones of NetworkManager.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MacRandomization {
+    /// The global default of the network backend is used.
+    #[default]
+    Default,
+
+    /// The permanent MAC address of the device is used.
+    Never,
+
+    /// A random MAC address is used on each connection.
+    Always,
+}
+
+impl MacRandomization {
+    /// Provides the name of the value, as it is accepted by `--mac-randomization`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MacRandomization::Default => "default",
+            MacRandomization::Never => "never",
+            MacRandomization::Always => "always",
+        }
+    }
+}
+
+impl fmt::Display for MacRandomization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Represents how a known network obtains its IPv4 address.
+///
+/// The names follow the `ipv4.method` values of NetworkManager.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IpMethod {
+    /// The address is obtained with DHCP.
+    #[default]
+    Auto,
+
+    /// The address is configured manually on the known network.
+    Manual,
+
+    /// A link-local address is used.
+    LinkLocal,
+
+    /// The connection is shared with the other devices on the network.
+    Shared,
+
+    /// IPv4 is disabled.
+    Disabled,
+}
+
+impl IpMethod {
+    /// Provides the name of the method, as it is accepted by `--ip-method`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            IpMethod::Auto => "auto",
+            IpMethod::Manual => "manual",
+            IpMethod::LinkLocal => "link-local",
+            IpMethod::Shared => "shared",
+            IpMethod::Disabled => "disabled",
+        }
+    }
+}
+
+impl fmt::Display for IpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Represents the settings of a known network (connection profile) of the host.
+///
+/// Unlike [`Network`], it is only provided for WiFi networks, and it holds the settings that can be changed by [`ProfileEdit`].
+///
+/// [`Network`]: crate::Network
+/// [`ProfileEdit`]: crate::ProfileEdit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Profile {
+    /// The name (connection id) of the known network.
+    ///
+    /// It is the SSID unless the known network is renamed.
+    #[serde(serialize_with = "output::serialize_ssid")]
+    pub name: Vec<u8>,
+
+    /// The SSID of the known network.
+    #[serde(serialize_with = "output::serialize_ssid")]
+    pub ssid: Vec<u8>,
+
+    /// The security type of the known network (e.g. `open`, `wpa-psk`, `sae`, `wpa-eap`).
+    pub security: String,
+
+    /// Whether the known network is hidden or not, which means that its SSID is probed explicitly.
+    pub hidden: bool,
+
+    /// Whether the known network is connected automatically when it is visible or not.
+    pub autoconnect: bool,
+
+    /// The priority of the known network among the other ones when they are connected automatically.
+    ///
+    /// The known networks with higher priorities are preferred.
+    pub priority: i32,
+
+    /// Whether the known network is metered or not.
+    pub metered: Metered,
+
+    /// Whether the MAC address of the host is randomized on the known network or not.
+    pub mac_randomization: MacRandomization,
+
+    /// How the known network obtains its IPv4 address.
+    pub ip_method: IpMethod,
+}
+
+/// Represents a known WiFi network in the portable file of [`export`] and [`import`].
+///
+/// The SSID and the secret are written as strings when they are valid UTF-8, so the file can be written and reviewed by hand.
+/// Otherwise, they are written as tables of their hex digits (e.g. `ssid = { hex = "ff00" }`), so their bytes are kept as they are.
+/// The fields other than `ssid` and `security` can be omitted, in which case their defaults are used.
+///
+/// [`export`]: crate::export
+/// [`import`]: crate::import
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedNetwork {
+    /// The SSID of the known network.
+    #[serde(
+        serialize_with = "output::serialize_portable",
+        deserialize_with = "output::deserialize_portable"
+    )]
+    pub ssid: Vec<u8>,
+
+    /// The security type of the known network.
+    pub security: Security,
+
+    /// The password of the known network.
+    ///
+    /// It is `None` for the open networks, and for the files that are exported without secrets.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "output::serialize_portable_opt",
+        deserialize_with = "output::deserialize_portable_opt"
+    )]
+    pub secret: Option<Vec<u8>>,
+
+    /// Whether the known network is hidden or not.
+    #[serde(default)]
+    pub hidden: bool,
+
+    /// The autoconnect priority of the known network.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Whether the known network is connected automatically or not.
+    #[serde(default = "default_autoconnect")]
+    pub autoconnect: bool,
+}
+
+fn default_autoconnect() -> bool {
+    true
+}
+
+/// Represents the changes to the settings of a known network.
+///
+/// The settings that are `None` are kept as they are.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileEdit {
+    /// The new security type of the known network.
+    ///
+    /// The stored password is kept, so a secured type can only be selected for a known network that has a password.
+    pub security: Option<Security>,
+
+    /// Whether the known network should be connected automatically or not.
+    pub autoconnect: Option<bool>,
+
+    /// The new priority of the known network.
+    pub priority: Option<i32>,
+
+    /// Whether the known network should be treated as metered or not.
+    pub metered: Option<Metered>,
+
+    /// Whether the MAC address of the host should be randomized on the known network or not.
+    pub mac_randomization: Option<MacRandomization>,
+
+    /// How the known network should obtain its IPv4 address.
+    pub ip_method: Option<IpMethod>,
+}
+
+impl ProfileEdit {
+    /// Provides whether the edit changes any setting or not.
+    pub fn is_empty(&self) -> bool {
+        self == &ProfileEdit::default()
+    }
+}
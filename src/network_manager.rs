@@ -0,0 +1,2039 @@
+use std::{
+    collections::HashMap, fs, io, iter, os::unix::ffi::OsStrExt, path::Path, sync::mpsc, thread,
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use zbus::{
+    MatchRule,
+    blocking::{Connection, MessageIterator, Proxy, fdo::DBusProxy, proxy::Builder},
+    message,
+    names::BusName,
+    proxy::CacheProperties,
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value},
+};
+
+use crate::{
+    AccessPoint, Band, EapCredentials, ExportedNetwork, Hotspot, HotspotClient, MacRandomization,
+    Metered, Network, NetworkPriority, Notification, Profile, ProfileEdit, Security, WifiStatus,
+    access_point::{channel_from_frequency, parse_bssid},
+    adapter::{Error, HOTSPOT_PROFILE, UNKNOWN_SSID_EXIT_CODE, Wl},
+};
+
+/// The well-known bus name of NetworkManager.
+const NM_BUS_NAME: &str = "org.freedesktop.NetworkManager";
+
+const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+const NM_IFACE: &str = "org.freedesktop.NetworkManager";
+const SETTINGS_PATH: &str = "/org/freedesktop/NetworkManager/Settings";
+const SETTINGS_IFACE: &str = "org.freedesktop.NetworkManager.Settings";
+const CONNECTION_IFACE: &str = "org.freedesktop.NetworkManager.Settings.Connection";
+const ACTIVE_IFACE: &str = "org.freedesktop.NetworkManager.Connection.Active";
+const DEVICE_IFACE: &str = "org.freedesktop.NetworkManager.Device";
+const WIRELESS_IFACE: &str = "org.freedesktop.NetworkManager.Device.Wireless";
+const AP_IFACE: &str = "org.freedesktop.NetworkManager.AccessPoint";
+const PROPERTIES_IFACE: &str = "org.freedesktop.DBus.Properties";
+
+/// `NM_DEVICE_TYPE_WIFI`.
+const DEVICE_TYPE_WIFI: u32 = 2;
+
+/// `NM_ACTIVE_CONNECTION_STATE_ACTIVATED`.
+const ACTIVE_STATE_ACTIVATED: u32 = 2;
+
+/// `NM_ACTIVE_CONNECTION_STATE_DEACTIVATED`.
+const ACTIVE_STATE_DEACTIVATED: u32 = 4;
+
+/// `NM_ACTIVE_CONNECTION_STATE_REASON_NO_SECRETS`.
+const ACTIVE_REASON_NO_SECRETS: u32 = 9;
+
+/// `NM_ACTIVE_CONNECTION_STATE_REASON_LOGIN_FAILED`.
+const ACTIVE_REASON_LOGIN_FAILED: u32 = 10;
+
+/// `NM_802_11_AP_FLAGS_PRIVACY`.
+const AP_FLAGS_PRIVACY: u32 = 0x1;
+
+/// `NM_802_11_AP_SEC_KEY_MGMT_PSK`.
+const AP_SEC_KEY_MGMT_PSK: u32 = 0x100;
+
+/// `NM_802_11_AP_SEC_KEY_MGMT_802_1X`.
+const AP_SEC_KEY_MGMT_802_1X: u32 = 0x200;
+
+/// `NM_802_11_AP_SEC_KEY_MGMT_SAE`.
+const AP_SEC_KEY_MGMT_SAE: u32 = 0x400;
+
+/// `NM_802_11_AP_SEC_KEY_MGMT_OWE` and `NM_802_11_AP_SEC_KEY_MGMT_OWE_TM`.
+const AP_SEC_KEY_MGMT_OWE: u32 = 0x800 | 0x1000;
+
+/// The directory that NetworkManager keeps the DHCP leases of its shared connections in.
+const DNSMASQ_LEASES_DIR: &str = "/var/lib/NetworkManager";
+
+/// The maximum number of polls to wait for a scan or an activation to complete.
+const MAX_POLLS: usize = 60;
+
+/// The interval between two polls.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The settings of a NetworkManager connection profile, grouped by their setting names (e.g. `connection`, `802-11-wireless`).
+type Settings = HashMap<String, HashMap<String, OwnedValue>>;
+
+/// The properties of a D-Bus object, keyed by their names.
+type Properties = HashMap<String, OwnedValue>;
+
+/// The adapter struct that implements [`Wl`] by talking to NetworkManager over the system D-Bus.
+///
+/// Unlike [`Nmcli`], `NetworkManager` does not spawn any programs and does not parse any text.
+/// Each method is executed with a few D-Bus calls on the `org.freedesktop.NetworkManager` service.
+///
+/// Since the struct can be changed in future versions, always
+/// prefer to initialize it by using [`NetworkManager::new`] or [`NetworkManager::with_connection`] instead.
+///
+/// [`Wl`]: crate::Wl
+/// [`Nmcli`]: crate::Nmcli
+/// [`NetworkManager::new`]: crate::NetworkManager::new
+/// [`NetworkManager::with_connection`]: crate::NetworkManager::with_connection
+#[derive(Clone, Default)]
+pub struct NetworkManager {
+    connection: Option<Connection>,
+}
+
+/// Represents a WiFi device of NetworkManager.
+struct WifiDevice {
+    path: OwnedObjectPath,
+    interface: String,
+}
+
+/// Represents a connection profile of NetworkManager.
+struct ConnectionProfile {
+    path: OwnedObjectPath,
+    id: Vec<u8>,
+    uuid: String,
+    kind: String,
+    autoconnect: bool,
+    priority: i32,
+}
+
+impl NetworkManager {
+    /// Creates a new `NetworkManager` instance that connects to the system bus on each call.
+    ///
+    /// The instance created by `new` can be reused multiple times
+    /// in a given context. It can also be cloned freely.
+    pub fn new() -> Self {
+        Self { connection: None }
+    }
+
+    /// Creates a new `NetworkManager` instance that uses the given D-Bus connection.
+    ///
+    /// This is useful to share a single connection between multiple calls,
+    /// or to talk to a NetworkManager service that is not on the system bus.
+    pub fn with_connection(connection: Connection) -> Self {
+        Self {
+            connection: Some(connection),
+        }
+    }
+
+    /// Provides whether the NetworkManager service is running on the system bus or not.
+    pub(crate) fn is_available(&self) -> bool {
+        let Ok(conn) = self.connection() else {
+            return false;
+        };
+
+        let (Ok(dbus), Ok(name)) = (DBusProxy::new(&conn), BusName::try_from(NM_BUS_NAME)) else {
+            return false;
+        };
+
+        dbus.name_has_owner(name).unwrap_or(false)
+    }
+
+    fn connection(&self) -> Result<Connection, (io::Error, i32)> {
+        match &self.connection {
+            Some(conn) => Ok(conn.clone()),
+            None => Connection::system().map_err(dbus_err),
+        }
+    }
+
+    fn proxy<'a, P>(
+        &self,
+        conn: &Connection,
+        path: P,
+        iface: &'a str,
+    ) -> Result<Proxy<'a>, (io::Error, i32)>
+    where
+        P: TryInto<ObjectPath<'a>>,
+        P::Error: Into<zbus::Error>,
+    {
+        Builder::<Proxy>::new(conn)
+            .destination(NM_BUS_NAME)
+            .and_then(|b| b.path(path))
+            .and_then(|b| b.interface(iface))
+            .map(|b| b.cache_properties(CacheProperties::No))
+            .and_then(|b| b.build())
+            .map_err(dbus_err)
+    }
+
+    /// Provides all the properties of the given interface with a single `GetAll` call.
+    fn get_all(
+        &self,
+        conn: &Connection,
+        path: &ObjectPath<'_>,
+        iface: &str,
+    ) -> Result<Properties, (io::Error, i32)> {
+        self.proxy(conn, path.to_owned(), PROPERTIES_IFACE)?
+            .call("GetAll", &(iface,))
+            .map_err(dbus_err)
+    }
+
+    fn get_wifi_devices(&self, conn: &Connection) -> Result<Vec<WifiDevice>, (io::Error, i32)> {
+        let device_paths: Vec<OwnedObjectPath> = self
+            .proxy(conn, NM_PATH, NM_IFACE)?
+            .call("GetDevices", &())
+            .map_err(dbus_err)?;
+
+        let mut devices = Vec::new();
+        for path in device_paths {
+            let mut props = self.get_all(conn, &path, DEVICE_IFACE)?;
+            if take::<u32>(&mut props, "DeviceType") != Some(DEVICE_TYPE_WIFI) {
+                continue;
+            }
+
+            devices.push(WifiDevice {
+                interface: take(&mut props, "Interface").unwrap_or_default(),
+                path,
+            });
+        }
+
+        Ok(devices)
+    }
+
+    fn get_wifi_device(&self, conn: &Connection) -> Result<WifiDevice, (io::Error, i32)> {
+        self.get_wifi_devices(conn)?
+            .into_iter()
+            .next()
+            .ok_or((io::Error::other("no WiFi device is found"), 1))
+    }
+
+    /// Provides the path and the properties of the access point of the given SSID on the WiFi device.
+    ///
+    /// If `bssid` is provided, then the access point with the BSSID is provided instead of the first one of the SSID.
+    /// It returns an error with the exit code `10` if the SSID is not on the scan list, as `nmcli` does.
+    fn find_access_point(
+        &self,
+        conn: &Connection,
+        ssid: &[u8],
+        bssid: Option<[u8; 6]>,
+    ) -> Result<(OwnedObjectPath, Properties), (io::Error, i32)> {
+        let device = self.get_wifi_device(conn)?;
+        let ap_paths: Vec<OwnedObjectPath> = self
+            .proxy(conn, device.path, WIRELESS_IFACE)?
+            .call("GetAllAccessPoints", &())
+            .map_err(dbus_err)?;
+
+        for path in ap_paths {
+            let mut props = self.get_all(conn, &path, AP_IFACE)?;
+            let ap_ssid: Vec<u8> = take(&mut props, "Ssid").unwrap_or_default();
+            let ap_bssid = take::<String>(&mut props, "HwAddress").and_then(|b| parse_bssid(&b));
+
+            if ap_ssid == ssid && bssid.is_none_or(|bssid| ap_bssid == Some(bssid)) {
+                return Ok((path, props));
+            }
+        }
+
+        let err = io::Error::other(format!(
+            "No network with SSID '{}' found.",
+            String::from_utf8_lossy(ssid)
+        ));
+        Err((err, UNKNOWN_SSID_EXIT_CODE))
+    }
+
+    fn get_profiles(&self, conn: &Connection) -> Result<Vec<ConnectionProfile>, (io::Error, i32)> {
+        let paths: Vec<OwnedObjectPath> = self
+            .proxy(conn, SETTINGS_PATH, SETTINGS_IFACE)?
+            .call("ListConnections", &())
+            .map_err(dbus_err)?;
+
+        let mut profiles = Vec::with_capacity(paths.len());
+        for path in paths {
+            let mut settings: Settings = self
+                .proxy(conn, path.clone(), CONNECTION_IFACE)?
+                .call("GetSettings", &())
+                .map_err(dbus_err)?;
+
+            let Some(mut connection) = settings.remove("connection") else {
+                continue;
+            };
+
+            profiles.push(ConnectionProfile {
+                path,
+                id: take::<String>(&mut connection, "id")
+                    .unwrap_or_default()
+                    .into_bytes(),
+                uuid: take(&mut connection, "uuid").unwrap_or_default(),
+                kind: take(&mut connection, "type").unwrap_or_default(),
+                autoconnect: take(&mut connection, "autoconnect").unwrap_or(true),
+                priority: take(&mut connection, "autoconnect-priority").unwrap_or_default(),
+            });
+        }
+
+        Ok(profiles)
+    }
+
+    /// Provides the active connections as pairs of their paths and properties.
+    fn get_active_connections(
+        &self,
+        conn: &Connection,
+    ) -> Result<Vec<(OwnedObjectPath, Properties)>, (io::Error, i32)> {
+        let paths: Vec<OwnedObjectPath> = self
+            .proxy(conn, NM_PATH, NM_IFACE)?
+            .get_property("ActiveConnections")
+            .map_err(dbus_err)?;
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let props = self.get_all(conn, &path, ACTIVE_IFACE)?;
+                Ok((path, props))
+            })
+            .collect()
+    }
+
+    /// Waits until the given active connection is either activated or deactivated.
+    ///
+    /// The `StateChanged` signals of the active connection are followed to obtain the reason of a deactivation,
+    /// so a wrong or missing password is reported as an authentication failure.
+    /// The state is also polled, since the signals that are sent before the subscription are missed.
+    fn wait_for_activation(
+        &self,
+        conn: &Connection,
+        active_path: &OwnedObjectPath,
+    ) -> Result<(), (io::Error, i32)> {
+        let active = self.proxy(conn, active_path.clone(), ACTIVE_IFACE)?;
+
+        let rule = MatchRule::builder()
+            .msg_type(message::Type::Signal)
+            .interface(ACTIVE_IFACE)
+            .and_then(|b| b.member("StateChanged"))
+            .and_then(|b| b.path(active_path.clone()))
+            .map(|b| b.build())
+            .map_err(dbus_err)?;
+        let signals = MessageIterator::for_match_rule(rule, conn, None).map_err(dbus_err)?;
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for signal in signals {
+                let Ok(signal) = signal else {
+                    break;
+                };
+
+                let Ok((state, reason)) = signal.body().deserialize::<(u32, u32)>() else {
+                    continue;
+                };
+                if tx.send((state, reason)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        for _ in 0..MAX_POLLS {
+            // The active connection object is removed once it is deactivated.
+            let state = active
+                .get_property::<u32>("State")
+                .unwrap_or(ACTIVE_STATE_DEACTIVATED);
+
+            match state {
+                ACTIVE_STATE_ACTIVATED => return Ok(()),
+                ACTIVE_STATE_DEACTIVATED => {
+                    // The signal of the deactivation may arrive after the state is changed.
+                    let reason = rx
+                        .try_iter()
+                        .chain(iter::once_with(|| rx.recv_timeout(POLL_INTERVAL)).flatten())
+                        .find(|(state, _)| *state == ACTIVE_STATE_DEACTIVATED)
+                        .map(|(_, reason)| reason);
+
+                    return Err(deactivation_err(reason));
+                }
+                _ => match rx.recv_timeout(POLL_INTERVAL) {
+                    Ok((ACTIVE_STATE_ACTIVATED, _)) => return Ok(()),
+                    Ok((ACTIVE_STATE_DEACTIVATED, reason)) => {
+                        return Err(deactivation_err(Some(reason)));
+                    }
+                    _ => {}
+                },
+            }
+        }
+
+        Err((
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                "timed out while waiting for the connection to be activated",
+            ),
+            3,
+        ))
+    }
+
+    fn delete_profiles(&self, conn: &Connection, ssid: &[u8]) -> Result<(), (io::Error, i32)> {
+        let profiles = self
+            .get_profiles(conn)?
+            .into_iter()
+            .filter(|p| p.id == ssid)
+            .collect::<Vec<ConnectionProfile>>();
+
+        if profiles.is_empty() {
+            return Err((
+                io::Error::other(format!(
+                    "unknown connection '{}'",
+                    String::from_utf8_lossy(ssid)
+                )),
+                UNKNOWN_SSID_EXIT_CODE,
+            ));
+        }
+
+        for profile in profiles {
+            self.proxy(conn, profile.path, CONNECTION_IFACE)?
+                .call::<_, _, ()>("Delete", &())
+                .map_err(dbus_err)?;
+        }
+
+        Ok(())
+    }
+
+    /// Provides the connection profile of the given SSID, or an error with the exit code `10` if the SSID is not a known one.
+    fn find_profile(
+        &self,
+        conn: &Connection,
+        ssid: &[u8],
+    ) -> Result<ConnectionProfile, (io::Error, i32)> {
+        self.get_profiles(conn)?
+            .into_iter()
+            .find(|p| p.id == ssid)
+            .ok_or_else(|| {
+                let err = io::Error::other(format!(
+                    "unknown connection '{}'",
+                    String::from_utf8_lossy(ssid)
+                ));
+                (err, UNKNOWN_SSID_EXIT_CODE)
+            })
+    }
+
+    /// Updates the connection profile of the given SSID with `update`, and saves it with `Update`.
+    ///
+    /// `Update` replaces the whole profile, so the secrets of the profile are merged into its settings first to keep them.
+    fn update_profile(
+        &self,
+        conn: &Connection,
+        ssid: &[u8],
+        update: impl FnOnce(&mut Settings) -> Result<(), (io::Error, i32)>,
+    ) -> Result<(), (io::Error, i32)> {
+        let profile = self.find_profile(conn, ssid)?;
+
+        let proxy = self.proxy(conn, profile.path, CONNECTION_IFACE)?;
+        let mut settings: Settings = proxy.call("GetSettings", &()).map_err(dbus_err)?;
+
+        if settings.contains_key("802-11-wireless-security") {
+            // The secrets that cannot be read (e.g. the ones of a secret agent) are not stored in the profile anyway.
+            if let Ok(secrets) =
+                proxy.call::<_, _, Settings>("GetSecrets", &("802-11-wireless-security",))
+            {
+                for (name, secret) in secrets {
+                    settings.entry(name).or_default().extend(secret);
+                }
+            }
+        }
+
+        update(&mut settings)?;
+
+        proxy
+            .call::<_, _, ()>("Update", &(settings,))
+            .map_err(dbus_err)
+    }
+
+    /// Activates the given SSID on the WiFi device, and waits until it is activated.
+    ///
+    /// If `settings` is provided, then a new connection profile is added with them, replacing the known network if `is_known_ssid` is set.
+    /// If not, then the known connection profile of the SSID is activated.
+    ///
+    /// If `access_point` is provided, then it is the specific object of the activation, which lets NetworkManager complete the security settings from it.
+    fn activate(
+        &self,
+        ssid: &[u8],
+        settings: Option<HashMap<&'static str, HashMap<&'static str, Value<'_>>>>,
+        access_point: Option<OwnedObjectPath>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, (io::Error, i32)> {
+        let conn = self.connection()?;
+
+        if is_known_ssid && settings.is_some() {
+            self.delete_profiles(&conn, ssid)?;
+        }
+
+        let device = self.get_wifi_device(&conn)?;
+        let nm = self.proxy(&conn, NM_PATH, NM_IFACE)?;
+        let no_object = OwnedObjectPath::try_from("/").map_err(|err| dbus_err(err.into()))?;
+        let specific_object = access_point.unwrap_or(no_object.clone());
+
+        let (profile_path, active_path) = if let Some(settings) = settings {
+            let (profile_path, active_path): (OwnedObjectPath, OwnedObjectPath) = nm
+                .call(
+                    "AddAndActivateConnection",
+                    &(settings, &device.path, &specific_object),
+                )
+                .map_err(dbus_err)?;
+
+            (Some(profile_path), active_path)
+        } else {
+            let profile = self
+                .get_profiles(&conn)?
+                .into_iter()
+                .find(|p| p.id == ssid)
+                .ok_or_else(|| {
+                    let err = io::Error::other(format!(
+                        "unknown connection '{}'",
+                        String::from_utf8_lossy(ssid)
+                    ));
+                    (err, UNKNOWN_SSID_EXIT_CODE)
+                })?;
+
+            let active_path: OwnedObjectPath = nm
+                .call(
+                    "ActivateConnection",
+                    &(&profile.path, &device.path, &no_object),
+                )
+                .map_err(dbus_err)?;
+
+            (None, active_path)
+        };
+
+        if let Err(err) = self.wait_for_activation(&conn, &active_path) {
+            if let Some(profile_path) = profile_path {
+                let _ = self
+                    .proxy(&conn, profile_path, CONNECTION_IFACE)
+                    .and_then(|p| p.call::<_, _, ()>("Delete", &()).map_err(dbus_err));
+            }
+
+            return Err(err);
+        }
+
+        let result = [
+            b"Device '",
+            device.interface.as_bytes(),
+            b"' is successfully activated with '",
+            ssid,
+            b"'.\n",
+        ]
+        .concat();
+
+        Ok(result)
+    }
+
+    /// Provides the hotspot, if its connection profile is active.
+    fn hotspot(&self, conn: &Connection) -> Result<Option<Hotspot>, (io::Error, i32)> {
+        for (_, mut props) in self.get_active_connections(conn)? {
+            if take::<String>(&mut props, "Id").as_deref() != Some(HOTSPOT_PROFILE) {
+                continue;
+            }
+
+            let device = match take::<Vec<OwnedObjectPath>>(&mut props, "Devices")
+                .and_then(|paths| paths.into_iter().next())
+            {
+                Some(path) => {
+                    let mut device_props = self.get_all(conn, &path, DEVICE_IFACE)?;
+                    take::<String>(&mut device_props, "Interface").unwrap_or_default()
+                }
+                None => String::new(),
+            };
+
+            let mut settings = match take::<OwnedObjectPath>(&mut props, "Connection") {
+                Some(path) => self
+                    .proxy(conn, path, CONNECTION_IFACE)?
+                    .call::<_, _, Settings>("GetSettings", &())
+                    .map_err(dbus_err)?,
+                None => Settings::new(),
+            };
+            let mut wireless = settings.remove("802-11-wireless").unwrap_or_default();
+
+            return Ok(Some(Hotspot {
+                ssid: take(&mut wireless, "ssid").unwrap_or_default(),
+                band: match take::<String>(&mut wireless, "band").as_deref() {
+                    Some("bg") => Some(Band::Bg),
+                    Some("a") => Some(Band::A),
+                    _ => None,
+                },
+                channel: take::<u32>(&mut wireless, "channel").filter(|c| c != &0),
+                secured: settings.contains_key("802-11-wireless-security"),
+                clients: read_hotspot_clients(&device),
+                device,
+            }));
+        }
+
+        Ok(None)
+    }
+}
+
+impl Wl for NetworkManager {
+    /// Provides the WiFi status.
+    ///
+    /// The WiFi status is the `WirelessEnabled` property of NetworkManager.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetWiFiStatus`] when it fails
+    /// to obtain the WiFi status.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    ///
+    /// let nm = NetworkManager::new();
+    /// let status = nm.get_wifi_status().unwrap();
+    /// println!("wifi: {}", status);
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetWiFiStatus`]: crate::NetworkAdapterError::CannotGetWiFiStatus
+    fn get_wifi_status(&self) -> Result<WifiStatus, Error> {
+        let conn = self.connection().map_err(Error::CannotGetWiFiStatus)?;
+
+        let enabled = self
+            .proxy(&conn, NM_PATH, NM_IFACE)
+            .and_then(|nm| nm.get_property::<bool>("WirelessEnabled").map_err(dbus_err))
+            .map_err(Error::CannotGetWiFiStatus)?;
+
+        Ok(if enabled {
+            WifiStatus::Enabled
+        } else {
+            WifiStatus::Disabled
+        })
+    }
+
+    /// Toggles the WiFi status.
+    ///
+    /// The WiFi status is toggled by setting the `WirelessEnabled` property of NetworkManager.
+    /// It returns the updated WiFi status.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method can return either a [`NetworkAdapterError::CannotGetWiFiStatus`] or a [`NetworkAdapterError::CannotToggleWiFi`] when it fails to toggle WiFi.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    ///
+    /// let nm = NetworkManager::new();
+    /// let updated_status = nm.toggle_wifi().unwrap();
+    /// println!("wifi: {}", updated_status);
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetWiFiStatus`]: crate::NetworkAdapterError::CannotGetWiFiStatus
+    /// [`NetworkAdapterError::CannotToggleWiFi`]: crate::NetworkAdapterError::CannotToggleWiFi
+    fn toggle_wifi(&self) -> Result<WifiStatus, Error> {
+        let new_status = self.get_wifi_status()?.toggled();
+
+        let conn = self.connection().map_err(Error::CannotToggleWiFi)?;
+        self.proxy(&conn, NM_PATH, NM_IFACE)
+            .and_then(|nm| {
+                nm.set_property("WirelessEnabled", new_status == WifiStatus::Enabled)
+                    .map_err(|err| dbus_err(err.into()))
+            })
+            .map_err(Error::CannotToggleWiFi)?;
+
+        Ok(new_status)
+    }
+
+    /// Provides the known networks on the host.
+    ///
+    /// The known networks are the connection profiles of NetworkManager, and the active ones are matched with their devices through the active connections.
+    /// The network types are shortened the same way [`Nmcli::get_networks`] does (e.g. `802-11-wireless` becomes `wifi`).
+    ///
+    /// The active networks are listed first, like `nmcli connection show` does.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`NetworkAdapterError::CannotListNetworks`] when it fails to retrieve the networks, or a [`NetworkAdapterError::CannotGetActiveConnections`] if `active_only` is set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    ///
+    /// let nm = NetworkManager::new();
+    /// let networks = nm.get_networks(true).unwrap();
+    ///
+    /// for network in networks {
+    ///     println!("{}", String::from_utf8_lossy(&network.ssid));
+    /// }
+    /// ```
+    ///
+    /// [`Nmcli::get_networks`]: crate::Nmcli::get_networks
+    /// [`NetworkAdapterError::CannotListNetworks`]: crate::NetworkAdapterError::CannotListNetworks
+    /// [`NetworkAdapterError::CannotGetActiveConnections`]: crate::NetworkAdapterError::CannotGetActiveConnections
+    fn get_networks(&self, active_only: bool) -> Result<Vec<Network>, Error> {
+        let map_err = if active_only {
+            Error::CannotGetActiveConnections
+        } else {
+            Error::CannotListNetworks
+        };
+
+        let conn = self.connection().map_err(map_err)?;
+
+        let mut devices = HashMap::new();
+        for (_, mut props) in self.get_active_connections(&conn).map_err(map_err)? {
+            let profile_path = take::<OwnedObjectPath>(&mut props, "Connection");
+            let device_path = take::<Vec<OwnedObjectPath>>(&mut props, "Devices")
+                .and_then(|paths| paths.into_iter().next());
+
+            if let (Some(profile_path), Some(device_path)) = (profile_path, device_path) {
+                let mut device_props = self
+                    .get_all(&conn, &device_path, DEVICE_IFACE)
+                    .map_err(map_err)?;
+                let interface = take::<String>(&mut device_props, "Interface").unwrap_or_default();
+                devices.insert(profile_path, interface);
+            }
+        }
+
+        let mut networks = self
+            .get_profiles(&conn)
+            .map_err(map_err)?
+            .into_iter()
+            .map(|profile| Network {
+                device: devices.get(&profile.path).cloned(),
+                ssid: profile.id,
+                uuid: profile.uuid,
+                kind: match profile.kind.as_str() {
+                    "802-11-wireless" => String::from("wifi"),
+                    "802-3-ethernet" => String::from("ethernet"),
+                    kind => kind.to_string(),
+                },
+            })
+            .filter(|n| !active_only || n.is_active())
+            .collect::<Vec<Network>>();
+
+        networks.sort_by_key(|n| !n.is_active());
+
+        Ok(networks)
+    }
+
+    /// Disconnects from the given SSID.
+    ///
+    /// The SSID is matched with the `id` of the connection profiles, like `nmcli connection down id SSID` does.
+    /// If `forget` is set, then the connection profiles are deleted instead, which also disconnects them.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`NetworkAdapterError::CannotDisconnect`] when it fails to disconnect, or when the given SSID is not active and `forget` is not set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    /// use std::io::{self, Write};
+    ///
+    /// let nm = NetworkManager::new();
+    /// let result = nm.disconnect(b"SSID", true).unwrap();
+    /// io::stdout().write_all(&result).unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotDisconnect`]: crate::NetworkAdapterError::CannotDisconnect
+    fn disconnect(&self, ssid: &[u8], forget: bool) -> Result<Vec<u8>, Error> {
+        let conn = self.connection().map_err(Error::CannotDisconnect)?;
+
+        if forget {
+            self.delete_profiles(&conn, ssid)
+                .map_err(Error::CannotDisconnect)?;
+        } else {
+            let active_paths = self
+                .get_active_connections(&conn)
+                .map_err(Error::CannotDisconnect)?
+                .into_iter()
+                .filter_map(|(path, mut props)| {
+                    let id = take::<String>(&mut props, "Id")?;
+                    (id.as_bytes() == ssid).then_some(path)
+                })
+                .collect::<Vec<OwnedObjectPath>>();
+
+            if active_paths.is_empty() {
+                let err = io::Error::other(format!(
+                    "{} is not an active network",
+                    String::from_utf8_lossy(ssid)
+                ));
+                return Err(Error::CannotDisconnect((err, UNKNOWN_SSID_EXIT_CODE)));
+            }
+
+            let nm = self
+                .proxy(&conn, NM_PATH, NM_IFACE)
+                .map_err(Error::CannotDisconnect)?;
+            for path in active_paths {
+                nm.call::<_, _, ()>("DeactivateConnection", &(path,))
+                    .map_err(|err| Error::CannotDisconnect(dbus_err(err)))?;
+            }
+        }
+
+        let action = if forget { "deleted" } else { "deactivated" };
+        let result = [
+            b"Connection '",
+            ssid,
+            b"' is successfully ",
+            action.as_bytes(),
+            b".\n",
+        ]
+        .concat();
+
+        Ok(result)
+    }
+
+    /// Provides the access points that are visible to the host.
+    ///
+    /// The access points of each WiFi device are obtained with `GetAllAccessPoints`, and the properties of each access point are obtained with a single `GetAll` call.
+    /// The SSIDs are kept as raw bytes, so the SSIDs that are not valid UTF-8 are preserved as is.
+    ///
+    /// If `re_scan` is set, then `get_access_points` requests a scan and waits for the `LastScan` property of the device to change.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotScanWiFi`] if it fails to scan the available networks.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    ///
+    /// let nm = NetworkManager::new();
+    /// let aps = nm.get_access_points(true).unwrap();
+    ///
+    /// for ap in aps {
+    ///     println!("{} ({})", String::from_utf8_lossy(&ap.ssid), ap.signal);
+    /// }
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotScanWiFi`]: crate::NetworkAdapterError::CannotScanWiFi
+    fn get_access_points(&self, re_scan: bool) -> Result<Vec<AccessPoint>, Error> {
+        let conn = self.connection().map_err(Error::CannotScanWiFi)?;
+        let devices = self
+            .get_wifi_devices(&conn)
+            .map_err(Error::CannotScanWiFi)?;
+
+        let mut aps = Vec::new();
+        for device in devices {
+            let wireless = self
+                .proxy(&conn, device.path.clone(), WIRELESS_IFACE)
+                .map_err(Error::CannotScanWiFi)?;
+
+            if re_scan {
+                let last_scan = wireless.get_property::<i64>("LastScan").unwrap_or_default();
+                let options: HashMap<&str, Value> = HashMap::new();
+
+                // NetworkManager rejects the scan requests that follow a recent scan,
+                // in that case the current scan results are used.
+                if wireless
+                    .call::<_, _, ()>("RequestScan", &(options,))
+                    .is_ok()
+                {
+                    for _ in 0..MAX_POLLS {
+                        thread::sleep(POLL_INTERVAL);
+                        let scan = wireless.get_property::<i64>("LastScan").unwrap_or_default();
+                        if scan != last_scan {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            let active_ap = wireless
+                .get_property::<OwnedObjectPath>("ActiveAccessPoint")
+                .map_err(|err| Error::CannotScanWiFi(dbus_err(err)))?;
+
+            let ap_paths: Vec<OwnedObjectPath> = wireless
+                .call("GetAllAccessPoints", &())
+                .map_err(|err| Error::CannotScanWiFi(dbus_err(err)))?;
+
+            for path in ap_paths {
+                let mut props = self
+                    .get_all(&conn, &path, AP_IFACE)
+                    .map_err(Error::CannotScanWiFi)?;
+
+                let frequency = take(&mut props, "Frequency").unwrap_or_default();
+                let flags = take(&mut props, "Flags").unwrap_or_default();
+                let wpa_flags = take(&mut props, "WpaFlags").unwrap_or_default();
+                let rsn_flags = take(&mut props, "RsnFlags").unwrap_or_default();
+
+                aps.push(AccessPoint {
+                    ssid: take(&mut props, "Ssid").unwrap_or_default(),
+                    bssid: take::<String>(&mut props, "HwAddress")
+                        .unwrap_or_default()
+                        .to_ascii_uppercase(),
+                    mode: mode_from_nm(take(&mut props, "Mode").unwrap_or_default()).to_string(),
+                    channel: channel_from_frequency(frequency),
+                    frequency,
+                    rate: take::<u32>(&mut props, "MaxBitrate").unwrap_or_default() / 1000,
+                    signal: take(&mut props, "Strength").unwrap_or_default(),
+                    security: security_from_flags(flags, wpa_flags, rsn_flags),
+                    in_use: path == active_ap,
+                });
+            }
+        }
+
+        Ok(aps)
+    }
+
+    /// Checks whether the given SSID is a known one or not.
+    ///
+    /// The SSID is matched with the `id` of the connection profiles.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetSSIDStatus`] if it fails to check the SSID.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    ///
+    /// let nm = NetworkManager::new();
+    /// let is_known_ssid = nm.is_known_ssid(b"SSID").unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetSSIDStatus`]: crate::NetworkAdapterError::CannotGetSSIDStatus
+    fn is_known_ssid(&self, ssid: &[u8]) -> Result<bool, Error> {
+        let conn = self.connection().map_err(Error::CannotGetSSIDStatus)?;
+
+        let exists = self
+            .get_profiles(&conn)
+            .map_err(Error::CannotGetSSIDStatus)?
+            .iter()
+            .any(|p| p.id == ssid);
+
+        Ok(exists)
+    }
+
+    /// Connects to the given SSID.
+    ///
+    /// The semantics are the same as [`Nmcli::connect`]:
+    ///
+    /// - To establish a new connection, provide both `ssid` and `passwd`. A new connection profile is added and activated with `AddAndActivateConnection`.
+    ///   The access point of the SSID is given as its specific object, so NetworkManager completes the security type (e.g. WPA2, WPA3) from the access point, as `nmcli device wifi connect` does.
+    /// - To re-use a known network, only provide `ssid`. Its connection profile is activated with `ActivateConnection`.
+    /// - To "update" a known network, provide all the arguments. The known network is deleted before the new connection attempt.
+    ///
+    /// If `bssid` is provided, then the `bssid` setting of the connection profile is set, which pins it to the access point.
+    /// A known network is updated with `Update` before it is activated.
+    ///
+    /// `connect` waits until the connection is activated.
+    /// If a new connection profile cannot be activated, then it is deleted.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotConnect`] if it fails to connect to the the SSID.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    /// use std::io::{self, Write};
+    ///
+    /// let nm = NetworkManager::new();
+    /// let result = nm.connect(b"SSID", Some(b"PASS"), None, false).unwrap();
+    /// io::stdout().write_all(&result).unwrap();
+    /// ```
+    ///
+    /// [`Nmcli::connect`]: crate::Nmcli::connect
+    /// [`NetworkAdapterError::CannotConnect`]: crate::NetworkAdapterError::CannotConnect
+    fn connect(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        bssid: Option<&str>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let bssid = match bssid {
+            Some(bssid) => Some(parse_bssid(bssid).ok_or_else(|| {
+                let err = io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid BSSID '{}'", bssid),
+                );
+                Error::CannotConnect((err, 2))
+            })?),
+            None => None,
+        };
+
+        let (settings, access_point) = match is_known_ssid && passwd.is_none() {
+            true => (None, None),
+            false => {
+                let conn = self.connection().map_err(Error::CannotConnect)?;
+                let (path, props) = self
+                    .find_access_point(&conn, ssid, bssid)
+                    .map_err(Error::CannotConnect)?;
+
+                (Some(new_wifi_settings(ssid, passwd, &props)), Some(path))
+            }
+        };
+
+        let settings = match (settings, bssid) {
+            (Some(mut settings), Some(bssid)) => {
+                if let Some(wireless) = settings.get_mut("802-11-wireless") {
+                    wireless.insert("bssid", Value::from(bssid.to_vec()));
+                }
+                Some(settings)
+            }
+            (None, Some(bssid)) => {
+                let conn = self.connection().map_err(Error::CannotConnect)?;
+                self.update_profile(&conn, ssid, |settings| {
+                    set_setting(
+                        settings,
+                        "802-11-wireless",
+                        "bssid",
+                        Value::from(bssid.to_vec()),
+                    )
+                })
+                .map_err(Error::CannotConnect)?;
+
+                None
+            }
+            (settings, None) => settings,
+        };
+
+        self.activate(ssid, settings, access_point, is_known_ssid)
+            .map_err(Error::CannotConnect)
+    }
+
+    /// Removes the BSSID that is pinned to the given known SSID.
+    ///
+    /// The `bssid` setting is removed from the connection profile, which is saved with `Update`.
+    /// The change is applied on the next activation of the profile.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotUpdateNetwork`] if the connection profile cannot be found or updated.
+    ///
+    /// [`NetworkAdapterError::CannotUpdateNetwork`]: crate::NetworkAdapterError::CannotUpdateNetwork
+    fn clear_bssid(&self, ssid: &[u8]) -> Result<Vec<u8>, Error> {
+        let conn = self.connection().map_err(Error::CannotUpdateNetwork)?;
+
+        self.update_profile(&conn, ssid, |settings| {
+            if let Some(wireless) = settings.get_mut("802-11-wireless") {
+                wireless.remove("bssid");
+            }
+            Ok(())
+        })
+        .map_err(Error::CannotUpdateNetwork)?;
+
+        let result = [
+            b"Connection '",
+            ssid,
+            b"' is no longer pinned to a BSSID.\n",
+        ]
+        .concat();
+        Ok(result)
+    }
+
+    /// Connects to the given hidden SSID.
+    ///
+    /// The semantics are the same as [`Nmcli::connect_hidden`]: a new connection profile with `hidden` set and the key management of the given security type
+    /// is added and activated with `AddAndActivateConnection`, and the known network is replaced if `is_known_ssid` is set.
+    ///
+    /// `connect_hidden` waits until the connection is activated.
+    /// If the new connection profile cannot be activated, then it is deleted.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotConnect`] if it fails to connect to the the SSID.
+    ///
+    /// [`Nmcli::connect_hidden`]: crate::Nmcli::connect_hidden
+    /// [`NetworkAdapterError::CannotConnect`]: crate::NetworkAdapterError::CannotConnect
+    fn connect_hidden(
+        &self,
+        ssid: &[u8],
+        security: Security,
+        passwd: Option<&[u8]>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let settings = new_hidden_settings(ssid, security, passwd);
+
+        self.activate(ssid, Some(settings), None, is_known_ssid)
+            .map_err(Error::CannotConnect)
+    }
+
+    /// Connects to the given enterprise (802.1X / WPA-EAP) SSID.
+    ///
+    /// The semantics are the same as [`Nmcli::connect_enterprise`]: a new connection profile with the `wpa-eap` key management
+    /// and the `802-1x` settings is added and activated with `AddAndActivateConnection`, and the known network is replaced if `is_known_ssid` is set.
+    ///
+    /// `connect_enterprise` waits until the connection is activated.
+    /// If the new connection profile cannot be activated, then it is deleted.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotConnect`] if it fails to connect to the the SSID.
+    ///
+    /// [`Nmcli::connect_enterprise`]: crate::Nmcli::connect_enterprise
+    /// [`NetworkAdapterError::CannotConnect`]: crate::NetworkAdapterError::CannotConnect
+    fn connect_enterprise(
+        &self,
+        ssid: &[u8],
+        credentials: &EapCredentials,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let settings = new_enterprise_settings(ssid, credentials);
+
+        self.activate(ssid, Some(settings), None, is_known_ssid)
+            .map_err(Error::CannotConnect)
+    }
+
+    /// Adds the given network as a known network with `AddConnection`, without activating it.
+    ///
+    /// The semantics are the same as [`Nmcli::add_network`]: the known network is replaced if `is_known_ssid` is set,
+    /// and a secured network without a secret is added with the `not-saved` secret flag, so its password is asked on each connection instead.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotAddNetwork`] if it fails to remove the known network, or to add the new one.
+    ///
+    /// [`Nmcli::add_network`]: crate::Nmcli::add_network
+    /// [`NetworkAdapterError::CannotAddNetwork`]: crate::NetworkAdapterError::CannotAddNetwork
+    fn add_network(
+        &self,
+        network: &ExportedNetwork,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let ssid = network.ssid.as_slice();
+        let conn = self.connection().map_err(Error::CannotAddNetwork)?;
+
+        if is_known_ssid {
+            self.delete_profiles(&conn, ssid)
+                .map_err(Error::CannotAddNetwork)?;
+        }
+
+        let mut settings = new_hidden_settings(ssid, network.security, network.secret.as_deref());
+        if let Some(wireless) = settings.get_mut("802-11-wireless") {
+            wireless.insert("hidden", Value::from(network.hidden));
+        }
+        if let Some(connection) = settings.get_mut("connection") {
+            connection.insert("autoconnect", Value::from(network.autoconnect));
+            connection.insert("autoconnect-priority", Value::from(network.priority));
+        }
+        if let (Some(wifi_sec), None) = (
+            settings.get_mut("802-11-wireless-security"),
+            &network.secret,
+        ) {
+            // NM_SETTING_SECRET_FLAG_NOT_SAVED
+            wifi_sec.insert("psk-flags", Value::from(2u32));
+        }
+
+        self.proxy(&conn, SETTINGS_PATH, SETTINGS_IFACE)
+            .and_then(|settings_proxy| {
+                settings_proxy
+                    .call::<_, _, OwnedObjectPath>("AddConnection", &(settings,))
+                    .map_err(dbus_err)
+            })
+            .map_err(Error::CannotAddNetwork)?;
+
+        let result = [b"Connection '", ssid, b"' successfully added.\n"].concat();
+        Ok(result)
+    }
+
+    /// Starts a hotspot that shares the connection of the host.
+    ///
+    /// The hotspot is a connection profile named `wl-hotspot`, which is added and activated with a single `AddAndActivateConnection` call
+    /// in the `ap` mode with the `shared` IPv4 method, like `nmcli device wifi hotspot` does.
+    /// The hotspot is open when `passwd` is not provided.
+    ///
+    /// The previous `wl-hotspot` profile is replaced, and the new profile is deleted if it cannot be activated.
+    /// If only `channel` is provided, then the band is derived from it, since NetworkManager requires both.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotStartHotspot`] if it fails to start the hotspot.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Band, NetworkManager, Wl};
+    ///
+    /// let nm = NetworkManager::new();
+    /// let hotspot = nm
+    ///     .start_hotspot(b"Field", Some(b"password"), Some(Band::Bg), None)
+    ///     .unwrap();
+    ///
+    /// println!("hotspot is running on {}", hotspot.device);
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotStartHotspot`]: crate::NetworkAdapterError::CannotStartHotspot
+    fn start_hotspot(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        band: Option<Band>,
+        channel: Option<u32>,
+    ) -> Result<Hotspot, Error> {
+        let conn = self.connection().map_err(Error::CannotStartHotspot)?;
+
+        match self.delete_profiles(&conn, HOTSPOT_PROFILE.as_bytes()) {
+            Ok(_) | Err((_, UNKNOWN_SSID_EXIT_CODE)) => {}
+            Err(err) => return Err(Error::CannotStartHotspot(err)),
+        }
+
+        let device = self
+            .get_wifi_device(&conn)
+            .map_err(Error::CannotStartHotspot)?;
+        let nm = self
+            .proxy(&conn, NM_PATH, NM_IFACE)
+            .map_err(Error::CannotStartHotspot)?;
+        let no_object = OwnedObjectPath::try_from("/")
+            .map_err(|err| Error::CannotStartHotspot(dbus_err(err.into())))?;
+
+        let settings = new_hotspot_settings(ssid, passwd, band, channel);
+        let (profile_path, active_path): (OwnedObjectPath, OwnedObjectPath) = nm
+            .call(
+                "AddAndActivateConnection",
+                &(settings, &device.path, &no_object),
+            )
+            .map_err(|err| Error::CannotStartHotspot(dbus_err(err)))?;
+
+        if let Err(err) = self.wait_for_activation(&conn, &active_path) {
+            let _ = self
+                .proxy(&conn, profile_path, CONNECTION_IFACE)
+                .and_then(|p| p.call::<_, _, ()>("Delete", &()).map_err(dbus_err));
+
+            return Err(Error::CannotStartHotspot(err));
+        }
+
+        self.hotspot(&conn)
+            .map_err(Error::CannotStartHotspot)?
+            .ok_or_else(|| {
+                let err = io::Error::other("the hotspot is not active after it is started");
+                Error::CannotStartHotspot((err, 1))
+            })
+    }
+
+    /// Stops the hotspot that is started by [`NetworkManager::start_hotspot`].
+    ///
+    /// The `wl-hotspot` connection profile is deleted, which also deactivates it.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotStopHotspot`] if it fails to stop the hotspot, or if the hotspot is not active.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    ///
+    /// let nm = NetworkManager::new();
+    /// let hotspot = nm.stop_hotspot().unwrap();
+    ///
+    /// println!("stopped {}", String::from_utf8_lossy(&hotspot.ssid));
+    /// ```
+    ///
+    /// [`NetworkManager::start_hotspot`]: crate::NetworkManager::start_hotspot
+    /// [`NetworkAdapterError::CannotStopHotspot`]: crate::NetworkAdapterError::CannotStopHotspot
+    fn stop_hotspot(&self) -> Result<Hotspot, Error> {
+        let conn = self.connection().map_err(Error::CannotStopHotspot)?;
+
+        let hotspot = self
+            .hotspot(&conn)
+            .map_err(Error::CannotStopHotspot)?
+            .ok_or_else(|| {
+                let err = io::Error::other("no hotspot is active");
+                Error::CannotStopHotspot((err, UNKNOWN_SSID_EXIT_CODE))
+            })?;
+
+        self.delete_profiles(&conn, HOTSPOT_PROFILE.as_bytes())
+            .map_err(Error::CannotStopHotspot)?;
+
+        Ok(hotspot)
+    }
+
+    /// Provides the hotspot that is started by [`NetworkManager::start_hotspot`], if it is active.
+    ///
+    /// The connected clients are read from the DHCP leases of the hotspot, which are kept by NetworkManager under `/var/lib/NetworkManager`.
+    /// If the leases cannot be read, then the clients are `None`.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetHotspot`] if it fails to get the hotspot.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    ///
+    /// let nm = NetworkManager::new();
+    /// match nm.get_hotspot().unwrap() {
+    ///     Some(hotspot) => println!("hotspot is running on {}", hotspot.device),
+    ///     None => println!("hotspot is not active"),
+    /// }
+    /// ```
+    ///
+    /// [`NetworkManager::start_hotspot`]: crate::NetworkManager::start_hotspot
+    /// [`NetworkAdapterError::CannotGetHotspot`]: crate::NetworkAdapterError::CannotGetHotspot
+    fn get_hotspot(&self) -> Result<Option<Hotspot>, Error> {
+        let conn = self.connection().map_err(Error::CannotGetHotspot)?;
+        self.hotspot(&conn).map_err(Error::CannotGetHotspot)
+    }
+
+    /// Provides the password of the given known SSID.
+    ///
+    /// The password is requested from the connection profile of the SSID with `GetSecrets`, which may require the privileges to read the secrets of the profile.
+    /// If the profile does not have a `802-11-wireless-security` setting, then the network is open and `None` is provided.
+    /// The password is the PSK of the profile, or its first WEP key for a WEP network.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetPasswd`] if it fails to read the connection profile, or if the SSID is not a known one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    ///
+    /// let nm = NetworkManager::new();
+    /// match nm.get_passwd(b"SSID").unwrap() {
+    ///     Some(passwd) => println!("password: {}", String::from_utf8_lossy(&passwd)),
+    ///     None => println!("SSID is an open network"),
+    /// }
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetPasswd`]: crate::NetworkAdapterError::CannotGetPasswd
+    fn get_passwd(&self, ssid: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let conn = self.connection().map_err(Error::CannotGetPasswd)?;
+
+        let profile = self
+            .get_profiles(&conn)
+            .map_err(Error::CannotGetPasswd)?
+            .into_iter()
+            .find(|p| p.id == ssid)
+            .ok_or_else(|| {
+                let err = io::Error::other(format!(
+                    "unknown connection '{}'",
+                    String::from_utf8_lossy(ssid)
+                ));
+                Error::CannotGetPasswd((err, UNKNOWN_SSID_EXIT_CODE))
+            })?;
+
+        let proxy = self
+            .proxy(&conn, profile.path, CONNECTION_IFACE)
+            .map_err(Error::CannotGetPasswd)?;
+
+        let settings: Settings = proxy
+            .call("GetSettings", &())
+            .map_err(|err| Error::CannotGetPasswd(dbus_err(err)))?;
+        if !settings.contains_key("802-11-wireless-security") {
+            return Ok(None);
+        }
+
+        let mut secrets: Settings = proxy
+            .call("GetSecrets", &("802-11-wireless-security",))
+            .map_err(|err| Error::CannotGetPasswd(dbus_err(err)))?;
+
+        secrets
+            .remove("802-11-wireless-security")
+            .and_then(|mut security| {
+                take::<String>(&mut security, "psk").or_else(|| take(&mut security, "wep-key0"))
+            })
+            .map(|psk| Some(psk.into_bytes()))
+            .ok_or_else(|| {
+                let err = io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "the password is not stored in the connection profile or cannot be read",
+                );
+                Error::CannotGetPasswd((err, 1))
+            })
+    }
+
+    /// Provides the settings of the given known SSID.
+    ///
+    /// The settings are read from the connection profile of the SSID with `GetSettings`.
+    /// The semantics are the same as [`Nmcli::get_profile`]: the security type is the key management of the profile, which is `open` if the profile does not have one.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetProfile`] if it fails to read the connection profile, or if the SSID is not a known one.
+    ///
+    /// [`Nmcli::get_profile`]: crate::Nmcli::get_profile
+    /// [`NetworkAdapterError::CannotGetProfile`]: crate::NetworkAdapterError::CannotGetProfile
+    fn get_profile(&self, ssid: &[u8]) -> Result<Profile, Error> {
+        let conn = self.connection().map_err(Error::CannotGetProfile)?;
+        let profile = self
+            .find_profile(&conn, ssid)
+            .map_err(Error::CannotGetProfile)?;
+
+        let mut settings: Settings = self
+            .proxy(&conn, profile.path, CONNECTION_IFACE)
+            .map_err(Error::CannotGetProfile)?
+            .call("GetSettings", &())
+            .map_err(|err| Error::CannotGetProfile(dbus_err(err)))?;
+
+        let mut connection = settings.remove("connection").unwrap_or_default();
+        let mut wireless = settings.remove("802-11-wireless").unwrap_or_default();
+        let mut wifi_sec = settings
+            .remove("802-11-wireless-security")
+            .unwrap_or_default();
+        let mut ipv4 = settings.remove("ipv4").unwrap_or_default();
+
+        let security = match take::<String>(&mut wifi_sec, "key-mgmt").as_deref() {
+            None | Some("") => String::from("open"),
+            Some("none") => String::from("wep"),
+            Some(key_mgmt) => key_mgmt.to_string(),
+        };
+
+        Ok(Profile {
+            name: profile.id,
+            ssid: take(&mut wireless, "ssid").unwrap_or_default(),
+            security,
+            hidden: take(&mut wireless, "hidden").unwrap_or_default(),
+            autoconnect: take(&mut connection, "autoconnect").unwrap_or(true),
+            priority: take(&mut connection, "autoconnect-priority").unwrap_or_default(),
+            metered: match take::<i32>(&mut connection, "metered") {
+                Some(1) | Some(3) => Metered::Yes,
+                Some(2) | Some(4) => Metered::No,
+                _ => Metered::Unknown,
+            },
+            mac_randomization: match take::<u32>(&mut wireless, "mac-address-randomization") {
+                Some(1) => MacRandomization::Never,
+                Some(2) => MacRandomization::Always,
+                _ => MacRandomization::Default,
+            },
+            ip_method: take::<String>(&mut ipv4, "method")
+                .and_then(|method| ValueEnum::from_str(&method, true).ok())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Changes the settings of the given known SSID.
+    ///
+    /// The settings of the connection profile are changed and saved with `Update`. Selecting the `open` security type removes the `802-11-wireless-security` setting of the profile.
+    /// The changes are applied on the next activation of the profile.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotUpdateNetwork`] if the connection profile cannot be found or updated (e.g. NetworkManager rejects a setting).
+    ///
+    /// [`NetworkAdapterError::CannotUpdateNetwork`]: crate::NetworkAdapterError::CannotUpdateNetwork
+    fn edit_profile(&self, ssid: &[u8], edit: &ProfileEdit) -> Result<Vec<u8>, Error> {
+        let conn = self.connection().map_err(Error::CannotUpdateNetwork)?;
+
+        self.update_profile(&conn, ssid, |settings| {
+            match edit.security {
+                Some(Security::Open) => {
+                    settings.remove("802-11-wireless-security");
+                    if let Some(wireless) = settings.get_mut("802-11-wireless") {
+                        wireless.remove("security");
+                    }
+                }
+                Some(security) => {
+                    set_setting(
+                        settings,
+                        "802-11-wireless-security",
+                        "key-mgmt",
+                        Value::from(security.name()),
+                    )?;
+                }
+                None => {}
+            }
+            if let Some(autoconnect) = edit.autoconnect {
+                set_setting(
+                    settings,
+                    "connection",
+                    "autoconnect",
+                    Value::from(autoconnect),
+                )?;
+            }
+            if let Some(priority) = edit.priority {
+                set_setting(
+                    settings,
+                    "connection",
+                    "autoconnect-priority",
+                    Value::from(priority),
+                )?;
+            }
+            if let Some(metered) = edit.metered {
+                let metered: i32 = match metered {
+                    Metered::Unknown => 0,
+                    Metered::Yes => 1,
+                    Metered::No => 2,
+                };
+                set_setting(settings, "connection", "metered", Value::from(metered))?;
+            }
+            if let Some(mac_randomization) = edit.mac_randomization {
+                let mac_randomization: u32 = match mac_randomization {
+                    MacRandomization::Default => 0,
+                    MacRandomization::Never => 1,
+                    MacRandomization::Always => 2,
+                };
+                set_setting(
+                    settings,
+                    "802-11-wireless",
+                    "mac-address-randomization",
+                    Value::from(mac_randomization),
+                )?;
+            }
+            if let Some(ip_method) = edit.ip_method {
+                set_setting(settings, "ipv4", "method", Value::from(ip_method.name()))?;
+            }
+
+            Ok(())
+        })
+        .map_err(Error::CannotUpdateNetwork)?;
+
+        let result = [b"Connection '", ssid, b"' successfully modified.\n"].concat();
+        Ok(result)
+    }
+
+    /// Renames the given known SSID.
+    ///
+    /// The `id` of the connection profile is changed and saved with `Update`, which keeps the SSID of the profile.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotUpdateNetwork`] if the connection profile cannot be found or updated.
+    ///
+    /// [`NetworkAdapterError::CannotUpdateNetwork`]: crate::NetworkAdapterError::CannotUpdateNetwork
+    fn rename_profile(&self, ssid: &[u8], name: &[u8]) -> Result<Vec<u8>, Error> {
+        let conn = self.connection().map_err(Error::CannotUpdateNetwork)?;
+
+        self.update_profile(&conn, ssid, |settings| {
+            let name = String::from_utf8_lossy(name).into_owned();
+            set_setting(settings, "connection", "id", Value::from(name))
+        })
+        .map_err(Error::CannotUpdateNetwork)?;
+
+        let result = [b"Connection '", ssid, b"' is renamed to '", name, b"'.\n"].concat();
+        Ok(result)
+    }
+
+    /// Provides the autoconnect priorities of the known WiFi networks.
+    ///
+    /// The known networks are provided in the order that is reported by `ListConnections`, and their `autoconnect` and `autoconnect-priority` settings are read with `GetSettings`.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotListNetworks`] if it fails to list the connection profiles.
+    ///
+    /// [`NetworkAdapterError::CannotListNetworks`]: crate::NetworkAdapterError::CannotListNetworks
+    fn get_priorities(&self) -> Result<Vec<NetworkPriority>, Error> {
+        let conn = self.connection().map_err(Error::CannotListNetworks)?;
+
+        let priorities = self
+            .get_profiles(&conn)
+            .map_err(Error::CannotListNetworks)?
+            .into_iter()
+            .filter(|p| p.kind == "802-11-wireless")
+            .map(|p| NetworkPriority {
+                ssid: p.id,
+                priority: p.priority,
+                autoconnect: p.autoconnect,
+            })
+            .collect();
+
+        Ok(priorities)
+    }
+
+    /// Sets the autoconnect priority of the given known SSID.
+    ///
+    /// The `autoconnect-priority` of the connection profile is changed and saved with `Update`.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotUpdateNetwork`] if the connection profile cannot be found or updated.
+    ///
+    /// [`NetworkAdapterError::CannotUpdateNetwork`]: crate::NetworkAdapterError::CannotUpdateNetwork
+    fn set_priority(&self, ssid: &[u8], priority: i32) -> Result<Vec<u8>, Error> {
+        let conn = self.connection().map_err(Error::CannotUpdateNetwork)?;
+
+        self.update_profile(&conn, ssid, |settings| {
+            set_setting(
+                settings,
+                "connection",
+                "autoconnect-priority",
+                Value::from(priority),
+            )
+        })
+        .map_err(Error::CannotUpdateNetwork)?;
+
+        let result = [b"Connection '", ssid, b"' successfully modified.\n"].concat();
+        Ok(result)
+    }
+
+    /// Subscribes to the signals of NetworkManager.
+    ///
+    /// The `StateChanged` signals of the devices are sent as [`Notification::DeviceState`], with the same state names as `nmcli monitor`.
+    /// The rest of the signals under `/org/freedesktop/NetworkManager` (e.g. `PropertiesChanged`) are sent as [`Notification::Changed`].
+    ///
+    /// The signals are received on a separate thread, until the returned receiver is dropped.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotWatch`] if it fails to subscribe to the signals.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{NetworkManager, Wl};
+    ///
+    /// let nm = NetworkManager::new();
+    /// let notifications = nm.subscribe().unwrap().unwrap();
+    /// for notification in notifications {
+    ///     println!("{:?}", notification);
+    /// }
+    /// ```
+    ///
+    /// [`Notification::DeviceState`]: crate::Notification::DeviceState
+    /// [`Notification::Changed`]: crate::Notification::Changed
+    /// [`NetworkAdapterError::CannotWatch`]: crate::NetworkAdapterError::CannotWatch
+    fn subscribe(&self) -> Result<Option<mpsc::Receiver<Notification>>, Error> {
+        let conn = self.connection().map_err(Error::CannotWatch)?;
+
+        let rule = MatchRule::builder()
+            .msg_type(message::Type::Signal)
+            .path_namespace(NM_PATH)
+            .map(|b| b.build())
+            .map_err(|err| Error::CannotWatch(dbus_err(err)))?;
+        let signals = MessageIterator::for_match_rule(rule, &conn, None)
+            .map_err(|err| Error::CannotWatch(dbus_err(err)))?;
+
+        let (tx, rx) = mpsc::channel();
+        let nm = self.clone();
+        thread::spawn(move || {
+            for signal in signals {
+                let Ok(signal) = signal else {
+                    break;
+                };
+
+                let header = signal.header();
+                let is_state_changed = header.interface().is_some_and(|i| i == DEVICE_IFACE)
+                    && header.member().is_some_and(|m| m == "StateChanged");
+
+                let notification = match (is_state_changed, header.path()) {
+                    (true, Some(path)) => {
+                        let device = nm
+                            .get_all(&conn, path, DEVICE_IFACE)
+                            .ok()
+                            .and_then(|mut props| take::<String>(&mut props, "Interface"))
+                            .unwrap_or_else(|| path.to_string());
+                        let state = signal
+                            .body()
+                            .deserialize::<(u32, u32, u32)>()
+                            .map(|(state, _, _)| device_state_from_nm(state))
+                            .unwrap_or("unknown");
+
+                        Notification::DeviceState {
+                            device,
+                            state: state.to_string(),
+                        }
+                    }
+                    _ => Notification::Changed,
+                };
+
+                if tx.send(notification).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Some(rx))
+    }
+}
+
+/// Builds the settings of a new WiFi connection profile.
+///
+/// The connection `id` and the `ssid` are both set to the given SSID.
+/// If `passwd` is provided, then it is set as the WEP key when the given access point properties only advertise WEP, and as the PSK otherwise.
+/// The key management is left out, so NetworkManager completes it from the access point of the activation (e.g. `wpa-psk`, `sae`).
+fn new_wifi_settings<'a>(
+    ssid: &'a [u8],
+    passwd: Option<&'a [u8]>,
+    ap_props: &Properties,
+) -> HashMap<&'static str, HashMap<&'static str, Value<'a>>> {
+    let mut settings = HashMap::new();
+
+    settings.insert(
+        "connection",
+        HashMap::from([
+            (
+                "id",
+                Value::from(String::from_utf8_lossy(ssid).into_owned()),
+            ),
+            ("type", Value::from("802-11-wireless")),
+        ]),
+    );
+    settings.insert(
+        "802-11-wireless",
+        HashMap::from([("ssid", Value::from(ssid))]),
+    );
+
+    if let Some(passwd) = passwd {
+        let flag = |key: &str| {
+            ap_props
+                .get(key)
+                .and_then(|v| u32::try_from(v).ok())
+                .unwrap_or_default()
+        };
+        let is_wep =
+            flag("Flags") & AP_FLAGS_PRIVACY != 0 && flag("WpaFlags") == 0 && flag("RsnFlags") == 0;
+
+        let passwd = Value::from(String::from_utf8_lossy(passwd).into_owned());
+        let wifi_sec = match is_wep {
+            true => HashMap::from([("wep-key0", passwd)]),
+            false => HashMap::from([("psk", passwd)]),
+        };
+        settings.insert("802-11-wireless-security", wifi_sec);
+    }
+
+    settings
+}
+
+/// Builds the settings of a new hidden connection profile.
+///
+/// `hidden` makes NetworkManager probe the SSID explicitly, since the access point does not broadcast it.
+fn new_hidden_settings<'a>(
+    ssid: &'a [u8],
+    security: Security,
+    passwd: Option<&'a [u8]>,
+) -> HashMap<&'static str, HashMap<&'static str, Value<'a>>> {
+    let mut settings = new_wifi_settings(ssid, None, &Properties::new());
+
+    settings.insert(
+        "802-11-wireless",
+        HashMap::from([("ssid", Value::from(ssid)), ("hidden", Value::from(true))]),
+    );
+
+    if security.is_secured() {
+        let mut wifi_sec = HashMap::from([("key-mgmt", Value::from(security.name()))]);
+        if let Some(passwd) = passwd {
+            wifi_sec.insert(
+                "psk",
+                Value::from(String::from_utf8_lossy(passwd).into_owned()),
+            );
+        }
+        settings.insert("802-11-wireless-security", wifi_sec);
+    }
+
+    settings
+}
+
+/// Builds the settings of a new enterprise (802.1X / WPA-EAP) connection profile.
+///
+/// The certificates and keys are referred by their paths with the `file://` scheme, which is how NetworkManager stores the paths in its byte array settings.
+fn new_enterprise_settings<'a>(
+    ssid: &'a [u8],
+    credentials: &'a EapCredentials,
+) -> HashMap<&'static str, HashMap<&'static str, Value<'a>>> {
+    let mut settings = new_wifi_settings(ssid, None, &Properties::new());
+    let lossy = |v: &[u8]| Value::from(String::from_utf8_lossy(v).into_owned());
+    let cert_path =
+        |path: &Path| Value::from([b"file://", path.as_os_str().as_bytes(), b"\0"].concat());
+
+    settings.insert(
+        "802-11-wireless-security",
+        HashMap::from([("key-mgmt", Value::from("wpa-eap"))]),
+    );
+
+    let mut eap = HashMap::from([
+        ("eap", Value::from(vec![credentials.method.name()])),
+        ("identity", lossy(&credentials.identity)),
+    ]);
+    if let Some(identity) = &credentials.anonymous_identity {
+        eap.insert("anonymous-identity", lossy(identity));
+    }
+    if let Some(auth) = &credentials.phase2_auth {
+        eap.insert("phase2-auth", Value::from(auth.name()));
+    }
+    if let Some(passwd) = &credentials.passwd {
+        eap.insert("password", lossy(passwd));
+    }
+    if let Some(cert) = &credentials.ca_cert {
+        eap.insert("ca-cert", cert_path(cert));
+    }
+    if let Some(suffix) = &credentials.domain_suffix_match {
+        eap.insert("domain-suffix-match", Value::from(suffix.as_str()));
+    }
+    if let Some(cert) = &credentials.client_cert {
+        eap.insert("client-cert", cert_path(cert));
+    }
+    if let Some(key) = &credentials.private_key {
+        eap.insert("private-key", cert_path(key));
+        match &credentials.private_key_passwd {
+            Some(passwd) => eap.insert("private-key-password", lossy(passwd)),
+            None => eap.insert("private-key-password-flags", Value::from(4u32)),
+        };
+    }
+    settings.insert("802-1x", eap);
+
+    settings
+}
+
+/// Builds the settings of a new hotspot connection profile.
+///
+/// If `passwd` is provided, then the profile is secured with WPA2-PSK (RSN, CCMP) like `nmcli device wifi hotspot` does.
+fn new_hotspot_settings<'a>(
+    ssid: &'a [u8],
+    passwd: Option<&'a [u8]>,
+    band: Option<Band>,
+    channel: Option<u32>,
+) -> HashMap<&'static str, HashMap<&'static str, Value<'a>>> {
+    let mut settings = HashMap::new();
+
+    settings.insert(
+        "connection",
+        HashMap::from([
+            ("id", Value::from(HOTSPOT_PROFILE)),
+            ("type", Value::from("802-11-wireless")),
+            ("autoconnect", Value::from(false)),
+        ]),
+    );
+
+    let mut wireless = HashMap::from([("ssid", Value::from(ssid)), ("mode", Value::from("ap"))]);
+    if let Some(band) = band.or(channel.map(Band::from_channel)) {
+        wireless.insert("band", Value::from(band.name()));
+    }
+    if let Some(channel) = channel {
+        wireless.insert("channel", Value::from(channel));
+    }
+    settings.insert("802-11-wireless", wireless);
+
+    if let Some(passwd) = passwd {
+        settings.insert(
+            "802-11-wireless-security",
+            HashMap::from([
+                ("key-mgmt", Value::from("wpa-psk")),
+                ("proto", Value::from(vec!["rsn"])),
+                ("pairwise", Value::from(vec!["ccmp"])),
+                ("group", Value::from(vec!["ccmp"])),
+                (
+                    "psk",
+                    Value::from(String::from_utf8_lossy(passwd).into_owned()),
+                ),
+            ]),
+        );
+    }
+
+    settings.insert("ipv4", HashMap::from([("method", Value::from("shared"))]));
+    settings.insert("ipv6", HashMap::from([("method", Value::from("ignore"))]));
+
+    settings
+}
+
+/// Provides the clients of the hotspot that is running on the given device.
+///
+/// NetworkManager runs `dnsmasq` for its shared connections, which keeps the DHCP leases of the clients under [`DNSMASQ_LEASES_DIR`].
+/// Each lease is a line of `EXPIRY MAC IP HOSTNAME CLIENT-ID`, where the unknown values are `*`.
+///
+/// It returns `None` if the leases cannot be read, since the clients are unknown in that case.
+pub(crate) fn read_hotspot_clients(device: &str) -> Option<Vec<HotspotClient>> {
+    let leases_path = Path::new(DNSMASQ_LEASES_DIR).join(format!("dnsmasq-{}.leases", device));
+    let leases = fs::read_to_string(leases_path).ok()?;
+
+    let known = |value: Option<&str>| value.filter(|v| v != &"*").map(String::from);
+
+    let clients = leases
+        .lines()
+        .filter_map(|lease| {
+            let mut values = lease.split_ascii_whitespace().skip(1);
+            let mac = known(values.next())?;
+
+            Some(HotspotClient {
+                mac,
+                ip: known(values.next()),
+                hostname: known(values.next()),
+            })
+        })
+        .collect();
+
+    Some(clients)
+}
+
+/// Sets the given property of the given setting, and adds the setting if it does not exist.
+fn set_setting(
+    settings: &mut Settings,
+    setting: &str,
+    key: &str,
+    value: Value<'_>,
+) -> Result<(), (io::Error, i32)> {
+    let value = OwnedValue::try_from(value).map_err(|err| dbus_err(err.into()))?;
+    settings
+        .entry(setting.to_string())
+        .or_default()
+        .insert(key.to_string(), value);
+
+    Ok(())
+}
+
+/// Removes the given property from `props` and converts it into `T`.
+///
+/// It returns `None` if the property does not exist or it has a different type.
+fn take<T: TryFrom<OwnedValue>>(props: &mut Properties, key: &str) -> Option<T> {
+    props.remove(key).and_then(|value| T::try_from(value).ok())
+}
+
+fn dbus_err(err: zbus::Error) -> (io::Error, i32) {
+    (io::Error::other(err), 1)
+}
+
+/// Converts the `NMActiveConnectionStateReason` of a deactivated connection into an error with the exit code `4`.
+///
+/// The messages of the authentication failures are the ones of `nmcli`, so they are categorized the same way.
+fn deactivation_err(reason: Option<u32>) -> (io::Error, i32) {
+    let message = match reason {
+        Some(ACTIVE_REASON_NO_SECRETS) => "Secrets were required, but not provided",
+        Some(ACTIVE_REASON_LOGIN_FAILED) => "the login failed due to a wrong password",
+        _ => "the connection could not be activated",
+    };
+
+    (io::Error::other(message), 4)
+}
+
+/// Provides the name of the given `NMDeviceState`, as it is named by `nmcli`.
+fn device_state_from_nm(state: u32) -> &'static str {
+    match state {
+        10 => "unmanaged",
+        20 => "unavailable",
+        30 => "disconnected",
+        40 => "connecting (prepare)",
+        50 => "connecting (configuring)",
+        60 => "connecting (need authentication)",
+        70 => "connecting (getting IP configuration)",
+        80 => "connecting (checking IP connectivity)",
+        90 => "connecting (starting secondary connections)",
+        100 => "connected",
+        110 => "deactivating",
+        120 => "connection failed",
+        _ => "unknown",
+    }
+}
+
+/// Converts the `NM80211Mode` of an access point into the mode names of `nmcli`.
+fn mode_from_nm(mode: u32) -> &'static str {
+    match mode {
+        1 => "Ad-Hoc",
+        2 => "Infra",
+        3 => "AP",
+        4 => "Mesh",
+        _ => "",
+    }
+}
+
+/// Converts the security flags of an access point into the security protocols of `nmcli`.
+///
+/// The protocols are determined the same way `nmcli` does, so each network backend
+/// shares the same vocabulary.
+fn security_from_flags(flags: u32, wpa_flags: u32, rsn_flags: u32) -> String {
+    let mut security = Vec::new();
+
+    if flags & AP_FLAGS_PRIVACY != 0 && wpa_flags == 0 && rsn_flags == 0 {
+        security.push("WEP");
+    }
+    if wpa_flags != 0 {
+        security.push("WPA1");
+    }
+    if rsn_flags & (AP_SEC_KEY_MGMT_PSK | AP_SEC_KEY_MGMT_802_1X) != 0 {
+        security.push("WPA2");
+    }
+    if rsn_flags & AP_SEC_KEY_MGMT_SAE != 0 {
+        security.push("WPA3");
+    }
+    if rsn_flags & AP_SEC_KEY_MGMT_OWE != 0 {
+        security.push("OWE");
+    }
+    if (wpa_flags | rsn_flags) & AP_SEC_KEY_MGMT_802_1X != 0 {
+        security.push("802.1X");
+    }
+
+    security.join(" ")
+}
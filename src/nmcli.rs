@@ -1,63 +1,206 @@
 use std::{
-    collections::HashMap,
-    ffi::OsString,
     io::{self, BufRead},
-    os::unix::ffi::OsStringExt,
-    process::Command,
+    os::unix::ffi::OsStrExt,
+    process::Output,
+    sync::mpsc,
+    thread,
 };
 
+use clap::ValueEnum;
+
 use crate::{
-    adapter::{CARRIAGE_RETURN, Decimal, Error, LINE_FEED, Wl},
-    api,
+    AccessPoint, Band, EapCredentials, ExportedNetwork, Hotspot, Network, NetworkPriority,
+    Notification, Profile, ProfileEdit, Security, WifiStatus,
+    adapter::{CARRIAGE_RETURN, Error, HOTSPOT_PROFILE, LINE_FEED, UNKNOWN_SSID_EXIT_CODE, Wl},
+    network_manager::read_hotspot_clients,
+    runner::{Runner, SystemRunner},
 };
 
+/// The byte that `nmcli` uses to separate the fields of its terse outputs.
+const FIELD_SEPARATOR: u8 = b':';
+
+/// The prompt that `nmcli --ask` writes to stdout before it reads the password of `nmcli device wifi connect`.
+const PASSWD_PROMPT: &[u8] = b"Password: ";
+
+/// The device states that are written by `nmcli monitor`.
+///
+/// The `connecting` states are written with their details (e.g. `connecting (prepare)`), so they are matched by their prefix.
+const DEVICE_STATES: [&str; 9] = [
+    "unknown",
+    "unmanaged",
+    "unavailable",
+    "disconnected",
+    "connecting (",
+    "connected",
+    "deactivating",
+    "connection failed",
+    "device removed",
+];
+
 /// The adapter struct that implements [`Wl`] by using `nmcli`.
 ///
+/// `nmcli` is run through a [`Runner`], which is [`SystemRunner`] by default.
+/// To replace `nmcli` with canned outputs (e.g. in tests), initialize the struct with [`Nmcli::with_runner`].
+///
 /// Since the struct can be changed in future versions, always
-/// prefer to initialize it by using [`Nmcli::new`] instead.
+/// prefer to initialize it by using [`Nmcli::new`] or [`Nmcli::with_runner`] instead.
 ///
 /// [`Wl`]: crate::Wl
+/// [`Runner`]: crate::runner::Runner
+/// [`SystemRunner`]: crate::runner::SystemRunner
 /// [`Nmcli::new`]: crate::Nmcli::new
+/// [`Nmcli::with_runner`]: crate::Nmcli::with_runner
 #[derive(Clone, Default)]
-pub struct Nmcli;
+pub struct Nmcli<R = SystemRunner> {
+    runner: R,
+}
 
 impl Nmcli {
-    /// Creates a new `Nmcli` instance.
+    /// Creates a new `Nmcli` instance that runs `nmcli` on the host.
     ///
     /// The instance created by `new` can be reused multiple times
     /// in a given context. It can also be cloned freely.
     pub fn new() -> Self {
-        Self
+        Self {
+            runner: SystemRunner,
+        }
+    }
+}
+
+impl<R: Runner> Nmcli<R> {
+    /// Creates a new `Nmcli` instance that runs `nmcli` through the given [`Runner`].
+    ///
+    /// [`Runner`]: crate::runner::Runner
+    pub fn with_runner(runner: R) -> Self {
+        Self { runner }
+    }
+
+    /// Provides the [`Runner`] of the instance.
+    ///
+    /// [`Runner`]: crate::runner::Runner
+    pub fn runner(&self) -> &R {
+        &self.runner
     }
 
     fn exec(&self, args: &[&[u8]]) -> Result<Vec<u8>, (io::Error, i32)> {
-        let default_ecode = 1i32;
-        let mut nmcli = Command::new("nmcli");
-        let args = args.iter().map(|s| OsString::from_vec(s.to_vec()));
-        let cmd = nmcli
-            .args(args)
-            .output()
-            .map_err(|err| (err, default_ecode))?;
+        into_stdout(self.runner.run("nmcli", args))
+    }
 
-        if !cmd.status.success() {
-            let nmcli_err = cmd
-                .stderr
-                .lines()
-                .collect::<Result<String, io::Error>>()
-                .map_err(|err| (err, default_ecode))?;
-            let ecode = cmd.status.code().unwrap_or(default_ecode);
-            return Err((io::Error::other(nmcli_err), ecode));
-        }
+    /// Runs `nmcli` with the given arguments, and writes `input` to its stdin.
+    ///
+    /// This is used to pass the passwords, which should not be visible in the arguments of `nmcli`.
+    fn exec_with_input(&self, args: &[&[u8]], input: &[u8]) -> Result<Vec<u8>, (io::Error, i32)> {
+        into_stdout(self.runner.run_with_input("nmcli", args, input))
+    }
+
+    /// Modifies the properties of the connection profile of the given SSID with `nmcli connection modify`.
+    ///
+    /// `properties` are the pairs of the property names and values.
+    fn modify(&self, ssid: &[u8], properties: &[&[u8]]) -> Result<Vec<u8>, (io::Error, i32)> {
+        let mut args = ["connection", "modify", "id"]
+            .map(|a| a.as_bytes())
+            .to_vec();
+        args.push(ssid);
+        args.extend_from_slice(properties);
+
+        self.exec(&args)
+    }
+
+    /// Adds a connection profile with the given `nmcli connection add` arguments, and activates it.
+    ///
+    /// The given secrets are the pairs of the property names and values, which are provided to `nmcli connection up`
+    /// through its `passwd-file` that is read from stdin, instead of its arguments.
+    /// The profile is deleted if it cannot be activated.
+    fn add_and_activate(
+        &self,
+        ssid: &[u8],
+        add_args: &[&[u8]],
+        secrets: &[(&str, &[u8])],
+    ) -> Result<Vec<u8>, (io::Error, i32)> {
+        let passwd_file = passwd_file(secrets)?;
+        self.exec(add_args)?;
+
+        let mut up_args = ["connection", "up", "id", ""]
+            .map(|a| a.as_bytes())
+            .to_vec();
+        up_args[3] = ssid;
+
+        let activated = match secrets.is_empty() {
+            true => self.exec(&up_args),
+            false => {
+                up_args.extend([b"passwd-file".as_slice(), b"/dev/stdin"]);
+                self.exec_with_input(&up_args, &passwd_file)
+            }
+        };
+
+        activated.inspect_err(|_| {
+            let mut delete_args = ["connection", "delete", "id", ""].map(|a| a.as_bytes());
+            delete_args[3] = ssid;
+            let _ = self.exec(&delete_args);
+        })
+    }
+
+    /// Provides the hotspot, if its connection profile is active.
+    ///
+    /// The device is obtained from the active connections, and the rest of the hotspot from its connection profile.
+    fn hotspot(&self) -> Result<Option<Hotspot>, (io::Error, i32)> {
+        let args = ["-g", "NAME,DEVICE", "connection", "show", "--active"].map(|a| a.as_bytes());
+        let active_connections = self.exec(&args)?;
+
+        let device = active_connections
+            .split(|b| b == &LINE_FEED)
+            .map(|l| l.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(l))
+            .map(|l| split_terse_line(l, FIELD_SEPARATOR))
+            .find(|fields| fields[0] == HOTSPOT_PROFILE.as_bytes())
+            .and_then(|mut fields| fields.pop());
+
+        let Some(device) = device else {
+            return Ok(None);
+        };
+        let device = String::from_utf8_lossy(&device).into_owned();
+
+        let args = [
+            "-g",
+            "802-11-wireless.ssid,802-11-wireless.band,802-11-wireless.channel,802-11-wireless-security.key-mgmt",
+            "connection",
+            "show",
+            "id",
+            HOTSPOT_PROFILE,
+        ]
+        .map(|a| a.as_bytes());
+        let profile = self.exec(&args)?;
 
-        Ok(cmd.stdout)
+        let mut values = profile
+            .split(|b| b == &LINE_FEED)
+            .map(|l| l.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(l))
+            .map(|l| split_terse_line(l, FIELD_SEPARATOR).concat());
+
+        let ssid = values.next().unwrap_or_default();
+        let band = match values.next().as_deref() {
+            Some(b"bg") => Some(Band::Bg),
+            Some(b"a") => Some(Band::A),
+            _ => None,
+        };
+        let channel = values
+            .next()
+            .map(|c| leading_number(&c))
+            .filter(|c| c != &0);
+        let secured = values.next().is_some_and(|k| !k.is_empty());
+
+        Ok(Some(Hotspot {
+            clients: read_hotspot_clients(&device),
+            ssid,
+            device,
+            band,
+            channel,
+            secured,
+        }))
     }
 }
 
-impl Wl for Nmcli {
+impl<R: Runner> Wl for Nmcli<R> {
     /// Provides the WiFi status.
     ///
-    /// It returns the WiFi status that is in a **human-readable format** and is a single line.
-    ///
     /// # Panics
     ///
     /// This method does not panic.
@@ -71,28 +214,28 @@ impl Wl for Nmcli {
     ///
     /// ```
     /// use wl::{Nmcli,Wl};
-    /// use std::io::{self, Write};
     ///
     /// let nmcli = Nmcli::new();
     /// let status = nmcli.get_wifi_status().unwrap();
-    /// io::stdout().write_all(&status).unwrap();
+    /// println!("wifi: {}", status);
     /// ```
     ///
     /// [`NetworkAdapterError::CannotGetWiFiStatus`]: crate::NetworkAdapterError::CannotGetWiFiStatus
-    fn get_wifi_status(&self) -> Result<Vec<u8>, Error> {
+    fn get_wifi_status(&self) -> Result<WifiStatus, Error> {
         let args = ["-g", "WIFI", "g"].map(|a| a.as_bytes());
         let result = self.exec(&args).map_err(Error::CannotGetWiFiStatus)?;
 
-        Ok(result
-            .split(|a| a == &LINE_FEED)
-            .flat_map(|l| l.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(l))
-            .copied()
-            .collect())
+        let status = match result.trim_ascii() {
+            b"enabled" => WifiStatus::Enabled,
+            _ => WifiStatus::Disabled,
+        };
+
+        Ok(status)
     }
 
     /// Toggles the WiFi status.
     ///
-    /// It returns the updated WiFi status in a **human-readable format** within a single line.
+    /// It returns the updated WiFi status.
     ///
     /// # Panics
     ///
@@ -106,28 +249,25 @@ impl Wl for Nmcli {
     ///
     /// ```
     /// use wl::{Nmcli,Wl};
-    /// use std::io::{self, Write};
     ///
     /// let nmcli = Nmcli::new();
     /// let updated_status = nmcli.toggle_wifi().unwrap();
-    /// io::stdout().write_all(&updated_status).unwrap();
+    /// println!("wifi: {}", updated_status);
     /// ```
     ///
     /// [`NetworkAdapterError::CannotGetWiFiStatus`]: crate::NetworkAdapterError::CannotGetWiFiStatus
     /// [`NetworkAdapterError::CannotToggleWiFi`]: crate::NetworkAdapterError::CannotToggleWiFi
-    fn toggle_wifi(&self) -> Result<Vec<u8>, Error> {
-        let cloned_process = self.clone();
-        let prev_status = cloned_process.get_wifi_status()?;
+    fn toggle_wifi(&self) -> Result<WifiStatus, Error> {
+        let new_status = self.get_wifi_status()?.toggled();
 
-        let mut args = ["radio", "wifi", ""];
-
-        let new_status = if &prev_status[..] == b"enabled" {
-            args[2] = "off";
-            b"disabled".to_vec()
-        } else {
-            args[2] = "on";
-            b"enabled".to_vec()
-        };
+        let args = [
+            "radio",
+            "wifi",
+            match new_status {
+                WifiStatus::Enabled => "on",
+                WifiStatus::Disabled => "off",
+            },
+        ];
 
         let _ = self
             .exec(&args.map(|a| a.as_bytes()))
@@ -136,42 +276,10 @@ impl Wl for Nmcli {
         Ok(new_status)
     }
 
-    /// Provides a list of SSID-Device pairs.
-    ///
-    /// The output is in a **terse format**.
-    /// To parse the output, [`get_field_separator`] can be used to split each element to get the SSID and the device.
-    ///
-    /// # Panics
-    ///
-    /// This method does not panic.
-    ///
-    /// # Errors
-    ///
-    /// This method returns a [`NetworkAdapterError::CannotGetActiveConnections`] when it fails to retrieve the pairs.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use wl::{Nmcli,Wl};
-    /// use std::io::{self, Write};
-    ///
-    /// let nmcli = Nmcli::new();
-    /// let pairs = nmcli.get_active_ssid_dev_pairs().unwrap();
-    /// io::stdout().write_all(&pairs).unwrap();
-    /// ```
-    ///
-    /// [`get_field_separator`]: crate::Wl::get_field_separator
-    /// [`NetworkAdapterError::CannotGetActiveConnections`]: crate::NetworkAdapterError::CannotGetActiveConnections
-    fn get_active_ssid_dev_pairs(&self) -> Result<Vec<u8>, Error> {
-        let args = ["-g", "NAME,DEVICE", "connection", "show", "--active"];
-
-        self.exec(&args.map(|a| a.as_bytes()))
-            .map_err(Error::CannotGetActiveConnections)
-    }
-
-    /// Provides a list of known networks on the host.
+    /// Provides the known networks on the host.
     ///
-    /// The output is in a **human-readable format**.
+    /// The networks are obtained with `nmcli -g NAME,UUID,TYPE,DEVICE connection show`.
+    /// The network types are shortened the same way `nmcli` does in its human-readable output (e.g. `802-11-wireless` becomes `wifi`).
     ///
     /// # Panics
     ///
@@ -179,42 +287,32 @@ impl Wl for Nmcli {
     ///
     /// # Errors
     ///
-    /// This method returns a [`NetworkAdapterError::CannotListNetworks`] when it fails to retrieve the pairs.
+    /// This method returns a [`NetworkAdapterError::CannotListNetworks`] when it fails to retrieve the networks, or a [`NetworkAdapterError::CannotGetActiveConnections`] if `active_only` is set.
     ///
     /// # Examples
     ///
     /// ```
     /// use wl::{Nmcli,Wl};
-    /// use std::io::{self, Write};
     ///
     /// let nmcli = Nmcli::new();
     ///
     /// // By default, it returns the whole list:
-    /// let net_list = nmcli.list_networks(false, false).unwrap();
-    ///
-    /// // `show_active` can be used to show the active connections on the host.
-    /// let net_list = nmcli.list_networks(true, false).unwrap();
-    ///
-    /// // `show_ssid` can be used to only show the SSIDs of the known networks.
-    /// let net_list = nmcli.list_networks(false, true).unwrap();
-    ///
-    /// // Both args can be used to only show the SSIDs of active connections in the known networks.
-    /// let net_list = nmcli.list_networks(true, true).unwrap();
+    /// let networks = nmcli.get_networks(false).unwrap();
     ///
+    /// // `active_only` can be used to get the active connections on the host.
+    /// let active_networks = nmcli.get_networks(true).unwrap();
     ///
-    /// io::stdout().write_all(&net_list).unwrap();
+    /// for network in active_networks {
+    ///     println!("{}", String::from_utf8_lossy(&network.ssid));
+    /// }
     /// ```
     ///
-    /// [`NetworkAdapterError::CannotListNetworks`]: crate::NetworkAdapterError::CannotListNetworks    
-    fn list_networks(&self, show_active: bool, show_ssid: bool) -> Result<Vec<u8>, Error> {
-        let mut args = ["", "", "connection", "show", ""];
-
-        if show_ssid {
-            args[0] = "--fields";
-            args[1] = "NAME";
-        }
+    /// [`NetworkAdapterError::CannotListNetworks`]: crate::NetworkAdapterError::CannotListNetworks
+    /// [`NetworkAdapterError::CannotGetActiveConnections`]: crate::NetworkAdapterError::CannotGetActiveConnections
+    fn get_networks(&self, active_only: bool) -> Result<Vec<Network>, Error> {
+        let mut args = ["-g", "NAME,UUID,TYPE,DEVICE", "connection", "show", ""];
 
-        if show_active {
+        if active_only {
             args[4] = "--active";
         }
 
@@ -224,37 +322,39 @@ impl Wl for Nmcli {
             .map(|a| a.as_bytes())
             .collect();
 
-        self.exec(&args).map_err(Error::CannotListNetworks)
-    }
+        let result = self.exec(&args).map_err(if active_only {
+            Error::CannotGetActiveConnections
+        } else {
+            Error::CannotListNetworks
+        })?;
 
-    /// Provides a list of active SSIDs on the host.
-    ///
-    /// The output is in a **terse format** and may contain multiple lines.
-    ///
-    /// # Panics
-    ///
-    /// This method does not panic.
-    ///
-    /// # Errors
-    ///
-    /// This method returns a [`NetworkAdapterError::CannotGetSSIDStatus`] when it fails to retrieve the SSIDs.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use wl::{Nmcli,Wl};
-    /// use std::io::{self, Write};
-    ///
-    /// let nmcli = Nmcli::new();
-    /// let active_ssids = nmcli.get_active_ssids().unwrap();
-    /// io::stdout().write_all(&active_ssids).unwrap();
-    /// ```
-    /// [`NetworkAdapterError::CannotGetSSIDStatus`]: crate::NetworkAdapterError::CannotGetSSIDStatus
-    fn get_active_ssids(&self) -> Result<Vec<u8>, Error> {
-        let args = ["-g", "NAME", "connection", "show", "--active"];
+        let networks = result
+            .split(|b| b == &LINE_FEED)
+            .map(|l| l.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(l))
+            .filter(|l| !l.is_empty())
+            .filter_map(|l| {
+                let fields = split_terse_line(l, FIELD_SEPARATOR);
+                let [name, uuid, kind, device] = &fields[..] else {
+                    return None;
+                };
+
+                let kind = match &kind[..] {
+                    b"802-11-wireless" => "wifi",
+                    b"802-3-ethernet" => "ethernet",
+                    kind => &String::from_utf8_lossy(kind),
+                };
+
+                Some(Network {
+                    ssid: name.to_vec(),
+                    uuid: String::from_utf8_lossy(uuid).into_owned(),
+                    kind: kind.to_string(),
+                    device: (!device.is_empty())
+                        .then(|| String::from_utf8_lossy(device).into_owned()),
+                })
+            })
+            .collect();
 
-        self.exec(&args.map(|a| a.as_bytes()))
-            .map_err(Error::CannotGetSSIDStatus)
+        Ok(networks)
     }
 
     /// Disconnects from the given SSID.
@@ -318,19 +418,12 @@ impl Wl for Nmcli {
         self.exec(&args).map_err(Error::CannotDisconnect)
     }
 
-    /// Scan the available SSIDs to connect.
-    ///
-    /// The output depends on the [`ScanArgs`]:
-    ///
-    /// - If `columns` is used, then the output is in a **human-readable format** and may contain multiple lines.
-    /// - If `get_values` is used, then the output is in a **terse format** and may contain multiple lines. In this case, each line element contains FIELDS that are separated by [`get_field_separator`].
-    /// - Using `columns` overrides `get_values`.
+    /// Provides the access points that are visible to the host.
     ///
-    /// `columns` and `get_values` both use the same values as `nmcli -f FIELDS` and `nmcli -g FIELDS` respectively, which are comma separated values of column names.
+    /// The access points are obtained with a single `nmcli -g FIELDS d wifi list` call, and the terse output is parsed into [`AccessPoint`]s.
+    /// The SSIDs are obtained through the `SSID-HEX` field, which means that the SSIDs that are not valid UTF-8 are preserved as is.
     ///
-    /// If `re-scan` is set, then `scan` refreshes the underlying cache of available networks.
-    ///
-    /// If `min_strength` is provided, the `scan` filters the list by the given signal strength.
+    /// If `re_scan` is set, then `get_access_points` refreshes the underlying cache of available networks.
     ///
     /// # Panics
     ///
@@ -342,136 +435,89 @@ impl Wl for Nmcli {
     ///
     /// # Examples
     ///
-    /// Use `columns` to get the scan result in a human-readable format.
-    /// ```
-    /// use wl::{Nmcli,Wl, api::ScanArgs};
-    /// use std::io::{self, Write};
-    ///
-    /// let args = ScanArgs {
-    ///     min_strength: 0,
-    ///     re_scan: false,
-    ///     get_values: None,
-    ///     columns: Some(String::from("SSID,SIGNAL")),
-    /// };
-    ///
-    /// let nmcli = Nmcli::new();
-    /// let scan_result = nmcli.scan(&args).unwrap();
-    /// io::stdout().write_all(&scan_result).unwrap();
-    /// ```
-    ///
-    /// Use `get_values` to get the scan result in a terse format.
     /// ```
-    /// use wl::{Nmcli,Wl, api::ScanArgs};
-    /// use std::io::{self, Write};
-    ///
-    /// let args = ScanArgs {
-    ///     min_strength: 0,
-    ///     re_scan: false,
-    ///     columns: None,
-    ///     get_values: Some(String::from("SSID,SIGNAL")),
-    /// };
+    /// use wl::{Nmcli, Wl};
     ///
     /// let nmcli = Nmcli::new();
-    /// let scan_result = nmcli.scan(&args).unwrap();
-    /// io::stdout().write_all(&scan_result).unwrap();
-    /// ```
-    ///
-    /// Use `min_strength` to filter the scan list by SIGNAL.
-    /// ```
-    /// use wl::{Nmcli,Wl, api::ScanArgs};
-    /// use std::io::{self, Write};
+    /// let aps = nmcli.get_access_points(false).unwrap();
     ///
-    /// let args = ScanArgs {
-    ///     min_strength: 60,
-    ///     re_scan: false,
-    ///     columns: None,
-    ///     get_values: Some(String::from("SSID,SIGNAL")),
-    /// };
-    ///
-    /// let nmcli = Nmcli::new();
-    /// let scan_result = nmcli.scan(&args).unwrap();
-    /// io::stdout().write_all(&scan_result).unwrap();
+    /// for ap in aps {
+    ///     println!("{} ({})", String::from_utf8_lossy(&ap.ssid), ap.signal);
+    /// }
     /// ```
     ///
     /// Use `re_scan` to refresh the scan cache.
     /// ```
-    /// use wl::{Nmcli,Wl, api::ScanArgs};
-    /// use std::io::{self, Write};
-    ///
-    /// let args = ScanArgs {
-    ///     min_strength: 0,
-    ///     re_scan: true,
-    ///     columns: None,
-    ///     get_values: Some(String::from("SSID,SIGNAL")),
-    /// };
+    /// use wl::{Nmcli, Wl};
     ///
     /// let nmcli = Nmcli::new();
-    /// let scan_result = nmcli.scan(&args).unwrap();
-    /// io::stdout().write_all(&scan_result).unwrap();
+    /// let aps = nmcli.get_access_points(true).unwrap();
     /// ```
     ///
     /// [`NetworkAdapterError::CannotScanWiFi`]: crate::NetworkAdapterError::CannotScanWiFi
-    /// [`ScanArgs`]: crate::api::ScanArgs
-    /// [`get_field_separator`]: crate::Nmcli::get_field_separator
-    fn scan(&self, args: &api::ScanArgs) -> Result<Vec<u8>, Error> {
-        let mut nmcli_args = ["", "", "d", "wifi", "list", "", ""];
-
-        let nmcli_global_args = match (&args.columns, &args.get_values) {
-            (None, None) => ["", ""],
-            (None, Some(values)) => ["-g", values],
-            (Some(columns), None) => ["-f", columns],
-            (Some(columns), Some(_)) => ["-f", columns],
-        };
-        nmcli_args[0..2].copy_from_slice(&nmcli_global_args);
+    /// [`AccessPoint`]: crate::AccessPoint
+    fn get_access_points(&self, re_scan: bool) -> Result<Vec<AccessPoint>, Error> {
+        let mut args = [
+            "-g",
+            "IN-USE,BSSID,SSID-HEX,MODE,CHAN,FREQ,RATE,SIGNAL,SECURITY",
+            "d",
+            "wifi",
+            "list",
+            "",
+            "",
+        ];
 
-        if args.re_scan {
-            nmcli_args[5..].copy_from_slice(&["--rescan", "yes"]);
+        if re_scan {
+            args[5..].copy_from_slice(&["--rescan", "yes"]);
         }
 
-        let nmcli_args: Vec<&[u8]> = nmcli_args
+        let args: Vec<&[u8]> = args
             .into_iter()
             .filter(|a| !a.is_empty())
             .map(|a| a.as_bytes())
             .collect();
 
-        let scan_result = self.exec(&nmcli_args).map_err(Error::CannotScanWiFi)?;
+        let result = self.exec(&args).map_err(Error::CannotScanWiFi)?;
 
-        let cloned_process = self.clone();
-        let nmcli_args = ["-g", "SIGNAL", "d", "wifi", "list"];
-
-        let signal_result = cloned_process
-            .exec(&nmcli_args.map(|a| a.as_bytes()))
-            .map_err(Error::CannotScanWiFi)?;
-        let signal_lines = signal_result
+        let aps = result
             .split(|b| b == &LINE_FEED)
             .map(|l| l.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(l))
-            .enumerate();
-
-        let mut valid_signals = HashMap::new();
-
-        for (idx, signal) in signal_lines {
-            let signal = Decimal::from(signal).inner();
-
-            if signal >= args.min_strength {
-                valid_signals.insert(idx + 1, signal);
-            }
-        }
+            .filter(|l| !l.is_empty())
+            .filter_map(|l| {
+                let fields = split_terse_line(l, FIELD_SEPARATOR);
+                let [
+                    in_use,
+                    bssid,
+                    ssid_hex,
+                    mode,
+                    chan,
+                    freq,
+                    rate,
+                    signal,
+                    security,
+                ] = &fields[..]
+                else {
+                    return None;
+                };
 
-        let filtered_scan = scan_result
-            .split(|b| b == &LINE_FEED)
-            .map(|l| l.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(l))
-            .enumerate()
-            .filter_map(|(idx, l)| {
-                if idx == 0 || valid_signals.contains_key(&idx) {
-                    Some([l, &[LINE_FEED]].concat())
-                } else {
-                    None
-                }
+                Some(AccessPoint {
+                    ssid: decode_hex(ssid_hex),
+                    bssid: String::from_utf8_lossy(bssid).into_owned(),
+                    mode: String::from_utf8_lossy(mode).into_owned(),
+                    channel: leading_number(chan),
+                    frequency: leading_number(freq),
+                    rate: leading_number(rate),
+                    signal: leading_number(signal).min(100) as u8,
+                    security: String::from_utf8_lossy(security)
+                        .replace("--", "")
+                        .trim()
+                        .to_string(),
+                    in_use: in_use.trim_ascii() == b"*",
+                })
             })
-            .flatten()
-            .collect::<Vec<u8>>();
+            .collect();
 
-        Ok(filtered_scan)
+        Ok(aps)
     }
 
     /// Checks whether the given SSID is a known one or not.
@@ -508,13 +554,18 @@ impl Wl for Nmcli {
         let exists = result
             .split(|b| b == &LINE_FEED)
             .map(|l| l.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(l))
-            .any(|l| ssid == l);
+            .any(|l| split_terse_line(l, FIELD_SEPARATOR).concat() == ssid);
 
         Ok(exists)
     }
 
     /// Connects to the given SSID.
     ///
+    /// If `passwd` is provided, then the SSID is connected with `nmcli --ask device wifi connect`, and the password is written to its stdin instead of its arguments,
+    /// since the arguments are visible to the other users of the host. A password with a line feed is rejected.
+    /// If `bssid` is provided, then it is passed as the `bssid` argument of `nmcli device wifi connect`, which pins the new connection profile to the access point.
+    /// For a known network without a password, the `802-11-wireless.bssid` property of its profile is modified before it is activated.
+    ///
     /// The output is in a **human-readable format** and may contain multiple lines.
     ///
     /// # Panics
@@ -538,7 +589,7 @@ impl Wl for Nmcli {
     /// let is_known_ssid = false;
     ///
     /// let nmcli = Nmcli::new();
-    /// let connect_result = nmcli.connect(ssid.as_bytes(), passwd, is_known_ssid);
+    /// let connect_result = nmcli.connect(ssid.as_bytes(), passwd, None, is_known_ssid);
     ///
     /// match connect_result {
     ///     Ok(res) => io::stdout().write_all(&res).unwrap(),
@@ -557,7 +608,7 @@ impl Wl for Nmcli {
     /// let is_known_ssid = true;
     ///
     /// let nmcli = Nmcli::new();
-    /// let connect_result = nmcli.connect(ssid.as_bytes(), passwd, is_known_ssid);
+    /// let connect_result = nmcli.connect(ssid.as_bytes(), passwd, None, is_known_ssid);
     ///
     /// match connect_result {
     ///     Ok(res) => io::stdout().write_all(&res).unwrap(),
@@ -576,31 +627,62 @@ impl Wl for Nmcli {
     /// let is_known_ssid = true;
     ///
     /// let nmcli = Nmcli::new();
-    /// let connect_result = nmcli.connect(ssid.as_bytes(), passwd, is_known_ssid);
+    /// let connect_result = nmcli.connect(ssid.as_bytes(), passwd, None, is_known_ssid);
     ///
     /// match connect_result {
     ///     Ok(res) => io::stdout().write_all(&res).unwrap(),
     ///     Err(err) => eprintln!("{}", err),
     /// };
     /// ```
+    ///
+    /// To connect to a specific access point of the SSID, provide its BSSID.
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli,Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// let connect_result = nmcli.connect(b"SSID", None, Some("AA:BB:CC:DD:EE:FF"), true);
+    /// ```
     /// [`NetworkAdapterError::CannotConnect`]: crate::NetworkAdapterError::CannotConnect
     fn connect(
         &self,
         ssid: &[u8],
         passwd: Option<&[u8]>,
+        bssid: Option<&str>,
         is_known_ssid: bool,
     ) -> Result<Vec<u8>, Error> {
         if is_known_ssid && passwd.is_some() {
             self.disconnect(ssid, true)?;
         }
 
-        let args = if let Some(passwd) = passwd {
-            let mut args = ["d", "wifi", "connect", "", "password", ""].map(|a| a.as_bytes());
-            args[3] = ssid;
-            args[5] = passwd;
+        if let Some(passwd) = passwd {
+            // The password is answered to the prompt of `--ask` through stdin, so it is not visible in the arguments.
+            let mut args = ["--ask", "d", "wifi", "connect", ""]
+                .map(|a| a.as_bytes())
+                .to_vec();
+            args[4] = ssid;
+
+            if let Some(bssid) = bssid {
+                args.extend([b"bssid".as_slice(), bssid.as_bytes()]);
+            }
+
+            let input = passwd_line(passwd).map_err(Error::CannotConnect)?;
+            let output = self
+                .exec_with_input(&args, &input)
+                .map_err(Error::CannotConnect)?;
+
+            return Ok(match output.strip_prefix(PASSWD_PROMPT) {
+                Some(output) => output.to_vec(),
+                None => output,
+            });
+        }
+
+        let args = {
+            if let Some(bssid) = bssid {
+                self.modify(ssid, &[b"802-11-wireless.bssid", bssid.as_bytes()])
+                    .map_err(Error::CannotConnect)?;
+            }
 
-            args.to_vec()
-        } else {
             let mut args = ["connection", "up", "id", ""].map(|a| a.as_bytes());
             args[3] = ssid;
 
@@ -610,7 +692,966 @@ impl Wl for Nmcli {
         self.exec(&args).map_err(Error::CannotConnect)
     }
 
-    fn get_field_separator(&self) -> u8 {
-        b':'
+    /// Removes the BSSID that is pinned to the given known SSID.
+    ///
+    /// The `802-11-wireless.bssid` property of the connection profile is cleared with `nmcli connection modify`.
+    /// The change is applied on the next activation of the profile.
+    ///
+    /// The output is the one of `nmcli connection modify`, which is empty on success.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotUpdateNetwork`] if the connection profile cannot be modified (e.g. the SSID is not a known one).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// nmcli.clear_bssid(b"SSID").unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotUpdateNetwork`]: crate::NetworkAdapterError::CannotUpdateNetwork
+    fn clear_bssid(&self, ssid: &[u8]) -> Result<Vec<u8>, Error> {
+        self.modify(ssid, &[b"802-11-wireless.bssid", b""])
+            .map_err(Error::CannotUpdateNetwork)
+    }
+
+    /// Connects to the given enterprise (802.1X / WPA-EAP) SSID.
+    ///
+    /// `nmcli device wifi connect` cannot provide the 802.1X settings, so the connection profile is added with
+    /// `nmcli connection add` with the `wpa-eap` key management, and activated with `nmcli connection up`.
+    /// The profile is named after the SSID, like the profiles of [`Nmcli::connect`].
+    ///
+    /// If `is_known_ssid` is set, then the known network is deleted before the new profile is added.
+    /// The new profile is deleted if it cannot be activated.
+    /// If the private key is not encrypted (`private_key_passwd` is not provided), then NetworkManager is told not to ask for its password.
+    /// The passwords are not added to the arguments of `nmcli`. Instead, they are written to the stdin of `nmcli connection up` as its `passwd-file`.
+    ///
+    /// The output is the one of `nmcli connection up`, in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotConnect`] if it fails to add or activate the connection profile.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{EapCredentials, EapMethod, Nmcli, Phase2Auth, Wl};
+    /// use std::io::{self, Write};
+    ///
+    /// let credentials = EapCredentials {
+    ///     method: EapMethod::Peap,
+    ///     identity: b"user".to_vec(),
+    ///     passwd: Some(b"PASS".to_vec()),
+    ///     phase2_auth: Some(Phase2Auth::Mschapv2),
+    ///     ca_cert: Some("/etc/ssl/certs/corp-ca.pem".into()),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let nmcli = Nmcli::new();
+    /// let result = nmcli.connect_enterprise(b"Corp", &credentials, false).unwrap();
+    /// io::stdout().write_all(&result).unwrap();
+    /// ```
+    ///
+    /// [`Nmcli::connect`]: crate::Nmcli::connect
+    /// [`NetworkAdapterError::CannotConnect`]: crate::NetworkAdapterError::CannotConnect
+    fn connect_enterprise(
+        &self,
+        ssid: &[u8],
+        credentials: &EapCredentials,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        if is_known_ssid {
+            self.disconnect(ssid, true)?;
+        }
+
+        let mut args = ["connection", "add", "type", "wifi", "con-name"]
+            .map(|a| a.as_bytes())
+            .to_vec();
+        args.extend([ssid, b"ssid", ssid]);
+        args.extend([
+            b"wifi-sec.key-mgmt".as_slice(),
+            b"wpa-eap",
+            b"802-1x.eap",
+            credentials.method.name().as_bytes(),
+            b"802-1x.identity",
+            &credentials.identity,
+        ]);
+
+        if let Some(identity) = &credentials.anonymous_identity {
+            args.extend([b"802-1x.anonymous-identity".as_slice(), identity]);
+        }
+        if let Some(auth) = &credentials.phase2_auth {
+            args.extend([b"802-1x.phase2-auth".as_slice(), auth.name().as_bytes()]);
+        }
+        if let Some(cert) = &credentials.ca_cert {
+            args.extend([b"802-1x.ca-cert".as_slice(), cert.as_os_str().as_bytes()]);
+        }
+        if let Some(suffix) = &credentials.domain_suffix_match {
+            args.extend([b"802-1x.domain-suffix-match".as_slice(), suffix.as_bytes()]);
+        }
+        if let Some(cert) = &credentials.client_cert {
+            args.extend([
+                b"802-1x.client-cert".as_slice(),
+                cert.as_os_str().as_bytes(),
+            ]);
+        }
+        let mut secrets = Vec::new();
+        if let Some(passwd) = &credentials.passwd {
+            secrets.push(("802-1x.password", passwd.as_slice()));
+        }
+        if let Some(key) = &credentials.private_key {
+            args.extend([b"802-1x.private-key".as_slice(), key.as_os_str().as_bytes()]);
+            match &credentials.private_key_passwd {
+                Some(passwd) => secrets.push(("802-1x.private-key-password", passwd.as_slice())),
+                None => args.extend([b"802-1x.private-key-password-flags".as_slice(), b"4"]),
+            }
+        }
+
+        self.add_and_activate(ssid, &args, &secrets)
+            .map_err(Error::CannotConnect)
     }
+
+    /// Connects to the given hidden SSID.
+    ///
+    /// The connection profile is added with `nmcli connection add` with `802-11-wireless.hidden`, so NetworkManager probes the SSID explicitly,
+    /// and activated with `nmcli connection up`.
+    /// The key management of the profile is the given security type (`wpa-psk` or `sae`), and it has no security settings if the network is open.
+    /// The password is not added to the arguments of `nmcli`. Instead, it is written to the stdin of `nmcli connection up` as its `passwd-file`.
+    ///
+    /// If `is_known_ssid` is set, then the known network is deleted before the new profile is added.
+    /// The new profile is deleted if it cannot be activated.
+    ///
+    /// The output is the one of `nmcli connection up`, in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotConnect`] if it fails to add or activate the connection profile.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, Security, Wl};
+    /// use std::io::{self, Write};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// let result = nmcli.connect_hidden(b"SSID", Security::WpaPsk, Some(b"PASS"), false).unwrap();
+    /// io::stdout().write_all(&result).unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotConnect`]: crate::NetworkAdapterError::CannotConnect
+    fn connect_hidden(
+        &self,
+        ssid: &[u8],
+        security: Security,
+        passwd: Option<&[u8]>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        if is_known_ssid {
+            self.disconnect(ssid, true)?;
+        }
+
+        let mut args = ["connection", "add", "type", "wifi", "con-name"]
+            .map(|a| a.as_bytes())
+            .to_vec();
+        args.extend([ssid, b"ssid", ssid, b"802-11-wireless.hidden", b"yes"]);
+
+        if security.is_secured() {
+            args.extend([b"wifi-sec.key-mgmt".as_slice(), security.name().as_bytes()]);
+        }
+        let secrets = match passwd {
+            Some(passwd) => vec![("802-11-wireless-security.psk", passwd)],
+            None => Vec::new(),
+        };
+
+        self.add_and_activate(ssid, &args, &secrets)
+            .map_err(Error::CannotConnect)
+    }
+
+    /// Adds the given network as a known network with `nmcli connection add`, without activating it.
+    ///
+    /// The semantics are the same as [`Nmcli::connect_hidden`]: the known network is replaced if `is_known_ssid` is set.
+    /// A secured network without a secret is added with `wifi-sec.psk-flags 2`, so its password is asked on each connection instead.
+    ///
+    /// The secret is not given to `nmcli connection add`, since the arguments are visible to the other users of the host,
+    /// and `nmcli connection up` cannot take it from its `passwd-file` without activating the network.
+    /// Instead, it is set through the stdin of `nmcli connection edit`, and the network is deleted if the secret cannot be set.
+    ///
+    /// The output is the one of `nmcli connection add`, which is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotDisconnect`] if it fails to remove the known network,
+    /// and [`NetworkAdapterError::CannotAddNetwork`] if it fails to add the new one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{ExportedNetwork, Nmcli, Security, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// let network = ExportedNetwork {
+    ///     ssid: b"Home".to_vec(),
+    ///     security: Security::WpaPsk,
+    ///     secret: Some(b"secret".to_vec()),
+    ///     hidden: false,
+    ///     priority: 10,
+    ///     autoconnect: true,
+    /// };
+    /// nmcli.add_network(&network, false).unwrap();
+    /// ```
+    ///
+    /// [`Nmcli::connect_hidden`]: crate::Nmcli::connect_hidden
+    /// [`NetworkAdapterError::CannotDisconnect`]: crate::NetworkAdapterError::CannotDisconnect
+    /// [`NetworkAdapterError::CannotAddNetwork`]: crate::NetworkAdapterError::CannotAddNetwork
+    fn add_network(
+        &self,
+        network: &ExportedNetwork,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let ssid = network.ssid.as_slice();
+        if is_known_ssid {
+            self.disconnect(ssid, true)?;
+        }
+
+        let priority = network.priority.to_string();
+        let autoconnect = if network.autoconnect { "yes" } else { "no" };
+
+        let mut args = ["connection", "add", "type", "wifi", "con-name"]
+            .map(|a| a.as_bytes())
+            .to_vec();
+        args.extend([
+            ssid,
+            b"ssid",
+            ssid,
+            b"connection.autoconnect",
+            autoconnect.as_bytes(),
+            b"connection.autoconnect-priority",
+            priority.as_bytes(),
+        ]);
+
+        if network.hidden {
+            args.extend([b"802-11-wireless.hidden".as_slice(), b"yes"]);
+        }
+        if network.security.is_secured() {
+            args.extend([
+                b"wifi-sec.key-mgmt".as_slice(),
+                network.security.name().as_bytes(),
+            ]);
+            if network.secret.is_none() {
+                args.extend([b"wifi-sec.psk-flags".as_slice(), b"2"]);
+            }
+        }
+
+        let edit_commands = match &network.secret {
+            Some(secret) if network.security.is_secured() => Some(
+                [
+                    b"set 802-11-wireless-security.psk ".as_slice(),
+                    &passwd_line(secret).map_err(Error::CannotAddNetwork)?,
+                    b"save persistent\nquit\n",
+                ]
+                .concat(),
+            ),
+            _ => None,
+        };
+
+        let added = self.exec(&args).map_err(Error::CannotAddNetwork)?;
+
+        if let Some(edit_commands) = edit_commands {
+            let edit_args = [b"connection".as_slice(), b"edit", b"id", ssid];
+            if let Err(err) = self.exec_with_input(&edit_args, &edit_commands) {
+                let _ = self.exec(&[b"connection".as_slice(), b"delete", b"id", ssid]);
+                return Err(Error::CannotAddNetwork(err));
+            }
+        }
+
+        Ok(added)
+    }
+
+    /// Starts a hotspot that shares the connection of the host.
+    ///
+    /// The hotspot is a connection profile named `wl-hotspot`, which is added with `nmcli connection add`
+    /// in the `ap` mode with the `shared` IPv4 method, and activated with `nmcli connection up`.
+    /// Unlike `nmcli device wifi hotspot`, the hotspot is open when `passwd` is not provided.
+    /// The password is written to the stdin of `nmcli connection up` as its `passwd-file`, instead of its arguments.
+    ///
+    /// The previous `wl-hotspot` profile is replaced, and the new profile is deleted if it cannot be activated.
+    /// If only `channel` is provided, then the band is derived from it, since NetworkManager requires both.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotStartHotspot`] if it fails to start the hotspot.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Band, Nmcli, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// let hotspot = nmcli
+    ///     .start_hotspot(b"Field", Some(b"password"), Some(Band::Bg), None)
+    ///     .unwrap();
+    ///
+    /// println!("hotspot is running on {}", hotspot.device);
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotStartHotspot`]: crate::NetworkAdapterError::CannotStartHotspot
+    fn start_hotspot(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        band: Option<Band>,
+        channel: Option<u32>,
+    ) -> Result<Hotspot, Error> {
+        let delete_args = ["connection", "delete", "id", HOTSPOT_PROFILE].map(|a| a.as_bytes());
+        match self.exec(&delete_args) {
+            Ok(_) | Err((_, UNKNOWN_SSID_EXIT_CODE)) => {}
+            Err(err) => return Err(Error::CannotStartHotspot(err)),
+        }
+
+        let band = band.or(channel.map(Band::from_channel));
+        let channel = channel.map(|c| c.to_string());
+
+        let mut args = [
+            "connection",
+            "add",
+            "type",
+            "wifi",
+            "con-name",
+            HOTSPOT_PROFILE,
+            "autoconnect",
+            "no",
+            "ssid",
+        ]
+        .map(|a| a.as_bytes())
+        .to_vec();
+        args.push(ssid);
+        args.extend(
+            [
+                "802-11-wireless.mode",
+                "ap",
+                "ipv4.method",
+                "shared",
+                "ipv6.method",
+                "ignore",
+            ]
+            .map(|a| a.as_bytes()),
+        );
+        if let Some(band) = band {
+            args.extend([b"802-11-wireless.band".as_slice(), band.name().as_bytes()]);
+        }
+        if let Some(channel) = &channel {
+            args.extend([b"802-11-wireless.channel".as_slice(), channel.as_bytes()]);
+        }
+        let secrets = match passwd {
+            Some(passwd) => {
+                args.extend([b"wifi-sec.key-mgmt".as_slice(), b"wpa-psk"]);
+                vec![("802-11-wireless-security.psk", passwd)]
+            }
+            None => Vec::new(),
+        };
+
+        self.add_and_activate(HOTSPOT_PROFILE.as_bytes(), &args, &secrets)
+            .map_err(Error::CannotStartHotspot)?;
+
+        self.hotspot()
+            .map_err(Error::CannotStartHotspot)?
+            .ok_or_else(|| {
+                let err = io::Error::other("the hotspot is not active after it is started");
+                Error::CannotStartHotspot((err, 1))
+            })
+    }
+
+    /// Stops the hotspot that is started by [`Nmcli::start_hotspot`].
+    ///
+    /// The `wl-hotspot` connection profile is deleted with `nmcli connection delete`, which also deactivates it.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotStopHotspot`] if it fails to stop the hotspot, or if the hotspot is not active.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// let hotspot = nmcli.stop_hotspot().unwrap();
+    ///
+    /// println!("stopped {}", String::from_utf8_lossy(&hotspot.ssid));
+    /// ```
+    ///
+    /// [`Nmcli::start_hotspot`]: crate::Nmcli::start_hotspot
+    /// [`NetworkAdapterError::CannotStopHotspot`]: crate::NetworkAdapterError::CannotStopHotspot
+    fn stop_hotspot(&self) -> Result<Hotspot, Error> {
+        let hotspot = self
+            .hotspot()
+            .map_err(Error::CannotStopHotspot)?
+            .ok_or_else(|| {
+                let err = io::Error::other("no hotspot is active");
+                Error::CannotStopHotspot((err, UNKNOWN_SSID_EXIT_CODE))
+            })?;
+
+        let args = ["connection", "delete", "id", HOTSPOT_PROFILE].map(|a| a.as_bytes());
+        self.exec(&args).map_err(Error::CannotStopHotspot)?;
+
+        Ok(hotspot)
+    }
+
+    /// Provides the hotspot that is started by [`Nmcli::start_hotspot`], if it is active.
+    ///
+    /// The connected clients are read from the DHCP leases of the hotspot, which are kept by NetworkManager under `/var/lib/NetworkManager`.
+    /// If the leases cannot be read, then the clients are `None`.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetHotspot`] if it fails to get the hotspot.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// match nmcli.get_hotspot().unwrap() {
+    ///     Some(hotspot) => println!("hotspot is running on {}", hotspot.device),
+    ///     None => println!("hotspot is not active"),
+    /// }
+    /// ```
+    ///
+    /// [`Nmcli::start_hotspot`]: crate::Nmcli::start_hotspot
+    /// [`NetworkAdapterError::CannotGetHotspot`]: crate::NetworkAdapterError::CannotGetHotspot
+    fn get_hotspot(&self) -> Result<Option<Hotspot>, Error> {
+        self.hotspot().map_err(Error::CannotGetHotspot)
+    }
+
+    /// Provides the password of the given known SSID.
+    ///
+    /// The password is read from the connection profile of the SSID with `nmcli --show-secrets`, which may require the privileges to read the secrets of the profile.
+    /// If the profile does not have a key management, then the network is open and `None` is provided.
+    /// The password is the PSK of the profile, or its first WEP key for a WEP network.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetPasswd`] if it fails to read the connection profile, or if the SSID is not a known one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// match nmcli.get_passwd(b"SSID").unwrap() {
+    ///     Some(passwd) => println!("password: {}", String::from_utf8_lossy(&passwd)),
+    ///     None => println!("SSID is an open network"),
+    /// }
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetPasswd`]: crate::NetworkAdapterError::CannotGetPasswd
+    fn get_passwd(&self, ssid: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let mut args = [
+            "--show-secrets",
+            "-g",
+            "802-11-wireless-security.key-mgmt,802-11-wireless-security.psk,802-11-wireless-security.wep-key0",
+            "connection",
+            "show",
+            "id",
+        ]
+        .map(|a| a.as_bytes())
+        .to_vec();
+        args.push(ssid);
+
+        let profile = self.exec(&args).map_err(Error::CannotGetPasswd)?;
+
+        let mut values = profile
+            .split(|b| b == &LINE_FEED)
+            .map(|l| l.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(l))
+            .map(|l| split_terse_line(l, FIELD_SEPARATOR).concat());
+
+        let key_mgmt = values.next().unwrap_or_default();
+        if key_mgmt.is_empty() {
+            return Ok(None);
+        }
+
+        match values.find(|v| !v.is_empty()) {
+            Some(passwd) => Ok(Some(passwd)),
+            None => {
+                let err = io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "the password is not stored in the connection profile or cannot be read",
+                );
+                Err(Error::CannotGetPasswd((err, 1)))
+            }
+        }
+    }
+
+    /// Provides the settings of the given known SSID.
+    ///
+    /// The settings are read from the connection profile of the SSID with a single `nmcli -g FIELDS connection show id SSID` call.
+    /// The security type is the key management of the profile (e.g. `wpa-psk`), which is `open` if the profile does not have one.
+    /// The settings that cannot be recognized are provided with their defaults.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetProfile`] if it fails to read the connection profile, or if the SSID is not a known one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// let profile = nmcli.get_profile(b"SSID").unwrap();
+    ///
+    /// println!("autoconnect: {}, priority: {}", profile.autoconnect, profile.priority);
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetProfile`]: crate::NetworkAdapterError::CannotGetProfile
+    fn get_profile(&self, ssid: &[u8]) -> Result<Profile, Error> {
+        let mut args = [
+            "-g",
+            "connection.id,802-11-wireless.ssid,802-11-wireless-security.key-mgmt,connection.autoconnect,connection.autoconnect-priority,connection.metered,802-11-wireless.mac-address-randomization,ipv4.method,802-11-wireless.hidden",
+            "connection",
+            "show",
+            "id",
+        ]
+        .map(|a| a.as_bytes())
+        .to_vec();
+        args.push(ssid);
+
+        let result = self.exec(&args).map_err(Error::CannotGetProfile)?;
+
+        let mut values = result
+            .split(|b| b == &LINE_FEED)
+            .map(|l| l.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(l))
+            .map(|l| split_terse_line(l, FIELD_SEPARATOR).concat());
+        let mut next_value =
+            || String::from_utf8_lossy(&values.next().unwrap_or_default()).into_owned();
+
+        let name = next_value().into_bytes();
+        let ssid = next_value().into_bytes();
+        let security = match next_value().as_str() {
+            "" => String::from("open"),
+            "none" => String::from("wep"),
+            key_mgmt => key_mgmt.to_string(),
+        };
+        let autoconnect = next_value() != "no";
+        let priority = next_value().parse().unwrap_or_default();
+        let metered = ValueEnum::from_str(&next_value(), true).unwrap_or_default();
+        let mac_randomization = ValueEnum::from_str(&next_value(), true).unwrap_or_default();
+        let ip_method = ValueEnum::from_str(&next_value(), true).unwrap_or_default();
+        let hidden = next_value() == "yes";
+
+        Ok(Profile {
+            name,
+            ssid,
+            security,
+            hidden,
+            autoconnect,
+            priority,
+            metered,
+            mac_randomization,
+            ip_method,
+        })
+    }
+
+    /// Changes the settings of the given known SSID.
+    ///
+    /// The settings are changed with a single `nmcli connection modify` call. Selecting the `open` security type removes the `802-11-wireless-security` setting of the profile.
+    /// The changes are applied on the next activation of the profile.
+    ///
+    /// The output is the one of `nmcli connection modify`, which is empty on success.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotUpdateNetwork`] if the connection profile cannot be modified (e.g. the SSID is not a known one, or `nmcli` rejects a setting).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, ProfileEdit, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// let edit = ProfileEdit {
+    ///     priority: Some(10),
+    ///     ..Default::default()
+    /// };
+    /// nmcli.edit_profile(b"SSID", &edit).unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotUpdateNetwork`]: crate::NetworkAdapterError::CannotUpdateNetwork
+    fn edit_profile(&self, ssid: &[u8], edit: &ProfileEdit) -> Result<Vec<u8>, Error> {
+        let mut properties: Vec<[String; 2]> = Vec::new();
+
+        match edit.security {
+            Some(Security::Open) => properties.push([
+                String::from("remove"),
+                String::from("802-11-wireless-security"),
+            ]),
+            Some(security) => properties.push([
+                String::from("802-11-wireless-security.key-mgmt"),
+                security.name().to_string(),
+            ]),
+            None => {}
+        }
+        if let Some(autoconnect) = edit.autoconnect {
+            let autoconnect = if autoconnect { "yes" } else { "no" };
+            properties.push([
+                String::from("connection.autoconnect"),
+                autoconnect.to_string(),
+            ]);
+        }
+        if let Some(priority) = edit.priority {
+            properties.push([
+                String::from("connection.autoconnect-priority"),
+                priority.to_string(),
+            ]);
+        }
+        if let Some(metered) = edit.metered {
+            properties.push([
+                String::from("connection.metered"),
+                metered.name().to_string(),
+            ]);
+        }
+        if let Some(mac_randomization) = edit.mac_randomization {
+            properties.push([
+                String::from("802-11-wireless.mac-address-randomization"),
+                mac_randomization.name().to_string(),
+            ]);
+        }
+        if let Some(ip_method) = edit.ip_method {
+            properties.push([String::from("ipv4.method"), ip_method.name().to_string()]);
+        }
+
+        let properties: Vec<&[u8]> = properties.iter().flatten().map(|p| p.as_bytes()).collect();
+        self.modify(ssid, &properties)
+            .map_err(Error::CannotUpdateNetwork)
+    }
+
+    /// Renames the given known SSID.
+    ///
+    /// The `connection.id` property of the connection profile is modified with `nmcli connection modify`, which keeps the SSID of the profile.
+    ///
+    /// The output is the one of `nmcli connection modify`, which is empty on success.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotUpdateNetwork`] if the connection profile cannot be modified (e.g. the SSID is not a known one).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// nmcli.rename_profile(b"SSID", b"Home").unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotUpdateNetwork`]: crate::NetworkAdapterError::CannotUpdateNetwork
+    fn rename_profile(&self, ssid: &[u8], name: &[u8]) -> Result<Vec<u8>, Error> {
+        self.modify(ssid, &[b"connection.id", name])
+            .map_err(Error::CannotUpdateNetwork)
+    }
+
+    /// Provides the autoconnect priorities of the known WiFi networks by using `nmcli connection show`.
+    ///
+    /// The known networks are provided in the order that is reported by `nmcli`. The connection profiles that are not WiFi ones are skipped.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotListNetworks`] if it fails to list the connection profiles.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// for network in nmcli.get_priorities().unwrap() {
+    ///     println!("{}: {}", String::from_utf8_lossy(&network.ssid), network.priority);
+    /// }
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotListNetworks`]: crate::NetworkAdapterError::CannotListNetworks
+    fn get_priorities(&self) -> Result<Vec<NetworkPriority>, Error> {
+        let args = [
+            "-g",
+            "NAME,TYPE,AUTOCONNECT,AUTOCONNECT-PRIORITY",
+            "connection",
+            "show",
+        ]
+        .map(|a| a.as_bytes());
+
+        let result = self.exec(&args).map_err(Error::CannotListNetworks)?;
+
+        let priorities = result
+            .split(|b| b == &LINE_FEED)
+            .map(|l| l.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(l))
+            .filter(|l| !l.is_empty())
+            .filter_map(|l| {
+                let fields = split_terse_line(l, FIELD_SEPARATOR);
+                let [name, kind, autoconnect, priority] = &fields[..] else {
+                    return None;
+                };
+
+                if kind != b"802-11-wireless" {
+                    return None;
+                }
+
+                Some(NetworkPriority {
+                    ssid: name.to_vec(),
+                    priority: String::from_utf8_lossy(priority)
+                        .parse()
+                        .unwrap_or_default(),
+                    autoconnect: autoconnect != b"no",
+                })
+            })
+            .collect();
+
+        Ok(priorities)
+    }
+
+    /// Sets the autoconnect priority of the given known SSID by modifying its `connection.autoconnect-priority`.
+    ///
+    /// The output is the one of `nmcli connection modify`, which is empty on success.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotUpdateNetwork`] if the connection profile cannot be modified (e.g. the SSID is not a known one, or the priority is out of the range that `nmcli` accepts).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// nmcli.set_priority(b"SSID", 10).unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotUpdateNetwork`]: crate::NetworkAdapterError::CannotUpdateNetwork
+    fn set_priority(&self, ssid: &[u8], priority: i32) -> Result<Vec<u8>, Error> {
+        let priority = priority.to_string();
+        self.modify(
+            ssid,
+            &[b"connection.autoconnect-priority", priority.as_bytes()],
+        )
+        .map_err(Error::CannotUpdateNetwork)
+    }
+
+    /// Subscribes to the changes that are written by `nmcli monitor`.
+    ///
+    /// The device state lines (e.g. `wlan0: connecting (prepare)`) are sent as [`Notification::DeviceState`], and the rest of the lines are sent as [`Notification::Changed`].
+    ///
+    /// `nmcli monitor` is spawned through [`Runner::spawn`], and its output is read on a separate thread until the returned receiver is dropped.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotWatch`] if it fails to spawn `nmcli monitor`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{Nmcli, Wl};
+    ///
+    /// let nmcli = Nmcli::new();
+    /// let notifications = nmcli.subscribe().unwrap().unwrap();
+    /// for notification in notifications {
+    ///     println!("{:?}", notification);
+    /// }
+    /// ```
+    ///
+    /// [`Notification::DeviceState`]: crate::Notification::DeviceState
+    /// [`Notification::Changed`]: crate::Notification::Changed
+    /// [`Runner::spawn`]: crate::runner::Runner::spawn
+    /// [`NetworkAdapterError::CannotWatch`]: crate::NetworkAdapterError::CannotWatch
+    fn subscribe(&self) -> Result<Option<mpsc::Receiver<Notification>>, Error> {
+        let monitor = self
+            .runner
+            .spawn("nmcli", &[b"monitor"])
+            .map_err(|err| Error::CannotWatch((err, 1)))?;
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for line in monitor.split(LINE_FEED) {
+                let Ok(line) = line else {
+                    break;
+                };
+                let line = line.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(&line);
+
+                if tx.send(parse_monitor_line(line)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Some(rx))
+    }
+}
+
+/// Parses a line of `nmcli monitor` into a [`Notification`].
+///
+/// [`Notification`]: crate::Notification
+fn parse_monitor_line(line: &[u8]) -> Notification {
+    let line = String::from_utf8_lossy(line);
+
+    line.split_once(": ")
+        .filter(|(device, state)| {
+            !device.contains(' ') && DEVICE_STATES.iter().any(|s| state.starts_with(s))
+        })
+        .map(|(device, state)| Notification::DeviceState {
+            device: device.to_string(),
+            state: state.to_string(),
+        })
+        .unwrap_or(Notification::Changed)
+}
+
+/// Provides the stdout of the given `nmcli` call, or its stderr and exit code if it fails.
+fn into_stdout(cmd: Result<Output, io::Error>) -> Result<Vec<u8>, (io::Error, i32)> {
+    let default_ecode = 1i32;
+    let cmd = cmd.map_err(|err| (err, default_ecode))?;
+
+    if !cmd.status.success() {
+        let nmcli_err = cmd
+            .stderr
+            .lines()
+            .collect::<Result<String, io::Error>>()
+            .map_err(|err| (err, default_ecode))?;
+        let ecode = cmd.status.code().unwrap_or(default_ecode);
+        return Err((io::Error::other(nmcli_err), ecode));
+    }
+
+    Ok(cmd.stdout)
+}
+
+/// Provides the given password as a single line, which is the answer of a prompt of `nmcli --ask`.
+///
+/// A password with a line feed is rejected, since it cannot be answered on a single line.
+fn passwd_line(passwd: &[u8]) -> Result<Vec<u8>, (io::Error, i32)> {
+    if passwd.contains(&LINE_FEED) {
+        let err = io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the password cannot contain a line feed",
+        );
+        return Err((err, 1));
+    }
+
+    Ok([passwd, &[LINE_FEED]].concat())
+}
+
+/// Provides the content of the `passwd-file` of `nmcli connection up` with the given property names and secrets.
+///
+/// Each secret is written on its own line as `<property>:<secret>`.
+fn passwd_file(secrets: &[(&str, &[u8])]) -> Result<Vec<u8>, (io::Error, i32)> {
+    let mut file = Vec::new();
+    for (property, secret) in secrets {
+        file.extend_from_slice(property.as_bytes());
+        file.push(FIELD_SEPARATOR);
+        file.extend(passwd_line(secret)?);
+    }
+
+    Ok(file)
+}
+
+/// Splits a line of a terse `nmcli` output into its fields.
+///
+/// `nmcli` escapes the separator and the backslash bytes with a backslash
+/// in terse outputs, so the escaped bytes are not treated as separators.
+fn split_terse_line(line: &[u8], separator: u8) -> Vec<Vec<u8>> {
+    let mut fields = vec![Vec::new()];
+    let mut bytes = line.iter();
+
+    while let Some(b) = bytes.next() {
+        match b {
+            b'\\' => {
+                if let Some(escaped) = bytes.next() {
+                    fields.last_mut().unwrap().push(*escaped);
+                }
+            }
+            b if b == &separator => fields.push(Vec::new()),
+            b => fields.last_mut().unwrap().push(*b),
+        }
+    }
+
+    fields
+}
+
+fn decode_hex(hex: &[u8]) -> Vec<u8> {
+    let nibble = |b: u8| match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    };
+
+    hex.chunks_exact(2)
+        .filter_map(|pair| Some(nibble(pair[0])? << 4 | nibble(pair[1])?))
+        .collect()
+}
+
+fn leading_number(value: &[u8]) -> u32 {
+    value
+        .trim_ascii()
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .fold(0u32, |acc, b| {
+            acc.saturating_mul(10).saturating_add((b - b'0') as u32)
+        })
 }
@@ -0,0 +1,127 @@
+use std::io;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::{adapter::LINE_FEED, api::OutputFormat, write_bytes};
+
+/// Writes a single value in the given machine-readable format.
+///
+/// A single value is represented the same way in both [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], as a single line.
+///
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+pub(crate) fn write_value(f: &mut impl io::Write, value: &impl Serialize) -> Result<(), io::Error> {
+    let mut out_buf = serde_json::to_vec(value)?;
+    out_buf.push(LINE_FEED);
+
+    write_bytes(f, &out_buf)
+}
+
+/// Writes a list of values in the given machine-readable format.
+///
+/// In [`OutputFormat::Json`], the list is written as a single array.
+/// In [`OutputFormat::Ndjson`], each value is written on its own line.
+///
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+pub(crate) fn write_list<T: Serialize>(
+    f: &mut impl io::Write,
+    format: OutputFormat,
+    values: &[T],
+) -> Result<(), io::Error> {
+    match format {
+        OutputFormat::Ndjson => {
+            let mut out_buf = Vec::new();
+            for value in values {
+                serde_json::to_writer(&mut out_buf, value)?;
+                out_buf.push(LINE_FEED);
+            }
+
+            write_bytes(f, &out_buf)
+        }
+        _ => write_value(f, &values),
+    }
+}
+
+/// Serializes an SSID as a string.
+///
+/// SSIDs are not guaranteed to be valid UTF-8, so the invalid sequences are replaced with `U+FFFD`.
+pub(crate) fn serialize_ssid<S: Serializer>(ssid: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&String::from_utf8_lossy(ssid))
+}
+
+/// Represents the bytes of an SSID or a secret in the portable files of [`export`] and [`backup`].
+///
+/// The bytes are written as a string when they are valid UTF-8, and as a table of their hex digits otherwise (e.g. `{ hex = "ff00" }`),
+/// so they are never rewritten.
+///
+/// [`export`]: crate::export
+/// [`backup`]: crate::backup
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum PortableBytes {
+    Text(String),
+    Hex { hex: String },
+}
+
+impl PortableBytes {
+    fn new(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => PortableBytes::Text(text.to_string()),
+            Err(_) => PortableBytes::Hex {
+                hex: bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+            },
+        }
+    }
+
+    fn into_bytes<E: de::Error>(self) -> Result<Vec<u8>, E> {
+        match self {
+            PortableBytes::Text(text) => Ok(text.into_bytes()),
+            PortableBytes::Hex { hex } => {
+                if !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(E::custom(format!("{} is not a valid hex string", hex)));
+                }
+
+                Ok((0..hex.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or_default())
+                    .collect())
+            }
+        }
+    }
+}
+
+/// Serializes the bytes of an SSID as [`PortableBytes`].
+pub(crate) fn serialize_portable<S: Serializer>(
+    bytes: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    PortableBytes::new(bytes).serialize(serializer)
+}
+
+/// Deserializes the bytes of an SSID from [`PortableBytes`].
+pub(crate) fn deserialize_portable<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<u8>, D::Error> {
+    PortableBytes::deserialize(deserializer)?.into_bytes()
+}
+
+/// Serializes the bytes of an optional secret as [`PortableBytes`].
+pub(crate) fn serialize_portable_opt<S: Serializer>(
+    bytes: &Option<Vec<u8>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    bytes
+        .as_deref()
+        .map(PortableBytes::new)
+        .serialize(serializer)
+}
+
+/// Deserializes the bytes of an optional secret from [`PortableBytes`].
+pub(crate) fn deserialize_portable_opt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<u8>>, D::Error> {
+    Option::<PortableBytes>::deserialize(deserializer)?
+        .map(PortableBytes::into_bytes)
+        .transpose()
+}
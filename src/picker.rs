@@ -0,0 +1,434 @@
+//! The full-screen SSID picker of the interactive subcommands.
+//!
+//! When an SSID is not provided and stdin is a terminal, [`connect`] and [`disconnect`] let the user
+//! choose the SSID from a full-screen list instead of a numbered prompt.
+//!
+//! The list supports the following keys:
+//!
+//! | Key                          | Action                                |
+//! |------------------------------|---------------------------------------|
+//! | `↑`/`↓`, `Ctrl-P`/`Ctrl-N`   | Move the selection                    |
+//! | `PageUp`/`PageDown`          | Move the selection by a page          |
+//! | `Home`/`End`                 | Move the selection to the first/last  |
+//! | Any character, `Backspace`   | Edit the search query                 |
+//! | `Ctrl-R`                     | Re-scan and refresh the list          |
+//! | `Enter`                      | Select the SSID                       |
+//! | `Esc`, `Ctrl-C`, `Ctrl-D`    | Abort                                 |
+//!
+//! The search is incremental and case-insensitive: the list only shows the SSIDs that contain the query.
+//!
+//! The picker of [`connect`] also has an "Other network…" row at the bottom of the list, which is always visible
+//! and switches to the connection flow of the hidden networks.
+//!
+//! [`Picker`] holds the state of the list and does not depend on a terminal, and [`pick_with`] drives it with
+//! the given keys and output. This makes it possible to embed or test the picker without a terminal.
+//!
+//! [`connect`]: crate::connect
+//! [`disconnect`]: crate::disconnect
+//! [`Picker`]: crate::picker::Picker
+//! [`pick_with`]: crate::picker::pick_with
+
+use std::io::{self, Write};
+
+use termion::{
+    clear, cursor, event::Key, input::TermRead, raw::IntoRawMode, screen::IntoAlternateScreen,
+    style,
+};
+
+use crate::{AccessPoint, access_point::bars, write_bytes};
+
+/// The number of terminal lines that are not used by the list (title, search query and key help).
+const RESERVED_LINES: u16 = 3;
+
+/// The key help that is shown at the bottom of the picker.
+const KEY_HELP: &str = "↑/↓ move  enter select  ctrl-r re-scan  esc quit";
+
+/// Represents a single SSID on the picker.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Entry {
+    /// The raw bytes of the SSID.
+    pub ssid: Vec<u8>,
+
+    /// The signal strength of the network, in percentage (0..=100).
+    ///
+    /// It is `None` if the signal strength is not known (e.g. the network is not on the scan list).
+    pub signal: Option<u8>,
+
+    /// Whether the network requires authentication or not.
+    pub secured: bool,
+
+    /// Whether the network is on the known network list of the host.
+    pub known: bool,
+
+    /// Whether the host is connected to the network or not.
+    pub in_use: bool,
+}
+
+impl Entry {
+    /// Converts the given scan list into entries.
+    ///
+    /// The hidden networks (empty SSIDs) are skipped, and the access points that share the same SSID
+    /// are merged into a single entry with the strongest signal.
+    /// The entries are sorted by their signal strength, strongest first.
+    ///
+    /// `known` is the known network list of the host, which is used to mark the known networks.
+    ///
+    /// # Panics
+    ///
+    /// This function does not panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wl::{AccessPoint, picker::Entry};
+    ///
+    /// let aps = vec![
+    ///     AccessPoint { ssid: b"Cafe".to_vec(), signal: 40, ..Default::default() },
+    ///     AccessPoint { ssid: b"Home".to_vec(), signal: 30, security: String::from("WPA2"), ..Default::default() },
+    ///     AccessPoint { ssid: b"Home".to_vec(), signal: 70, security: String::from("WPA2"), ..Default::default() },
+    /// ];
+    ///
+    /// let entries = Entry::from_access_points(aps, &[b"Home".to_vec()]);
+    ///
+    /// assert_eq!(2, entries.len());
+    /// assert_eq!(b"Home".as_slice(), entries[0].ssid);
+    /// assert_eq!(Some(70), entries[0].signal);
+    /// assert!(entries[0].secured && entries[0].known);
+    /// ```
+    pub fn from_access_points(aps: Vec<AccessPoint>, known: &[Vec<u8>]) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = Vec::with_capacity(aps.len());
+
+        for ap in aps.into_iter().filter(|ap| !ap.ssid.is_empty()) {
+            match entries.iter_mut().find(|e| e.ssid == ap.ssid) {
+                Some(entry) => {
+                    entry.signal = entry.signal.max(Some(ap.signal));
+                    entry.in_use |= ap.in_use;
+                }
+                None => entries.push(Entry {
+                    known: known.contains(&ap.ssid),
+                    signal: Some(ap.signal),
+                    secured: ap.is_secured(),
+                    in_use: ap.in_use,
+                    ssid: ap.ssid,
+                }),
+            }
+        }
+
+        entries.sort_by_key(|e| std::cmp::Reverse(e.signal));
+        entries
+    }
+
+    fn name(&self) -> String {
+        String::from_utf8_lossy(&self.ssid).into_owned()
+    }
+}
+
+/// Represents the result of a key that is handled by [`Picker`].
+///
+/// [`Picker`]: crate::picker::Picker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The user selected the SSID.
+    Select(Vec<u8>),
+
+    /// The user selected the "other" row of the picker.
+    Other,
+
+    /// The user asked for a re-scan.
+    Rescan,
+
+    /// The user aborted the picker.
+    Abort,
+}
+
+/// Represents the SSID that is picked by the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selection {
+    /// The user selected the SSID.
+    Ssid(Vec<u8>),
+
+    /// The user selected the "other" row of the picker.
+    Other,
+}
+
+/// Represents the state of the picker: the entries, the search query and the selection.
+#[derive(Debug, Clone)]
+pub struct Picker {
+    title: String,
+    entries: Vec<Entry>,
+    other: Option<String>,
+    query: String,
+    selected: usize,
+    offset: usize,
+    page: usize,
+}
+
+impl Picker {
+    /// Creates a picker with the given title and entries.
+    pub fn new(title: &str, entries: Vec<Entry>) -> Self {
+        Self {
+            title: title.to_string(),
+            entries,
+            other: None,
+            query: String::new(),
+            selected: 0,
+            offset: 0,
+            page: 1,
+        }
+    }
+
+    /// Adds an "other" row with the given label at the bottom of the list.
+    ///
+    /// The row is not filtered by the search query, and selecting it ends the picker with [`Outcome::Other`].
+    ///
+    /// [`Outcome::Other`]: crate::picker::Outcome::Other
+    pub fn with_other(mut self, label: &str) -> Self {
+        self.other = Some(label.to_string());
+        self
+    }
+
+    /// Replaces the entries (e.g. after a re-scan).
+    ///
+    /// The selection is kept on the same SSID if it is still on the list.
+    pub fn set_entries(&mut self, entries: Vec<Entry>) {
+        let selected = self.selected().map(|e| e.ssid.clone());
+        self.entries = entries;
+
+        self.selected = selected
+            .and_then(|ssid| self.visible().iter().position(|e| e.ssid == ssid))
+            .unwrap_or_default();
+    }
+
+    /// Provides the search query.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Provides the entries that match the search query.
+    pub fn visible(&self) -> Vec<&Entry> {
+        let query = self.query.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|e| e.name().to_lowercase().contains(&query))
+            .collect()
+    }
+
+    /// Provides the selected entry, if the list is not empty and the "other" row is not selected.
+    pub fn selected(&self) -> Option<&Entry> {
+        self.visible().get(self.selected).copied()
+    }
+
+    fn is_other_selected(&self) -> bool {
+        self.other.is_some() && self.selected == self.visible().len()
+    }
+
+    /// Handles the given key, and provides an [`Outcome`] if the key ends the picker or requires a re-scan.
+    ///
+    /// [`Outcome`]: crate::picker::Outcome
+    pub fn handle(&mut self, key: Key) -> Option<Outcome> {
+        let rows = self.visible().len() + usize::from(self.other.is_some());
+        let last = rows.saturating_sub(1);
+
+        match key {
+            Key::Char('\n') if self.is_other_selected() => return Some(Outcome::Other),
+            Key::Char('\n') => return self.selected().map(|e| Outcome::Select(e.ssid.clone())),
+            Key::Esc | Key::Ctrl('c') | Key::Ctrl('d') => return Some(Outcome::Abort),
+            Key::Ctrl('r') => return Some(Outcome::Rescan),
+            Key::Up | Key::Ctrl('p') => self.selected = self.selected.saturating_sub(1),
+            Key::Down | Key::Ctrl('n') => self.selected = (self.selected + 1).min(last),
+            Key::PageUp => self.selected = self.selected.saturating_sub(self.page),
+            Key::PageDown => self.selected = (self.selected + self.page).min(last),
+            Key::Home => self.selected = 0,
+            Key::End => self.selected = last,
+            Key::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            Key::Char(c) if !c.is_control() => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Renders the picker for a terminal with the given size (columns, rows).
+    ///
+    /// The output is meant for a terminal in raw mode, hence the lines end with `\r\n`.
+    pub fn render(&mut self, size: (u16, u16)) -> Vec<u8> {
+        let (width, height) = (size.0 as usize, size.1.max(RESERVED_LINES + 1));
+        self.page = (height - RESERVED_LINES) as usize;
+
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + self.page {
+            self.offset = self.selected + 1 - self.page;
+        }
+
+        let mut out = format!(
+            "{}{}{}{}{}\r\nsearch: {}\r\n",
+            clear::All,
+            cursor::Goto(1, 1),
+            style::Bold,
+            self.title,
+            style::Reset,
+            self.query
+        );
+
+        let visible = self.visible();
+        if visible.is_empty() {
+            out.push_str("no networks found\r\n");
+        }
+
+        let mut rows = visible
+            .iter()
+            .map(|entry| {
+                let row = format!(
+                    "{} {} {} {}",
+                    entry.signal.map(bars).unwrap_or("    "),
+                    if entry.secured { "🔒" } else { "  " },
+                    if entry.known { "✓" } else { " " },
+                    entry.name()
+                );
+                (row, entry.in_use)
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(other) = &self.other {
+            rows.push((other.clone(), false));
+        }
+
+        for (idx, (row, in_use)) in rows
+            .into_iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(self.page)
+        {
+            let row = format!("{} {}", if idx == self.selected { ">" } else { " " }, row);
+            let row = row.chars().take(width).collect::<String>();
+
+            match (idx == self.selected, in_use) {
+                (true, _) => out.push_str(&format!("{}{}{}", style::Invert, row, style::Reset)),
+                (false, true) => out.push_str(&format!("{}{}{}", style::Bold, row, style::Reset)),
+                (false, false) => out.push_str(&row),
+            }
+            out.push_str("\r\n");
+        }
+
+        out.push_str(&format!("{}{}", cursor::Goto(1, height), KEY_HELP));
+
+        out.into_bytes()
+    }
+}
+
+/// Lets the user pick an SSID on the terminal of the process.
+///
+/// The picker is drawn on the alternate screen of the terminal in raw mode, and the terminal is restored
+/// once the picker ends.
+///
+/// `load` provides the entries of the picker, and it is called with `true` for the initial list and each re-scan.
+/// `other` is the label of the "other" row, if the picker has one.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Io`] if the terminal cannot be used, [`Error::UserAborted`] if the user aborts the picker,
+/// and the errors of `load` as is.
+///
+/// [`Error::Io`]: crate::Error::Io
+/// [`Error::UserAborted`]: crate::Error::UserAborted
+pub(crate) fn pick(
+    title: &str,
+    other: Option<&str>,
+    load: impl FnMut(bool) -> Result<Vec<Entry>, crate::Error>,
+) -> Result<Selection, crate::Error> {
+    let tty = termion::get_tty()?;
+    let size = termion::terminal_size_fd(&tty)?;
+    let keys = tty.try_clone()?.keys();
+
+    let mut screen = tty.into_raw_mode()?.into_alternate_screen()?;
+    write!(screen, "{}", cursor::Hide)?;
+
+    let picked = pick_with(title, other, &mut screen, keys, size, load);
+
+    write!(screen, "{}", cursor::Show)?;
+    screen.flush()?;
+
+    picked
+}
+
+/// Lets the user pick an SSID by using the given keys, and draws the picker on the given [`io::Write`].
+///
+/// The behavior is the same as the picker of [`connect`] and [`disconnect`], except:
+///
+/// - The keys are read from `keys` instead of the terminal.
+/// - The picker is drawn on `f` for a terminal with the given size (columns, rows).
+///
+/// `load` provides the entries of the picker, and it is called with `true` for the initial list and each re-scan.
+/// `other` is the label of the "other" row, if the picker has one.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::UserAborted`] if the user aborts the picker or `keys` ends before a selection,
+/// and [`Error::Io`] if a key cannot be read or the picker cannot be drawn on the given [`io::Write`].
+/// The errors of `load` are returned as is.
+///
+/// # Examples
+///
+/// ```
+/// use termion::event::Key;
+/// use wl::picker::{self, Entry, Selection};
+///
+/// let entries = vec![
+///     Entry { ssid: b"Home".to_vec(), ..Default::default() },
+///     Entry { ssid: b"Cafe".to_vec(), ..Default::default() },
+/// ];
+///
+/// let keys = [Key::Char('c'), Key::Char('\n')].into_iter().map(Ok);
+/// let picked = picker::pick_with("Select the SSID", None, &mut Vec::new(), keys, (80, 24), |_| Ok(entries.clone()));
+///
+/// assert_eq!(Selection::Ssid(b"Cafe".to_vec()), picked.unwrap());
+/// ```
+///
+/// [`io::Write`]: std::io::Write
+/// [`connect`]: crate::connect
+/// [`disconnect`]: crate::disconnect
+/// [`Error::UserAborted`]: crate::Error::UserAborted
+/// [`Error::Io`]: crate::Error::Io
+pub fn pick_with(
+    title: &str,
+    other: Option<&str>,
+    f: &mut impl io::Write,
+    keys: impl IntoIterator<Item = Result<Key, io::Error>>,
+    size: (u16, u16),
+    mut load: impl FnMut(bool) -> Result<Vec<Entry>, crate::Error>,
+) -> Result<Selection, crate::Error> {
+    let mut picker = Picker::new(title, load(true)?);
+    if let Some(label) = other {
+        picker = picker.with_other(label);
+    }
+
+    let mut keys = keys.into_iter();
+
+    loop {
+        write_bytes(f, &picker.render(size))?;
+
+        let key = keys.next().ok_or(crate::Error::UserAborted)??;
+        match picker.handle(key) {
+            Some(Outcome::Select(ssid)) => return Ok(Selection::Ssid(ssid)),
+            Some(Outcome::Other) => return Ok(Selection::Other),
+            Some(Outcome::Abort) => return Err(crate::Error::UserAborted),
+            Some(Outcome::Rescan) => picker.set_entries(load(true)?),
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use std::{error, fmt, io};
+
+use crate::{NetworkPriority, adapter::Wl, api::OutputFormat, format, output};
+
+/// Defines [`Error`] variants that may return whilst reordering the known networks.
+///
+/// [`Error`]: std::error::Error
+#[derive(Debug)]
+pub enum Error {
+    /// Represents an SSID that is not among the prioritized known networks.
+    UnknownSsid(Vec<u8>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownSsid(ssid) => write!(
+                f,
+                "{} is not a known network",
+                String::from_utf8_lossy(ssid)
+            ),
+        }
+    }
+}
+impl error::Error for Error {}
+
+/// Lists the known networks sorted by their autoconnect priorities by using the given [`Wl`] implementation, and writes them on the given [`io::Write`].
+///
+/// The known networks with the highest priority come first. The ones with the same priority are kept in the order that is reported by the network backend.
+///
+/// In [`OutputFormat::Text`], the known networks are written as a table:
+///
+/// ```text
+/// PRIORITY  AUTOCONNECT  NAME
+/// 10        yes          Home
+/// 0         no           Office
+/// ```
+///
+/// In [`OutputFormat::Json`], the known networks are a JSON array, and in [`OutputFormat::Ndjson`], each known network is a JSON object on its own line:
+///
+/// ```json
+/// [{"ssid":"Home","priority":10,"autoconnect":true},{"ssid":"Office","priority":0,"autoconnect":false}]
+/// ```
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails (e.g. the network backend does not prioritize its known networks),
+/// and [`Error::Io`] when the known networks cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new()
+///     .with_known_network(b"Home", None)
+///     .with_known_network(b"Office", None);
+/// wl::priority_set(&fake, &mut Vec::new(), b"Office".to_vec(), 5, OutputFormat::Json).unwrap();
+///
+/// let mut out = Vec::new();
+/// let priorities = wl::priority_list(&fake, &mut out, OutputFormat::Text).unwrap();
+///
+/// assert_eq!(b"Office".to_vec(), priorities[0].ssid);
+/// assert!(out.starts_with(b"PRIORITY"));
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn priority_list(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    output_format: OutputFormat,
+) -> Result<Vec<NetworkPriority>, crate::Error> {
+    let priorities = get_sorted_priorities(process)?;
+
+    write_priorities(f, &priorities, output_format)?;
+
+    Ok(priorities)
+}
+
+/// Sets the autoconnect priority of the given known SSID by using the given [`Wl`] implementation, and writes the reordered known networks on the given [`io::Write`].
+///
+/// The reordered known networks are written in the same format as [`priority_list`], and they are also returned.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails (e.g. the SSID is not a known one, or the network backend rejects the priority),
+/// and [`Error::Io`] when the known networks cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new().with_known_network(b"Home", None);
+///
+/// let priorities = wl::priority_set(&fake, &mut Vec::new(), b"Home".to_vec(), 10, OutputFormat::Json).unwrap();
+///
+/// assert_eq!(10, priorities[0].priority);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`priority_list`]: crate::priority_list
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn priority_set(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    ssid: Vec<u8>,
+    priority: i32,
+    output_format: OutputFormat,
+) -> Result<Vec<NetworkPriority>, crate::Error> {
+    process.set_priority(&ssid, priority)?;
+
+    priority_list(process, f, output_format)
+}
+
+/// Moves the given known SSID one place up in the order of [`priority_list`] by using the given [`Wl`] implementation, and writes the reordered known networks on the given [`io::Write`].
+///
+/// The priority of the known network is set to one above the priority of the known network before it.
+/// The known network that already comes first is kept as it is.
+///
+/// The reordered known networks are written in the same format as [`priority_list`], and they are also returned.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Priority`] with [`PriorityError::UnknownSsid`] if the SSID is not a known one.
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails,
+/// and [`Error::Io`] when the known networks cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new()
+///     .with_known_network(b"Home", None)
+///     .with_known_network(b"Office", None);
+///
+/// let priorities = wl::priority_up(&fake, &mut Vec::new(), b"Office".to_vec(), OutputFormat::Json).unwrap();
+///
+/// assert_eq!(b"Office".to_vec(), priorities[0].ssid);
+/// assert_eq!(1, priorities[0].priority);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`priority_list`]: crate::priority_list
+/// [`Error::Priority`]: crate::Error::Priority
+/// [`PriorityError::UnknownSsid`]: crate::PriorityError::UnknownSsid
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn priority_up(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    ssid: Vec<u8>,
+    output_format: OutputFormat,
+) -> Result<Vec<NetworkPriority>, crate::Error> {
+    shift_priority(process, f, ssid, true, output_format)
+}
+
+/// Moves the given known SSID one place down in the order of [`priority_list`] by using the given [`Wl`] implementation, and writes the reordered known networks on the given [`io::Write`].
+///
+/// The priority of the known network is set to one below the priority of the known network after it.
+/// The known network that already comes last is kept as it is.
+///
+/// The reordered known networks are written in the same format as [`priority_list`], and they are also returned.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Priority`] with [`PriorityError::UnknownSsid`] if the SSID is not a known one.
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails,
+/// and [`Error::Io`] when the known networks cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new()
+///     .with_known_network(b"Home", None)
+///     .with_known_network(b"Office", None);
+///
+/// let priorities = wl::priority_down(&fake, &mut Vec::new(), b"Home".to_vec(), OutputFormat::Json).unwrap();
+///
+/// assert_eq!(b"Home".to_vec(), priorities[1].ssid);
+/// assert_eq!(-1, priorities[1].priority);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`priority_list`]: crate::priority_list
+/// [`Error::Priority`]: crate::Error::Priority
+/// [`PriorityError::UnknownSsid`]: crate::PriorityError::UnknownSsid
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn priority_down(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    ssid: Vec<u8>,
+    output_format: OutputFormat,
+) -> Result<Vec<NetworkPriority>, crate::Error> {
+    shift_priority(process, f, ssid, false, output_format)
+}
+
+fn shift_priority(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    ssid: Vec<u8>,
+    up: bool,
+    output_format: OutputFormat,
+) -> Result<Vec<NetworkPriority>, crate::Error> {
+    let priorities = get_sorted_priorities(process)?;
+
+    let Some(idx) = priorities.iter().position(|p| p.ssid == ssid) else {
+        Err(Error::UnknownSsid(ssid))?
+    };
+
+    let neighbor = if up {
+        idx.checked_sub(1)
+            .map(|n| priorities[n].priority.saturating_add(1))
+    } else {
+        priorities
+            .get(idx + 1)
+            .map(|n| n.priority.saturating_sub(1))
+    };
+
+    match neighbor {
+        Some(priority) => priority_set(process, f, ssid, priority, output_format),
+        None => {
+            write_priorities(f, &priorities, output_format)?;
+            Ok(priorities)
+        }
+    }
+}
+
+fn get_sorted_priorities(process: &impl Wl) -> Result<Vec<NetworkPriority>, crate::Error> {
+    let mut priorities = process.get_priorities()?;
+    priorities.sort_by_key(|p| std::cmp::Reverse(p.priority));
+
+    Ok(priorities)
+}
+
+fn write_priorities(
+    f: &mut impl io::Write,
+    priorities: &[NetworkPriority],
+    output_format: OutputFormat,
+) -> Result<(), io::Error> {
+    match output_format {
+        OutputFormat::Text => format::write_priority_table(f, priorities),
+        _ => output::write_list(f, output_format, priorities),
+    }
+}
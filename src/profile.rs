@@ -0,0 +1,272 @@
+use std::{error, fmt, io};
+
+use serde::Serialize;
+
+use crate::{Profile, ProfileEdit, adapter::Wl, api::OutputFormat, output, write_bytes};
+
+#[derive(Serialize)]
+struct RenamedProfile<'a> {
+    #[serde(serialize_with = "output::serialize_ssid")]
+    from: &'a [u8],
+    #[serde(serialize_with = "output::serialize_ssid")]
+    to: &'a [u8],
+    renamed: bool,
+}
+
+/// Defines [`Error`] variants that may return whilst managing a known network.
+///
+/// [`Error`]: std::error::Error
+#[derive(Debug)]
+pub enum Error {
+    /// Represents an edit that does not change any setting of the known network.
+    EmptyEdit,
+
+    /// Represents an empty name that is given to rename the known network.
+    EmptyName,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::EmptyEdit => write!(f, "at least one setting should be given to edit"),
+            Error::EmptyName => write!(f, "the new name of the known network cannot be empty"),
+        }
+    }
+}
+impl error::Error for Error {}
+
+/// Provides the settings of the given known SSID by using the given [`Wl`] implementation, and writes them on the given [`io::Write`].
+///
+/// In [`OutputFormat::Text`], each setting is written on its own line:
+///
+/// ```text
+/// name: Home
+/// ssid: Home
+/// security: wpa-psk
+/// hidden: no
+/// autoconnect: yes
+/// priority: 0
+/// metered: unknown
+/// mac-randomization: default
+/// ip-method: auto
+/// ```
+///
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], the settings are a single JSON object:
+///
+/// ```json
+/// {"name":"Home","ssid":"Home","security":"wpa-psk","hidden":false,"autoconnect":true,"priority":0,"metered":"unknown","mac_randomization":"default","ip_method":"auto"}
+/// ```
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails (e.g. the SSID is not a known one),
+/// and [`Error::Io`] when the settings cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new().with_known_network(b"Home", Some(b"secret"));
+///
+/// let mut out = Vec::new();
+/// let profile = wl::profile_show(&fake, &mut out, b"Home".to_vec(), OutputFormat::Text).unwrap();
+///
+/// assert_eq!("wpa-psk", profile.security);
+/// assert!(out.starts_with(b"name: Home\nssid: Home\nsecurity: wpa-psk\n"));
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn profile_show(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    ssid: Vec<u8>,
+    output_format: OutputFormat,
+) -> Result<Profile, crate::Error> {
+    let profile = process.get_profile(&ssid)?;
+
+    write_profile(f, &profile, output_format)?;
+
+    Ok(profile)
+}
+
+/// Changes the settings of the given known SSID by using the given [`Wl`] implementation, and writes the updated settings on the given [`io::Write`].
+///
+/// The settings that are not set in `edit` are kept as they are. The changes are applied on the next connection to the known network.
+///
+/// The updated settings are written in the same format as [`profile_show`], and they are also returned.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Profile`] with [`ProfileError::EmptyEdit`] if `edit` does not change any setting.
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails (e.g. the SSID is not a known one, or the network backend rejects a setting),
+/// and [`Error::Io`] when the settings cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, Metered, ProfileEdit, api::OutputFormat};
+///
+/// let fake = FakeWl::new().with_known_network(b"Phone", Some(b"secret"));
+/// let edit = ProfileEdit {
+///     autoconnect: Some(false),
+///     metered: Some(Metered::Yes),
+///     ..Default::default()
+/// };
+///
+/// let profile = wl::profile_edit(&fake, &mut Vec::new(), b"Phone".to_vec(), &edit, OutputFormat::Json).unwrap();
+///
+/// assert!(!profile.autoconnect);
+/// assert_eq!(Metered::Yes, profile.metered);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`profile_show`]: crate::profile_show
+/// [`Error::Profile`]: crate::Error::Profile
+/// [`ProfileError::EmptyEdit`]: crate::ProfileError::EmptyEdit
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn profile_edit(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    ssid: Vec<u8>,
+    edit: &ProfileEdit,
+    output_format: OutputFormat,
+) -> Result<Profile, crate::Error> {
+    if edit.is_empty() {
+        Err(Error::EmptyEdit)?;
+    }
+
+    process.edit_profile(&ssid, edit)?;
+    let profile = process.get_profile(&ssid)?;
+
+    write_profile(f, &profile, output_format)?;
+
+    Ok(profile)
+}
+
+/// Renames the given known SSID to `name` by using the given [`Wl`] implementation, and writes the result on the given [`io::Write`].
+///
+/// Only the name (connection id) of the known network changes, its SSID is kept.
+/// Once it is renamed, the known network is referred by its new name (e.g. `wl connect -i NAME`).
+///
+/// In [`OutputFormat::Text`], the result is the one that is provided by the [`Wl`] implementation.
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], the result is a single JSON object:
+///
+/// ```json
+/// {"from":"Home","to":"Home (5G)","renamed":true}
+/// ```
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Profile`] with [`ProfileError::EmptyName`] if `name` is empty.
+///
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails (e.g. the SSID is not a known one),
+/// and [`Error::Io`] when the result cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new().with_known_network(b"Home", None);
+///
+/// let mut out = Vec::new();
+/// wl::profile_rename(&fake, &mut out, b"Home".to_vec(), b"Home (5G)".to_vec(), OutputFormat::Json).unwrap();
+///
+/// assert_eq!(vec![b"Home (5G)".to_vec()], fake.known_ssids());
+/// assert_eq!(b"{\"from\":\"Home\",\"to\":\"Home (5G)\",\"renamed\":true}\n".as_slice(), out);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Profile`]: crate::Error::Profile
+/// [`ProfileError::EmptyName`]: crate::ProfileError::EmptyName
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn profile_rename(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    ssid: Vec<u8>,
+    name: Vec<u8>,
+    output_format: OutputFormat,
+) -> Result<(), crate::Error> {
+    if name.trim_ascii().is_empty() {
+        Err(Error::EmptyName)?;
+    }
+
+    let message = process.rename_profile(&ssid, &name)?;
+
+    match output_format {
+        OutputFormat::Text => write_bytes(f, &message)?,
+        _ => output::write_value(
+            f,
+            &RenamedProfile {
+                from: &ssid,
+                to: &name,
+                renamed: true,
+            },
+        )?,
+    }
+
+    Ok(())
+}
+
+fn write_profile(
+    f: &mut impl io::Write,
+    profile: &Profile,
+    output_format: OutputFormat,
+) -> Result<(), io::Error> {
+    if output_format != OutputFormat::Text {
+        return output::write_value(f, profile);
+    }
+
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    let out_buf = [
+        b"name: ",
+        &profile.name[..],
+        b"\nssid: ",
+        &profile.ssid[..],
+        b"\nsecurity: ",
+        profile.security.as_bytes(),
+        b"\nhidden: ",
+        yes_no(profile.hidden).as_bytes(),
+        b"\nautoconnect: ",
+        yes_no(profile.autoconnect).as_bytes(),
+        b"\npriority: ",
+        profile.priority.to_string().as_bytes(),
+        b"\nmetered: ",
+        profile.metered.name().as_bytes(),
+        b"\nmac-randomization: ",
+        profile.mac_randomization.name().as_bytes(),
+        b"\nip-method: ",
+        profile.ip_method.name().as_bytes(),
+        b"\n",
+    ]
+    .concat();
+
+    write_bytes(f, &out_buf)
+}
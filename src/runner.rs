@@ -0,0 +1,307 @@
+//! The command runners that are used by the network backends which rely on an external program.
+//!
+//! Network backends such as [`Nmcli`] do not spawn their programs directly.
+//! Instead, they are generic over a [`Runner`], which makes it possible to replace the
+//! external program with canned outputs.
+//!
+//! - [`SystemRunner`] spawns the programs on the host. It is used by default.
+//! - [`FakeRunner`] replays the canned outputs of the given arguments, and records each call.
+//!
+//! [`Nmcli`]: crate::Nmcli
+
+use std::{
+    ffi::OsString,
+    io::{self, BufRead, BufReader, Read, Write},
+    os::unix::{ffi::OsStringExt, process::ExitStatusExt},
+    process::{Child, Command, ExitStatus, Output, Stdio},
+    sync::Mutex,
+};
+
+/// The interface of running an external program.
+pub trait Runner {
+    /// Runs the given program with the given arguments, and provides its output once it exits.
+    ///
+    /// The implementors should only return an [`io::Error`] when the program cannot be run.
+    /// A program that exits with a failure should be represented by its [`Output`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    /// [`Output`]: std::process::Output
+    fn run(&self, program: &str, args: &[&[u8]]) -> Result<Output, io::Error>;
+
+    /// Spawns the given program with the given arguments, and provides its stdout as a stream.
+    ///
+    /// This is used for the programs that do not exit on their own (e.g. `nmcli monitor`).
+    /// The implementors should stop the program once the stream is dropped.
+    ///
+    /// The default implementation returns an [`io::Error`] with [`io::ErrorKind::Unsupported`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn spawn(&self, program: &str, args: &[&[u8]]) -> Result<Box<dyn BufRead + Send>, io::Error> {
+        let _ = (program, args);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the runner does not support spawning programs",
+        ))
+    }
+
+    /// Runs the given program with the given arguments, writes `input` to its stdin, and provides its output once it exits.
+    ///
+    /// This is used to pass the secrets (e.g. passwords) to the programs, since their arguments are visible to the other users of the host.
+    ///
+    /// The default implementation returns an [`io::Error`] with [`io::ErrorKind::Unsupported`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    /// [`io::ErrorKind::Unsupported`]: std::io::ErrorKind::Unsupported
+    fn run_with_input(
+        &self,
+        program: &str,
+        args: &[&[u8]],
+        input: &[u8],
+    ) -> Result<Output, io::Error> {
+        let _ = (program, args, input);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the runner does not support writing to the stdin of programs",
+        ))
+    }
+}
+
+/// The [`Runner`] that spawns the programs on the host.
+///
+/// [`Runner`]: crate::runner::Runner
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemRunner;
+
+impl Runner for SystemRunner {
+    fn run(&self, program: &str, args: &[&[u8]]) -> Result<Output, io::Error> {
+        let args = args.iter().map(|s| OsString::from_vec(s.to_vec()));
+        Command::new(program).args(args).output()
+    }
+
+    fn spawn(&self, program: &str, args: &[&[u8]]) -> Result<Box<dyn BufRead + Send>, io::Error> {
+        let args = args.iter().map(|s| OsString::from_vec(s.to_vec()));
+        let child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        Ok(Box::new(BufReader::new(ChildStdout(child))))
+    }
+
+    fn run_with_input(
+        &self,
+        program: &str,
+        args: &[&[u8]],
+        input: &[u8],
+    ) -> Result<Output, io::Error> {
+        let args = args.iter().map(|s| OsString::from_vec(s.to_vec()));
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // The stdin is closed once it is dropped, so that the program does not wait for more input.
+        let written = match child.stdin.take() {
+            Some(mut stdin) => stdin.write_all(input),
+            None => Ok(()),
+        };
+
+        // The program is always waited for, so it does not outlive the call.
+        let output = child.wait_with_output()?;
+        match written {
+            // The program exits without reading all of its input (e.g. on an error), which its output reports instead.
+            Err(err) if err.kind() != io::ErrorKind::BrokenPipe => Err(err),
+            _ => Ok(output),
+        }
+    }
+}
+
+/// The stdout of a spawned program, which kills the program once it is dropped.
+struct ChildStdout(Child);
+
+impl Read for ChildStdout {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0.stdout.as_mut() {
+            Some(stdout) => stdout.read(buf),
+            None => Ok(0),
+        }
+    }
+}
+
+impl Drop for ChildStdout {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Represents a canned output of [`FakeRunner`].
+///
+/// [`FakeRunner`]: crate::runner::FakeRunner
+#[derive(Clone, Debug)]
+pub struct Reply {
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    code: i32,
+}
+
+impl Reply {
+    /// Creates a successful reply that writes the given bytes to stdout.
+    pub fn stdout(stdout: impl Into<Vec<u8>>) -> Self {
+        Self {
+            stdout: stdout.into(),
+            stderr: Vec::new(),
+            code: 0,
+        }
+    }
+
+    /// Creates a failed reply that writes the given bytes to stderr, and exits with the given code.
+    pub fn failure(code: i32, stderr: impl Into<Vec<u8>>) -> Self {
+        Self {
+            stdout: Vec::new(),
+            stderr: stderr.into(),
+            code,
+        }
+    }
+}
+
+/// The [`Runner`] that replays canned outputs instead of spawning programs.
+///
+/// Each reply is registered for a program and its exact arguments.
+/// When a call does not have a reply, [`Runner::run`] returns an [`io::Error`] with [`io::ErrorKind::NotFound`], as if the program did not exist.
+///
+/// Each call is recorded as a single line, which consists of the program and its arguments separated by spaces.
+///
+/// [`Runner::spawn`] uses the same replies: the stdout of the reply is streamed as if the program wrote it before it exits, and a failed reply is returned as an [`io::Error`].
+/// [`Runner::run_with_input`] uses the same replies as well, and records the input of each call, which can be obtained with [`FakeRunner::inputs`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{Nmcli, Wl, WifiStatus, runner::{FakeRunner, Reply}};
+///
+/// let runner = FakeRunner::new().reply("nmcli", &["-g", "WIFI", "g"], Reply::stdout("enabled\n"));
+/// let nmcli = Nmcli::with_runner(runner);
+///
+/// assert_eq!(WifiStatus::Enabled, nmcli.get_wifi_status().unwrap());
+/// assert_eq!(vec!["nmcli -g WIFI g"], nmcli.runner().calls());
+/// ```
+///
+/// [`Runner`]: crate::runner::Runner
+/// [`Runner::run`]: crate::runner::Runner::run
+/// [`Runner::spawn`]: crate::runner::Runner::spawn
+/// [`Runner::run_with_input`]: crate::runner::Runner::run_with_input
+/// [`FakeRunner::inputs`]: crate::runner::FakeRunner::inputs
+/// [`io::Error`]: std::io::Error
+/// [`io::ErrorKind::NotFound`]: std::io::ErrorKind::NotFound
+#[derive(Debug, Default)]
+pub struct FakeRunner {
+    replies: Vec<(Vec<Vec<u8>>, Reply)>,
+    calls: Mutex<Vec<String>>,
+    inputs: Mutex<Vec<Vec<u8>>>,
+}
+
+impl FakeRunner {
+    /// Creates a new `FakeRunner` without any replies.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the reply of the given program and arguments.
+    ///
+    /// If the same call is registered more than once, then the first reply is used.
+    pub fn reply(self, program: &str, args: &[&str], reply: Reply) -> Self {
+        self.reply_bytes(
+            program,
+            &args.iter().map(|a| a.as_bytes()).collect::<Vec<_>>(),
+            reply,
+        )
+    }
+
+    /// Registers the reply of the given program and arguments, where the arguments are not valid UTF-8.
+    pub fn reply_bytes(mut self, program: &str, args: &[&[u8]], reply: Reply) -> Self {
+        let call = std::iter::once(program.as_bytes())
+            .chain(args.iter().copied())
+            .map(|a| a.to_vec())
+            .collect();
+
+        self.replies.push((call, reply));
+        self
+    }
+
+    /// Provides the calls that are made so far, in order.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    /// Provides the inputs that are written to the stdin of the calls of [`Runner::run_with_input`] so far, in order.
+    ///
+    /// [`Runner::run_with_input`]: crate::runner::Runner::run_with_input
+    pub fn inputs(&self) -> Vec<Vec<u8>> {
+        self.inputs.lock().map(|i| i.clone()).unwrap_or_default()
+    }
+}
+
+impl FakeRunner {
+    fn find_reply(&self, program: &str, args: &[&[u8]]) -> Result<&Reply, io::Error> {
+        let call = std::iter::once(program.as_bytes())
+            .chain(args.iter().copied())
+            .collect::<Vec<&[u8]>>();
+
+        let line = String::from_utf8_lossy(&call.join(&b' ')).into_owned();
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.push(line.clone());
+        }
+
+        self.replies
+            .iter()
+            .find(|(c, _)| c.iter().map(|a| a.as_slice()).eq(call.iter().copied()))
+            .map(|(_, reply)| reply)
+            .ok_or(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no reply is found for '{}'", line),
+            ))
+    }
+}
+
+impl Runner for FakeRunner {
+    fn run(&self, program: &str, args: &[&[u8]]) -> Result<Output, io::Error> {
+        let reply = self.find_reply(program, args)?;
+
+        Ok(Output {
+            status: ExitStatus::from_raw(reply.code << 8),
+            stdout: reply.stdout.clone(),
+            stderr: reply.stderr.clone(),
+        })
+    }
+
+    fn spawn(&self, program: &str, args: &[&[u8]]) -> Result<Box<dyn BufRead + Send>, io::Error> {
+        let reply = self.find_reply(program, args)?;
+
+        if reply.code != 0 {
+            return Err(io::Error::other(
+                String::from_utf8_lossy(&reply.stderr).trim().to_string(),
+            ));
+        }
+
+        Ok(Box::new(io::Cursor::new(reply.stdout.clone())))
+    }
+
+    fn run_with_input(
+        &self,
+        program: &str,
+        args: &[&[u8]],
+        input: &[u8],
+    ) -> Result<Output, io::Error> {
+        if let Ok(mut inputs) = self.inputs.lock() {
+            inputs.push(input.to_vec());
+        }
+
+        self.run(program, args)
+    }
+}
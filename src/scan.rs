@@ -1,8 +1,10 @@
 use std::{error, fmt, io};
 
-use crate::adapter::{self, Wl};
-use crate::api::ScanArgs;
-use crate::write_bytes;
+use crate::AccessPoint;
+use crate::adapter::Wl;
+use crate::api::{OutputFormat, ScanArgs};
+use crate::format::{self, Field};
+use crate::output;
 
 /// Defines [`Error`] variants that may return during a scan.
 ///
@@ -29,15 +31,24 @@ impl error::Error for Error {}
 
 /// Writes the list of the available WiFi networks. To see a list of the known WiFi networks, please refer to [`list_networks`] instead.
 ///
-/// The list is retrieved by using a [`Wl`] implementation.
-/// The list is written to the provided [`io::Write`] implementation.
+/// The list is retrieved as [`AccessPoint`]s by using the given [`Wl`] implementation.
+/// The list is rendered by [`format`] and written to the provided [`io::Write`] implementation.
 ///
-/// The default list format depends on the [`Wl`] implementation.
-/// [`ScanArgs`] is used to manipulate the list.
+/// [`ScanArgs`] is used to manipulate the list:
 ///
-/// To see how [`ScanArgs`] manipulates the list, check out the network backend modules:
+/// - If `columns` is used, then the output is in a **human-readable format** (table) and contains the given columns only.
+/// - If `get_values` is used, then the output is in a **terse format** and contains the given fields only.
+/// - If none of them are used, then the output is a table with the default columns ([`DEFAULT_FIELDS`]).
+/// - If `min_strength` is provided, then the list is filtered by the given signal strength.
+/// - If `re_scan` is set, then the list is refreshed by the [`Wl`] implementation.
 ///
-/// - nmcli: [`nmcli::scan`]
+/// The options above are used in [`OutputFormat::Text`].
+/// In [`OutputFormat::Json`], the list is written as a JSON array of objects, and in [`OutputFormat::Ndjson`] each object is written on its own line.
+/// The objects contain all fields regardless of `columns` and `get_values`:
+///
+/// ```json
+/// {"in_use":true,"bssid":"AA:BB:CC:DD:EE:FF","ssid":"SSID1","mode":"Infra","channel":6,"frequency":2437,"rate":130,"signal":70,"bars":"▂▄▆_","security":"WPA2"}
+/// ```
 ///
 /// # Panics
 ///
@@ -45,23 +56,52 @@ impl error::Error for Error {}
 ///
 /// # Errors
 ///
-/// This function returns [`Error::InvalidSignalStrength`] if the provided signal strength is above 100.
+/// This function returns [`Error::Scan`] with [`ScanError::InvalidSignalStrength`] if the provided signal strength is above 100, and [`Error::Format`] with [`FormatError::InvalidField`] if one of the provided columns or fields does not exist.
 ///
-/// This function can also return an [`NetworkAdapterError`] when the underlying [`Wl`] implementation fails or [`io::Error`] when the information cannot be written on the given [`io::Write`].
+/// This function can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the information cannot be written on the given [`io::Write`].
 ///
-/// [`Error::InvalidSignalStrength`]: crate::scan::Error::InvalidSignalStrength
-/// [`nmcli::scan`]: crate::Nmcli::scan
-/// [`NetworkAdapterError`]: crate::adapter::Error
-/// [`io::Error`]: std::io::Error
+/// [`Error::Scan`]: crate::Error::Scan
+/// [`ScanError::InvalidSignalStrength`]: crate::ScanError::InvalidSignalStrength
+/// [`Error::Format`]: crate::Error::Format
+/// [`FormatError::InvalidField`]: crate::format::Error::InvalidField
+/// [`AccessPoint`]: crate::AccessPoint
+/// [`format`]: crate::format
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`DEFAULT_FIELDS`]: crate::format::DEFAULT_FIELDS
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
 /// [`list_networks`]: crate::list_networks
-pub fn scan(f: &mut impl io::Write, args: ScanArgs) -> Result<(), Box<dyn error::Error>> {
+pub fn scan(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    args: ScanArgs,
+    output_format: OutputFormat,
+) -> Result<(), crate::Error> {
     const MAX_SIGNAL_STRENGTH: u8 = 100u8;
 
     let 0u8..=MAX_SIGNAL_STRENGTH = &args.min_strength else {
         return Err(Error::InvalidSignalStrength(args.min_strength))?;
     };
 
-    let process = adapter::new();
-    let result = process.scan(&args)?;
-    Ok(write_bytes(f, &result[..])?)
+    let (fields, terse) = match (&args.columns, &args.get_values) {
+        (Some(columns), _) => (Field::parse_list(columns)?, false),
+        (None, Some(values)) => (Field::parse_list(values)?, true),
+        (None, None) => (format::DEFAULT_FIELDS.to_vec(), false),
+    };
+
+    let aps = process
+        .get_access_points(args.re_scan)?
+        .into_iter()
+        .filter(|ap| ap.signal >= args.min_strength)
+        .collect::<Vec<AccessPoint>>();
+
+    match output_format {
+        OutputFormat::Text if terse => format::write_terse(f, &aps, &fields)?,
+        OutputFormat::Text => format::write_table(f, &aps, &fields)?,
+        _ => output::write_list(f, output_format, &aps)?,
+    }
+
+    Ok(())
 }
@@ -0,0 +1,271 @@
+use std::{error, fmt, io};
+
+use qrcode::{Color, QrCode, types::QrError};
+use serde::Serialize;
+use termion::color;
+
+use crate::{
+    Profile,
+    adapter::{Error as NetworkAdapterError, Wl},
+    api::OutputFormat,
+    output, write_bytes,
+};
+
+/// The number of light modules around a QR code, as required by the QR code specification.
+const QUIET_ZONE: usize = 4;
+
+/// The bytes that should be escaped in the fields of a WiFi QR code payload.
+const SPECIAL_BYTES: [u8; 5] = [b'\\', b';', b',', b':', b'"'];
+
+#[derive(Serialize)]
+struct SharedNetwork<'a> {
+    #[serde(serialize_with = "output::serialize_ssid")]
+    ssid: &'a [u8],
+    security: &'a str,
+    #[serde(serialize_with = "output::serialize_ssid")]
+    payload: &'a [u8],
+}
+
+/// Defines [`Error`] variants that may return whilst sharing a network.
+///
+/// [`Error`]: std::error::Error
+#[derive(Debug)]
+pub enum Error {
+    /// Represents a payload that cannot be encoded as a QR code (e.g. it is too long).
+    CannotEncodeQr(QrError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::CannotEncodeQr(err) => {
+                write!(f, "unable to encode the network as a QR code: {}", err)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::CannotEncodeQr(err) => Some(err),
+        }
+    }
+}
+
+/// Builds the WiFi QR code payload of the given known network and its password.
+///
+/// The payload follows the `WIFI:` URI format that is recognized by the camera apps of the phones:
+///
+/// ```text
+/// WIFI:T:WPA;S:<ssid>;P:<password>;H:true;;
+/// ```
+///
+/// The `T:` field is derived from the security type of the profile: `WEP` for `wep`, `nopass` for the networks without a password (e.g. `open`, `owe`), and `WPA` for the others.
+/// The `P:` field is only written if `passwd` is provided for a secured network, and the `H:true` field is only written if the profile is hidden.
+/// The `\`, `;`, `,`, `:` and `"` bytes of the SSID and the password are escaped with a backslash.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Examples
+///
+/// ```
+/// use wl::Profile;
+///
+/// let profile = Profile {
+///     ssid: b"Cafe;Bar".to_vec(),
+///     security: String::from("wpa-psk"),
+///     ..Default::default()
+/// };
+/// let payload = wl::share_payload(&profile, Some(b"p:ss\\word"));
+/// assert_eq!(b"WIFI:T:WPA;S:Cafe\\;Bar;P:p\\:ss\\\\word;;".as_slice(), payload);
+///
+/// let profile = Profile {
+///     ssid: b"Cafe".to_vec(),
+///     security: String::from("open"),
+///     hidden: true,
+///     ..Default::default()
+/// };
+/// let payload = wl::share_payload(&profile, None);
+/// assert_eq!(b"WIFI:T:nopass;S:Cafe;H:true;;".as_slice(), payload);
+/// ```
+pub fn share_payload(profile: &Profile, passwd: Option<&[u8]>) -> Vec<u8> {
+    let security = security(&profile.security, passwd);
+
+    let mut payload = Vec::from(b"WIFI:T:");
+    payload.extend_from_slice(security.as_bytes());
+    payload.extend_from_slice(b";S:");
+    payload.extend(escape(&profile.ssid));
+    payload.push(b';');
+    if let Some(passwd) = passwd.filter(|_| security != "nopass") {
+        payload.extend_from_slice(b"P:");
+        payload.extend(escape(passwd));
+        payload.push(b';');
+    }
+    if profile.hidden {
+        payload.extend_from_slice(b"H:true;");
+    }
+    payload.push(b';');
+
+    payload
+}
+
+/// Shares the given known SSID as a WiFi QR code by using the given [`Wl`] implementation, and writes it on the given [`io::Write`].
+///
+/// The password of the SSID is read from the stored connection profile of the network backend, and encoded into the payload of [`share_payload`]
+/// along with the security type and the hidden flag of the profile.
+/// If the network backend does not expose the settings of its known networks, then the network is shared as a visible WPA network, or as an open one if it does not have a password.
+///
+/// In [`OutputFormat::Text`], the QR code is drawn with the block characters and the colors of the terminal, and it is followed by the payload itself:
+///
+/// ```text
+/// <QR code>
+/// WIFI:T:WPA;S:Home;P:secret;;
+/// ```
+///
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], the QR code is not drawn, and the output is a single JSON object:
+///
+/// ```json
+/// {"ssid":"Home","security":"WPA","payload":"WIFI:T:WPA;S:Home;P:secret;;"}
+/// ```
+///
+/// The payload is also returned.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails (e.g. the SSID is not a known one, or the network backend does not expose its stored passwords).
+///
+/// It can also return [`Error::Share`] when the payload cannot be encoded as a QR code, and [`Error::Io`] when the output cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new().with_known_network(b"Home", Some(b"secret"));
+///
+/// let mut out = Vec::new();
+/// let payload = wl::share(&fake, &mut out, b"Home".to_vec(), OutputFormat::Json).unwrap();
+///
+/// assert_eq!(b"WIFI:T:WPA;S:Home;P:secret;;".as_slice(), payload);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`share_payload`]: crate::share_payload
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Share`]: crate::Error::Share
+/// [`Error::Io`]: crate::Error::Io
+pub fn share(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    ssid: Vec<u8>,
+    output_format: OutputFormat,
+) -> Result<Vec<u8>, crate::Error> {
+    let passwd = process.get_passwd(&ssid)?;
+    let profile = match process.get_profile(&ssid) {
+        Ok(profile) => profile,
+        Err(NetworkAdapterError::CannotGetProfile((err, _)))
+            if err.kind() == io::ErrorKind::Unsupported =>
+        {
+            let security = match passwd {
+                Some(_) => "wpa-psk",
+                None => "open",
+            };
+            Profile {
+                name: ssid.clone(),
+                ssid: ssid.clone(),
+                security: security.to_string(),
+                ..Default::default()
+            }
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let payload = share_payload(&profile, passwd.as_deref());
+
+    match output_format {
+        OutputFormat::Text => {
+            let qr = QrCode::new(&payload).map_err(Error::CannotEncodeQr)?;
+            let out_buf = [draw_qr(&qr).as_bytes(), &payload, b"\n"].concat();
+            write_bytes(f, &out_buf)?;
+        }
+        _ => output::write_value(
+            f,
+            &SharedNetwork {
+                ssid: &profile.ssid,
+                security: security(&profile.security, passwd.as_deref()),
+                payload: &payload,
+            },
+        )?,
+    }
+
+    Ok(payload)
+}
+
+/// Provides the `T:` field of a WiFi QR code payload for the given security type of a profile.
+fn security(security: &str, passwd: Option<&[u8]>) -> &'static str {
+    match (security, passwd) {
+        (_, None) | ("open" | "owe", _) => "nopass",
+        ("wep", _) => "WEP",
+        _ => "WPA",
+    }
+}
+
+fn escape(field: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(field.len());
+    for b in field {
+        if SPECIAL_BYTES.contains(b) {
+            escaped.push(b'\\');
+        }
+        escaped.push(*b);
+    }
+
+    escaped
+}
+
+/// Draws the given QR code with the upper half block character.
+///
+/// Each character represents two rows of modules: the foreground color is the upper module, and the background color is the lower one.
+/// The colors are set explicitly, so the QR code can be scanned regardless of the color scheme of the terminal.
+fn draw_qr(qr: &QrCode) -> String {
+    let width = qr.width();
+    let size = width + QUIET_ZONE * 2;
+    let is_dark = |x: usize, y: usize| {
+        let (Some(x), Some(y)) = (x.checked_sub(QUIET_ZONE), y.checked_sub(QUIET_ZONE)) else {
+            return false;
+        };
+        x < width && y < width && qr[(x, y)] == Color::Dark
+    };
+
+    let mut out = String::new();
+    for y in (0..size).step_by(2) {
+        for x in 0..size {
+            let upper = module_color(is_dark(x, y));
+            let lower = module_color(is_dark(x, y + 1));
+            out.push_str(&format!("{}{}▀", color::Fg(upper), color::Bg(lower)));
+        }
+        out.push_str(&format!(
+            "{}{}\n",
+            color::Fg(color::Reset),
+            color::Bg(color::Reset)
+        ));
+    }
+
+    out
+}
+
+fn module_color(is_dark: bool) -> color::AnsiValue {
+    match is_dark {
+        true => color::AnsiValue::grayscale(0),
+        false => color::AnsiValue::grayscale(23),
+    }
+}
@@ -1,19 +1,50 @@
-use std::{error, io};
+use std::io;
 
-use crate::{
-    adapter::{self, CARRIAGE_RETURN, LINE_FEED},
-    write_bytes,
-};
+use serde::Serialize;
 
-/// Provides the WiFi status and connected SSID-Device pairs by using a [`Wl`] implementation.
+use crate::{Error, WifiStatus, adapter::Wl, api::OutputFormat, output, write_bytes};
+
+/// Represents the result of [`status_with`].
+///
+/// [`status_with`]: crate::status_with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusReport {
+    /// The WiFi status of the host.
+    pub wifi: WifiStatus,
+
+    /// The active connections of the host.
+    pub connections: Vec<ActiveConnection>,
+}
+
+/// Represents an active connection of [`StatusReport`].
+///
+/// [`StatusReport`]: crate::StatusReport
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActiveConnection {
+    /// The SSID (name) of the connection.
+    #[serde(serialize_with = "output::serialize_ssid")]
+    pub ssid: Vec<u8>,
+
+    /// The device that the connection is active on.
+    pub device: String,
+}
+
+/// Provides the WiFi status and connected SSID-Device pairs by using the given [`Wl`] implementation.
 ///
 /// The WiFi status and SSID-Device pairs are written to the stdout stream.
 ///
-/// The format of the WiFi status depends on the [`Wl`] implementation.
+/// In [`OutputFormat::Text`], the output is like below:
+///
+/// ```text
+/// wifi: enabled
+/// connected networks: SSID1/Dev1, SSID2/Dev2, ..., SSIDN/DevN
+/// ```
 ///
-/// The format of the SSID-Device pairs is like below:
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], the output is a single JSON object:
 ///
-/// `connected networks: SSID1/Dev1, SSID2/Dev2, ..., SSIDN/DevN`
+/// ```json
+/// {"wifi":"enabled","connections":[{"ssid":"SSID1","device":"Dev1"}]}
+/// ```
 ///
 /// # Panics
 ///
@@ -21,58 +52,95 @@ use crate::{
 ///
 /// # Errors
 ///
-/// This function can return an [`adapter::Error`] when the underlying [`Wl`] implementation fails or [`io::Error`] when the information cannot be written on the stdout stream.
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the information cannot be written on the stdout stream.
 ///
 /// [`Wl`]: crate::Wl
-/// [`adapter::Error`]: crate::adapter::Error
-/// [`io::Error`]: std::io::Error
-pub fn status() -> Result<(), Box<dyn error::Error>> {
-    let mut stdout = io::stdout();
-    let process = adapter::new();
-
-    write_wifi_status(&mut stdout, &process)?;
-    write_active_ssid_dev_pairs(&mut stdout, &process)?;
-    Ok(())
-}
-
-fn write_wifi_status(
-    f: &mut impl io::Write,
-    process: &impl adapter::Wl,
-) -> Result<(), Box<dyn error::Error>> {
-    let wifi_status = process.get_wifi_status()?;
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn status(process: &impl Wl, output_format: OutputFormat) -> Result<(), Error> {
+    status_with(process, &mut io::stdout(), output_format)?;
 
-    let wifi_status = [b"wifi: ", &wifi_status[..], b" \n"].concat();
-    write_bytes(f, &wifi_status)?;
     Ok(())
 }
 
-fn write_active_ssid_dev_pairs(
+/// Provides the WiFi status and connected SSID-Device pairs by using the given [`Wl`] implementation, and writes them on the given [`io::Write`].
+///
+/// The output is the same as [`status`], and the information is also returned as a [`StatusReport`].
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the information cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, WifiStatus, api::OutputFormat};
+///
+/// let fake = FakeWl::new().with_active_network(b"SSID1");
+///
+/// let mut out = Vec::new();
+/// let report = wl::status_with(&fake, &mut out, OutputFormat::Text).unwrap();
+///
+/// assert_eq!(WifiStatus::Enabled, report.wifi);
+/// assert_eq!(b"wifi: enabled \nconnected networks: SSID1/wlan0\n".as_slice(), out);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`status`]: crate::status
+/// [`StatusReport`]: crate::StatusReport
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn status_with(
+    process: &impl Wl,
     f: &mut impl io::Write,
-    process: &impl adapter::Wl,
-) -> Result<(), Box<dyn error::Error>> {
-    let pairs = process.get_active_ssid_dev_pairs()?;
-    let field_separator = process.get_field_separator();
+    output_format: OutputFormat,
+) -> Result<StatusReport, Error> {
+    let status = StatusReport {
+        wifi: process.get_wifi_status()?,
+        connections: process
+            .get_networks(true)?
+            .into_iter()
+            .filter_map(|n| {
+                Some(ActiveConnection {
+                    device: n.device?,
+                    ssid: n.ssid,
+                })
+            })
+            .collect(),
+    };
 
-    let pair_iter = pairs.split(|b| b == &LINE_FEED).filter_map(|s| {
-        let line = s.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(s);
+    match output_format {
+        OutputFormat::Text => write_status(f, &status)?,
+        _ => output::write_value(f, &status)?,
+    };
 
-        if line.is_empty() {
-            None
-        } else {
-            let pair = line
-                .split(|b| b == &field_separator)
-                .collect::<Vec<&[u8]>>();
+    Ok(status)
+}
 
-            Some((pair[0].to_vec(), pair[1].to_vec()))
-        }
-    });
+fn write_status(f: &mut impl io::Write, status: &StatusReport) -> Result<(), io::Error> {
+    let pairs = status
+        .connections
+        .iter()
+        .map(|c| [&c.ssid[..], b"/", c.device.as_bytes()].concat())
+        .collect::<Vec<Vec<u8>>>()
+        .join(&b", "[..]);
 
-    let mut active_ssid_dev_pairs = ["connected networks: ".as_bytes()].concat();
-    for (ssid, dev) in pair_iter {
-        let mut pair = [&ssid[..], b"/", &dev[..], b", "].concat();
-        active_ssid_dev_pairs.append(&mut pair);
-    }
-    write_bytes(f, active_ssid_dev_pairs.strip_suffix(b", ").unwrap())?;
+    let out_buf = [
+        b"wifi: ",
+        status.wifi.to_string().as_bytes(),
+        b" \nconnected networks: ",
+        &pairs[..],
+        b"\n",
+    ]
+    .concat();
 
-    Ok(())
+    write_bytes(f, &out_buf)
 }
@@ -1,15 +1,24 @@
-use std::{error, io};
+use std::io;
 
-use crate::{
-    adapter::{self, Wl},
-    write_bytes,
-};
+use serde::Serialize;
 
-/// Toggles the WiFi status by using a [`Wl`] implementation.
+use crate::{Error, WifiStatus, adapter::Wl, api::OutputFormat, output, write_bytes};
+
+#[derive(Serialize)]
+struct Toggle {
+    wifi: WifiStatus,
+}
+
+/// Toggles the WiFi status by using the given [`Wl`] implementation.
 ///
 /// The latest WiFi status is written to the stdout stream.
 ///
-/// The format of the WiFi status depends on the [`Wl`] implementation.
+/// In [`OutputFormat::Text`], the output is `wifi: enabled|disabled`.
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], the output is a single JSON object:
+///
+/// ```json
+/// {"wifi":"disabled"}
+/// ```
 ///
 /// # Panics
 ///
@@ -17,16 +26,69 @@ use crate::{
 ///
 /// # Errors
 ///
-/// This function can return an [`adapter::Error`] when the underlying [`Wl`] implementation fails or [`io::Error`] when the information cannot be written on the stdout stream.
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the information cannot be written on the stdout stream.
+///
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn toggle(process: &impl Wl, output_format: OutputFormat) -> Result<(), Error> {
+    toggle_with(process, &mut io::stdout(), output_format)?;
+
+    Ok(())
+}
+
+/// Toggles the WiFi status by using the given [`Wl`] implementation, and writes the latest WiFi status on the given [`io::Write`].
+///
+/// The output is the same as [`toggle`], and the latest WiFi status is also returned.
+///
+/// # Panics
 ///
-/// [`adapter::Error`]: crate::adapter::Error
-/// [`io::Error`]: std::io::Error
-pub fn toggle() -> Result<(), Box<dyn error::Error>> {
-    let process = adapter::new();
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails, and [`Error::Io`] when the information cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{FakeWl, WifiStatus, api::OutputFormat};
+///
+/// let fake = FakeWl::new();
+///
+/// let mut out = Vec::new();
+/// let wifi = wl::toggle_with(&fake, &mut out, OutputFormat::Json).unwrap();
+///
+/// assert_eq!(WifiStatus::Disabled, wifi);
+/// assert_eq!(b"{\"wifi\":\"disabled\"}\n".as_slice(), out);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`toggle`]: crate::toggle
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn toggle_with(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    output_format: OutputFormat,
+) -> Result<WifiStatus, Error> {
     let toggled_status = process.toggle_wifi()?;
 
-    let out_buf = [b"wifi: ", &toggled_status[..], b" \n"].concat();
-    write_bytes(&mut io::stdout(), &out_buf)?;
+    match output_format {
+        OutputFormat::Text => {
+            let out_buf = [b"wifi: ", toggled_status.to_string().as_bytes(), b" \n"].concat();
+            write_bytes(f, &out_buf)?;
+        }
+        _ => output::write_value(
+            f,
+            &Toggle {
+                wifi: toggled_status,
+            },
+        )?,
+    }
 
-    Ok(())
+    Ok(toggled_status)
 }
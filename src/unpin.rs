@@ -0,0 +1,87 @@
+use std::io;
+
+use serde::Serialize;
+
+use crate::{adapter::Wl, api::OutputFormat, output, write_bytes};
+
+#[derive(Serialize)]
+struct UnpinnedNetwork<'a> {
+    #[serde(serialize_with = "output::serialize_ssid")]
+    ssid: &'a [u8],
+    pinned: bool,
+}
+
+/// Removes the BSSID that is pinned to the given known SSID by using the given [`Wl`] implementation, and writes the result on the given [`io::Write`].
+///
+/// A known network is pinned to an access point by [`connect`] with the BSSID of [`Auth::Psk`]. Once the pin is removed, the known network can connect to any access point of the SSID again.
+///
+/// In [`OutputFormat::Text`], the result is the one that is provided by the [`Wl`] implementation.
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], the result is a single JSON object:
+///
+/// ```json
+/// {"ssid":"SSID1","pinned":false}
+/// ```
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Backend`] when the underlying [`Wl`] implementation fails (e.g. the SSID is not a known one, or the network backend cannot pin the known networks),
+/// and [`Error::Io`] when the result cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use wl::{AccessPoint, Auth, FakeWl, api::OutputFormat};
+///
+/// let fake = FakeWl::new()
+///     .with_access_point(AccessPoint {
+///         ssid: b"Office".to_vec(),
+///         bssid: String::from("AA:BB:CC:DD:EE:01"),
+///         ..Default::default()
+///     })
+///     .with_known_network(b"Office", None);
+///
+/// let auth = Auth::Psk { force_passwd: false, bssid: Some(String::from("AA:BB:CC:DD:EE:01")) };
+/// wl::connect_with(&fake, &mut Vec::new(), &mut Vec::new(), &mut &b""[..], None, auth, OutputFormat::Json).unwrap();
+/// assert!(fake.pinned_bssid(b"Office").is_some());
+///
+/// let mut out = Vec::new();
+/// wl::unpin(&fake, &mut out, b"Office".to_vec(), OutputFormat::Json).unwrap();
+///
+/// assert!(fake.pinned_bssid(b"Office").is_none());
+/// assert_eq!(b"{\"ssid\":\"Office\",\"pinned\":false}\n".as_slice(), out);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`connect`]: crate::connect
+/// [`Auth::Psk`]: crate::Auth::Psk
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn unpin(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    ssid: Vec<u8>,
+    output_format: OutputFormat,
+) -> Result<(), crate::Error> {
+    let message = process.clear_bssid(&ssid)?;
+
+    match output_format {
+        OutputFormat::Text => write_bytes(f, &message)?,
+        _ => output::write_value(
+            f,
+            &UnpinnedNetwork {
+                ssid: &ssid,
+                pinned: false,
+            },
+        )?,
+    }
+
+    Ok(())
+}
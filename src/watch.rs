@@ -0,0 +1,424 @@
+use std::{
+    error, fmt, io,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use serde::Serialize;
+
+use crate::{
+    NetworkAdapterError, WifiStatus,
+    adapter::Wl,
+    api::{OutputFormat, WatchArgs},
+    output, write_bytes,
+};
+
+/// Represents a change that is reported by the network backend through [`Wl::subscribe`].
+///
+/// [`Wl::subscribe`]: crate::Wl::subscribe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notification {
+    /// Represents a change that is not described by the network backend.
+    ///
+    /// The state of the host should be read again to find out what is changed.
+    Changed,
+
+    /// Represents a device that changed its state (e.g. `disconnected`, `connecting (prepare)`, `connected`).
+    DeviceState {
+        /// The name of the device.
+        device: String,
+
+        /// The new state of the device, as it is reported by the network backend.
+        state: String,
+    },
+}
+
+/// Represents the direction of a signal strength that crossed a threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// The signal strength is risen above the threshold.
+    Up,
+
+    /// The signal strength is fallen below the threshold.
+    Down,
+}
+
+/// Represents an event that is written by [`watch`].
+///
+/// [`watch`]: crate::watch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Event {
+    /// Represents the WiFi radio that is toggled.
+    Wifi {
+        /// The new WiFi status.
+        status: WifiStatus,
+    },
+
+    /// Represents a connection that came up.
+    Connected {
+        /// The SSID (name) of the connection.
+        #[serde(serialize_with = "output::serialize_ssid")]
+        ssid: Vec<u8>,
+
+        /// The device that the connection is active on.
+        device: String,
+    },
+
+    /// Represents a connection that went down.
+    Disconnected {
+        /// The SSID (name) of the connection.
+        #[serde(serialize_with = "output::serialize_ssid")]
+        ssid: Vec<u8>,
+
+        /// The device that the connection was active on.
+        device: String,
+    },
+
+    /// Represents a device that changed its state.
+    Device {
+        /// The name of the device.
+        device: String,
+
+        /// The new state of the device.
+        state: String,
+    },
+
+    /// Represents the signal strength of the connected access point that crossed a threshold.
+    Signal {
+        /// The SSID of the connected access point.
+        #[serde(serialize_with = "output::serialize_ssid")]
+        ssid: Vec<u8>,
+
+        /// The new signal strength.
+        signal: u8,
+
+        /// The threshold that is crossed.
+        threshold: u8,
+
+        /// The direction of the crossing.
+        direction: Direction,
+    },
+}
+
+/// Defines [`Error`] variants that may return whilst watching the host.
+///
+/// [`Error`]: std::error::Error
+#[derive(Debug)]
+pub enum Error {
+    /// Represents an interval that is not a positive number of seconds, or that is too large to represent as a [`Duration`].
+    ///
+    /// [`Duration`]: std::time::Duration
+    InvalidInterval(f64),
+
+    /// Represents a signal threshold that is not in limits (1..100).
+    InvalidThreshold(u8),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidInterval(i) => write!(
+                f,
+                "the given interval {} is not a positive number of seconds",
+                i
+            ),
+            Error::InvalidThreshold(t) => write!(
+                f,
+                "the given signal threshold {} is not in limits (1..100)",
+                t
+            ),
+        }
+    }
+}
+impl error::Error for Error {}
+
+/// The state of the host that is compared between two reads.
+#[derive(Debug)]
+struct Snapshot {
+    wifi: WifiStatus,
+    connections: Vec<(Vec<u8>, String)>,
+    signal: Option<(Vec<u8>, u8)>,
+}
+
+/// Watches the connectivity changes of the host by using the given [`Wl`] implementation, and writes an event for each of them on the given [`io::Write`].
+///
+/// The state of the host is read again whenever the network backend reports a change through [`Wl::subscribe`], or every `interval` seconds otherwise.
+/// The events are the differences between two reads, in the order below:
+///
+/// - The WiFi radio is toggled.
+/// - A connection went down.
+/// - A connection came up.
+/// - A device changed its state. This is only reported by the network backends that support [`Wl::subscribe`].
+/// - The signal strength of the connected access point crossed one of the `thresholds`.
+///
+/// The initial state of the host is not written, use [`status`] for it instead.
+///
+/// In [`OutputFormat::Text`], each event is written on its own line:
+///
+/// ```text
+/// wifi: disabled
+/// disconnected: SSID1/wlan0
+/// connected: SSID1/wlan0
+/// device: wlan0 connecting (prepare)
+/// signal: SSID1 48 (below 50)
+/// ```
+///
+/// In [`OutputFormat::Json`] and [`OutputFormat::Ndjson`], each event is written as a JSON object on its own line:
+///
+/// ```json
+/// {"event":"signal","ssid":"SSID1","signal":48,"threshold":50,"direction":"down"}
+/// ```
+///
+/// `watch` runs until it is interrupted. If `count` is provided, then it returns after writing the given number of events.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function returns [`Error::Watch`] when the given `interval` or `thresholds` are invalid.
+///
+/// It can also return [`Error::Backend`] when the underlying [`Wl`] implementation fails or stops reporting its changes, and [`Error::Io`] when an event cannot be written on the given [`io::Write`].
+///
+/// # Examples
+///
+/// ```
+/// use std::{thread, time::Duration};
+/// use wl::{FakeWl, Wl, api::{OutputFormat, WatchArgs}, fake::Operation};
+///
+/// let fake = FakeWl::new();
+/// let args = WatchArgs {
+///     count: Some(1),
+///     interval: 0.01,
+///     thresholds: vec![50],
+/// };
+///
+/// let mut out = Vec::new();
+/// thread::scope(|s| {
+///     s.spawn(|| {
+///         // Toggle the WiFi once `watch` reads the initial state of the host, which ends with the access points.
+///         while fake.call_count(Operation::GetAccessPoints) == 0 {
+///             thread::sleep(Duration::from_millis(1));
+///         }
+///         fake.toggle_wifi().unwrap();
+///     });
+///     wl::watch(&fake, &mut out, args, OutputFormat::Text).unwrap();
+/// });
+///
+/// assert_eq!(b"wifi: disabled\n".as_slice(), out);
+/// ```
+///
+/// [`Wl`]: crate::Wl
+/// [`io::Write`]: std::io::Write
+/// [`Wl::subscribe`]: crate::Wl::subscribe
+/// [`status`]: crate::status
+/// [`OutputFormat::Text`]: crate::api::OutputFormat::Text
+/// [`OutputFormat::Json`]: crate::api::OutputFormat::Json
+/// [`OutputFormat::Ndjson`]: crate::api::OutputFormat::Ndjson
+/// [`Error::Watch`]: crate::Error::Watch
+/// [`Error::Backend`]: crate::Error::Backend
+/// [`Error::Io`]: crate::Error::Io
+pub fn watch(
+    process: &impl Wl,
+    f: &mut impl io::Write,
+    args: WatchArgs,
+    output_format: OutputFormat,
+) -> Result<(), crate::Error> {
+    // The interval is not finite, not positive or too large for a `Duration`.
+    let interval = match Duration::try_from_secs_f64(args.interval) {
+        Ok(interval) if !interval.is_zero() => interval,
+        _ => Err(Error::InvalidInterval(args.interval))?,
+    };
+    if let Some(t) = args.thresholds.iter().find(|t| !(1..=100).contains(*t)) {
+        Err(Error::InvalidThreshold(*t))?;
+    }
+
+    let mut thresholds = args.thresholds;
+    thresholds.sort_unstable();
+    thresholds.dedup();
+
+    let notifications = process.subscribe()?;
+    let mut prev = read_snapshot(process)?;
+    let mut written = 0;
+
+    loop {
+        let device_events = match &notifications {
+            Some(rx) => wait_for_notifications(rx, interval)?,
+            None => {
+                thread::sleep(interval);
+                Vec::new()
+            }
+        };
+
+        let next = read_snapshot(process)?;
+        let mut events = diff_connections(&prev, &next);
+        events.extend(device_events);
+        events.extend(diff_signal(&prev, &next, &thresholds));
+        prev = next;
+
+        for event in events {
+            write_event(f, &event, output_format)?;
+
+            written += 1;
+            if args.count.is_some_and(|count| written >= count) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Waits for the next notification, and drains the ones that are sent right after it.
+///
+/// The notifications come in bursts, so the state of the host is read once per burst.
+fn wait_for_notifications(
+    rx: &mpsc::Receiver<Notification>,
+    interval: Duration,
+) -> Result<Vec<Event>, NetworkAdapterError> {
+    let first = match rx.recv_timeout(interval) {
+        Ok(notification) => notification,
+        Err(RecvTimeoutError::Timeout) => return Ok(Vec::new()),
+        Err(RecvTimeoutError::Disconnected) => {
+            let err = io::Error::other("the network backend stopped reporting its changes");
+            return Err(NetworkAdapterError::CannotWatch((err, 1)));
+        }
+    };
+
+    let events = std::iter::once(first)
+        .chain(rx.try_iter())
+        .filter_map(|notification| match notification {
+            Notification::Changed => None,
+            Notification::DeviceState { device, state } => Some(Event::Device { device, state }),
+        })
+        .collect();
+
+    Ok(events)
+}
+
+fn read_snapshot(process: &impl Wl) -> Result<Snapshot, NetworkAdapterError> {
+    let wifi = process.get_wifi_status()?;
+
+    let connections = process
+        .get_networks(true)?
+        .into_iter()
+        .filter_map(|n| Some((n.ssid, n.device?)))
+        .collect();
+
+    let signal = match wifi {
+        WifiStatus::Enabled => process
+            .get_access_points(false)?
+            .into_iter()
+            .find(|ap| ap.in_use)
+            .map(|ap| (ap.ssid, ap.signal)),
+        WifiStatus::Disabled => None,
+    };
+
+    Ok(Snapshot {
+        wifi,
+        connections,
+        signal,
+    })
+}
+
+fn diff_connections(prev: &Snapshot, next: &Snapshot) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    if prev.wifi != next.wifi {
+        events.push(Event::Wifi { status: next.wifi });
+    }
+
+    for (ssid, device) in &prev.connections {
+        if !next.connections.contains(&(ssid.clone(), device.clone())) {
+            events.push(Event::Disconnected {
+                ssid: ssid.clone(),
+                device: device.clone(),
+            });
+        }
+    }
+
+    for (ssid, device) in &next.connections {
+        if !prev.connections.contains(&(ssid.clone(), device.clone())) {
+            events.push(Event::Connected {
+                ssid: ssid.clone(),
+                device: device.clone(),
+            });
+        }
+    }
+
+    events
+}
+
+/// Provides the signal event between two reads, if the signal strength crossed one of the given thresholds.
+///
+/// The signal is only compared for the same access point, a new connection starts a new baseline.
+fn diff_signal(prev: &Snapshot, next: &Snapshot, thresholds: &[u8]) -> Option<Event> {
+    let ((prev_ssid, prev_signal), (ssid, signal)) = (prev.signal.as_ref()?, next.signal.as_ref()?);
+    if prev_ssid != ssid {
+        return None;
+    }
+
+    let (threshold, direction) = crossed_threshold(*prev_signal, *signal, thresholds)?;
+    Some(Event::Signal {
+        ssid: ssid.clone(),
+        signal: *signal,
+        threshold,
+        direction,
+    })
+}
+
+/// Provides the threshold that is crossed between the given signal strengths, if any.
+///
+/// If more than one threshold is crossed, then the closest one to the new signal strength is provided.
+fn crossed_threshold(prev: u8, next: u8, thresholds: &[u8]) -> Option<(u8, Direction)> {
+    if next > prev {
+        thresholds
+            .iter()
+            .rev()
+            .find(|t| prev < **t && **t <= next)
+            .map(|t| (*t, Direction::Up))
+    } else {
+        thresholds
+            .iter()
+            .find(|t| next < **t && **t <= prev)
+            .map(|t| (*t, Direction::Down))
+    }
+}
+
+fn write_event(
+    f: &mut impl io::Write,
+    event: &Event,
+    output_format: OutputFormat,
+) -> Result<(), io::Error> {
+    if output_format != OutputFormat::Text {
+        return output::write_value(f, event);
+    }
+
+    let out_buf = match event {
+        Event::Wifi { status } => format!("wifi: {}\n", status).into_bytes(),
+        Event::Connected { ssid, device } => {
+            [b"connected: ", &ssid[..], b"/", device.as_bytes(), b"\n"].concat()
+        }
+        Event::Disconnected { ssid, device } => {
+            [b"disconnected: ", &ssid[..], b"/", device.as_bytes(), b"\n"].concat()
+        }
+        Event::Device { device, state } => format!("device: {} {}\n", device, state).into_bytes(),
+        Event::Signal {
+            ssid,
+            signal,
+            threshold,
+            direction,
+        } => {
+            let side = match direction {
+                Direction::Up => "above",
+                Direction::Down => "below",
+            };
+            let detail = format!(" {} ({} {})\n", signal, side, threshold);
+            [b"signal: ", &ssid[..], detail.as_bytes()].concat()
+        }
+    };
+
+    write_bytes(f, &out_buf)
+}
@@ -0,0 +1,1086 @@
+use std::{
+    fs, io,
+    os::unix::net::UnixDatagram,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    AccessPoint, ExportedNetwork, Network, NetworkPriority, Security, WifiStatus,
+    access_point::{channel_from_frequency, signal_from_dbm},
+    adapter::{Error, UNKNOWN_SSID_EXIT_CODE, Wl},
+};
+
+/// The directories that are searched for the control sockets, in order.
+const CTRL_DIRS: [&str; 2] = ["/var/run/wpa_supplicant", "/run/wpa_supplicant"];
+
+/// The maximum size of a control interface reply.
+const MAX_REPLY_SIZE: usize = 64 * 1024;
+
+/// The time to wait for a reply to a command.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The time to wait for a scan to complete.
+const SCAN_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The time to wait for a connection to complete.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Used to create unique local socket paths within the same process.
+static LOCAL_SOCKET_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// The adapter struct that implements [`Wl`] by using the control interface of `wpa_supplicant`.
+///
+/// The control interface is a UNIX datagram socket, which is usually located under `/var/run/wpa_supplicant/IFACE`.
+/// By default, the first interface socket under the control directory is used.
+/// To use a specific socket, initialize the struct with [`WpaSupplicant::with_ctrl_path`].
+///
+/// `wpa_supplicant` does not control the radio of the device.
+/// Instead, the WiFi status represents whether the interface is allowed to connect or not (`DISCONNECT`/`RECONNECT`).
+///
+/// Since the struct can be changed in future versions, always
+/// prefer to initialize it by using [`WpaSupplicant::new`] or [`WpaSupplicant::with_ctrl_path`] instead.
+///
+/// [`Wl`]: crate::Wl
+/// [`WpaSupplicant::new`]: crate::WpaSupplicant::new
+/// [`WpaSupplicant::with_ctrl_path`]: crate::WpaSupplicant::with_ctrl_path
+#[derive(Clone, Default)]
+pub struct WpaSupplicant {
+    ctrl_path: Option<PathBuf>,
+}
+
+/// A single connection to the control interface.
+///
+/// The local socket is bound to a unique path, since `wpa_supplicant` replies to the address of the sender.
+/// The path is removed once the connection is dropped.
+struct Ctrl {
+    socket: UnixDatagram,
+    local_path: PathBuf,
+}
+
+impl Drop for Ctrl {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.local_path);
+    }
+}
+
+impl Ctrl {
+    fn open(ctrl_path: &Path) -> Result<Self, io::Error> {
+        let local_path = std::env::temp_dir().join(format!(
+            "wl_wpa_ctrl_{}-{}",
+            process::id(),
+            LOCAL_SOCKET_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_file(&local_path);
+
+        let socket = UnixDatagram::bind(&local_path)?;
+        let ctrl = Self { socket, local_path };
+
+        ctrl.socket.connect(ctrl_path)?;
+        ctrl.socket.set_read_timeout(Some(REPLY_TIMEOUT))?;
+
+        Ok(ctrl)
+    }
+
+    /// Sends the given command, and provides its reply.
+    ///
+    /// The unsolicited event messages (`<LEVEL>...`) that are received in the meantime are skipped.
+    fn request(&self, cmd: &[u8]) -> Result<Vec<u8>, io::Error> {
+        self.socket.send(cmd)?;
+
+        let mut buf = vec![0u8; MAX_REPLY_SIZE];
+        loop {
+            let len = self.socket.recv(&mut buf)?;
+            let reply = &buf[..len];
+
+            if !is_event(reply) {
+                return Ok(reply.to_vec());
+            }
+        }
+    }
+
+    /// Sends the given command, and fails if the reply is not `OK`.
+    fn request_ok(&self, cmd: &[u8]) -> Result<(), io::Error> {
+        let reply = self.request(cmd)?;
+
+        if reply.trim_ascii() == b"OK" {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "{} failed: {}",
+                String::from_utf8_lossy(cmd),
+                String::from_utf8_lossy(reply.trim_ascii())
+            )))
+        }
+    }
+
+    /// Waits for one of the given events, and provides the received event.
+    ///
+    /// The connection has to be attached (`ATTACH`) to receive the events.
+    fn wait_for_event(&self, events: &[&str], timeout: Duration) -> Result<String, io::Error> {
+        let deadline = Instant::now() + timeout;
+        let mut buf = vec![0u8; MAX_REPLY_SIZE];
+
+        while Instant::now() < deadline {
+            let len = match self.socket.recv(&mut buf) {
+                Ok(len) => len,
+                Err(err)
+                    if err.kind() == io::ErrorKind::WouldBlock
+                        || err.kind() == io::ErrorKind::TimedOut =>
+                {
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            let msg = String::from_utf8_lossy(&buf[..len]);
+            if let Some(event) = events.iter().find(|e| msg.contains(*e)) {
+                return Ok(event.to_string());
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("timed out waiting for {}", events.join(" or ")),
+        ))
+    }
+}
+
+/// Represents a row of the `LIST_NETWORKS` reply.
+struct ConfiguredNetwork {
+    id: String,
+    ssid: Vec<u8>,
+    is_current: bool,
+    is_disabled: bool,
+}
+
+impl WpaSupplicant {
+    /// Creates a new `WpaSupplicant` instance that uses the first interface socket under the control directory.
+    ///
+    /// The instance created by `new` can be reused multiple times
+    /// in a given context. It can also be cloned freely.
+    pub fn new() -> Self {
+        Self { ctrl_path: None }
+    }
+
+    /// Creates a new `WpaSupplicant` instance that uses the given control socket (e.g. `/var/run/wpa_supplicant/wlan0`).
+    pub fn with_ctrl_path(ctrl_path: impl Into<PathBuf>) -> Self {
+        Self {
+            ctrl_path: Some(ctrl_path.into()),
+        }
+    }
+
+    /// Provides whether a control socket is found for the instance or not.
+    pub(crate) fn is_available(&self) -> bool {
+        self.ctrl_path().is_ok_and(|path| path.exists())
+    }
+
+    fn ctrl_path(&self) -> Result<PathBuf, io::Error> {
+        if let Some(path) = &self.ctrl_path {
+            return Ok(path.clone());
+        }
+
+        CTRL_DIRS
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .find_map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.file_name()
+                            .is_some_and(|name| !name.to_string_lossy().starts_with("p2p-dev-"))
+                    })
+                    .min()
+            })
+            .ok_or(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no wpa_supplicant control socket is found",
+            ))
+    }
+
+    fn interface(&self) -> Result<String, io::Error> {
+        Ok(self
+            .ctrl_path()?
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default())
+    }
+
+    /// Selects the given SSID, and waits until it is connected.
+    ///
+    /// A known network is re-used as is, unless `passwd` or `hidden` is provided. In that case, the known network is replaced with a new one.
+    /// `hidden` holds the security type of a hidden network.
+    /// If `bssid` is provided, then the network is pinned to the access point before it is selected.
+    fn select_network(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        bssid: Option<&str>,
+        hidden: Option<Security>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let ctrl = self.open().map_err(Error::CannotConnect)?;
+        let map_err = |err| Error::CannotConnect((err, 1));
+
+        let known_network = if is_known_ssid {
+            Some(self.find_network(&ctrl, ssid).map_err(map_err)?)
+        } else {
+            None
+        };
+
+        let (id, is_new) = match (known_network, passwd, hidden) {
+            (Some(network), None, None) => (network.id, false),
+            (known_network, passwd, hidden) => {
+                if let Some(network) = known_network {
+                    ctrl.request_ok(format!("REMOVE_NETWORK {}", network.id).as_bytes())
+                        .map_err(map_err)?;
+                }
+
+                (
+                    add_network(&ctrl, ssid, passwd, hidden, hidden.is_some()).map_err(map_err)?,
+                    true,
+                )
+            }
+        };
+
+        if let Some(bssid) = bssid {
+            ctrl.request_ok(format!("SET_NETWORK {} bssid {}", id, bssid).as_bytes())
+                .map_err(|err| {
+                    if is_new {
+                        let _ = ctrl.request_ok(format!("REMOVE_NETWORK {}", id).as_bytes());
+                    }
+                    map_err(err)
+                })?;
+        }
+
+        ctrl.request_ok(b"ATTACH").map_err(map_err)?;
+
+        let connected = ctrl
+            .request_ok(format!("SELECT_NETWORK {}", id).as_bytes())
+            .and_then(|_| {
+                ctrl.wait_for_event(
+                    &[
+                        "CTRL-EVENT-CONNECTED",
+                        "CTRL-EVENT-SSID-TEMP-DISABLED",
+                        "CTRL-EVENT-ASSOC-REJECT",
+                        "CTRL-EVENT-NETWORK-NOT-FOUND",
+                    ],
+                    CONNECT_TIMEOUT,
+                )
+            })
+            .and_then(|event| match event.as_str() {
+                "CTRL-EVENT-CONNECTED" => Ok(()),
+                "CTRL-EVENT-SSID-TEMP-DISABLED" => Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "authentication failed",
+                )),
+                "CTRL-EVENT-NETWORK-NOT-FOUND" => Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "the network is not found",
+                )),
+                _ => Err(io::Error::other("the association is rejected")),
+            });
+
+        let _ = ctrl.request_ok(b"DETACH");
+
+        if let Err(err) = connected {
+            if is_new {
+                let _ = ctrl.request_ok(format!("REMOVE_NETWORK {}", id).as_bytes());
+            }
+            return Err(map_err(err));
+        }
+
+        let _ = ctrl.request_ok(b"SAVE_CONFIG");
+
+        let interface = self.interface().map_err(map_err)?;
+        let result = [
+            b"Device '",
+            interface.as_bytes(),
+            b"' is successfully connected to '",
+            ssid,
+            b"'.\n",
+        ]
+        .concat();
+
+        Ok(result)
+    }
+
+    fn open(&self) -> Result<Ctrl, (io::Error, i32)> {
+        self.ctrl_path()
+            .and_then(|path| Ctrl::open(&path))
+            .map_err(|err| (err, 1))
+    }
+
+    fn get_status(&self, ctrl: &Ctrl) -> Result<Vec<(String, Vec<u8>)>, io::Error> {
+        let reply = ctrl.request(b"STATUS")?;
+
+        let status = reply
+            .split(|b| b == &b'\n')
+            .filter_map(|l| {
+                let idx = l.iter().position(|b| b == &b'=')?;
+                let key = String::from_utf8_lossy(&l[..idx]).into_owned();
+                Some((key, l[idx + 1..].to_vec()))
+            })
+            .collect();
+
+        Ok(status)
+    }
+
+    fn list_networks(&self, ctrl: &Ctrl) -> Result<Vec<ConfiguredNetwork>, io::Error> {
+        let reply = ctrl.request(b"LIST_NETWORKS")?;
+
+        // network id / ssid / bssid / flags
+        let networks = reply
+            .split(|b| b == &b'\n')
+            .skip(1)
+            .filter(|l| !l.is_empty())
+            .filter_map(|l| {
+                let fields = l.split(|b| b == &b'\t').collect::<Vec<&[u8]>>();
+                let [id, ssid, _bssid, flags] = &fields[..] else {
+                    return None;
+                };
+
+                Some(ConfiguredNetwork {
+                    id: String::from_utf8_lossy(id).into_owned(),
+                    ssid: decode_ssid(ssid),
+                    is_current: flags.windows(9).any(|w| w == b"[CURRENT]"),
+                    is_disabled: flags.windows(10).any(|w| w == b"[DISABLED]"),
+                })
+            })
+            .collect();
+
+        Ok(networks)
+    }
+
+    fn find_network(&self, ctrl: &Ctrl, ssid: &[u8]) -> Result<ConfiguredNetwork, io::Error> {
+        self.list_networks(ctrl)?
+            .into_iter()
+            .find(|n| n.ssid == ssid)
+            .ok_or(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not a known network", String::from_utf8_lossy(ssid)),
+            ))
+    }
+}
+
+impl Wl for WpaSupplicant {
+    /// Provides the WiFi status.
+    ///
+    /// The WiFi status is `disabled` when the interface is either disabled or disconnected by the `DISCONNECT` command.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetWiFiStatus`] when it fails
+    /// to obtain the WiFi status.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{WpaSupplicant, Wl};
+    ///
+    /// let wpa = WpaSupplicant::new();
+    /// let status = wpa.get_wifi_status().unwrap();
+    /// println!("wifi: {}", status);
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetWiFiStatus`]: crate::NetworkAdapterError::CannotGetWiFiStatus
+    fn get_wifi_status(&self) -> Result<WifiStatus, Error> {
+        let ctrl = self.open().map_err(Error::CannotGetWiFiStatus)?;
+        let status = self
+            .get_status(&ctrl)
+            .map_err(|err| Error::CannotGetWiFiStatus((err, 1)))?;
+
+        let state = status
+            .iter()
+            .find(|(key, _)| key == "wpa_state")
+            .map(|(_, value)| &value[..]);
+
+        Ok(match state {
+            Some(b"DISCONNECTED") | Some(b"INTERFACE_DISABLED") => WifiStatus::Disabled,
+            _ => WifiStatus::Enabled,
+        })
+    }
+
+    /// Toggles the WiFi status.
+    ///
+    /// The WiFi status is toggled with the `DISCONNECT` and `RECONNECT` commands.
+    /// It returns the updated WiFi status.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method can return either a [`NetworkAdapterError::CannotGetWiFiStatus`] or a [`NetworkAdapterError::CannotToggleWiFi`] when it fails to toggle WiFi.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{WpaSupplicant, Wl};
+    ///
+    /// let wpa = WpaSupplicant::new();
+    /// let updated_status = wpa.toggle_wifi().unwrap();
+    /// println!("wifi: {}", updated_status);
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetWiFiStatus`]: crate::NetworkAdapterError::CannotGetWiFiStatus
+    /// [`NetworkAdapterError::CannotToggleWiFi`]: crate::NetworkAdapterError::CannotToggleWiFi
+    fn toggle_wifi(&self) -> Result<WifiStatus, Error> {
+        let new_status = self.get_wifi_status()?.toggled();
+
+        let cmd: &[u8] = match new_status {
+            WifiStatus::Enabled => b"RECONNECT",
+            WifiStatus::Disabled => b"DISCONNECT",
+        };
+
+        let ctrl = self.open().map_err(Error::CannotToggleWiFi)?;
+        ctrl.request_ok(cmd)
+            .map_err(|err| Error::CannotToggleWiFi((err, 1)))?;
+
+        Ok(new_status)
+    }
+
+    /// Provides the known networks on the host.
+    ///
+    /// The known networks are obtained with the `LIST_NETWORKS` command.
+    /// The `uuid` of each [`Network`] is the network id of `wpa_supplicant`.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`NetworkAdapterError::CannotListNetworks`] when it fails to retrieve the networks, or a [`NetworkAdapterError::CannotGetActiveConnections`] if `active_only` is set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{WpaSupplicant, Wl};
+    ///
+    /// let wpa = WpaSupplicant::new();
+    /// let networks = wpa.get_networks(false).unwrap();
+    ///
+    /// for network in networks {
+    ///     println!("{}", String::from_utf8_lossy(&network.ssid));
+    /// }
+    /// ```
+    ///
+    /// [`Network`]: crate::Network
+    /// [`NetworkAdapterError::CannotListNetworks`]: crate::NetworkAdapterError::CannotListNetworks
+    /// [`NetworkAdapterError::CannotGetActiveConnections`]: crate::NetworkAdapterError::CannotGetActiveConnections
+    fn get_networks(&self, active_only: bool) -> Result<Vec<Network>, Error> {
+        let map_err = if active_only {
+            Error::CannotGetActiveConnections
+        } else {
+            Error::CannotListNetworks
+        };
+
+        let ctrl = self.open().map_err(map_err)?;
+        let interface = self.interface().map_err(|err| map_err((err, 1)))?;
+
+        let networks = self
+            .list_networks(&ctrl)
+            .map_err(|err| map_err((err, 1)))?
+            .into_iter()
+            .filter(|n| !active_only || n.is_current)
+            .map(|n| Network {
+                ssid: n.ssid,
+                uuid: n.id,
+                kind: String::from("wifi"),
+                device: n.is_current.then(|| interface.clone()),
+            })
+            .collect();
+
+        Ok(networks)
+    }
+
+    /// Disconnects from the given SSID.
+    ///
+    /// If `forget` is not set, then the network is disabled with `DISABLE_NETWORK`, which keeps it in the known network list.
+    /// The configuration is not saved, so the network is enabled again when `wpa_supplicant` restarts, as the known networks of the other backends are.
+    ///
+    /// If `forget` is set, then the network is removed with `REMOVE_NETWORK`, and the configuration is saved afterwards,
+    /// if `wpa_supplicant` allows it (`update_config=1`).
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`NetworkAdapterError::CannotDisconnect`] when it fails to disconnect.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{WpaSupplicant, Wl};
+    /// use std::io::{self, Write};
+    ///
+    /// let wpa = WpaSupplicant::new();
+    /// let result = wpa.disconnect(b"SSID", true).unwrap();
+    /// io::stdout().write_all(&result).unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotDisconnect`]: crate::NetworkAdapterError::CannotDisconnect
+    fn disconnect(&self, ssid: &[u8], forget: bool) -> Result<Vec<u8>, Error> {
+        let ctrl = self.open().map_err(Error::CannotDisconnect)?;
+        let network = self
+            .find_network(&ctrl, ssid)
+            .map_err(|err| Error::CannotDisconnect((err, UNKNOWN_SSID_EXIT_CODE)))?;
+
+        let cmd = if forget {
+            format!("REMOVE_NETWORK {}", network.id)
+        } else {
+            format!("DISABLE_NETWORK {}", network.id)
+        };
+
+        ctrl.request_ok(cmd.as_bytes())
+            .map_err(|err| Error::CannotDisconnect((err, 1)))?;
+        if forget {
+            let _ = ctrl.request_ok(b"SAVE_CONFIG");
+        }
+
+        let action = if forget { "forgotten" } else { "disconnected" };
+        let result = [
+            b"Network '",
+            ssid,
+            b"' is successfully ",
+            action.as_bytes(),
+            b".\n",
+        ]
+        .concat();
+
+        Ok(result)
+    }
+
+    /// Provides the access points that are visible to the host.
+    ///
+    /// The access points are obtained with the `SCAN_RESULTS` command.
+    /// `wpa_supplicant` does not provide the rate of the access points, so it is left empty.
+    ///
+    /// If `re_scan` is set, then `get_access_points` triggers a scan with the `SCAN` command and waits for it to complete.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotScanWiFi`] if it fails to scan the available networks.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{WpaSupplicant, Wl};
+    ///
+    /// let wpa = WpaSupplicant::new();
+    /// let aps = wpa.get_access_points(true).unwrap();
+    ///
+    /// for ap in aps {
+    ///     println!("{} ({})", String::from_utf8_lossy(&ap.ssid), ap.signal);
+    /// }
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotScanWiFi`]: crate::NetworkAdapterError::CannotScanWiFi
+    fn get_access_points(&self, re_scan: bool) -> Result<Vec<AccessPoint>, Error> {
+        let ctrl = self.open().map_err(Error::CannotScanWiFi)?;
+        let map_err = |err| Error::CannotScanWiFi((err, 1));
+
+        if re_scan {
+            ctrl.request_ok(b"ATTACH").map_err(map_err)?;
+
+            // `FAIL-BUSY` means that a scan is already in progress, so its results are awaited as well.
+            let reply = ctrl.request(b"SCAN").map_err(map_err)?;
+            if reply.trim_ascii() != b"OK" && reply.trim_ascii() != b"FAIL-BUSY" {
+                let err = io::Error::other(format!(
+                    "SCAN failed: {}",
+                    String::from_utf8_lossy(reply.trim_ascii())
+                ));
+                return Err(map_err(err));
+            }
+
+            ctrl.wait_for_event(&["CTRL-EVENT-SCAN-RESULTS"], SCAN_TIMEOUT)
+                .map_err(map_err)?;
+            let _ = ctrl.request_ok(b"DETACH");
+        }
+
+        let status = self.get_status(&ctrl).map_err(map_err)?;
+        let current_bssid = status
+            .into_iter()
+            .find(|(key, _)| key == "bssid")
+            .map(|(_, value)| value);
+
+        let reply = ctrl.request(b"SCAN_RESULTS").map_err(map_err)?;
+
+        // bssid / frequency / signal level / flags / ssid
+        let aps = reply
+            .split(|b| b == &b'\n')
+            .skip(1)
+            .filter(|l| !l.is_empty())
+            .filter_map(|l| {
+                let fields = l.split(|b| b == &b'\t').collect::<Vec<&[u8]>>();
+                let [bssid, freq, level, flags, ssid @ ..] = &fields[..] else {
+                    return None;
+                };
+
+                let frequency = String::from_utf8_lossy(freq).parse().unwrap_or_default();
+                let level = String::from_utf8_lossy(level).parse().unwrap_or(-100);
+                let flags = String::from_utf8_lossy(flags);
+
+                Some(AccessPoint {
+                    ssid: decode_ssid(&ssid.join(&b'\t')),
+                    bssid: String::from_utf8_lossy(bssid).to_ascii_uppercase(),
+                    mode: mode_from_flags(&flags).to_string(),
+                    channel: channel_from_frequency(frequency),
+                    frequency,
+                    rate: 0,
+                    signal: signal_from_dbm(level),
+                    security: security_from_flags(&flags),
+                    in_use: current_bssid.as_deref() == Some(*bssid),
+                })
+            })
+            .collect();
+
+        Ok(aps)
+    }
+
+    /// Checks whether the given SSID is a known one or not.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotGetSSIDStatus`] if it fails to check the SSID.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{WpaSupplicant, Wl};
+    ///
+    /// let wpa = WpaSupplicant::new();
+    /// let is_known_ssid = wpa.is_known_ssid(b"SSID").unwrap();
+    /// ```
+    ///
+    /// [`NetworkAdapterError::CannotGetSSIDStatus`]: crate::NetworkAdapterError::CannotGetSSIDStatus
+    fn is_known_ssid(&self, ssid: &[u8]) -> Result<bool, Error> {
+        let ctrl = self.open().map_err(Error::CannotGetSSIDStatus)?;
+        let networks = self
+            .list_networks(&ctrl)
+            .map_err(|err| Error::CannotGetSSIDStatus((err, 1)))?;
+
+        Ok(networks.iter().any(|n| n.ssid == ssid))
+    }
+
+    /// Connects to the given SSID.
+    ///
+    /// The semantics are the same as [`Nmcli::connect`]:
+    ///
+    /// - To establish a new connection, provide both `ssid` and `passwd`. The network is created with `ADD_NETWORK` and `SET_NETWORK`.
+    /// - To re-use a known network, only provide `ssid`.
+    /// - To "update" a known network, provide all the arguments. The known network is removed before the new connection attempt.
+    ///
+    /// In all cases, the network is selected with `SELECT_NETWORK`, and `connect` waits until the connection is completed.
+    /// If a new network fails to connect, then it is removed again.
+    ///
+    /// If `bssid` is provided, then the `bssid` of the network is set before it is selected, which pins it to the access point.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotConnect`] if it fails to connect to the the SSID.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wl::{WpaSupplicant, Wl};
+    /// use std::io::{self, Write};
+    ///
+    /// let wpa = WpaSupplicant::new();
+    /// let result = wpa.connect(b"SSID", Some(b"PASS"), None, false).unwrap();
+    /// io::stdout().write_all(&result).unwrap();
+    /// ```
+    ///
+    /// [`Nmcli::connect`]: crate::Nmcli::connect
+    /// [`NetworkAdapterError::CannotConnect`]: crate::NetworkAdapterError::CannotConnect
+    fn connect(
+        &self,
+        ssid: &[u8],
+        passwd: Option<&[u8]>,
+        bssid: Option<&str>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        self.select_network(ssid, passwd, bssid, None, is_known_ssid)
+    }
+
+    /// Removes the BSSID that is pinned to the given known SSID.
+    ///
+    /// The `bssid` of the network is set to `any` with `SET_NETWORK`, and the configuration is saved with `SAVE_CONFIG`.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotUpdateNetwork`] if the SSID is not a known network, or its `bssid` cannot be set.
+    ///
+    /// [`NetworkAdapterError::CannotUpdateNetwork`]: crate::NetworkAdapterError::CannotUpdateNetwork
+    fn clear_bssid(&self, ssid: &[u8]) -> Result<Vec<u8>, Error> {
+        let ctrl = self.open().map_err(Error::CannotUpdateNetwork)?;
+        let network = self
+            .find_network(&ctrl, ssid)
+            .map_err(|err| Error::CannotUpdateNetwork((err, UNKNOWN_SSID_EXIT_CODE)))?;
+
+        ctrl.request_ok(format!("SET_NETWORK {} bssid any", network.id).as_bytes())
+            .map_err(|err| Error::CannotUpdateNetwork((err, 1)))?;
+        let _ = ctrl.request_ok(b"SAVE_CONFIG");
+
+        let result = [b"Network '", ssid, b"' is no longer pinned to a BSSID.\n"].concat();
+        Ok(result)
+    }
+
+    /// Provides the autoconnect priorities of the configured networks.
+    ///
+    /// The networks are provided in the order of `LIST_NETWORKS`, and their `priority` is read with `GET_NETWORK`.
+    /// The disabled networks are not selected automatically, so they are provided without autoconnect.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotListNetworks`] if it fails to list the configured networks.
+    ///
+    /// [`NetworkAdapterError::CannotListNetworks`]: crate::NetworkAdapterError::CannotListNetworks
+    fn get_priorities(&self) -> Result<Vec<NetworkPriority>, Error> {
+        let ctrl = self.open().map_err(Error::CannotListNetworks)?;
+        let networks = self
+            .list_networks(&ctrl)
+            .map_err(|err| Error::CannotListNetworks((err, 1)))?;
+
+        let priorities = networks
+            .into_iter()
+            .map(|n| {
+                let priority = ctrl
+                    .request(format!("GET_NETWORK {} priority", n.id).as_bytes())
+                    .map(|r| {
+                        String::from_utf8_lossy(r.trim_ascii())
+                            .parse()
+                            .unwrap_or_default()
+                    })
+                    .map_err(|err| Error::CannotListNetworks((err, 1)))?;
+
+                Ok(NetworkPriority {
+                    ssid: n.ssid,
+                    priority,
+                    autoconnect: !n.is_disabled,
+                })
+            })
+            .collect::<Result<Vec<NetworkPriority>, Error>>()?;
+
+        Ok(priorities)
+    }
+
+    /// Sets the autoconnect priority of the given known SSID.
+    ///
+    /// The `priority` of the network is set with `SET_NETWORK`, and the configuration is saved with `SAVE_CONFIG`.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotUpdateNetwork`] if the SSID is not a known network, or its `priority` cannot be set.
+    ///
+    /// [`NetworkAdapterError::CannotUpdateNetwork`]: crate::NetworkAdapterError::CannotUpdateNetwork
+    fn set_priority(&self, ssid: &[u8], priority: i32) -> Result<Vec<u8>, Error> {
+        let ctrl = self.open().map_err(Error::CannotUpdateNetwork)?;
+        let network = self
+            .find_network(&ctrl, ssid)
+            .map_err(|err| Error::CannotUpdateNetwork((err, UNKNOWN_SSID_EXIT_CODE)))?;
+
+        ctrl.request_ok(format!("SET_NETWORK {} priority {}", network.id, priority).as_bytes())
+            .map_err(|err| Error::CannotUpdateNetwork((err, 1)))?;
+        let _ = ctrl.request_ok(b"SAVE_CONFIG");
+
+        let result = [
+            b"Network '",
+            ssid,
+            b"' priority is set to ",
+            priority.to_string().as_bytes(),
+            b".\n",
+        ]
+        .concat();
+        Ok(result)
+    }
+
+    /// Connects to the given hidden SSID.
+    ///
+    /// The network is added with `scan_ssid 1`, so `wpa_supplicant` probes the SSID explicitly, and its `key_mgmt` is set from the given security type.
+    /// WPA3 networks are added with `ieee80211w 2`, since SAE requires management frame protection.
+    ///
+    /// The rest of the semantics are the same as [`WpaSupplicant::connect`] with a password: the known network is replaced if `is_known_ssid` is set,
+    /// and the new network is removed if the connection attempt fails.
+    ///
+    /// The output is in a **human-readable format**.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotConnect`] if it fails to connect to the the SSID.
+    ///
+    /// [`WpaSupplicant::connect`]: crate::WpaSupplicant::connect
+    /// [`NetworkAdapterError::CannotConnect`]: crate::NetworkAdapterError::CannotConnect
+    fn connect_hidden(
+        &self,
+        ssid: &[u8],
+        security: Security,
+        passwd: Option<&[u8]>,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        self.select_network(ssid, passwd, None, Some(security), is_known_ssid)
+    }
+
+    /// Adds the given network as a configured network, without selecting it.
+    ///
+    /// The network is added with `ADD_NETWORK` and `SET_NETWORK`, and the known network is removed first if `is_known_ssid` is set.
+    /// The networks that are connected automatically are enabled with `ENABLE_NETWORK`, and the rest of them are kept disabled.
+    /// The configuration is saved with `SAVE_CONFIG`.
+    ///
+    /// Since `wpa_supplicant` cannot ask for a password on connection, a secured network must have a secret.
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`NetworkAdapterError::CannotAddNetwork`] if the secured network does not have a secret, or if the network cannot be added.
+    ///
+    /// [`NetworkAdapterError::CannotAddNetwork`]: crate::NetworkAdapterError::CannotAddNetwork
+    fn add_network(
+        &self,
+        network: &ExportedNetwork,
+        is_known_ssid: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let ssid = network.ssid.as_slice();
+        if network.security.is_secured() && network.secret.is_none() {
+            let err = io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "the password of {} is missing",
+                    String::from_utf8_lossy(&network.ssid)
+                ),
+            );
+            return Err(Error::CannotAddNetwork((err, 2)));
+        }
+
+        let ctrl = self.open().map_err(Error::CannotAddNetwork)?;
+        let map_err = |err| Error::CannotAddNetwork((err, 1));
+
+        if is_known_ssid {
+            let known_network = self
+                .find_network(&ctrl, ssid)
+                .map_err(|err| Error::CannotAddNetwork((err, UNKNOWN_SSID_EXIT_CODE)))?;
+            ctrl.request_ok(format!("REMOVE_NETWORK {}", known_network.id).as_bytes())
+                .map_err(map_err)?;
+        }
+
+        let id = add_network(
+            &ctrl,
+            ssid,
+            network.secret.as_deref(),
+            Some(network.security),
+            network.hidden,
+        )
+        .map_err(map_err)?;
+
+        let mut commands = vec![format!("SET_NETWORK {} priority {}", id, network.priority)];
+        if network.autoconnect {
+            commands.push(format!("ENABLE_NETWORK {}", id));
+        }
+
+        for cmd in commands {
+            if let Err(err) = ctrl.request_ok(cmd.as_bytes()) {
+                let _ = ctrl.request_ok(format!("REMOVE_NETWORK {}", id).as_bytes());
+                return Err(map_err(err));
+            }
+        }
+        let _ = ctrl.request_ok(b"SAVE_CONFIG");
+
+        let result = [b"Network '", ssid, b"' is added.\n"].concat();
+        Ok(result)
+    }
+}
+
+fn add_network(
+    ctrl: &Ctrl,
+    ssid: &[u8],
+    passwd: Option<&[u8]>,
+    security: Option<Security>,
+    scan_ssid: bool,
+) -> Result<String, io::Error> {
+    let reply = ctrl.request(b"ADD_NETWORK")?;
+    let id = String::from_utf8_lossy(reply.trim_ascii()).into_owned();
+
+    if id.parse::<u32>().is_err() {
+        return Err(io::Error::other(format!("ADD_NETWORK failed: {}", id)));
+    }
+
+    // The SSID is set in hex, so any byte of the SSID is preserved as is.
+    let mut commands = vec![
+        [
+            format!("SET_NETWORK {} ssid ", id).as_bytes(),
+            encode_hex(ssid).as_bytes(),
+        ]
+        .concat(),
+    ];
+
+    if scan_ssid {
+        commands.push(format!("SET_NETWORK {} scan_ssid 1", id).into_bytes());
+    }
+
+    if let Some(security) = security {
+        match security {
+            Security::Open => {}
+            Security::WpaPsk => {
+                commands.push(format!("SET_NETWORK {} key_mgmt WPA-PSK", id).into_bytes())
+            }
+            Security::Sae => {
+                commands.push(format!("SET_NETWORK {} key_mgmt SAE", id).into_bytes());
+                commands.push(format!("SET_NETWORK {} ieee80211w 2", id).into_bytes());
+            }
+        }
+    }
+
+    match passwd {
+        // A PSK hash (64 hex digits) is set as is, and a passphrase is quoted.
+        Some(passwd) if passwd.len() == 64 && passwd.iter().all(u8::is_ascii_hexdigit) => {
+            commands.push([format!("SET_NETWORK {} psk ", id).as_bytes(), passwd].concat())
+        }
+        Some(passwd) => commands.push(
+            [
+                format!("SET_NETWORK {} psk \"", id).as_bytes(),
+                passwd,
+                b"\"",
+            ]
+            .concat(),
+        ),
+        None => commands.push(format!("SET_NETWORK {} key_mgmt NONE", id).into_bytes()),
+    }
+
+    for cmd in commands {
+        if let Err(err) = ctrl.request_ok(&cmd) {
+            let _ = ctrl.request_ok(format!("REMOVE_NETWORK {}", id).as_bytes());
+            return Err(err);
+        }
+    }
+
+    Ok(id)
+}
+
+fn is_event(msg: &[u8]) -> bool {
+    msg.first() == Some(&b'<')
+        && msg
+            .iter()
+            .skip(1)
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+            > 0
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decodes the SSIDs that are escaped by `wpa_supplicant` (`printf_encode`).
+pub(crate) fn decode_ssid(ssid: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(ssid.len());
+    let mut bytes = ssid.iter().copied();
+
+    while let Some(b) = bytes.next() {
+        if b != b'\\' {
+            decoded.push(b);
+            continue;
+        }
+
+        match bytes.next() {
+            Some(b'n') => decoded.push(b'\n'),
+            Some(b'r') => decoded.push(b'\r'),
+            Some(b't') => decoded.push(b'\t'),
+            Some(b'e') => decoded.push(0x1B),
+            Some(b'x') => {
+                let hex = [bytes.next(), bytes.next()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<u8>>();
+                match u8::from_str_radix(&String::from_utf8_lossy(&hex), 16) {
+                    Ok(b) => decoded.push(b),
+                    Err(_) => decoded.extend([b'\\', b'x'].iter().chain(&hex)),
+                }
+            }
+            Some(b) => decoded.push(b),
+            None => decoded.push(b'\\'),
+        }
+    }
+
+    decoded
+}
+
+fn mode_from_flags(flags: &str) -> &'static str {
+    if flags.contains("[IBSS]") {
+        "Ad-Hoc"
+    } else if flags.contains("[MESH]") {
+        "Mesh"
+    } else {
+        "Infra"
+    }
+}
+
+/// Converts the flags of a scan result into the security protocols that are used by [`AccessPoint`].
+///
+/// [`AccessPoint`]: crate::AccessPoint
+fn security_from_flags(flags: &str) -> String {
+    let mut security = Vec::new();
+
+    if flags.contains("[WEP]") {
+        security.push("WEP");
+    }
+    if flags.contains("[WPA-") {
+        security.push("WPA1");
+    }
+    if flags.contains("[WPA2-") || flags.contains("[RSN-") {
+        security.push("WPA2");
+    }
+    if flags.contains("SAE") {
+        security.push("WPA3");
+    }
+    if flags.contains("EAP") {
+        security.push("802.1X");
+    }
+
+    security.join(" ")
+}
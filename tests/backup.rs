@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use wl::{
+        BackupError, Error, ErrorKind, FakeWl, ImportStatus, Wl,
+        api::{ConflictPolicy, OutputFormat},
+    };
+
+    #[test]
+    fn backup_should_round_trip_known_networks() {
+        let fake = FakeWl::new()
+            .with_known_network(b"Home", Some(b"secret"))
+            .with_known_network(b"Cafe", None);
+        fake.set_priority(b"Home", 10).unwrap();
+
+        let mut archive = Vec::new();
+        let report = wl::backup(&fake, &mut archive, b"passphrase").unwrap();
+        assert_eq!(2, report.networks.len());
+        assert!(!String::from_utf8_lossy(&archive).contains("secret"));
+
+        let target = FakeWl::new().with_known_network(b"Cafe", None);
+        let mut out = Vec::new();
+        let restored = wl::restore(
+            &target,
+            &mut out,
+            &mut archive.as_slice(),
+            b"passphrase",
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![ImportStatus::Added, ImportStatus::Skipped],
+            restored.iter().map(|n| n.status).collect::<Vec<_>>()
+        );
+        assert_eq!(b"Home: added\nCafe: skipped\n".as_slice(), out);
+        assert_eq!(
+            Some(b"secret".to_vec()),
+            target.get_passwd(b"Home").unwrap()
+        );
+        assert_eq!(10, target.get_profile(b"Home").unwrap().priority);
+
+        // Nothing is restored with a wrong passphrase or a modified archive.
+        let target = FakeWl::new();
+        let err = wl::restore(
+            &target,
+            &mut Vec::new(),
+            &mut archive.as_slice(),
+            b"wrong",
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Backup(BackupError::CannotDecrypt)));
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+
+        let mut modified = archive.clone();
+        *modified.last_mut().unwrap() ^= 1;
+        let err = wl::restore(
+            &target,
+            &mut Vec::new(),
+            &mut modified.as_slice(),
+            b"passphrase",
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Backup(BackupError::CannotDecrypt)));
+        assert!(target.known_ssids().is_empty());
+
+        let err = wl::restore(
+            &target,
+            &mut Vec::new(),
+            &mut b"version = 1\n".as_slice(),
+            b"passphrase",
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Backup(BackupError::InvalidArchive)));
+
+        let err = wl::backup(&fake, &mut Vec::new(), b"").unwrap_err();
+        assert!(matches!(err, Error::Backup(BackupError::EmptyPassphrase)));
+
+        // The archives with unbounded key derivation costs are rejected before the key is derived.
+        for offset in [9, 13, 17] {
+            let mut crafted = archive.clone();
+            crafted[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+            let err = wl::restore(
+                &target,
+                &mut Vec::new(),
+                &mut crafted.as_slice(),
+                b"passphrase",
+                ConflictPolicy::Skip,
+                OutputFormat::Text,
+            )
+            .unwrap_err();
+            assert!(matches!(err, Error::Backup(BackupError::InvalidArchive)));
+        }
+    }
+
+    #[test]
+    fn backup_should_round_trip_non_utf8_networks() {
+        let fake = FakeWl::new().with_known_network(b"Caf\xe9", Some(b"s\xffcret"));
+
+        let mut archive = Vec::new();
+        wl::backup(&fake, &mut archive, b"passphrase").unwrap();
+
+        let target = FakeWl::new();
+        let restored = wl::restore(
+            &target,
+            &mut Vec::new(),
+            &mut archive.as_slice(),
+            b"passphrase",
+            ConflictPolicy::Skip,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(b"Caf\xe9".as_slice(), restored[0].ssid);
+        assert_eq!(vec![b"Caf\xe9".to_vec()], target.known_ssids());
+        assert_eq!(
+            Some(b"s\xffcret".to_vec()),
+            target.get_passwd(b"Caf\xe9").unwrap()
+        );
+    }
+}
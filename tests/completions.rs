@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use wl::{
+        AccessPoint, FakeWl,
+        api::{Shell, SsidSource},
+    };
+
+    fn completions(shell: Shell) -> String {
+        let mut out = Vec::new();
+        wl::completions(&mut out, shell).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn completions_should_complete_ssids_from_backend() {
+        let bash = completions(Shell::Bash);
+        assert!(bash.contains("_wl() {"));
+        assert!(bash.contains("wl __complete-ssid \"$source\""));
+        assert!(
+            bash.trim_end()
+                .ends_with("complete -F _wl_ssids -o bashdefault -o default wl")
+        );
+
+        let zsh = completions(Shell::Zsh);
+        assert!(zsh.contains("\n_wl_clap() {\n"));
+        assert_eq!(1, zsh.matches("\n_wl() {\n").count());
+        assert!(zsh.find("\n_wl() {\n") < zsh.find("if [ \"$funcstack[1]\" = \"_wl\" ]"));
+
+        let fish = completions(Shell::Fish);
+        assert!(fish.contains("(wl __complete-ssid connect 2>/dev/null)"));
+        assert!(fish.contains("(wl __complete-ssid disconnect 2>/dev/null)"));
+    }
+
+    #[test]
+    fn completions_should_not_list_hidden_subcommand() {
+        let bash = completions(Shell::Bash);
+        assert!(!bash.contains("__complete-ssid\" "));
+
+        let mut out = Vec::new();
+        wl::man(&mut out).unwrap();
+        let man = String::from_utf8(out).unwrap();
+
+        assert!(man.contains("wl\\-connect"));
+        assert!(!man.contains("__complete"));
+    }
+
+    #[test]
+    fn complete_ssids_should_list_access_points_for_connect() {
+        let fake = FakeWl::new()
+            .with_access_point(AccessPoint {
+                ssid: b"Home".to_vec(),
+                signal: 80,
+                ..Default::default()
+            })
+            .with_access_point(AccessPoint {
+                ssid: b"Home Office".to_vec(),
+                signal: 60,
+                ..Default::default()
+            })
+            .with_access_point(AccessPoint {
+                ssid: b"Home".to_vec(),
+                signal: 30,
+                ..Default::default()
+            })
+            .with_hidden_network(AccessPoint {
+                ssid: b"Secret".to_vec(),
+                signal: 50,
+                ..Default::default()
+            });
+
+        let mut out = Vec::new();
+        let ssids = wl::complete_ssids(&fake, &mut out, SsidSource::Connect).unwrap();
+
+        assert_eq!(vec![b"Home".to_vec(), b"Home Office".to_vec()], ssids);
+        assert_eq!(b"Home\nHome Office\n".as_slice(), out);
+    }
+
+    #[test]
+    fn complete_ssids_should_list_active_then_known_networks_for_disconnect() {
+        let fake = FakeWl::new()
+            .with_known_network(b"Cafe", None)
+            .with_known_network(b"Home", Some(b"secret"))
+            .with_active_network(b"Home");
+
+        let mut out = Vec::new();
+        let ssids = wl::complete_ssids(&fake, &mut out, SsidSource::Disconnect).unwrap();
+
+        assert_eq!(vec![b"Home".to_vec(), b"Cafe".to_vec()], ssids);
+        assert_eq!(b"Home\nCafe\n".as_slice(), out);
+    }
+}
@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use clap::FromArgMatches;
+    use wl::{
+        Alias, Backend, Config, ConfigError, ScanDefaults,
+        api::{Args, OutputFormat, WlCommand},
+    };
+
+    const CONFIG: &str = r#"
+backend = "nmcli,iwd"
+output = "json"
+
+[scan]
+min-strength = 40
+re-scan = true
+columns = "ssid,signal"
+
+[nicknames]
+office = "ACME Corp 5G"
+
+[aliases]
+home = "connect -i Home"
+strong = ["scan", "--min-strength", "70"]
+"#;
+
+    fn parse_args(config: &Config, args: &[&str]) -> Args {
+        let args = config.expand_alias(args.iter().map(OsString::from));
+        let matches = config.command().try_get_matches_from(args).unwrap();
+
+        Args::from_arg_matches(&matches).unwrap()
+    }
+
+    #[test]
+    fn config_should_parse_file() {
+        let config = Config::parse(CONFIG).unwrap();
+
+        assert_eq!(vec![Backend::Nmcli, Backend::Iwd], config.backend);
+        assert_eq!(Some(OutputFormat::Json), config.output);
+        assert_eq!(
+            ScanDefaults {
+                min_strength: Some(40),
+                re_scan: Some(true),
+                columns: Some(String::from("ssid,signal")),
+                get_values: None,
+            },
+            config.scan
+        );
+        assert_eq!(
+            Some(&Alias::Line(String::from("connect -i Home"))),
+            config.aliases.get("home")
+        );
+        assert_eq!("ACME Corp 5G", config.resolve_ssid(String::from("office")));
+
+        assert_eq!(Config::default(), Config::parse("").unwrap());
+        assert_eq!(
+            vec![Backend::NetworkManager, Backend::WpaSupplicant],
+            Config::parse("backend = [\"nm\", \"wpa-supplicant\"]\n")
+                .unwrap()
+                .backend
+        );
+
+        for content in [
+            "backend = \"ethernet\"\n",
+            "output = \"yaml\"\n",
+            "[scan]\nmin-strength = 300\n",
+            "[scan]\nunknown = 1\n",
+            "backend = [",
+        ] {
+            assert!(matches!(
+                Config::parse(content),
+                Err(ConfigError::InvalidFile(..))
+            ));
+        }
+
+        for content in [
+            "[aliases]\nscan = \"status\"\n",
+            "[aliases]\nnothing = \"\"\n",
+        ] {
+            assert!(matches!(
+                Config::parse(content),
+                Err(ConfigError::InvalidAlias(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn config_should_replace_defaults_of_args() {
+        let config = Config::parse(CONFIG).unwrap();
+
+        let args = parse_args(&config, &["wl", "scan"]);
+        assert_eq!(vec![Backend::Nmcli, Backend::Iwd], args.backend);
+        assert_eq!(OutputFormat::Json, args.output);
+        let Some(WlCommand::Scan { args: scan }) = args.wl_command else {
+            panic!("unexpected subcommand");
+        };
+        assert_eq!(40, scan.min_strength);
+        assert!(scan.re_scan);
+        assert_eq!(Some(String::from("ssid,signal")), scan.columns);
+
+        // The arguments take precedence over the configuration.
+        let args = parse_args(
+            &config,
+            &[
+                "wl", "-o", "text", "-b", "iwd", "scan", "-s", "10", "-g", "ssid",
+            ],
+        );
+        assert_eq!(vec![Backend::Iwd], args.backend);
+        assert_eq!(OutputFormat::Text, args.output);
+        let Some(WlCommand::Scan { args: scan }) = args.wl_command else {
+            panic!("unexpected subcommand");
+        };
+        assert_eq!(10, scan.min_strength);
+        assert_eq!(None, scan.columns);
+        assert_eq!(Some(String::from("ssid")), scan.get_values);
+
+        let args = parse_args(&Config::default(), &["wl", "scan"]);
+        assert!(args.backend.is_empty());
+        assert_eq!(OutputFormat::Text, args.output);
+    }
+
+    #[test]
+    fn config_should_expand_aliases() {
+        let config = Config::parse(CONFIG).unwrap();
+
+        let args = parse_args(&config, &["wl", "-o", "text", "strong", "-r"]);
+        let Some(WlCommand::Scan { args: scan }) = args.wl_command else {
+            panic!("unexpected subcommand");
+        };
+        assert_eq!(70, scan.min_strength);
+
+        assert_eq!(
+            ["wl", "--backend", "iwd", "connect", "-i", "Home"]
+                .map(OsString::from)
+                .to_vec(),
+            config.expand_alias(["wl", "--backend", "iwd", "home"].map(OsString::from))
+        );
+
+        // Only the subcommand position is expanded.
+        assert_eq!(
+            ["wl", "connect", "-i", "home"].map(OsString::from).to_vec(),
+            config.expand_alias(["wl", "connect", "-i", "home"].map(OsString::from))
+        );
+    }
+
+    #[test]
+    fn config_should_show_effective_configuration() {
+        let config =
+            Config::parse("[scan]\nmin-strength = 40\n\n[nicknames]\noffice = \"ACME\"\n").unwrap();
+
+        let mut out = Vec::new();
+        let effective =
+            wl::config_show(&mut out, &config, &[Backend::Iwd], OutputFormat::Text).unwrap();
+        assert_eq!(vec![Backend::Iwd], effective.backend);
+        assert_eq!(
+            "# No configuration file is loaded.\n\nbackend = [\"iwd\"]\noutput = \"text\"\n\n[scan]\nmin-strength = 40\nre-scan = false\n\n[nicknames]\noffice = \"ACME\"\n\n[aliases]\n",
+            String::from_utf8(out).unwrap()
+        );
+
+        let mut out = Vec::new();
+        wl::config_show(&mut out, &Config::default(), &[], OutputFormat::Json).unwrap();
+        assert_eq!(
+            "{\"backend\":[\"networkmanager\",\"nmcli\",\"iwd\",\"wpa_supplicant\"],\"output\":\"json\",\"scan\":{\"min-strength\":0,\"re-scan\":false},\"nicknames\":{},\"aliases\":{}}\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
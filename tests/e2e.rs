@@ -82,4 +82,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn wl_should_fail_when_no_backend_is_found() -> io::Result<()> {
+        let program = fs::canonicalize(BINARY)?;
+
+        let status_cmd = Command::new(&program)
+            .args(["s", "--backend", "nmcli,iwd"])
+            .env("PATH", "")
+            .output()?;
+
+        assert_eq!(Some(127), status_cmd.status.code());
+        assert!(status_cmd.stdout.is_empty());
+
+        let stderr = String::from_utf8_lossy(&status_cmd.stderr);
+        assert!(stderr.contains("nmcli, iwd"));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod tests {
+    use std::{error::Error as _, io};
+
+    use wl::{
+        ConnectError, Error, ErrorKind, NetworkAdapterError, Nmcli, UNKNOWN_SSID_EXIT_CODE, Wl,
+        api::OutputFormat,
+        runner::{FakeRunner, Reply},
+    };
+
+    const WIFI_CONNECT_WRONG_PASSWORD: &[u8] =
+        include_bytes!("fixtures/nmcli/wifi_connect_wrong_password.txt");
+    const CONNECTION_DOWN_UNKNOWN: &[u8] =
+        include_bytes!("fixtures/nmcli/connection_down_unknown.txt");
+
+    fn classify(err: NetworkAdapterError) -> ErrorKind {
+        Error::from(err).kind()
+    }
+
+    #[test]
+    fn error_should_classify_nmcli_failures() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &["--ask", "d", "wifi", "connect", "Home"],
+                Reply::failure(4, WIFI_CONNECT_WRONG_PASSWORD),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "down", "id", "Unknown"],
+                Reply::failure(10, CONNECTION_DOWN_UNKNOWN),
+            )
+            .reply(
+                "nmcli",
+                &["-g", "WIFI", "g"],
+                Reply::failure(8, "Error: NetworkManager is not running.\n"),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let err = Error::from(
+            nmcli
+                .connect(b"Home", Some(b"wrong"), None, false)
+                .unwrap_err(),
+        );
+        assert_eq!(ErrorKind::AuthFailed, err.kind());
+        assert_eq!(3, err.exit_code());
+
+        let err = Error::from(nmcli.disconnect(b"Unknown", false).unwrap_err());
+        assert_eq!(ErrorKind::SsidNotFound, err.kind());
+        assert_eq!(4, err.exit_code());
+
+        let err = wl::status(&nmcli, OutputFormat::Text).unwrap_err();
+        assert_eq!(ErrorKind::BackendMissing, err.kind());
+        assert!(err.to_string().contains("NetworkManager is not running"));
+    }
+
+    #[test]
+    fn error_should_classify_by_message_before_exit_code() {
+        let err = io::Error::other("Error: Not authorized to control networking.");
+        assert_eq!(
+            ErrorKind::PermissionDenied,
+            classify(NetworkAdapterError::CannotToggleWiFi((err, 1)))
+        );
+
+        let err = io::Error::other("Error: Timeout expired (90 seconds)");
+        assert_eq!(
+            ErrorKind::Timeout,
+            classify(NetworkAdapterError::CannotConnect((err, 3)))
+        );
+
+        let err = io::Error::other("Error: No network with SSID 'Cafe' found.");
+        assert_eq!(
+            ErrorKind::SsidNotFound,
+            classify(NetworkAdapterError::CannotConnect((
+                err,
+                UNKNOWN_SSID_EXIT_CODE
+            )))
+        );
+
+        let err = io::Error::other("Error: unexpected failure");
+        assert_eq!(
+            ErrorKind::Timeout,
+            classify(NetworkAdapterError::CannotScanWiFi((err, 3)))
+        );
+
+        let err = io::Error::other("Error: unexpected failure");
+        assert_eq!(
+            ErrorKind::Other,
+            classify(NetworkAdapterError::CannotScanWiFi((err, 300)))
+        );
+
+        let err = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert_eq!(
+            ErrorKind::PermissionDenied,
+            classify(NetworkAdapterError::CannotConnect((err, 1)))
+        );
+    }
+
+    #[test]
+    fn error_should_map_missing_backends_to_127() {
+        let err = Error::from(NetworkAdapterError::NoBackendFound((
+            io::Error::from(io::ErrorKind::NotFound),
+            wl::NO_BACKEND_EXIT_CODE,
+        )));
+
+        assert_eq!(ErrorKind::BackendMissing, err.kind());
+        assert_eq!(wl::NO_BACKEND_EXIT_CODE, i32::from(err.exit_code()));
+    }
+
+    #[test]
+    fn error_should_preserve_sources() {
+        let err = Error::from(ConnectError::CannotReadSSID(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "stdin is closed",
+        )));
+
+        assert_eq!(ErrorKind::Io, err.kind());
+        assert_eq!(74, err.exit_code());
+
+        let source = err.source().unwrap();
+        let source = source.downcast_ref::<io::Error>().unwrap();
+        assert_eq!(io::ErrorKind::UnexpectedEof, source.kind());
+    }
+
+    #[test]
+    fn error_should_preserve_backend_sources() {
+        let runner = FakeRunner::new().reply(
+            "nmcli",
+            &["connection", "down", "id", "Unknown"],
+            Reply::failure(10, CONNECTION_DOWN_UNKNOWN),
+        );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let backend_err = nmcli.disconnect(b"Unknown", false).unwrap_err();
+        let source = backend_err.source().unwrap();
+        assert!(source.to_string().contains("not an active connection"));
+
+        let err = Error::from(backend_err);
+        let source = err.source().unwrap();
+        let source = source.downcast_ref::<io::Error>().unwrap();
+        assert_eq!(io::ErrorKind::Other, source.kind());
+        assert!(source.to_string().contains("not an active connection"));
+
+        let err = Error::from(NetworkAdapterError::CannotConnect((
+            io::Error::from(io::ErrorKind::PermissionDenied),
+            1,
+        )));
+        let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(io::ErrorKind::PermissionDenied, source.kind());
+    }
+}
@@ -0,0 +1,196 @@
+#[cfg(test)]
+mod tests {
+    use wl::{
+        Error, ErrorKind, ExportedNetwork, FakeWl, ImportError, ImportStatus, NetworkAdapterError,
+        Security, Wl,
+        api::{ConflictPolicy, ExportFormat, OutputFormat},
+        fake::Operation,
+    };
+
+    #[test]
+    fn export_should_round_trip_known_networks() {
+        let fake = FakeWl::new()
+            .with_known_network(b"Home", Some(b"secret"))
+            .with_known_network(b"Cafe", None);
+        fake.set_priority(b"Home", 10).unwrap();
+
+        let mut out = Vec::new();
+        let report = wl::export(&fake, &mut out, ExportFormat::Toml, false).unwrap();
+        assert_eq!(
+            vec![
+                ExportedNetwork {
+                    ssid: b"Home".to_vec(),
+                    security: Security::WpaPsk,
+                    secret: Some(b"secret".to_vec()),
+                    hidden: false,
+                    priority: 10,
+                    autoconnect: true,
+                },
+                ExportedNetwork {
+                    ssid: b"Cafe".to_vec(),
+                    security: Security::Open,
+                    secret: None,
+                    hidden: false,
+                    priority: 0,
+                    autoconnect: true,
+                },
+            ],
+            report.networks
+        );
+        assert!(report.skipped.is_empty());
+
+        let target = FakeWl::new().with_known_network(b"Cafe", None);
+        let mut imported_out = Vec::new();
+        let imported = wl::import(
+            &target,
+            &mut imported_out,
+            &mut out.as_slice(),
+            false,
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![ImportStatus::Added, ImportStatus::Skipped],
+            imported.iter().map(|n| n.status).collect::<Vec<_>>()
+        );
+        assert_eq!(b"Home: added\nCafe: skipped\n".as_slice(), imported_out);
+        assert_eq!(
+            Some(b"secret".to_vec()),
+            target.get_passwd(b"Home").unwrap()
+        );
+        assert_eq!(10, target.get_profile(b"Home").unwrap().priority);
+
+        // The secrets are left out of the file, and the known networks are replaced.
+        let mut out = Vec::new();
+        wl::export(&fake, &mut out, ExportFormat::Json, true).unwrap();
+        assert!(!String::from_utf8_lossy(&out).contains("secret"));
+
+        let mut imported_out = Vec::new();
+        wl::import(
+            &target,
+            &mut imported_out,
+            &mut out.as_slice(),
+            false,
+            ConflictPolicy::Replace,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(
+            b"[{\"ssid\":\"Home\",\"status\":\"replaced\"},{\"ssid\":\"Cafe\",\"status\":\"replaced\"}]\n".as_slice(),
+            imported_out
+        );
+        assert_eq!(None, target.get_passwd(b"Home").unwrap());
+        assert_eq!(
+            vec![b"Home".to_vec(), b"Cafe".to_vec()],
+            target.known_ssids()
+        );
+
+        let err = wl::import(
+            &target,
+            &mut Vec::new(),
+            &mut out.as_slice(),
+            false,
+            ConflictPolicy::Fail,
+            OutputFormat::Text,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Import(ImportError::AlreadyKnown(_))));
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+
+        let err = wl::import(
+            &target,
+            &mut Vec::new(),
+            &mut b"version = 2\n".as_slice(),
+            false,
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Import(ImportError::UnsupportedVersion(2))
+        ));
+
+        let err = wl::import(
+            &target,
+            &mut Vec::new(),
+            &mut b"[[networks]]\nssid = \"Home\"\n".as_slice(),
+            false,
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Import(ImportError::InvalidFile(_))));
+    }
+
+    #[test]
+    fn export_should_round_trip_non_utf8_networks() {
+        let fake = FakeWl::new().with_known_network(b"Caf\xe9", Some(b"s\xffcret"));
+
+        for format in [ExportFormat::Toml, ExportFormat::Json] {
+            let mut out = Vec::new();
+            let report = wl::export(&fake, &mut out, format, false).unwrap();
+            assert_eq!(b"Caf\xe9".as_slice(), report.networks[0].ssid);
+            assert_eq!(Some(b"s\xffcret".to_vec()), report.networks[0].secret);
+
+            // The bytes that are not valid UTF-8 are written as hex digits instead of being replaced.
+            let file = String::from_utf8(out).unwrap();
+            assert!(file.contains("436166e9"));
+            assert!(file.contains("73ff63726574"));
+            assert!(!file.contains('\u{FFFD}'));
+
+            let target = FakeWl::new();
+            wl::import(
+                &target,
+                &mut Vec::new(),
+                &mut file.as_bytes(),
+                false,
+                ConflictPolicy::Skip,
+                OutputFormat::Json,
+            )
+            .unwrap();
+            assert_eq!(vec![b"Caf\xe9".to_vec()], target.known_ssids());
+            assert_eq!(
+                Some(b"s\xffcret".to_vec()),
+                target.get_passwd(b"Caf\xe9").unwrap()
+            );
+        }
+
+        let err = wl::import(
+            &FakeWl::new(),
+            &mut Vec::new(),
+            &mut b"version = 1\n\n[[networks]]\nssid = { hex = \"e9f\" }\nsecurity = \"open\"\n"
+                .as_slice(),
+            false,
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Import(ImportError::InvalidFile(_))));
+    }
+
+    #[test]
+    fn export_should_keep_the_networks_with_unreadable_secrets() {
+        // The secrets that are kept by the secret agent of a user session cannot be read by the backends.
+        let fake = FakeWl::new()
+            .with_known_network(b"Home", Some(b"secret"))
+            .with_known_network(b"Office", Some(b"secret"))
+            .with_failure(Operation::GetPasswd, 1);
+
+        let report = wl::export(&fake, &mut Vec::new(), ExportFormat::Toml, false).unwrap();
+        assert_eq!(2, report.networks.len());
+        assert!(report.networks.iter().all(|n| n.secret.is_none()));
+
+        let report = wl::backup(&fake, &mut Vec::new(), b"passphrase").unwrap();
+        assert_eq!(2, report.networks.len());
+
+        // The other failures of the backend still abort the export.
+        let fake = fake.with_failure(Operation::GetProfile, 1);
+        let err = wl::export(&fake, &mut Vec::new(), ExportFormat::Toml, false).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Backend(_, NetworkAdapterError::CannotGetProfile(_))
+        ));
+    }
+}
@@ -0,0 +1,557 @@
+#[cfg(test)]
+mod tests {
+    use wl::{
+        AccessPoint, ActiveConnection, Auth, ConnectError, DisconnectError, EapCredentials,
+        EapMethod, Error, ErrorKind, FakeWl, NetworkAdapterError, PasswdSource,
+        UNKNOWN_SSID_EXIT_CODE, WifiStatus, Wl,
+        api::{OutputFormat, ScanArgs},
+        fake::Operation,
+    };
+
+    fn access_point(ssid: &str, signal: u8, security: &str) -> AccessPoint {
+        AccessPoint {
+            ssid: ssid.as_bytes().to_vec(),
+            signal,
+            security: security.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fake_should_connect_and_disconnect_through_public_functions() {
+        let fake = FakeWl::new()
+            .with_access_point(access_point("Home", 80, "WPA2"))
+            .with_secret(b"Home", b"secret")
+            .with_known_network(b"Home", Some(b"secret"));
+
+        wl::connect(
+            &fake,
+            Some(b"Home".to_vec()),
+            Auth::default(),
+            PasswdSource::Prompt,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(Some(b"Home".to_vec()), fake.active_ssid());
+
+        let active_networks = fake.get_networks(true).unwrap();
+        assert_eq!(1, active_networks.len());
+        assert_eq!(
+            Some(wl::fake::FAKE_DEVICE),
+            active_networks[0].device.as_deref()
+        );
+
+        wl::disconnect(&fake, Some(b"Home".to_vec()), true, OutputFormat::Json).unwrap();
+        assert_eq!(None, fake.active_ssid());
+        assert!(fake.known_ssids().is_empty());
+    }
+
+    #[test]
+    fn fake_should_render_scan_results() {
+        let fake = FakeWl::new()
+            .with_access_point(access_point("Home", 80, "WPA2"))
+            .with_access_point(access_point("Cafe", 40, ""))
+            .with_active_network(b"Home");
+
+        let args = ScanArgs {
+            min_strength: 50,
+            re_scan: true,
+            columns: None,
+            get_values: Some(String::from("IN-USE,SSID,SECURITY")),
+        };
+
+        let mut out = Vec::new();
+        wl::scan(&fake, &mut out, args, OutputFormat::Text).unwrap();
+
+        assert_eq!(b"*:Home:WPA2\n".as_slice(), out);
+    }
+
+    #[test]
+    fn fake_should_connect_to_enterprise_networks() {
+        let fake = FakeWl::new()
+            .with_access_point(access_point("Corp", 80, "WPA2 802.1X"))
+            .with_secret(b"Corp", b"secret");
+        let peap = EapCredentials {
+            method: EapMethod::Peap,
+            identity: b"user".to_vec(),
+            ..Default::default()
+        };
+
+        let (mut out, mut prompt) = (Vec::new(), Vec::new());
+        let err = wl::connect_with(
+            &fake,
+            &mut out,
+            &mut prompt,
+            &mut &b"wrong\n"[..],
+            Some(b"Corp".to_vec()),
+            Auth::Enterprise(peap.clone()),
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert_eq!(b"Enter the password for user: ".as_slice(), prompt);
+        assert!(matches!(
+            err,
+            Error::Backend(_, NetworkAdapterError::CannotConnect((_, 4)))
+        ));
+
+        let report = wl::connect_with(
+            &fake,
+            &mut out,
+            &mut Vec::new(),
+            &mut &b"secret\n"[..],
+            Some(b"Corp".to_vec()),
+            Auth::Enterprise(peap),
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert!(report.connected);
+        assert_eq!(Some(b"Corp".to_vec()), fake.active_ssid());
+
+        let peap_with_key = EapCredentials {
+            method: EapMethod::Peap,
+            identity: b"user".to_vec(),
+            private_key: Some("user.key".into()),
+            ..Default::default()
+        };
+        let mut prompt = Vec::new();
+        wl::connect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut prompt,
+            &mut &b"secret\n"[..],
+            Some(b"Corp".to_vec()),
+            Auth::Enterprise(peap_with_key),
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(b"Enter the password for user: ".as_slice(), prompt);
+
+        let tls = EapCredentials {
+            method: EapMethod::Tls,
+            identity: b"user".to_vec(),
+            client_cert: Some("user.pem".into()),
+            ..Default::default()
+        };
+        let err = wl::connect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut &b""[..],
+            Some(b"Corp".to_vec()),
+            Auth::Enterprise(tls),
+            OutputFormat::Text,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Connect(ConnectError::MissingEapCredential(
+                EapMethod::Tls,
+                "private key"
+            ))
+        ));
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+    }
+
+    #[test]
+    fn fake_should_pin_known_networks_to_bssids() {
+        let fake = FakeWl::new()
+            .with_access_point(AccessPoint {
+                bssid: String::from("AA:BB:CC:DD:EE:01"),
+                ..access_point("Office", 80, "")
+            })
+            .with_access_point(AccessPoint {
+                bssid: String::from("AA:BB:CC:DD:EE:02"),
+                ..access_point("Office", 40, "")
+            });
+
+        let err = wl::connect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut &b""[..],
+            None,
+            Auth::Psk {
+                force_passwd: false,
+                bssid: Some(String::from("AA:BB:CC:DD:EE")),
+            },
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Connect(ConnectError::InvalidBssid(_))));
+
+        // A signed byte is not a hex digit pair.
+        let err = wl::connect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut &b""[..],
+            None,
+            Auth::Psk {
+                force_passwd: false,
+                bssid: Some(String::from("AA:BB:CC:DD:EE:+F")),
+            },
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Connect(ConnectError::InvalidBssid(_))));
+
+        let err = wl::connect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut &b""[..],
+            None,
+            Auth::Psk {
+                force_passwd: false,
+                bssid: Some(String::from("AA:BB:CC:DD:EE:03")),
+            },
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert_eq!(ErrorKind::SsidNotFound, err.kind());
+
+        let report = wl::connect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut &b"\n"[..],
+            None,
+            Auth::Psk {
+                force_passwd: false,
+                bssid: Some(String::from("aa:bb:cc:dd:ee:02")),
+            },
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(b"Office".as_slice(), report.ssid);
+        assert_eq!(Some("AA:BB:CC:DD:EE:02"), report.bssid.as_deref());
+        assert_eq!(
+            Some("AA:BB:CC:DD:EE:02"),
+            fake.pinned_bssid(b"Office").as_deref()
+        );
+
+        let mut out = Vec::new();
+        wl::unpin(&fake, &mut out, b"Office".to_vec(), OutputFormat::Text).unwrap();
+        assert_eq!(
+            b"Connection 'Office' is no longer pinned to a BSSID.\n".as_slice(),
+            out
+        );
+        assert_eq!(None, fake.pinned_bssid(b"Office"));
+
+        let err = wl::unpin(
+            &fake,
+            &mut Vec::new(),
+            b"Unknown".to_vec(),
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert_eq!(ErrorKind::SsidNotFound, err.kind());
+    }
+
+    #[test]
+    fn fake_should_connect_with_passwd_source() {
+        let fake = FakeWl::new()
+            .with_access_point(access_point("Cafe", 60, "WPA2"))
+            .with_secret(b"Cafe", b"secret");
+
+        let path = std::env::temp_dir().join(format!("wl-test-passwd-{}", std::process::id()));
+        std::fs::write(&path, "secret\nignored\n").unwrap();
+
+        let connected = wl::connect(
+            &fake,
+            Some(b"Cafe".to_vec()),
+            Auth::default(),
+            PasswdSource::File(path.clone()),
+            OutputFormat::Json,
+        );
+        std::fs::remove_file(&path).unwrap();
+        connected.unwrap();
+        assert_eq!(Some(b"Cafe".to_vec()), fake.active_ssid());
+
+        let err = wl::connect(
+            &fake,
+            Some(b"Cafe".to_vec()),
+            Auth::Psk {
+                force_passwd: true,
+                bssid: None,
+            },
+            PasswdSource::File(path),
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Connect(ConnectError::CannotReadPasswdFile(..))
+        ));
+        assert_eq!(ErrorKind::Io, err.kind());
+
+        let err = wl::connect(
+            &fake,
+            Some(b"Cafe".to_vec()),
+            Auth::Psk {
+                force_passwd: true,
+                bssid: None,
+            },
+            PasswdSource::Env(String::from("WL_TEST_UNSET_PASSWD")),
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Connect(ConnectError::CannotReadPasswdEnv(ref name, _)) if name == "WL_TEST_UNSET_PASSWD"
+        ));
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+    }
+
+    #[test]
+    fn fake_should_connect_to_hidden_networks() {
+        let fake = FakeWl::new()
+            .with_hidden_network(access_point("Attic", 60, "WPA3"))
+            .with_secret(b"Attic", b"secret");
+
+        let aps = fake.get_access_points(true).unwrap();
+        assert_eq!(1, aps.len());
+        assert!(aps[0].ssid.is_empty());
+
+        // A hidden network is only found by the regular connect if it is known.
+        let result = wl::connect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut &b"secret\n"[..],
+            Some(b"Attic".to_vec()),
+            Auth::default(),
+            OutputFormat::Json,
+        );
+        assert!(matches!(result, Err(Error::Backend(..))));
+
+        let err = wl::connect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut &b"Attic\nwep\n"[..],
+            None,
+            Auth::Hidden { security: None },
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::Connect(ConnectError::InvalidSecurity(security)) if security == "wep"
+        ));
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+
+        let (mut out, mut prompt) = (Vec::new(), Vec::new());
+        let report = wl::connect_with(
+            &fake,
+            &mut out,
+            &mut prompt,
+            &mut &b"Attic\nSAE\nsecret\n"[..],
+            None,
+            Auth::Hidden { security: None },
+            OutputFormat::Json,
+        )
+        .unwrap();
+
+        assert!(report.connected);
+        assert_eq!(
+            b"Enter the SSID of the hidden network: Select the security of the hidden network (open, wpa-psk, sae): Enter the password for Attic: ".as_slice(),
+            prompt
+        );
+        assert_eq!(Some(b"Attic".to_vec()), fake.active_ssid());
+        assert_eq!(vec![b"Attic".to_vec()], fake.known_ssids());
+    }
+
+    #[test]
+    fn fake_should_validate_passwords() {
+        let fake = FakeWl::new()
+            .with_access_point(access_point("Home", 80, "WPA2"))
+            .with_secret(b"Home", b"secret");
+
+        let err = fake
+            .connect(b"Home", Some(b"wrong"), None, false)
+            .unwrap_err();
+        assert!(matches!(err, NetworkAdapterError::CannotConnect((_, 4))));
+        assert!(fake.known_ssids().is_empty());
+
+        let err = fake.connect(b"Unknown", None, None, false).unwrap_err();
+        assert!(matches!(
+            err,
+            NetworkAdapterError::CannotConnect((_, UNKNOWN_SSID_EXIT_CODE))
+        ));
+
+        fake.connect(b"Home", Some(b"secret"), None, false).unwrap();
+        assert_eq!(vec![b"Home".to_vec()], fake.known_ssids());
+
+        fake.disconnect(b"Home", false).unwrap();
+        fake.connect(b"Home", None, None, true).unwrap();
+        assert_eq!(Some(b"Home".to_vec()), fake.active_ssid());
+    }
+
+    #[test]
+    fn fake_should_disconnect_when_wifi_is_disabled() {
+        let fake = FakeWl::new()
+            .with_access_point(access_point("Home", 80, ""))
+            .with_active_network(b"Home");
+
+        assert_eq!(WifiStatus::Disabled, fake.toggle_wifi().unwrap());
+        assert_eq!(None, fake.active_ssid());
+        assert!(fake.get_access_points(false).is_err());
+        assert!(fake.connect(b"Home", None, None, true).is_err());
+
+        wl::toggle(&fake, OutputFormat::Json).unwrap();
+        assert_eq!(WifiStatus::Enabled, fake.wifi_status());
+    }
+
+    #[test]
+    fn fake_should_inject_failures() {
+        let fake = FakeWl::new().with_failure(Operation::GetWifiStatus, 8);
+
+        let err = wl::status(&fake, OutputFormat::Text).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Backend(
+                ErrorKind::BackendMissing,
+                NetworkAdapterError::CannotGetWiFiStatus((_, 8))
+            )
+        ));
+
+        assert!(fake.get_networks(false).is_ok());
+    }
+
+    #[test]
+    fn fake_should_write_status_and_networks_on_the_given_writer() {
+        let fake = FakeWl::new()
+            .with_known_network(b"Cafe", None)
+            .with_active_network(b"Home");
+
+        let mut out = Vec::new();
+        let report = wl::status_with(&fake, &mut out, OutputFormat::Json).unwrap();
+
+        assert_eq!(WifiStatus::Enabled, report.wifi);
+        assert_eq!(
+            vec![ActiveConnection {
+                ssid: b"Home".to_vec(),
+                device: wl::fake::FAKE_DEVICE.to_string(),
+            }],
+            report.connections
+        );
+        assert_eq!(
+            b"{\"wifi\":\"enabled\",\"connections\":[{\"ssid\":\"Home\",\"device\":\"wlan0\"}]}\n"
+                .as_slice(),
+            out
+        );
+
+        let mut out = Vec::new();
+        let networks =
+            wl::list_networks_with(&fake, &mut out, false, true, OutputFormat::Ndjson).unwrap();
+
+        assert_eq!(2, networks.len());
+        assert_eq!(
+            b"{\"ssid\":\"Cafe\"}\n{\"ssid\":\"Home\"}\n".as_slice(),
+            out
+        );
+    }
+
+    #[test]
+    fn fake_should_prompt_and_read_from_the_given_streams() {
+        let fake = FakeWl::new()
+            .with_access_point(access_point("Home", 80, "WPA2"))
+            .with_access_point(access_point("", 70, ""))
+            .with_access_point(access_point("Cafe", 40, ""))
+            .with_secret(b"Home", b"secret");
+
+        let (mut out, mut prompt) = (Vec::new(), Vec::new());
+        let mut input = &b"0\n  secret \n"[..];
+
+        let report = wl::connect_with(
+            &fake,
+            &mut out,
+            &mut prompt,
+            &mut input,
+            None,
+            Auth::default(),
+            OutputFormat::Text,
+        )
+        .unwrap();
+
+        assert!(report.connected);
+        assert_eq!(b"Home".as_slice(), report.ssid);
+        assert_eq!(report.message, out);
+        assert_eq!(
+            b"(0) Home (sig: 80)\n(1) Cafe (sig: 40)\nSelect the SSID to connect: Enter the password for Home: "
+                .as_slice(),
+            prompt
+        );
+        assert_eq!(Some(b"Home".to_vec()), fake.active_ssid());
+
+        let (mut out, mut prompt) = (Vec::new(), Vec::new());
+        let report = wl::disconnect_with(
+            &fake,
+            &mut out,
+            &mut prompt,
+            &mut &b"0\n"[..],
+            None,
+            true,
+            OutputFormat::Text,
+        )
+        .unwrap();
+
+        assert!(report.disconnected && report.forgotten);
+        assert_eq!(report.message, out);
+        assert_eq!(None, fake.active_ssid());
+        assert!(fake.known_ssids().is_empty());
+    }
+
+    #[test]
+    fn fake_should_fail_on_invalid_answers() {
+        let fake = FakeWl::new()
+            .with_access_point(access_point("Home", 80, ""))
+            .with_active_network(b"Home");
+
+        let err = wl::connect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut &b"5\n"[..],
+            None,
+            Auth::default(),
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::Connect(ConnectError::InvalidSSIDSelection(selection)) if selection == "5"
+        ));
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+
+        let err = wl::disconnect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut &b"home\n"[..],
+            None,
+            false,
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Disconnect(DisconnectError::InvalidActiveSSID(_))
+        ));
+        assert_eq!(Some(b"Home".to_vec()), fake.active_ssid());
+
+        let err = wl::connect_with(
+            &fake,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut &b""[..],
+            None,
+            Auth::default(),
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::UserAborted));
+        assert_eq!(130, err.exit_code());
+    }
+}
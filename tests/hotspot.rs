@@ -0,0 +1,284 @@
+#[cfg(test)]
+mod tests {
+    use wl::{
+        Band, ConnectError, Error, ErrorKind, FakeWl, HotspotClient, HotspotError,
+        NetworkAdapterError, Nmcli, PasswdSource, UNKNOWN_SSID_EXIT_CODE, Wl, WpaSupplicant,
+        api::OutputFormat,
+        runner::{FakeRunner, Reply},
+    };
+
+    const HOTSPOT_PROFILE_FIELDS: &str = "802-11-wireless.ssid,802-11-wireless.band,802-11-wireless.channel,802-11-wireless-security.key-mgmt";
+
+    fn client(mac: &str, ip: Option<&str>, hostname: Option<&str>) -> HotspotClient {
+        HotspotClient {
+            mac: mac.to_string(),
+            ip: ip.map(String::from),
+            hostname: hostname.map(String::from),
+        }
+    }
+
+    #[test]
+    fn hotspot_should_start_with_passwd_source() {
+        let fake = FakeWl::new();
+
+        let path = std::env::temp_dir().join(format!("wl-test-hotspot-{}", std::process::id()));
+        std::fs::write(&path, "  password  \nignored\n").unwrap();
+
+        let passwd = wl::read_passwd(
+            b"Field",
+            PasswdSource::File(path.clone()),
+            OutputFormat::Json,
+        );
+        std::fs::remove_file(&path).unwrap();
+        let passwd = passwd.unwrap();
+        assert_eq!(b"password".as_slice(), passwd);
+
+        let hotspot = wl::hotspot_start(
+            &fake,
+            &mut Vec::new(),
+            b"Field".to_vec(),
+            Some(passwd),
+            None,
+            None,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert!(hotspot.secured);
+
+        let err =
+            wl::read_passwd(b"Field", PasswdSource::File(path), OutputFormat::Json).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Connect(ConnectError::CannotReadPasswdFile(..))
+        ));
+    }
+
+    #[test]
+    fn hotspot_should_start_report_and_stop_on_fake() {
+        let fake = FakeWl::new()
+            .with_active_network(b"Home")
+            .with_hotspot_client(client(
+                "aa:bb:cc:dd:ee:ff",
+                Some("10.42.0.23"),
+                Some("phone"),
+            ))
+            .with_hotspot_client(client("11:22:33:44:55:66", None, None));
+
+        let mut out = Vec::new();
+        let hotspot = wl::hotspot_start(
+            &fake,
+            &mut out,
+            b"Field".to_vec(),
+            Some(b"password".to_vec()),
+            Some(Band::Bg),
+            Some(6),
+            OutputFormat::Text,
+        )
+        .unwrap();
+
+        assert!(hotspot.secured);
+        assert_eq!(None, fake.active_ssid());
+        assert_eq!(
+            b"hotspot: active\nssid: Field\ndevice: wlan0\nband: bg\nchannel: 6\nsecurity: wpa-psk\nclients: aa:bb:cc:dd:ee:ff/10.42.0.23 (phone), 11:22:33:44:55:66\n"
+                .as_slice(),
+            out
+        );
+
+        let mut out = Vec::new();
+        let status = wl::hotspot_status(&fake, &mut out, OutputFormat::Json).unwrap();
+
+        assert_eq!(Some(hotspot), status);
+        assert_eq!(
+            b"{\"active\":true,\"ssid\":\"Field\",\"device\":\"wlan0\",\"band\":\"bg\",\"channel\":6,\"secured\":true,\"clients\":[{\"mac\":\"aa:bb:cc:dd:ee:ff\",\"ip\":\"10.42.0.23\",\"hostname\":\"phone\"},{\"mac\":\"11:22:33:44:55:66\",\"ip\":null,\"hostname\":null}]}\n"
+                .as_slice(),
+            out
+        );
+
+        let mut out = Vec::new();
+        wl::hotspot_stop(&fake, &mut out, OutputFormat::Json).unwrap();
+
+        assert_eq!(None, fake.hotspot());
+        assert_eq!(
+            b"{\"ssid\":\"Field\",\"device\":\"wlan0\",\"stopped\":true}\n".as_slice(),
+            out
+        );
+
+        let mut out = Vec::new();
+        wl::hotspot_status(&fake, &mut out, OutputFormat::Text).unwrap();
+        assert_eq!(b"hotspot: inactive\n".as_slice(), out);
+
+        let err = wl::hotspot_stop(&fake, &mut Vec::new(), OutputFormat::Text).unwrap_err();
+        assert_eq!(ErrorKind::SsidNotFound, err.kind());
+    }
+
+    #[test]
+    fn hotspot_should_validate_passwords() {
+        let fake = FakeWl::new();
+
+        for passwd in ["short", &"x".repeat(64)] {
+            let err = wl::hotspot_start(
+                &fake,
+                &mut Vec::new(),
+                b"Field".to_vec(),
+                Some(passwd.as_bytes().to_vec()),
+                None,
+                None,
+                OutputFormat::Text,
+            )
+            .unwrap_err();
+
+            assert!(matches!(
+                err,
+                Error::Hotspot(HotspotError::InvalidPassword(len)) if len == passwd.len()
+            ));
+            assert_eq!(2, err.exit_code());
+        }
+        assert_eq!(None, fake.hotspot());
+
+        let mut out = Vec::new();
+        let hotspot = wl::hotspot_start(
+            &fake,
+            &mut out,
+            b"Field".to_vec(),
+            None,
+            None,
+            None,
+            OutputFormat::Text,
+        )
+        .unwrap();
+
+        assert!(!hotspot.secured);
+        assert!(out.ends_with(b"band: auto\nchannel: auto\nsecurity: open\nclients: none\n"));
+    }
+
+    #[test]
+    fn hotspot_should_start_and_stop_nmcli_profile() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &["connection", "delete", "id", "wl-hotspot"],
+                Reply::failure(10, "Error: unknown connection 'wl-hotspot'.\n"),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "add",
+                    "type",
+                    "wifi",
+                    "con-name",
+                    "wl-hotspot",
+                    "autoconnect",
+                    "no",
+                    "ssid",
+                    "Field",
+                    "802-11-wireless.mode",
+                    "ap",
+                    "ipv4.method",
+                    "shared",
+                    "ipv6.method",
+                    "ignore",
+                    "802-11-wireless.band",
+                    "a",
+                    "802-11-wireless.channel",
+                    "36",
+                    "wifi-sec.key-mgmt",
+                    "wpa-psk",
+                ],
+                Reply::stdout("Connection 'wl-hotspot' successfully added.\n"),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "up",
+                    "id",
+                    "wl-hotspot",
+                    "passwd-file",
+                    "/dev/stdin",
+                ],
+                Reply::stdout("Connection successfully activated.\n"),
+            )
+            .reply(
+                "nmcli",
+                &["-g", "NAME,DEVICE", "connection", "show", "--active"],
+                Reply::stdout("wl-hotspot:wl-test0\nlo:lo\n"),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "-g",
+                    HOTSPOT_PROFILE_FIELDS,
+                    "connection",
+                    "show",
+                    "id",
+                    "wl-hotspot",
+                ],
+                Reply::stdout("Field\na\n36\nwpa-psk\n"),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let hotspot = nmcli
+            .start_hotspot(b"Field", Some(b"password"), None, Some(36))
+            .unwrap();
+
+        assert_eq!(b"Field".as_slice(), hotspot.ssid);
+        assert_eq!("wl-test0", hotspot.device);
+        assert_eq!(Some(Band::A), hotspot.band);
+        assert_eq!(Some(36), hotspot.channel);
+        assert!(hotspot.secured);
+        assert_eq!(None, hotspot.clients);
+        assert_eq!(
+            vec![
+                "nmcli connection delete id wl-hotspot",
+                "nmcli connection add type wifi con-name wl-hotspot autoconnect no ssid Field 802-11-wireless.mode ap ipv4.method shared ipv6.method ignore 802-11-wireless.band a 802-11-wireless.channel 36 wifi-sec.key-mgmt wpa-psk",
+                "nmcli connection up id wl-hotspot passwd-file /dev/stdin",
+                "nmcli -g NAME,DEVICE connection show --active",
+                "nmcli -g 802-11-wireless.ssid,802-11-wireless.band,802-11-wireless.channel,802-11-wireless-security.key-mgmt connection show id wl-hotspot",
+            ],
+            nmcli.runner().calls()
+        );
+
+        // The password is written to stdin instead of the arguments.
+        assert!(
+            nmcli
+                .runner()
+                .calls()
+                .iter()
+                .all(|call| !call.contains("password"))
+        );
+        assert_eq!(
+            vec![b"802-11-wireless-security.psk:password\n".to_vec()],
+            nmcli.runner().inputs()
+        );
+    }
+
+    #[test]
+    fn hotspot_should_report_inactive_nmcli_profile() {
+        let runner = FakeRunner::new().reply(
+            "nmcli",
+            &["-g", "NAME,DEVICE", "connection", "show", "--active"],
+            Reply::stdout("Home:wlp2s0\nlo:lo\n"),
+        );
+        let nmcli = Nmcli::with_runner(runner);
+
+        assert_eq!(None, nmcli.get_hotspot().unwrap());
+
+        let err = nmcli.stop_hotspot().unwrap_err();
+        assert!(matches!(
+            err,
+            NetworkAdapterError::CannotStopHotspot((_, UNKNOWN_SSID_EXIT_CODE))
+        ));
+        assert_eq!(2, nmcli.runner().calls().len());
+    }
+
+    #[test]
+    fn hotspot_should_be_unsupported_by_default() {
+        let wpa = WpaSupplicant::with_ctrl_path("/nonexistent/wl-test");
+
+        let err = wl::hotspot_status(&wpa, &mut Vec::new(), OutputFormat::Text).unwrap_err();
+
+        assert_eq!(ErrorKind::Unsupported, err.kind());
+        assert_eq!(69, err.exit_code());
+    }
+}
@@ -0,0 +1,478 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        os::unix::net::UnixStream,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use wl::{
+        Iwd, NetworkAdapterError, Security, UNKNOWN_SSID_EXIT_CODE, WifiStatus, Wl,
+        runner::{FakeRunner, Reply},
+    };
+    use zbus::{
+        Guid,
+        blocking::{Connection, connection::Builder},
+        fdo, interface,
+        zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value},
+    };
+
+    type Calls = Arc<Mutex<Vec<String>>>;
+    type Agent = Arc<Mutex<Option<OwnedObjectPath>>>;
+
+    const STATION_PATH: &str = "/net/connman/iwd/0/4";
+    const NETWORK_PATH: &str = "/net/connman/iwd/0/4/486f6d65_psk";
+
+    const DEVICE_LIST: &[u8] = include_bytes!("fixtures/iwctl/device_list.txt");
+    const DEVICE_LIST_POWERED_OFF: &[u8] =
+        include_bytes!("fixtures/iwctl/device_list_powered_off.txt");
+    const STATION_SHOW: &[u8] = include_bytes!("fixtures/iwctl/station_show.txt");
+    const STATION_LIST: &[u8] = include_bytes!("fixtures/iwctl/station_list.txt");
+    const KNOWN_NETWORKS: &[u8] = include_bytes!("fixtures/iwctl/known_networks.txt");
+    const GET_NETWORKS: &[u8] = include_bytes!("fixtures/iwctl/get_networks.txt");
+
+    fn runner() -> FakeRunner {
+        FakeRunner::new()
+            .reply("iwctl", &["device", "list"], Reply::stdout(DEVICE_LIST))
+            .reply(
+                "iwctl",
+                &["station", "wlan0", "show"],
+                Reply::stdout(STATION_SHOW),
+            )
+            .reply(
+                "iwctl",
+                &["known-networks", "list"],
+                Reply::stdout(KNOWN_NETWORKS),
+            )
+    }
+
+    #[test]
+    fn iwd_should_get_and_toggle_wifi_status() {
+        let runner = runner().reply(
+            "iwctl",
+            &["device", "wlan0", "set-property", "Powered", "off"],
+            Reply::stdout(""),
+        );
+        let iwd = Iwd::with_runner(runner);
+
+        assert_eq!(WifiStatus::Enabled, iwd.get_wifi_status().unwrap());
+        assert_eq!(WifiStatus::Disabled, iwd.toggle_wifi().unwrap());
+        assert_eq!(
+            "iwctl device wlan0 set-property Powered off",
+            iwd.runner().calls()[2]
+        );
+
+        // The devices that are not in the station mode are skipped.
+        let runner = FakeRunner::new()
+            .reply(
+                "iwctl",
+                &["device", "list"],
+                Reply::stdout(DEVICE_LIST_POWERED_OFF),
+            )
+            .reply(
+                "iwctl",
+                &["device", "wlan0", "set-property", "Powered", "on"],
+                Reply::stdout(""),
+            );
+        let iwd = Iwd::with_runner(runner);
+
+        assert_eq!(WifiStatus::Disabled, iwd.get_wifi_status().unwrap());
+        assert_eq!(WifiStatus::Enabled, iwd.toggle_wifi().unwrap());
+    }
+
+    #[test]
+    fn iwd_should_list_known_networks() {
+        let iwd = Iwd::with_runner(runner());
+
+        let networks = iwd.get_networks(false).unwrap();
+        assert_eq!(
+            vec![b"Home".to_vec(), b"Attic".to_vec(), b"Library".to_vec()],
+            networks.iter().map(|n| n.ssid.clone()).collect::<Vec<_>>()
+        );
+        assert_eq!(Some("wlan0"), networks[0].device.as_deref());
+        assert!(networks[1..].iter().all(|n| n.device.is_none()));
+        assert!(
+            networks
+                .iter()
+                .all(|n| n.uuid.is_empty() && n.kind == "wifi")
+        );
+
+        let active = iwd.get_networks(true).unwrap();
+        assert_eq!(1, active.len());
+        assert_eq!(b"Home".as_slice(), active[0].ssid);
+
+        assert!(iwd.is_known_ssid(b"Attic").unwrap());
+        assert!(!iwd.is_known_ssid(b"Cafe").unwrap());
+    }
+
+    #[test]
+    fn iwd_should_get_access_points_from_colored_output() {
+        let runner = runner().reply(
+            "iwctl",
+            &["station", "wlan0", "get-networks", "rssi-dbms"],
+            Reply::stdout(GET_NETWORKS),
+        );
+        let iwd = Iwd::with_runner(runner);
+
+        let aps = iwd.get_access_points(false).unwrap();
+        assert_eq!(3, aps.len());
+
+        // Only the connected access point has its BSSID, frequency and rate.
+        let home = &aps[0];
+        assert_eq!(b"Home".as_slice(), home.ssid);
+        assert!(home.in_use);
+        assert_eq!("AA:BB:CC:DD:EE:01", home.bssid);
+        assert_eq!(5180, home.frequency);
+        assert_eq!(36, home.channel);
+        assert_eq!(866, home.rate);
+        assert_eq!(100, home.signal);
+        assert_eq!("WPA2", home.security);
+
+        let cafe = &aps[1];
+        assert_eq!(b"Cafe".as_slice(), cafe.ssid);
+        assert!(!cafe.in_use);
+        assert!(cafe.bssid.is_empty());
+        assert_eq!(0, cafe.frequency);
+        assert_eq!(56, cafe.signal);
+        assert_eq!("", cafe.security);
+
+        let corp = &aps[2];
+        assert_eq!(b"ACME Corp 5G".as_slice(), corp.ssid);
+        assert_eq!(34, corp.signal);
+        assert_eq!("802.1X", corp.security);
+    }
+
+    #[test]
+    fn iwd_should_re_scan_and_parse_plain_dbm() {
+        // The older versions of iwd report the RSSI in dBm instead of 100 * dBm.
+        let get_networks = "\
+                               Available networks
+--------------------------------------------------------------------------------
+    Network name                      Security            Signal
+--------------------------------------------------------------------------------
+    Library                           open                -60
+";
+        let runner = runner()
+            .reply("iwctl", &["station", "wlan0", "scan"], Reply::stdout(""))
+            .reply("iwctl", &["station", "list"], Reply::stdout(STATION_LIST))
+            .reply(
+                "iwctl",
+                &["station", "wlan0", "get-networks", "rssi-dbms"],
+                Reply::stdout(get_networks),
+            );
+        let iwd = Iwd::with_runner(runner);
+
+        let aps = iwd.get_access_points(true).unwrap();
+        assert_eq!(1, aps.len());
+        assert_eq!(b"Library".as_slice(), aps[0].ssid);
+        assert_eq!(80, aps[0].signal);
+        assert!(!aps[0].in_use);
+
+        assert_eq!(
+            vec![
+                "iwctl device list",
+                "iwctl station wlan0 scan",
+                "iwctl station list",
+                "iwctl station wlan0 get-networks rssi-dbms",
+                "iwctl station wlan0 show",
+            ],
+            iwd.runner().calls()
+        );
+    }
+
+    #[test]
+    fn iwd_should_connect() {
+        let runner = runner()
+            .reply(
+                "iwctl",
+                &["--dont-ask", "station", "wlan0", "connect", "Home"],
+                Reply::stdout(""),
+            )
+            .reply(
+                "iwctl",
+                &["known-networks", "Home", "forget"],
+                Reply::stdout(""),
+            )
+            .reply(
+                "iwctl",
+                &["--dont-ask", "station", "wlan0", "connect-hidden", "Attic"],
+                Reply::stdout(""),
+            );
+        let iwd = Iwd::with_runner(runner);
+
+        assert_eq!(
+            b"Device 'wlan0' is successfully connected to 'Home'.\n".as_slice(),
+            iwd.connect(b"Home", None, None, true).unwrap()
+        );
+        iwd.connect_hidden(b"Attic", Security::Open, None, false)
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                "iwctl device list",
+                "iwctl --dont-ask station wlan0 connect Home",
+                "iwctl device list",
+                "iwctl --dont-ask station wlan0 connect-hidden Attic",
+            ],
+            iwd.runner().calls()
+        );
+
+        let err = iwd
+            .connect(b"Home", None, Some("AA:BB:CC:DD:EE:01"), true)
+            .unwrap_err();
+        assert!(matches!(err, NetworkAdapterError::CannotConnect(_)));
+    }
+
+    #[test]
+    fn iwd_should_disconnect_and_forget() {
+        let runner = runner()
+            .reply(
+                "iwctl",
+                &["station", "wlan0", "disconnect"],
+                Reply::stdout(""),
+            )
+            .reply(
+                "iwctl",
+                &["known-networks", "Attic", "forget"],
+                Reply::stdout(""),
+            );
+        let iwd = Iwd::with_runner(runner);
+
+        assert_eq!(
+            b"Network 'Home' is successfully disconnected.\n".as_slice(),
+            iwd.disconnect(b"Home", false).unwrap()
+        );
+        assert_eq!(
+            b"Network 'Attic' is successfully forgotten.\n".as_slice(),
+            iwd.disconnect(b"Attic", true).unwrap()
+        );
+
+        let err = iwd.disconnect(b"Cafe", false).unwrap_err();
+        assert!(matches!(
+            err,
+            NetworkAdapterError::CannotDisconnect((_, UNKNOWN_SSID_EXIT_CODE))
+        ));
+        assert!(err.to_string().contains("Cafe is not an active network"));
+    }
+
+    #[test]
+    fn iwd_should_strip_colors_from_errors() {
+        let runner = FakeRunner::new().reply(
+            "iwctl",
+            &["device", "list"],
+            Reply::failure(1, "\x1b[1;91mOperation failed\x1b[0m\n"),
+        );
+        let iwd = Iwd::with_runner(runner);
+
+        let err = iwd.get_wifi_status().unwrap_err();
+        match err {
+            NetworkAdapterError::CannotGetWiFiStatus((err, 1)) => {
+                assert_eq!("Operation failed", err.to_string())
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+
+    /// Asks the passphrase of the given network to the registered agent, as iwd does.
+    async fn request_passphrase(
+        conn: &zbus::Connection,
+        agent: &Agent,
+        network: &str,
+    ) -> fdo::Result<String> {
+        let agent = agent.lock().unwrap().clone();
+        let agent = agent.ok_or_else(|| fdo::Error::Failed("no agent".to_string()))?;
+        let reply = conn
+            .call_method(
+                None::<&str>,
+                &agent,
+                Some("net.connman.iwd.Agent"),
+                "RequestPassphrase",
+                &(ObjectPath::try_from(network).unwrap(),),
+            )
+            .await?;
+
+        Ok(reply.body().deserialize()?)
+    }
+
+    struct FakeObjectManager;
+
+    #[interface(name = "org.freedesktop.DBus.ObjectManager")]
+    impl FakeObjectManager {
+        fn get_managed_objects(
+            &self,
+        ) -> HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>> {
+            let value = |v: Value<'_>| OwnedValue::try_from(v).unwrap();
+            let station = HashMap::from([
+                (
+                    "net.connman.iwd.Device".to_string(),
+                    HashMap::from([("Name".to_string(), value(Value::from("wlan0")))]),
+                ),
+                ("net.connman.iwd.Station".to_string(), HashMap::new()),
+            ]);
+            let network = HashMap::from([(
+                "net.connman.iwd.Network".to_string(),
+                HashMap::from([
+                    ("Name".to_string(), value(Value::from("Home"))),
+                    (
+                        "Device".to_string(),
+                        value(Value::from(ObjectPath::try_from(STATION_PATH).unwrap())),
+                    ),
+                ]),
+            )]);
+
+            HashMap::from([
+                (OwnedObjectPath::try_from(STATION_PATH).unwrap(), station),
+                (OwnedObjectPath::try_from(NETWORK_PATH).unwrap(), network),
+            ])
+        }
+    }
+
+    struct FakeAgentManager {
+        calls: Calls,
+        agent: Agent,
+    }
+
+    #[interface(name = "net.connman.iwd.AgentManager")]
+    impl FakeAgentManager {
+        fn register_agent(&self, path: OwnedObjectPath) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("RegisterAgent {}", path.as_str()));
+            *self.agent.lock().unwrap() = Some(path);
+        }
+
+        fn unregister_agent(&self, path: OwnedObjectPath) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("UnregisterAgent {}", path.as_str()));
+            *self.agent.lock().unwrap() = None;
+        }
+    }
+
+    struct FakeStation {
+        calls: Calls,
+        agent: Agent,
+    }
+
+    #[interface(name = "net.connman.iwd.Station")]
+    impl FakeStation {
+        async fn connect_hidden_network(
+            &self,
+            ssid: String,
+            #[zbus(connection)] conn: &zbus::Connection,
+        ) -> fdo::Result<()> {
+            let passphrase = request_passphrase(conn, &self.agent, STATION_PATH).await?;
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("ConnectHiddenNetwork {ssid} {passphrase}"));
+            Ok(())
+        }
+    }
+
+    struct FakeNetwork {
+        calls: Calls,
+        agent: Agent,
+    }
+
+    #[interface(name = "net.connman.iwd.Network")]
+    impl FakeNetwork {
+        async fn connect(&self, #[zbus(connection)] conn: &zbus::Connection) -> fdo::Result<()> {
+            let passphrase = request_passphrase(conn, &self.agent, NETWORK_PATH).await?;
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("Connect Home {passphrase}"));
+            Ok(())
+        }
+    }
+
+    fn start_fake_iwd(calls: &Calls) -> zbus::Result<(Connection, Connection)> {
+        let (server_stream, client_stream) = UnixStream::pair()?;
+
+        let calls = Arc::clone(calls);
+        let agent = Agent::default();
+        let server = thread::spawn(move || {
+            Builder::async_io_unix_stream(server_stream)
+                .server(Guid::generate())?
+                .p2p()
+                .serve_at("/", FakeObjectManager)?
+                .serve_at(
+                    "/net/connman/iwd",
+                    FakeAgentManager {
+                        calls: Arc::clone(&calls),
+                        agent: Arc::clone(&agent),
+                    },
+                )?
+                .serve_at(
+                    STATION_PATH,
+                    FakeStation {
+                        calls: Arc::clone(&calls),
+                        agent: Arc::clone(&agent),
+                    },
+                )?
+                .serve_at(NETWORK_PATH, FakeNetwork { calls, agent })?
+                .build()
+        });
+
+        let client = Builder::async_io_unix_stream(client_stream).p2p().build()?;
+        let server = server.join().unwrap()?;
+
+        Ok((server, client))
+    }
+
+    #[test]
+    fn iwd_should_provide_passphrases_through_an_agent() -> zbus::Result<()> {
+        let calls = Calls::default();
+        let (_server, client) = start_fake_iwd(&calls)?;
+
+        let runner = runner().reply(
+            "iwctl",
+            &["known-networks", "Home", "forget"],
+            Reply::stdout(""),
+        );
+        let iwd = Iwd::with_runner(runner).with_connection(client);
+
+        assert_eq!(
+            b"Device 'wlan0' is successfully connected to 'Home'.\n".as_slice(),
+            iwd.connect(b"Home", Some(b"secret"), None, true).unwrap()
+        );
+        iwd.connect_hidden(b"Attic", Security::Sae, Some(b"hunter22"), false)
+            .unwrap();
+
+        let err = iwd
+            .connect(b"Cafe", Some(b"secret"), None, false)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            NetworkAdapterError::CannotConnect((_, UNKNOWN_SSID_EXIT_CODE))
+        ));
+
+        // The passphrase is never passed to `iwctl`.
+        assert_eq!(
+            vec![
+                "iwctl known-networks Home forget",
+                "iwctl device list",
+                "iwctl device list",
+                "iwctl device list",
+            ],
+            iwd.runner().calls()
+        );
+        assert_eq!(
+            vec![
+                "RegisterAgent /org/wl/iwd/agent",
+                "Connect Home secret",
+                "UnregisterAgent /org/wl/iwd/agent",
+                "RegisterAgent /org/wl/iwd/agent",
+                "ConnectHiddenNetwork Attic hunter22",
+                "UnregisterAgent /org/wl/iwd/agent",
+            ],
+            *calls.lock().unwrap()
+        );
+
+        Ok(())
+    }
+}
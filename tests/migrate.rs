@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use wl::{
+        Error, ExportedNetwork, FakeWl, ImportError, ImportStatus, Security, Wl,
+        api::{ConflictPolicy, ImportSource, OutputFormat},
+    };
+
+    const WPA_SUPPLICANT_CONF: &str = include_str!("fixtures/wpa_supplicant/wpa_supplicant.conf");
+    const LATIN1_CONF: &str = include_str!("fixtures/wpa_supplicant/latin1.conf");
+    const PSK_HASH: &str = "2c4ad6e5a3e1f1f6ab7d1d8e8d0e3a3c8a7b6f5e4d3c2b1a0f9e8d7c6b5a4f3e";
+
+    fn fixture(path: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), path)
+    }
+
+    #[test]
+    fn migrate_should_parse_wpa_supplicant_conf() {
+        let report = wl::parse_wpa_supplicant_conf(WPA_SUPPLICANT_CONF).unwrap();
+
+        assert_eq!(
+            vec![
+                ExportedNetwork {
+                    ssid: b"Home".to_vec(),
+                    security: Security::WpaPsk,
+                    secret: Some(b"secret".to_vec()),
+                    hidden: false,
+                    priority: 10,
+                    autoconnect: true,
+                },
+                ExportedNetwork {
+                    ssid: "Café".as_bytes().to_vec(),
+                    security: Security::WpaPsk,
+                    secret: Some(PSK_HASH.as_bytes().to_vec()),
+                    hidden: true,
+                    priority: 0,
+                    autoconnect: false,
+                },
+                ExportedNetwork {
+                    ssid: b"Attic!".to_vec(),
+                    security: Security::Sae,
+                    secret: Some(b"attic-secret".to_vec()),
+                    hidden: false,
+                    priority: 0,
+                    autoconnect: true,
+                },
+                ExportedNetwork {
+                    ssid: b"Library".to_vec(),
+                    security: Security::Open,
+                    secret: None,
+                    hidden: false,
+                    priority: 0,
+                    autoconnect: true,
+                },
+            ],
+            report.networks
+        );
+        assert_eq!(vec![b"Corp".to_vec(), b"Legacy".to_vec()], report.skipped);
+
+        for (conf, line) in [
+            (
+                "network={\n\tpsk=\"secret\"\n}\n",
+                "line 1: the network block has no ssid",
+            ),
+            (
+                "\nnetwork={\n\tssid=\"Home\"\n",
+                "line 2: the network block is not closed",
+            ),
+            (
+                "network={\n\tssid=zz\n}\n",
+                "line 1: the ssid cannot be decoded",
+            ),
+            (
+                "network={\n\tssid\n}\n",
+                "line 2: the line is not in the key=value format",
+            ),
+        ] {
+            match wl::parse_wpa_supplicant_conf(conf) {
+                Err(ImportError::InvalidFile(err)) => assert_eq!(line, err),
+                result => panic!("unexpected result for {:?}: {:?}", conf, result),
+            }
+        }
+    }
+
+    #[test]
+    fn migrate_should_parse_iwd_profiles() {
+        let report = wl::parse_iwd_profile(
+            "=436166c3a9.psk",
+            &format!(
+                "[Security]\nPreSharedKey={}\n\n[Settings]\nHidden=true\n",
+                PSK_HASH.to_uppercase()
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![ExportedNetwork {
+                ssid: "Café".as_bytes().to_vec(),
+                security: Security::WpaPsk,
+                secret: Some(PSK_HASH.as_bytes().to_vec()),
+                hidden: true,
+                priority: 0,
+                autoconnect: true,
+            }],
+            report.networks
+        );
+
+        let report = wl::parse_iwd_profile("Corp.8021x", "[Security]\nEAP-Method=PEAP\n").unwrap();
+        assert!(report.networks.is_empty());
+        assert_eq!(vec![b"Corp".to_vec()], report.skipped);
+
+        for file_name in ["Home", "Home.conf", "=zz.psk", ".psk"] {
+            assert!(matches!(
+                wl::parse_iwd_profile(file_name, ""),
+                Err(ImportError::InvalidFile(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn migrate_should_keep_non_utf8_hex_ssids() {
+        let report = wl::parse_wpa_supplicant_conf(LATIN1_CONF).unwrap();
+        assert_eq!(b"Caf\xe9".as_slice(), report.networks[0].ssid);
+        assert_eq!(Some(b"secret".to_vec()), report.networks[0].secret);
+
+        let report = wl::parse_iwd_profile("=436166e9.open", "").unwrap();
+        assert_eq!(b"Caf\xe9".as_slice(), report.networks[0].ssid);
+
+        let report = wl::parse_iwd_profile("=436166e9.8021x", "").unwrap();
+        assert_eq!(vec![b"Caf\xe9".to_vec()], report.skipped);
+
+        let fake = FakeWl::new();
+        wl::import_from(
+            &fake,
+            &mut Vec::new(),
+            ImportSource::WpaSupplicant,
+            Path::new(&fixture("wpa_supplicant/latin1.conf")),
+            false,
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap();
+        assert_eq!(vec![b"Caf\xe9".to_vec()], fake.known_ssids());
+        assert_eq!(
+            Some(b"secret".to_vec()),
+            fake.get_passwd(b"Caf\xe9").unwrap()
+        );
+    }
+
+    #[test]
+    fn migrate_should_import_from_wpa_supplicant_and_iwd() {
+        let fake = FakeWl::new().with_known_network(b"Library", None);
+
+        let mut out = Vec::new();
+        let imported = wl::import_from(
+            &fake,
+            &mut out,
+            ImportSource::WpaSupplicant,
+            Path::new(&fixture("wpa_supplicant/wpa_supplicant.conf")),
+            false,
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap();
+        assert_eq!(6, imported.len());
+        assert_eq!(
+            "Home: added\nCafé: added\nAttic!: added\nLibrary: skipped\nCorp: unsupported\nLegacy: unsupported\n",
+            String::from_utf8(out).unwrap()
+        );
+        assert_eq!(
+            Some(PSK_HASH.as_bytes().to_vec()),
+            fake.get_passwd("Café".as_bytes()).unwrap()
+        );
+        assert!(fake.get_profile("Café".as_bytes()).unwrap().hidden);
+
+        let fake = FakeWl::new();
+        let mut out = Vec::new();
+        let imported = wl::import_from(
+            &fake,
+            &mut out,
+            ImportSource::Iwd,
+            Path::new(&fixture("iwd")),
+            true,
+            ConflictPolicy::Skip,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                ImportStatus::Added,
+                ImportStatus::Added,
+                ImportStatus::Added,
+                ImportStatus::Unsupported,
+            ],
+            imported.iter().map(|n| n.status).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "[{\"ssid\":\"Café\",\"status\":\"added\"},{\"ssid\":\"Home\",\"status\":\"added\"},{\"ssid\":\"Library\",\"status\":\"added\"},{\"ssid\":\"Corp\",\"status\":\"unsupported\"}]\n",
+            String::from_utf8(out).unwrap()
+        );
+        assert_eq!(None, fake.get_passwd(b"Home").unwrap());
+        assert!(!fake.get_profile(b"Home").unwrap().autoconnect);
+
+        let err = wl::import_from(
+            &fake,
+            &mut Vec::new(),
+            ImportSource::Iwd,
+            Path::new(&fixture("iwd/Missing.psk")),
+            false,
+            ConflictPolicy::Skip,
+            OutputFormat::Text,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Io(_)));
+    }
+}
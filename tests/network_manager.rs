@@ -0,0 +1,568 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        os::unix::net::UnixStream,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use wl::{
+        EapCredentials, EapMethod, NetworkAdapterError, NetworkManager, UNKNOWN_SSID_EXIT_CODE,
+        WifiStatus, Wl,
+    };
+    use zbus::{
+        Guid,
+        blocking::{Connection, connection::Builder},
+        interface,
+        zvariant::{OwnedObjectPath, OwnedValue, Value},
+    };
+
+    type Calls = Arc<Mutex<Vec<String>>>;
+    type Settings = HashMap<String, HashMap<String, OwnedValue>>;
+
+    fn path(p: &str) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(p).unwrap()
+    }
+
+    fn value<'a>(v: impl Into<Value<'a>>) -> OwnedValue {
+        OwnedValue::try_from(v.into()).unwrap()
+    }
+
+    struct FakeNm {
+        calls: Calls,
+        wireless_enabled: bool,
+    }
+
+    #[interface(name = "org.freedesktop.NetworkManager")]
+    impl FakeNm {
+        #[zbus(property)]
+        fn wireless_enabled(&self) -> bool {
+            self.wireless_enabled
+        }
+
+        #[zbus(property)]
+        fn set_wireless_enabled(&mut self, enabled: bool) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("WirelessEnabled={}", enabled));
+            self.wireless_enabled = enabled;
+        }
+
+        #[zbus(property)]
+        fn active_connections(&self) -> Vec<OwnedObjectPath> {
+            vec![path("/org/freedesktop/NetworkManager/ActiveConnection/1")]
+        }
+
+        fn get_devices(&self) -> Vec<OwnedObjectPath> {
+            vec![
+                path("/org/freedesktop/NetworkManager/Devices/1"),
+                path("/org/freedesktop/NetworkManager/Devices/2"),
+            ]
+        }
+
+        fn deactivate_connection(&self, active: OwnedObjectPath) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("DeactivateConnection {}", active.as_str()));
+        }
+
+        fn add_and_activate_connection(
+            &self,
+            settings: HashMap<String, HashMap<String, OwnedValue>>,
+            device: OwnedObjectPath,
+            specific_object: OwnedObjectPath,
+        ) -> (OwnedObjectPath, OwnedObjectPath) {
+            let ssid = Vec::<u8>::try_from(settings["802-11-wireless"]["ssid"].clone()).unwrap();
+            let secret = match settings.get("802-1x") {
+                Some(eap) => {
+                    let method = Vec::<String>::try_from(eap["eap"].clone()).unwrap();
+                    let identity = String::try_from(eap["identity"].clone()).unwrap();
+                    let ca_cert = Vec::<u8>::try_from(eap["ca-cert"].clone()).unwrap();
+                    format!(
+                        "{} {} {}",
+                        method.join(","),
+                        identity,
+                        String::from_utf8_lossy(&ca_cert).trim_end_matches('\0')
+                    )
+                }
+                None => {
+                    let wifi_sec = &settings["802-11-wireless-security"];
+                    let psk = String::try_from(wifi_sec["psk"].clone()).unwrap();
+                    match wifi_sec.get("key-mgmt") {
+                        Some(key_mgmt) => {
+                            format!("{} {psk}", String::try_from(key_mgmt.clone()).unwrap())
+                        }
+                        None => psk,
+                    }
+                }
+            };
+
+            self.calls.lock().unwrap().push(format!(
+                "AddAndActivateConnection {} {} {} {}",
+                String::from_utf8_lossy(&ssid),
+                secret,
+                device.as_str(),
+                specific_object.as_str()
+            ));
+
+            (
+                path("/org/freedesktop/NetworkManager/Settings/3"),
+                path("/org/freedesktop/NetworkManager/ActiveConnection/1"),
+            )
+        }
+    }
+
+    struct FakeSettings;
+
+    #[interface(name = "org.freedesktop.NetworkManager.Settings")]
+    impl FakeSettings {
+        fn list_connections(&self) -> Vec<OwnedObjectPath> {
+            vec![
+                path("/org/freedesktop/NetworkManager/Settings/1"),
+                path("/org/freedesktop/NetworkManager/Settings/2"),
+            ]
+        }
+    }
+
+    struct FakeProfile {
+        calls: Calls,
+        id: &'static str,
+        uuid: &'static str,
+        kind: &'static str,
+        psk: Option<&'static str>,
+    }
+
+    #[interface(name = "org.freedesktop.NetworkManager.Settings.Connection")]
+    impl FakeProfile {
+        fn get_settings(&self) -> Settings {
+            let mut settings = HashMap::from([(
+                String::from("connection"),
+                HashMap::from([
+                    (String::from("id"), value(self.id)),
+                    (String::from("uuid"), value(self.uuid)),
+                    (String::from("type"), value(self.kind)),
+                ]),
+            )]);
+
+            if self.psk.is_some() {
+                settings.insert(
+                    String::from("802-11-wireless-security"),
+                    HashMap::from([(String::from("key-mgmt"), value("wpa-psk"))]),
+                );
+            }
+
+            settings
+        }
+
+        fn get_secrets(&self, setting_name: &str) -> Settings {
+            self.psk
+                .map(|psk| {
+                    HashMap::from([(
+                        setting_name.to_string(),
+                        HashMap::from([(String::from("psk"), value(psk))]),
+                    )])
+                })
+                .unwrap_or_default()
+        }
+
+        fn delete(&self) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("Delete {}", self.id));
+        }
+    }
+
+    struct FakeDevice {
+        device_type: u32,
+        interface: &'static str,
+    }
+
+    #[interface(name = "org.freedesktop.NetworkManager.Device")]
+    impl FakeDevice {
+        #[zbus(property)]
+        fn device_type(&self) -> u32 {
+            self.device_type
+        }
+
+        #[zbus(property)]
+        fn interface(&self) -> &str {
+            self.interface
+        }
+    }
+
+    struct FakeWireless;
+
+    #[interface(name = "org.freedesktop.NetworkManager.Device.Wireless")]
+    impl FakeWireless {
+        #[zbus(property)]
+        fn active_access_point(&self) -> OwnedObjectPath {
+            path("/org/freedesktop/NetworkManager/AccessPoint/1")
+        }
+
+        fn get_all_access_points(&self) -> Vec<OwnedObjectPath> {
+            vec![
+                path("/org/freedesktop/NetworkManager/AccessPoint/1"),
+                path("/org/freedesktop/NetworkManager/AccessPoint/2"),
+                path("/org/freedesktop/NetworkManager/AccessPoint/3"),
+            ]
+        }
+    }
+
+    struct FakeAccessPoint {
+        ssid: &'static [u8],
+        hw_address: &'static str,
+        frequency: u32,
+        strength: u8,
+        wpa_flags: u32,
+        rsn_flags: u32,
+    }
+
+    #[interface(name = "org.freedesktop.NetworkManager.AccessPoint")]
+    impl FakeAccessPoint {
+        #[zbus(property)]
+        fn ssid(&self) -> Vec<u8> {
+            self.ssid.to_vec()
+        }
+
+        #[zbus(property)]
+        fn hw_address(&self) -> &str {
+            self.hw_address
+        }
+
+        #[zbus(property)]
+        fn mode(&self) -> u32 {
+            2
+        }
+
+        #[zbus(property)]
+        fn frequency(&self) -> u32 {
+            self.frequency
+        }
+
+        #[zbus(property)]
+        fn max_bitrate(&self) -> u32 {
+            130000
+        }
+
+        #[zbus(property)]
+        fn strength(&self) -> u8 {
+            self.strength
+        }
+
+        #[zbus(property)]
+        fn flags(&self) -> u32 {
+            u32::from(self.rsn_flags != 0)
+        }
+
+        #[zbus(property)]
+        fn wpa_flags(&self) -> u32 {
+            self.wpa_flags
+        }
+
+        #[zbus(property)]
+        fn rsn_flags(&self) -> u32 {
+            self.rsn_flags
+        }
+    }
+
+    struct FakeActiveConnection;
+
+    #[interface(name = "org.freedesktop.NetworkManager.Connection.Active")]
+    impl FakeActiveConnection {
+        #[zbus(property)]
+        fn id(&self) -> &str {
+            "Home"
+        }
+
+        #[zbus(property)]
+        fn connection(&self) -> OwnedObjectPath {
+            path("/org/freedesktop/NetworkManager/Settings/2")
+        }
+
+        #[zbus(property)]
+        fn devices(&self) -> Vec<OwnedObjectPath> {
+            vec![path("/org/freedesktop/NetworkManager/Devices/2")]
+        }
+
+        #[zbus(property)]
+        fn state(&self) -> u32 {
+            2
+        }
+    }
+
+    /// Starts a fake NetworkManager service on a peer-to-peer connection,
+    /// and provides the client side of the connection.
+    fn start_fake_nm(calls: &Calls) -> zbus::Result<(Connection, Connection)> {
+        let (server_stream, client_stream) = UnixStream::pair()?;
+        let nm = "/org/freedesktop/NetworkManager";
+
+        let server_calls = Arc::clone(calls);
+        let server = thread::spawn(move || {
+            Builder::async_io_unix_stream(server_stream)
+                .server(Guid::generate())?
+                .p2p()
+                .serve_at(
+                    nm,
+                    FakeNm {
+                        calls: Arc::clone(&server_calls),
+                        wireless_enabled: true,
+                    },
+                )?
+                .serve_at(format!("{nm}/Settings"), FakeSettings)?
+                .serve_at(
+                    format!("{nm}/Settings/1"),
+                    FakeProfile {
+                        calls: Arc::clone(&server_calls),
+                        id: "Office",
+                        uuid: "11111111-1111-1111-1111-111111111111",
+                        kind: "802-11-wireless",
+                        psk: None,
+                    },
+                )?
+                .serve_at(
+                    format!("{nm}/Settings/2"),
+                    FakeProfile {
+                        calls: Arc::clone(&server_calls),
+                        id: "Home",
+                        uuid: "22222222-2222-2222-2222-222222222222",
+                        kind: "802-11-wireless",
+                        psk: Some("secret"),
+                    },
+                )?
+                .serve_at(
+                    format!("{nm}/Devices/1"),
+                    FakeDevice {
+                        device_type: 1,
+                        interface: "eth0",
+                    },
+                )?
+                .serve_at(
+                    format!("{nm}/Devices/2"),
+                    FakeDevice {
+                        device_type: 2,
+                        interface: "wlan0",
+                    },
+                )?
+                .serve_at(format!("{nm}/Devices/2"), FakeWireless)?
+                .serve_at(
+                    format!("{nm}/AccessPoint/1"),
+                    FakeAccessPoint {
+                        ssid: b"Home",
+                        hw_address: "aa:bb:cc:dd:ee:01",
+                        frequency: 5180,
+                        strength: 90,
+                        wpa_flags: 0,
+                        rsn_flags: 0x100 | 0x8,
+                    },
+                )?
+                .serve_at(
+                    format!("{nm}/AccessPoint/2"),
+                    FakeAccessPoint {
+                        ssid: b"Caf\xc3\xa9",
+                        hw_address: "aa:bb:cc:dd:ee:02",
+                        frequency: 2437,
+                        strength: 40,
+                        wpa_flags: 0,
+                        rsn_flags: 0,
+                    },
+                )?
+                .serve_at(
+                    format!("{nm}/AccessPoint/3"),
+                    FakeAccessPoint {
+                        ssid: b"Lab",
+                        hw_address: "aa:bb:cc:dd:ee:03",
+                        frequency: 5745,
+                        strength: 60,
+                        wpa_flags: 0,
+                        rsn_flags: 0x400 | 0x8,
+                    },
+                )?
+                .serve_at(format!("{nm}/ActiveConnection/1"), FakeActiveConnection)?
+                .build()
+        });
+
+        let client = Builder::async_io_unix_stream(client_stream).p2p().build()?;
+        let server = server.join().unwrap()?;
+
+        Ok((server, client))
+    }
+
+    #[test]
+    fn network_manager_should_get_and_toggle_wifi_status() -> zbus::Result<()> {
+        let calls = Calls::default();
+        let (_server, client) = start_fake_nm(&calls)?;
+
+        let nm = NetworkManager::with_connection(client);
+
+        assert_eq!(WifiStatus::Enabled, nm.get_wifi_status().unwrap());
+        assert_eq!(WifiStatus::Disabled, nm.toggle_wifi().unwrap());
+        assert_eq!(WifiStatus::Disabled, nm.get_wifi_status().unwrap());
+        assert_eq!(vec!["WirelessEnabled=false"], *calls.lock().unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    fn network_manager_should_list_networks() -> zbus::Result<()> {
+        let calls = Calls::default();
+        let (_server, client) = start_fake_nm(&calls)?;
+
+        let nm = NetworkManager::with_connection(client);
+        let networks = nm.get_networks(false).unwrap();
+
+        assert_eq!(2, networks.len());
+        assert_eq!(b"Home".as_slice(), networks[0].ssid);
+        assert_eq!("wifi", networks[0].kind);
+        assert_eq!(Some("wlan0"), networks[0].device.as_deref());
+        assert_eq!(b"Office".as_slice(), networks[1].ssid);
+        assert!(!networks[1].is_active());
+
+        let active_networks = nm.get_networks(true).unwrap();
+        assert_eq!(1, active_networks.len());
+
+        assert!(nm.is_known_ssid(b"Office").unwrap());
+        assert!(!nm.is_known_ssid(b"Unknown").unwrap());
+
+        assert_eq!(Some(b"secret".to_vec()), nm.get_passwd(b"Home").unwrap());
+        assert_eq!(None, nm.get_passwd(b"Office").unwrap());
+        assert!(matches!(
+            nm.get_passwd(b"Unknown"),
+            Err(NetworkAdapterError::CannotGetPasswd((
+                _,
+                UNKNOWN_SSID_EXIT_CODE
+            )))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn network_manager_should_provide_access_points() -> zbus::Result<()> {
+        let calls = Calls::default();
+        let (_server, client) = start_fake_nm(&calls)?;
+
+        let nm = NetworkManager::with_connection(client);
+        let aps = nm.get_access_points(false).unwrap();
+
+        assert_eq!(3, aps.len());
+        assert_eq!(b"Home".as_slice(), aps[0].ssid);
+        assert_eq!("AA:BB:CC:DD:EE:01", aps[0].bssid);
+        assert_eq!("Infra", aps[0].mode);
+        assert_eq!(36, aps[0].channel);
+        assert_eq!(130, aps[0].rate);
+        assert_eq!(90, aps[0].signal);
+        assert_eq!("WPA2", aps[0].security);
+        assert!(aps[0].in_use);
+
+        assert_eq!("Café".as_bytes(), aps[1].ssid);
+        assert_eq!(6, aps[1].channel);
+        assert!(!aps[1].is_secured());
+        assert!(!aps[1].in_use);
+
+        assert_eq!(b"Lab".as_slice(), aps[2].ssid);
+        assert_eq!("WPA3", aps[2].security);
+
+        Ok(())
+    }
+
+    #[test]
+    fn network_manager_should_connect_to_new_network() -> zbus::Result<()> {
+        let calls = Calls::default();
+        let (_server, client) = start_fake_nm(&calls)?;
+
+        let nm = NetworkManager::with_connection(client);
+        let result = nm.connect(b"Home", Some(b"secret"), None, true).unwrap();
+
+        assert!(!result.is_empty());
+        assert_eq!(
+            vec![
+                "Delete Home",
+                "AddAndActivateConnection Home secret /org/freedesktop/NetworkManager/Devices/2 /org/freedesktop/NetworkManager/AccessPoint/1",
+            ],
+            *calls.lock().unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn network_manager_should_connect_to_new_sae_network() -> zbus::Result<()> {
+        let calls = Calls::default();
+        let (_server, client) = start_fake_nm(&calls)?;
+
+        let nm = NetworkManager::with_connection(client);
+        let result = nm.connect(b"Lab", Some(b"secret"), None, false).unwrap();
+
+        assert!(!result.is_empty());
+        assert_eq!(
+            vec![
+                "AddAndActivateConnection Lab secret /org/freedesktop/NetworkManager/Devices/2 /org/freedesktop/NetworkManager/AccessPoint/3",
+            ],
+            *calls.lock().unwrap()
+        );
+
+        let err = nm
+            .connect(b"Unknown", Some(b"secret"), None, false)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            NetworkAdapterError::CannotConnect((_, UNKNOWN_SSID_EXIT_CODE))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn network_manager_should_connect_to_enterprise_network() -> zbus::Result<()> {
+        let calls = Calls::default();
+        let (_server, client) = start_fake_nm(&calls)?;
+
+        let credentials = EapCredentials {
+            method: EapMethod::Peap,
+            identity: b"user".to_vec(),
+            passwd: Some(b"secret".to_vec()),
+            ca_cert: Some("/etc/ssl/corp-ca.pem".into()),
+            ..Default::default()
+        };
+
+        let nm = NetworkManager::with_connection(client);
+        let result = nm.connect_enterprise(b"Corp", &credentials, false).unwrap();
+
+        assert!(!result.is_empty());
+        assert_eq!(
+            vec![
+                "AddAndActivateConnection Corp peap user file:///etc/ssl/corp-ca.pem /org/freedesktop/NetworkManager/Devices/2 /",
+            ],
+            *calls.lock().unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn network_manager_should_disconnect_and_forget() -> zbus::Result<()> {
+        let calls = Calls::default();
+        let (_server, client) = start_fake_nm(&calls)?;
+
+        let nm = NetworkManager::with_connection(client);
+
+        nm.disconnect(b"Home", false).unwrap();
+        nm.disconnect(b"Office", true).unwrap();
+        assert!(nm.disconnect(b"Office", false).is_err());
+        assert!(nm.disconnect(b"Unknown", true).is_err());
+
+        assert_eq!(
+            vec![
+                "DeactivateConnection /org/freedesktop/NetworkManager/ActiveConnection/1",
+                "Delete Office",
+            ],
+            *calls.lock().unwrap()
+        );
+
+        Ok(())
+    }
+}
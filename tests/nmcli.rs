@@ -0,0 +1,794 @@
+#[cfg(test)]
+mod tests {
+    use wl::{
+        EapCredentials, EapMethod, ExportedNetwork, IpMethod, MacRandomization, Metered,
+        NetworkAdapterError, NetworkPriority, Nmcli, Phase2Auth, Profile, ProfileEdit, Security,
+        UNKNOWN_SSID_EXIT_CODE, WifiStatus, Wl,
+        runner::{FakeRunner, Reply},
+    };
+
+    const GENERAL_WIFI: &[u8] = include_bytes!("fixtures/nmcli/general_wifi.txt");
+    const CONNECTION_SHOW: &[u8] = include_bytes!("fixtures/nmcli/connection_show.txt");
+    const CONNECTION_SHOW_ACTIVE: &[u8] =
+        include_bytes!("fixtures/nmcli/connection_show_active.txt");
+    const CONNECTION_NAMES: &[u8] = include_bytes!("fixtures/nmcli/connection_names.txt");
+    const WIFI_LIST: &[u8] = include_bytes!("fixtures/nmcli/wifi_list.txt");
+    const WIFI_CONNECT: &[u8] = include_bytes!("fixtures/nmcli/wifi_connect.txt");
+    const WIFI_CONNECT_WRONG_PASSWORD: &[u8] =
+        include_bytes!("fixtures/nmcli/wifi_connect_wrong_password.txt");
+    const CONNECTION_UP: &[u8] = include_bytes!("fixtures/nmcli/connection_up.txt");
+    const CONNECTION_DOWN: &[u8] = include_bytes!("fixtures/nmcli/connection_down.txt");
+    const CONNECTION_DOWN_UNKNOWN: &[u8] =
+        include_bytes!("fixtures/nmcli/connection_down_unknown.txt");
+    const CONNECTION_DELETE: &[u8] = include_bytes!("fixtures/nmcli/connection_delete.txt");
+    const CONNECTION_SHOW_PROFILE: &[u8] =
+        include_bytes!("fixtures/nmcli/connection_show_profile.txt");
+    const CONNECTION_SHOW_PRIORITIES: &[u8] =
+        include_bytes!("fixtures/nmcli/connection_show_priorities.txt");
+
+    const WIFI_LIST_ARGS: &str = "IN-USE,BSSID,SSID-HEX,MODE,CHAN,FREQ,RATE,SIGNAL,SECURITY";
+    const PROFILE_ARGS: &str = "connection.id,802-11-wireless.ssid,802-11-wireless-security.key-mgmt,connection.autoconnect,connection.autoconnect-priority,connection.metered,802-11-wireless.mac-address-randomization,ipv4.method,802-11-wireless.hidden";
+
+    #[test]
+    fn nmcli_should_get_wifi_status() {
+        let runner =
+            FakeRunner::new().reply("nmcli", &["-g", "WIFI", "g"], Reply::stdout(GENERAL_WIFI));
+        let nmcli = Nmcli::with_runner(runner);
+
+        assert_eq!(WifiStatus::Enabled, nmcli.get_wifi_status().unwrap());
+        assert_eq!(vec!["nmcli -g WIFI g"], nmcli.runner().calls());
+
+        let runner =
+            FakeRunner::new().reply("nmcli", &["-g", "WIFI", "g"], Reply::stdout("disabled\n"));
+        let nmcli = Nmcli::with_runner(runner);
+
+        assert_eq!(WifiStatus::Disabled, nmcli.get_wifi_status().unwrap());
+    }
+
+    #[test]
+    fn nmcli_should_toggle_wifi() {
+        let runner = FakeRunner::new()
+            .reply("nmcli", &["-g", "WIFI", "g"], Reply::stdout(GENERAL_WIFI))
+            .reply("nmcli", &["radio", "wifi", "off"], Reply::stdout(""));
+        let nmcli = Nmcli::with_runner(runner);
+
+        assert_eq!(WifiStatus::Disabled, nmcli.toggle_wifi().unwrap());
+        assert_eq!(
+            vec!["nmcli -g WIFI g", "nmcli radio wifi off"],
+            nmcli.runner().calls()
+        );
+    }
+
+    #[test]
+    fn nmcli_should_get_networks() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &["-g", "NAME,UUID,TYPE,DEVICE", "connection", "show"],
+                Reply::stdout(CONNECTION_SHOW),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "-g",
+                    "NAME,UUID,TYPE,DEVICE",
+                    "connection",
+                    "show",
+                    "--active",
+                ],
+                Reply::stdout(CONNECTION_SHOW_ACTIVE),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let networks = nmcli.get_networks(false).unwrap();
+        assert_eq!(4, networks.len());
+
+        assert_eq!(b"Home".as_slice(), networks[0].ssid);
+        assert_eq!("6f1b5a9e-2c1d-4a8e-9a51-0d3c1f7a2b11", networks[0].uuid);
+        assert_eq!("wifi", networks[0].kind);
+        assert_eq!(Some("wlp2s0"), networks[0].device.as_deref());
+
+        assert_eq!("loopback", networks[1].kind);
+
+        assert_eq!(b"Office:5G".as_slice(), networks[2].ssid);
+        assert!(!networks[2].is_active());
+
+        assert_eq!("ethernet", networks[3].kind);
+
+        let active_networks = nmcli.get_networks(true).unwrap();
+        assert_eq!(2, active_networks.len());
+        assert!(active_networks.iter().all(|n| n.is_active()));
+    }
+
+    #[test]
+    fn nmcli_should_fail_to_get_networks_with_context() {
+        let runner = FakeRunner::new().reply(
+            "nmcli",
+            &[
+                "-g",
+                "NAME,UUID,TYPE,DEVICE",
+                "connection",
+                "show",
+                "--active",
+            ],
+            Reply::failure(8, "Error: NetworkManager is not running.\n"),
+        );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let err = nmcli.get_networks(true).unwrap_err();
+        assert!(matches!(
+            err,
+            NetworkAdapterError::CannotGetActiveConnections((_, 8))
+        ));
+        assert!(err.to_string().contains("NetworkManager is not running"));
+    }
+
+    #[test]
+    fn nmcli_should_get_access_points() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &["-g", WIFI_LIST_ARGS, "d", "wifi", "list"],
+                Reply::stdout(WIFI_LIST),
+            )
+            .reply(
+                "nmcli",
+                &["-g", WIFI_LIST_ARGS, "d", "wifi", "list", "--rescan", "yes"],
+                Reply::stdout(WIFI_LIST),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let aps = nmcli.get_access_points(false).unwrap();
+        assert_eq!(4, aps.len());
+
+        assert!(aps[0].in_use);
+        assert_eq!(b"Home".as_slice(), aps[0].ssid);
+        assert_eq!("AA:BB:CC:DD:EE:01", aps[0].bssid);
+        assert_eq!("Infra", aps[0].mode);
+        assert_eq!(36, aps[0].channel);
+        assert_eq!(5180, aps[0].frequency);
+        assert_eq!(270, aps[0].rate);
+        assert_eq!(90, aps[0].signal);
+        assert_eq!("WPA2", aps[0].security);
+
+        assert!(!aps[1].in_use);
+        assert_eq!("Café".as_bytes(), aps[1].ssid);
+        assert_eq!("WPA1 WPA2", aps[1].security);
+
+        assert!(aps[2].ssid.is_empty());
+        assert!(!aps[2].is_secured());
+
+        assert_eq!(100, aps[3].signal);
+        assert!(!aps[3].is_secured());
+
+        nmcli.get_access_points(true).unwrap();
+        assert_eq!(
+            Some(&format!(
+                "nmcli -g {} d wifi list --rescan yes",
+                WIFI_LIST_ARGS
+            )),
+            nmcli.runner().calls().last()
+        );
+    }
+
+    #[test]
+    fn nmcli_should_check_known_ssids() {
+        let runner = FakeRunner::new().reply(
+            "nmcli",
+            &["-g", "NAME", "connection", "show"],
+            Reply::stdout(CONNECTION_NAMES),
+        );
+        let nmcli = Nmcli::with_runner(runner);
+
+        assert!(nmcli.is_known_ssid(b"Home").unwrap());
+        assert!(nmcli.is_known_ssid(b"Office:5G").unwrap());
+        assert!(!nmcli.is_known_ssid(b"Unknown").unwrap());
+    }
+
+    #[test]
+    fn nmcli_should_disconnect_and_forget() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &["connection", "down", "id", "Home"],
+                Reply::stdout(CONNECTION_DOWN),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "delete", "id", "Home"],
+                Reply::stdout(CONNECTION_DELETE),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "down", "id", "Unknown"],
+                Reply::failure(10, CONNECTION_DOWN_UNKNOWN),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        assert_eq!(CONNECTION_DOWN, nmcli.disconnect(b"Home", false).unwrap());
+        assert_eq!(CONNECTION_DELETE, nmcli.disconnect(b"Home", true).unwrap());
+
+        let err = nmcli.disconnect(b"Unknown", false).unwrap_err();
+        assert!(matches!(
+            err,
+            NetworkAdapterError::CannotDisconnect((_, UNKNOWN_SSID_EXIT_CODE))
+        ));
+        assert!(
+            err.to_string()
+                .contains("'Unknown' is not an active connection")
+        );
+    }
+
+    #[test]
+    fn nmcli_should_connect() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &["--ask", "d", "wifi", "connect", "Home"],
+                Reply::stdout([b"Password: ".as_slice(), WIFI_CONNECT].concat()),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "up", "id", "Home"],
+                Reply::stdout(CONNECTION_UP),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "delete", "id", "Home"],
+                Reply::stdout(CONNECTION_DELETE),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        assert_eq!(
+            WIFI_CONNECT,
+            nmcli
+                .connect(b"Home", Some(b"secret"), None, false)
+                .unwrap()
+        );
+        assert_eq!(
+            CONNECTION_UP,
+            nmcli.connect(b"Home", None, None, true).unwrap()
+        );
+        assert_eq!(
+            WIFI_CONNECT,
+            nmcli.connect(b"Home", Some(b"secret"), None, true).unwrap()
+        );
+
+        assert_eq!(
+            vec![
+                "nmcli --ask d wifi connect Home",
+                "nmcli connection up id Home",
+                "nmcli connection delete id Home",
+                "nmcli --ask d wifi connect Home",
+            ],
+            nmcli.runner().calls()
+        );
+        assert_eq!(
+            vec![b"secret\n".to_vec(), b"secret\n".to_vec()],
+            nmcli.runner().inputs()
+        );
+    }
+
+    #[test]
+    fn nmcli_should_pin_and_unpin_bssid() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &[
+                    "--ask",
+                    "d",
+                    "wifi",
+                    "connect",
+                    "Office",
+                    "bssid",
+                    "AA:BB:CC:DD:EE:02",
+                ],
+                Reply::stdout(WIFI_CONNECT),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "modify",
+                    "id",
+                    "Office",
+                    "802-11-wireless.bssid",
+                    "AA:BB:CC:DD:EE:01",
+                ],
+                Reply::stdout(""),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "modify",
+                    "id",
+                    "Office",
+                    "802-11-wireless.bssid",
+                    "",
+                ],
+                Reply::stdout(""),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "up", "id", "Office"],
+                Reply::stdout(CONNECTION_UP),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        nmcli
+            .connect(b"Office", Some(b"secret"), Some("AA:BB:CC:DD:EE:02"), false)
+            .unwrap();
+        nmcli
+            .connect(b"Office", None, Some("AA:BB:CC:DD:EE:01"), true)
+            .unwrap();
+        nmcli.clear_bssid(b"Office").unwrap();
+
+        assert_eq!(
+            vec![
+                "nmcli --ask d wifi connect Office bssid AA:BB:CC:DD:EE:02",
+                "nmcli connection modify id Office 802-11-wireless.bssid AA:BB:CC:DD:EE:01",
+                "nmcli connection up id Office",
+                "nmcli connection modify id Office 802-11-wireless.bssid ",
+            ],
+            nmcli.runner().calls()
+        );
+    }
+
+    #[test]
+    fn nmcli_should_show_edit_and_rename_profiles() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &["-g", PROFILE_ARGS, "connection", "show", "id", "Home"],
+                Reply::stdout(CONNECTION_SHOW_PROFILE),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "modify",
+                    "id",
+                    "Home",
+                    "remove",
+                    "802-11-wireless-security",
+                    "connection.autoconnect",
+                    "no",
+                    "connection.autoconnect-priority",
+                    "10",
+                    "ipv4.method",
+                    "link-local",
+                ],
+                Reply::stdout(""),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "modify",
+                    "id",
+                    "Home",
+                    "connection.id",
+                    "Home (5G)",
+                ],
+                Reply::stdout(""),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "modify",
+                    "id",
+                    "Unknown",
+                    "connection.id",
+                    "New",
+                ],
+                Reply::failure(10, "Error: unknown connection 'Unknown'."),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        assert_eq!(
+            Profile {
+                name: b"Home".to_vec(),
+                ssid: b"Home".to_vec(),
+                security: String::from("wpa-psk"),
+                hidden: true,
+                autoconnect: true,
+                priority: -5,
+                metered: Metered::Yes,
+                mac_randomization: MacRandomization::Always,
+                ip_method: IpMethod::Auto,
+            },
+            nmcli.get_profile(b"Home").unwrap()
+        );
+
+        let edit = ProfileEdit {
+            security: Some(Security::Open),
+            autoconnect: Some(false),
+            priority: Some(10),
+            ip_method: Some(IpMethod::LinkLocal),
+            ..Default::default()
+        };
+        nmcli.edit_profile(b"Home", &edit).unwrap();
+        nmcli.rename_profile(b"Home", b"Home (5G)").unwrap();
+
+        let err = nmcli.rename_profile(b"Unknown", b"New").unwrap_err();
+        assert!(matches!(
+            err,
+            NetworkAdapterError::CannotUpdateNetwork((_, UNKNOWN_SSID_EXIT_CODE))
+        ));
+
+        assert_eq!(
+            vec![
+                format!("nmcli -g {PROFILE_ARGS} connection show id Home"),
+                String::from(
+                    "nmcli connection modify id Home remove 802-11-wireless-security connection.autoconnect no connection.autoconnect-priority 10 ipv4.method link-local"
+                ),
+                String::from("nmcli connection modify id Home connection.id Home (5G)"),
+                String::from("nmcli connection modify id Unknown connection.id New"),
+            ],
+            nmcli.runner().calls()
+        );
+    }
+
+    #[test]
+    fn nmcli_should_get_and_set_priorities() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &[
+                    "-g",
+                    "NAME,TYPE,AUTOCONNECT,AUTOCONNECT-PRIORITY",
+                    "connection",
+                    "show",
+                ],
+                Reply::stdout(CONNECTION_SHOW_PRIORITIES),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "modify",
+                    "id",
+                    "Home",
+                    "connection.autoconnect-priority",
+                    "11",
+                ],
+                Reply::stdout(""),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let priority = |ssid: &str, priority: i32, autoconnect: bool| NetworkPriority {
+            ssid: ssid.as_bytes().to_vec(),
+            priority,
+            autoconnect,
+        };
+        assert_eq!(
+            vec![
+                priority("Home", -5, true),
+                priority("Office", 10, false),
+                priority("Cafe:Bar", 0, true),
+            ],
+            nmcli.get_priorities().unwrap()
+        );
+
+        nmcli.set_priority(b"Home", 11).unwrap();
+
+        assert_eq!(
+            vec![
+                "nmcli -g NAME,TYPE,AUTOCONNECT,AUTOCONNECT-PRIORITY connection show",
+                "nmcli connection modify id Home connection.autoconnect-priority 11",
+            ],
+            nmcli.runner().calls()
+        );
+    }
+
+    #[test]
+    fn nmcli_should_add_known_networks() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "add",
+                    "type",
+                    "wifi",
+                    "con-name",
+                    "Home",
+                    "ssid",
+                    "Home",
+                    "connection.autoconnect",
+                    "no",
+                    "connection.autoconnect-priority",
+                    "10",
+                    "802-11-wireless.hidden",
+                    "yes",
+                    "wifi-sec.key-mgmt",
+                    "sae",
+                ],
+                Reply::stdout("Connection 'Home' (9b9f0f2e) successfully added.\n"),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "edit", "id", "Home"],
+                Reply::stdout("Connection 'Home' (9b9f0f2e) successfully updated.\n"),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "delete", "id", "Office"],
+                Reply::stdout(""),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "add",
+                    "type",
+                    "wifi",
+                    "con-name",
+                    "Office",
+                    "ssid",
+                    "Office",
+                    "connection.autoconnect",
+                    "yes",
+                    "connection.autoconnect-priority",
+                    "0",
+                    "wifi-sec.key-mgmt",
+                    "wpa-psk",
+                    "wifi-sec.psk-flags",
+                    "2",
+                ],
+                Reply::stdout("Connection 'Office' (6f1b3d4c) successfully added.\n"),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let home = ExportedNetwork {
+            ssid: b"Home".to_vec(),
+            security: Security::Sae,
+            secret: Some(b"secret".to_vec()),
+            hidden: true,
+            priority: 10,
+            autoconnect: false,
+        };
+        nmcli.add_network(&home, false).unwrap();
+
+        // A secured network without a secret asks for its password on each connection.
+        let office = ExportedNetwork {
+            ssid: b"Office".to_vec(),
+            security: Security::WpaPsk,
+            secret: None,
+            hidden: false,
+            priority: 0,
+            autoconnect: true,
+        };
+        nmcli.add_network(&office, true).unwrap();
+
+        assert_eq!(
+            vec![
+                "nmcli connection add type wifi con-name Home ssid Home connection.autoconnect no connection.autoconnect-priority 10 802-11-wireless.hidden yes wifi-sec.key-mgmt sae",
+                "nmcli connection edit id Home",
+                "nmcli connection delete id Office",
+                "nmcli connection add type wifi con-name Office ssid Office connection.autoconnect yes connection.autoconnect-priority 0 wifi-sec.key-mgmt wpa-psk wifi-sec.psk-flags 2",
+            ],
+            nmcli.runner().calls()
+        );
+
+        // The secret is set through the stdin of the editor instead of the arguments.
+        assert!(
+            nmcli
+                .runner()
+                .calls()
+                .iter()
+                .all(|call| !call.contains("secret"))
+        );
+        assert_eq!(
+            vec![b"set 802-11-wireless-security.psk secret\nsave persistent\nquit\n".to_vec()],
+            nmcli.runner().inputs()
+        );
+    }
+
+    #[test]
+    fn nmcli_should_connect_to_enterprise_network() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "add",
+                    "type",
+                    "wifi",
+                    "con-name",
+                    "Corp",
+                    "ssid",
+                    "Corp",
+                    "wifi-sec.key-mgmt",
+                    "wpa-eap",
+                    "802-1x.eap",
+                    "tls",
+                    "802-1x.identity",
+                    "user",
+                    "802-1x.ca-cert",
+                    "/etc/ssl/corp-ca.pem",
+                    "802-1x.domain-suffix-match",
+                    "corp.example",
+                    "802-1x.client-cert",
+                    "/etc/ssl/user.pem",
+                    "802-1x.private-key",
+                    "/etc/ssl/user.key",
+                    "802-1x.private-key-password-flags",
+                    "4",
+                ],
+                Reply::stdout("Connection 'Corp' successfully added.\n"),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "up", "id", "Corp"],
+                Reply::stdout(CONNECTION_UP),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let credentials = EapCredentials {
+            method: EapMethod::Tls,
+            identity: b"user".to_vec(),
+            ca_cert: Some("/etc/ssl/corp-ca.pem".into()),
+            domain_suffix_match: Some(String::from("corp.example")),
+            client_cert: Some("/etc/ssl/user.pem".into()),
+            private_key: Some("/etc/ssl/user.key".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            CONNECTION_UP,
+            nmcli
+                .connect_enterprise(b"Corp", &credentials, false)
+                .unwrap()
+        );
+        assert_eq!(2, nmcli.runner().calls().len());
+    }
+
+    #[test]
+    fn nmcli_should_delete_enterprise_profile_when_activation_fails() {
+        let add_args = [
+            "connection",
+            "add",
+            "type",
+            "wifi",
+            "con-name",
+            "Corp",
+            "ssid",
+            "Corp",
+            "wifi-sec.key-mgmt",
+            "wpa-eap",
+            "802-1x.eap",
+            "peap",
+            "802-1x.identity",
+            "user",
+            "802-1x.anonymous-identity",
+            "anonymous",
+            "802-1x.phase2-auth",
+            "mschapv2",
+        ];
+        let runner = FakeRunner::new()
+            .reply("nmcli", &add_args, Reply::stdout(""))
+            .reply(
+                "nmcli",
+                &["connection", "up", "id", "Corp", "passwd-file", "/dev/stdin"],
+                Reply::failure(
+                    4,
+                    "Error: Connection activation failed: Secrets were required, but not provided.\n",
+                ),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "delete", "id", "Corp"],
+                Reply::stdout(CONNECTION_DELETE),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let credentials = EapCredentials {
+            method: EapMethod::Peap,
+            identity: b"user".to_vec(),
+            anonymous_identity: Some(b"anonymous".to_vec()),
+            passwd: Some(b"wrong".to_vec()),
+            phase2_auth: Some(Phase2Auth::Mschapv2),
+            ..Default::default()
+        };
+
+        let err = nmcli
+            .connect_enterprise(b"Corp", &credentials, false)
+            .unwrap_err();
+        assert!(matches!(err, NetworkAdapterError::CannotConnect((_, 4))));
+        assert_eq!("nmcli connection delete id Corp", nmcli.runner().calls()[2]);
+        assert_eq!(
+            vec![b"802-1x.password:wrong\n".to_vec()],
+            nmcli.runner().inputs()
+        );
+    }
+
+    #[test]
+    fn nmcli_should_connect_to_hidden_network() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "add",
+                    "type",
+                    "wifi",
+                    "con-name",
+                    "Attic",
+                    "ssid",
+                    "Attic",
+                    "802-11-wireless.hidden",
+                    "yes",
+                    "wifi-sec.key-mgmt",
+                    "sae",
+                ],
+                Reply::stdout("Connection 'Attic' successfully added.\n"),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "connection",
+                    "up",
+                    "id",
+                    "Attic",
+                    "passwd-file",
+                    "/dev/stdin",
+                ],
+                Reply::stdout(CONNECTION_UP),
+            )
+            .reply(
+                "nmcli",
+                &["connection", "delete", "id", "Attic"],
+                Reply::stdout(CONNECTION_DELETE),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        assert_eq!(
+            CONNECTION_UP,
+            nmcli
+                .connect_hidden(b"Attic", Security::Sae, Some(b"secret"), true)
+                .unwrap()
+        );
+        assert_eq!(
+            vec![
+                "nmcli connection delete id Attic",
+                "nmcli connection add type wifi con-name Attic ssid Attic 802-11-wireless.hidden yes wifi-sec.key-mgmt sae",
+                "nmcli connection up id Attic passwd-file /dev/stdin",
+            ],
+            nmcli.runner().calls()
+        );
+        assert_eq!(
+            vec![b"802-11-wireless-security.psk:secret\n".to_vec()],
+            nmcli.runner().inputs()
+        );
+    }
+
+    #[test]
+    fn nmcli_should_fail_to_connect_with_wrong_password() {
+        let runner = FakeRunner::new().reply_bytes(
+            "nmcli",
+            &[b"--ask", b"d", b"wifi", b"connect", b"Caf\xe9"],
+            Reply::failure(4, WIFI_CONNECT_WRONG_PASSWORD),
+        );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let err = nmcli
+            .connect(b"Caf\xe9", Some(b"wrong"), None, false)
+            .unwrap_err();
+        assert!(matches!(err, NetworkAdapterError::CannotConnect((_, 4))));
+        assert!(err.to_string().contains("Secrets were required"));
+    }
+
+    #[test]
+    fn nmcli_should_not_pass_password_with_line_feed() {
+        let nmcli = Nmcli::with_runner(FakeRunner::new());
+
+        let err = nmcli
+            .connect(b"Home", Some(b"secret\nconnect"), None, false)
+            .unwrap_err();
+        assert!(matches!(err, NetworkAdapterError::CannotConnect((_, 1))));
+        assert!(nmcli.runner().calls().is_empty());
+    }
+}
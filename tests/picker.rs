@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, io};
+
+    use termion::event::Key;
+    use wl::{
+        Error,
+        picker::{self, Entry, Outcome, Picker, Selection},
+    };
+
+    fn entry(ssid: &[u8], signal: u8) -> Entry {
+        Entry {
+            ssid: ssid.to_vec(),
+            signal: Some(signal),
+            ..Default::default()
+        }
+    }
+
+    fn keys(keys: &[Key]) -> Vec<Result<Key, io::Error>> {
+        keys.iter().copied().map(Ok).collect()
+    }
+
+    #[test]
+    fn picker_should_move_and_search() {
+        let mut picker = Picker::new(
+            "Select",
+            vec![entry(b"Home", 80), entry(b"Cafe", 60), entry(b"Office", 40)],
+        );
+
+        assert_eq!(None, picker.handle(Key::Up));
+        assert_eq!(None, picker.handle(Key::Down));
+        assert_eq!(None, picker.handle(Key::Ctrl('n')));
+        assert_eq!(None, picker.handle(Key::Down));
+        assert_eq!(b"Office".as_slice(), picker.selected().unwrap().ssid);
+
+        assert_eq!(None, picker.handle(Key::Home));
+        assert_eq!(None, picker.handle(Key::Char('O')));
+        assert_eq!("O", picker.query());
+        assert_eq!(2, picker.visible().len());
+        assert_eq!(None, picker.handle(Key::Char('f')));
+        assert_eq!(1, picker.visible().len());
+
+        assert_eq!(
+            Some(Outcome::Select(b"Office".to_vec())),
+            picker.handle(Key::Char('\n'))
+        );
+
+        assert_eq!(None, picker.handle(Key::Char('x')));
+        assert!(picker.selected().is_none());
+        assert_eq!(None, picker.handle(Key::Char('\n')));
+        assert_eq!(None, picker.handle(Key::Backspace));
+        assert_eq!(Some(Outcome::Abort), picker.handle(Key::Esc));
+    }
+
+    #[test]
+    fn picker_should_scroll_to_the_selection() {
+        let entries = (0..10)
+            .map(|i| entry(format!("SSID{i}").as_bytes(), 50))
+            .collect();
+        let mut picker = Picker::new("Select", entries);
+
+        // 3 lines are reserved, so 4 entries fit into the list.
+        let out = String::from_utf8(picker.render((40, 7))).unwrap();
+        assert!(out.contains("SSID3") && !out.contains("SSID4"));
+
+        picker.handle(Key::PageDown);
+        picker.handle(Key::Down);
+        let out = String::from_utf8(picker.render((40, 7))).unwrap();
+        assert!(out.contains("> ▂▄__") && out.contains("SSID5"));
+        assert!(!out.contains("SSID1"));
+
+        picker.handle(Key::End);
+        assert_eq!(b"SSID9".as_slice(), picker.selected().unwrap().ssid);
+    }
+
+    #[test]
+    fn picker_should_keep_the_selection_on_rescan() {
+        let scans = Cell::new(0);
+        let load = |re_scan: bool| {
+            assert!(re_scan);
+            scans.set(scans.get() + 1);
+            match scans.get() {
+                1 => Ok(vec![entry(b"Home", 80), entry(b"Cafe", 60)]),
+                _ => Ok(vec![
+                    entry(b"Cafe", 90),
+                    entry(b"Office", 70),
+                    entry(b"Home", 50),
+                ]),
+            }
+        };
+
+        let mut out = Vec::new();
+        let picked = picker::pick_with(
+            "Select",
+            None,
+            &mut out,
+            keys(&[Key::Down, Key::Ctrl('r'), Key::Down, Key::Char('\n')]),
+            (80, 24),
+            load,
+        )
+        .unwrap();
+
+        assert_eq!(2, scans.get());
+        assert_eq!(Selection::Ssid(b"Office".to_vec()), picked);
+        assert!(String::from_utf8(out).unwrap().contains("Select"));
+    }
+
+    #[test]
+    fn picker_should_select_the_other_row() {
+        let mut picker = Picker::new("Select", vec![entry(b"Home", 80), entry(b"Cafe", 60)])
+            .with_other("Other network…");
+
+        picker.handle(Key::End);
+        assert!(picker.selected().is_none());
+        let out = String::from_utf8(picker.render((40, 24))).unwrap();
+        assert!(out.contains("> Other network…"));
+
+        // The other row is not filtered by the search query.
+        picker.handle(Key::Char('x'));
+        assert!(picker.visible().is_empty());
+        let out = String::from_utf8(picker.render((40, 24))).unwrap();
+        assert!(out.contains("no networks found") && out.contains("> Other network…"));
+        assert_eq!(Some(Outcome::Other), picker.handle(Key::Char('\n')));
+
+        let picked = picker::pick_with(
+            "Select",
+            Some("Other network…"),
+            &mut Vec::new(),
+            keys(&[Key::Down, Key::Down, Key::Down, Key::Char('\n')]),
+            (80, 24),
+            |_| Ok(vec![entry(b"Home", 80)]),
+        )
+        .unwrap();
+        assert_eq!(Selection::Other, picked);
+    }
+
+    #[test]
+    fn picker_should_abort() {
+        let load = |_| Ok(vec![entry(b"Home", 80)]);
+
+        let err = picker::pick_with(
+            "Select",
+            None,
+            &mut Vec::new(),
+            keys(&[Key::Ctrl('c')]),
+            (80, 24),
+            load,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::UserAborted));
+
+        let err = picker::pick_with(
+            "Select",
+            None,
+            &mut Vec::new(),
+            keys(&[Key::Down]),
+            (80, 24),
+            load,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::UserAborted));
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use wl::{
+        Error, ErrorKind, FakeWl, NetworkAdapterError, PriorityError, UNKNOWN_SSID_EXIT_CODE, Wl,
+        api::OutputFormat,
+    };
+
+    #[test]
+    fn priority_should_reorder_known_networks() {
+        let fake = FakeWl::new()
+            .with_known_network(b"Home", None)
+            .with_known_network(b"Office", None)
+            .with_known_network(b"Cafe", None);
+
+        let names = |priorities: Vec<wl::NetworkPriority>| {
+            priorities
+                .into_iter()
+                .map(|p| (String::from_utf8(p.ssid).unwrap(), p.priority))
+                .collect::<Vec<(String, i32)>>()
+        };
+        let pair = |ssid: &str, priority: i32| (ssid.to_string(), priority);
+
+        let mut out = Vec::new();
+        let priorities =
+            wl::priority_set(&fake, &mut out, b"Cafe".to_vec(), 10, OutputFormat::Text).unwrap();
+        assert_eq!(
+            vec![pair("Cafe", 10), pair("Home", 0), pair("Office", 0)],
+            names(priorities)
+        );
+        assert_eq!(
+            b"PRIORITY  AUTOCONNECT  NAME\n10        yes          Cafe\n0         yes          Home\n0         yes          Office\n".as_slice(),
+            out
+        );
+
+        let priorities = wl::priority_up(
+            &fake,
+            &mut Vec::new(),
+            b"Office".to_vec(),
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![pair("Cafe", 10), pair("Office", 1), pair("Home", 0)],
+            names(priorities)
+        );
+
+        // The known network that already comes first is kept as it is.
+        let priorities =
+            wl::priority_up(&fake, &mut Vec::new(), b"Cafe".to_vec(), OutputFormat::Json).unwrap();
+        assert_eq!(
+            vec![pair("Cafe", 10), pair("Office", 1), pair("Home", 0)],
+            names(priorities)
+        );
+
+        let mut out = Vec::new();
+        let priorities =
+            wl::priority_down(&fake, &mut out, b"Cafe".to_vec(), OutputFormat::Ndjson).unwrap();
+        assert_eq!(
+            vec![pair("Office", 1), pair("Home", 0), pair("Cafe", 0)],
+            names(priorities)
+        );
+        assert!(out.starts_with(b"{\"ssid\":\"Office\",\"priority\":1,\"autoconnect\":true}\n"));
+
+        let err = wl::priority_up(
+            &fake,
+            &mut Vec::new(),
+            b"Unknown".to_vec(),
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Priority(PriorityError::UnknownSsid(_))
+        ));
+        assert_eq!(ErrorKind::SsidNotFound, err.kind());
+
+        let err = fake.set_priority(b"Unknown", 1).unwrap_err();
+        assert!(matches!(
+            err,
+            NetworkAdapterError::CannotUpdateNetwork((_, UNKNOWN_SSID_EXIT_CODE))
+        ));
+    }
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use wl::{
+        AccessPoint, Auth, Error, ErrorKind, FakeWl, IpMethod, Metered, NetworkAdapterError,
+        PasswdSource, ProfileEdit, ProfileError, Security, Wl, api::OutputFormat,
+    };
+
+    fn access_point(ssid: &str, signal: u8, security: &str) -> AccessPoint {
+        AccessPoint {
+            ssid: ssid.as_bytes().to_vec(),
+            signal,
+            security: security.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn profile_should_edit_and_rename_known_networks() {
+        let fake = FakeWl::new()
+            .with_access_point(access_point("Home", 80, "WPA2"))
+            .with_secret(b"Home", b"secret")
+            .with_known_network(b"Home", Some(b"secret"))
+            .with_known_network(b"Cafe", None);
+
+        let mut out = Vec::new();
+        let profile =
+            wl::profile_show(&fake, &mut out, b"Home".to_vec(), OutputFormat::Text).unwrap();
+        assert_eq!(
+            b"name: Home\nssid: Home\nsecurity: wpa-psk\nhidden: no\nautoconnect: yes\npriority: 0\nmetered: unknown\nmac-randomization: default\nip-method: auto\n".as_slice(),
+            out
+        );
+        assert!(profile.autoconnect);
+
+        let edit = ProfileEdit {
+            security: Some(Security::Sae),
+            priority: Some(-1),
+            metered: Some(Metered::No),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        let profile =
+            wl::profile_edit(&fake, &mut out, b"Home".to_vec(), &edit, OutputFormat::Json).unwrap();
+        assert_eq!("sae", profile.security);
+        assert_eq!(
+            b"{\"name\":\"Home\",\"ssid\":\"Home\",\"security\":\"sae\",\"hidden\":false,\"autoconnect\":true,\"priority\":-1,\"metered\":\"no\",\"mac_randomization\":\"default\",\"ip_method\":\"auto\"}\n".as_slice(),
+            out
+        );
+
+        let err = wl::profile_edit(
+            &fake,
+            &mut Vec::new(),
+            b"Home".to_vec(),
+            &ProfileEdit::default(),
+            OutputFormat::Json,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Profile(ProfileError::EmptyEdit)));
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+
+        let edit = ProfileEdit {
+            ip_method: Some(IpMethod::Manual),
+            ..Default::default()
+        };
+        let err = fake.edit_profile(b"Cafe", &edit).unwrap_err();
+        assert!(matches!(
+            err,
+            NetworkAdapterError::CannotUpdateNetwork((_, 2))
+        ));
+
+        let err = wl::profile_rename(
+            &fake,
+            &mut Vec::new(),
+            b"Home".to_vec(),
+            b" ".to_vec(),
+            OutputFormat::Text,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Profile(ProfileError::EmptyName)));
+
+        let mut out = Vec::new();
+        wl::profile_rename(
+            &fake,
+            &mut out,
+            b"Home".to_vec(),
+            b"Home (5G)".to_vec(),
+            OutputFormat::Text,
+        )
+        .unwrap();
+        assert_eq!(
+            b"Connection 'Home' is renamed to 'Home (5G)'.\n".as_slice(),
+            out
+        );
+        assert_eq!(
+            vec![b"Home (5G)".to_vec(), b"Cafe".to_vec()],
+            fake.known_ssids()
+        );
+
+        // The renamed known network is still connected to its own SSID.
+        wl::connect(
+            &fake,
+            Some(b"Home (5G)".to_vec()),
+            Auth::default(),
+            PasswdSource::Prompt,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(Some(b"Home".to_vec()), fake.active_ssid());
+
+        let err = wl::profile_show(&fake, &mut Vec::new(), b"Home".to_vec(), OutputFormat::Text)
+            .unwrap_err();
+        assert_eq!(ErrorKind::SsidNotFound, err.kind());
+    }
+}
@@ -0,0 +1,16 @@
+#[cfg(test)]
+mod tests {
+    use wl::runner::{Runner, SystemRunner};
+
+    #[test]
+    fn system_runner_should_wait_for_programs_that_do_not_read_their_input() {
+        // The input is larger than a pipe buffer, so writing it fails once the program exits.
+        let input = vec![b'x'; 1 << 20];
+        let output = SystemRunner
+            .run_with_input("sh", &[b"-c", b"echo failed >&2; exit 3"], &input)
+            .unwrap();
+
+        assert_eq!(Some(3), output.status.code());
+        assert_eq!(b"failed\n".as_slice(), output.stderr);
+    }
+}
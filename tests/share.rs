@@ -0,0 +1,195 @@
+#[cfg(test)]
+mod tests {
+    use wl::{
+        AccessPoint, ErrorKind, FakeWl, NetworkAdapterError, Nmcli, Profile, Security, Wl,
+        WpaSupplicant,
+        api::OutputFormat,
+        runner::{FakeRunner, Reply},
+    };
+
+    const PASSWD_ARGS: &str = "802-11-wireless-security.key-mgmt,802-11-wireless-security.psk,802-11-wireless-security.wep-key0";
+    const PROFILE_ARGS: &str = "connection.id,802-11-wireless.ssid,802-11-wireless-security.key-mgmt,connection.autoconnect,connection.autoconnect-priority,connection.metered,802-11-wireless.mac-address-randomization,ipv4.method,802-11-wireless.hidden";
+
+    fn profile(ssid: &[u8], security: &str) -> Profile {
+        Profile {
+            name: ssid.to_vec(),
+            ssid: ssid.to_vec(),
+            security: security.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn share_should_escape_special_characters() {
+        assert_eq!(
+            b"WIFI:T:WPA;S:My\\;Net\\,\\:\\\\\\\";P:a\\;b\\:c\\,d\\\\e;;".as_slice(),
+            wl::share_payload(&profile(b"My;Net,:\\\"", "wpa-psk"), Some(b"a;b:c,d\\e"))
+        );
+        assert_eq!(
+            b"WIFI:T:nopass;S:Cafe;;".as_slice(),
+            wl::share_payload(&profile(b"Cafe", "open"), None)
+        );
+    }
+
+    #[test]
+    fn share_should_follow_the_security_and_hidden_flag_of_the_profile() {
+        assert_eq!(
+            b"WIFI:T:WEP;S:Attic;P:12345;;".as_slice(),
+            wl::share_payload(&profile(b"Attic", "wep"), Some(b"12345"))
+        );
+        assert_eq!(
+            b"WIFI:T:WPA;S:Lab;P:secret;;".as_slice(),
+            wl::share_payload(&profile(b"Lab", "sae"), Some(b"secret"))
+        );
+        assert_eq!(
+            b"WIFI:T:nopass;S:Cafe;;".as_slice(),
+            wl::share_payload(&profile(b"Cafe", "owe"), None)
+        );
+
+        let fake = FakeWl::new().with_hidden_network(AccessPoint {
+            ssid: b"Attic".to_vec(),
+            security: String::from("WPA2"),
+            ..Default::default()
+        });
+        fake.connect_hidden(b"Attic", Security::WpaPsk, Some(b"secret"), false)
+            .unwrap();
+
+        let payload = wl::share(
+            &fake,
+            &mut Vec::new(),
+            b"Attic".to_vec(),
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(b"WIFI:T:WPA;S:Attic;P:secret;H:true;;".as_slice(), payload);
+    }
+
+    #[test]
+    fn share_should_read_nmcli_wep_profiles() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &[
+                    "--show-secrets",
+                    "-g",
+                    PASSWD_ARGS,
+                    "connection",
+                    "show",
+                    "id",
+                    "Attic",
+                ],
+                Reply::stdout("none\n\n12345\n"),
+            )
+            .reply(
+                "nmcli",
+                &["-g", PROFILE_ARGS, "connection", "show", "id", "Attic"],
+                Reply::stdout("Attic\nAttic\nnone\nyes\n0\nunknown\ndefault\nauto\nyes\n"),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        let mut out = Vec::new();
+        let payload = wl::share(&nmcli, &mut out, b"Attic".to_vec(), OutputFormat::Json).unwrap();
+
+        assert_eq!(b"WIFI:T:WEP;S:Attic;P:12345;H:true;;".as_slice(), payload);
+        assert_eq!(
+            b"{\"ssid\":\"Attic\",\"security\":\"WEP\",\"payload\":\"WIFI:T:WEP;S:Attic;P:12345;H:true;;\"}\n"
+                .as_slice(),
+            out
+        );
+    }
+
+    #[test]
+    fn share_should_write_payload_and_qr_code() {
+        let fake = FakeWl::new()
+            .with_known_network(b"Home", Some(b"secret"))
+            .with_known_network(b"Cafe", None);
+
+        let mut out = Vec::new();
+        let payload = wl::share(&fake, &mut out, b"Home".to_vec(), OutputFormat::Text).unwrap();
+
+        assert_eq!(b"WIFI:T:WPA;S:Home;P:secret;;".as_slice(), payload);
+
+        let out = String::from_utf8(out).unwrap();
+        let mut lines = out.lines().collect::<Vec<&str>>();
+        assert_eq!(Some("WIFI:T:WPA;S:Home;P:secret;;"), lines.pop());
+
+        let size = lines[0].matches('▀').count();
+        assert_eq!(size.div_ceil(2), lines.len());
+        assert!(lines.iter().all(|l| l.matches('▀').count() == size));
+
+        let mut out = Vec::new();
+        wl::share(&fake, &mut out, b"Cafe".to_vec(), OutputFormat::Json).unwrap();
+
+        assert_eq!(
+            b"{\"ssid\":\"Cafe\",\"security\":\"nopass\",\"payload\":\"WIFI:T:nopass;S:Cafe;;\"}\n"
+                .as_slice(),
+            out
+        );
+    }
+
+    #[test]
+    fn share_should_fail_on_unknown_networks() {
+        let fake = FakeWl::new().with_known_network(b"Home", Some(b"secret"));
+
+        let err =
+            wl::share(&fake, &mut Vec::new(), b"Cafe".to_vec(), OutputFormat::Text).unwrap_err();
+        assert_eq!(ErrorKind::SsidNotFound, err.kind());
+
+        let wpa = WpaSupplicant::with_ctrl_path("/nonexistent/wl-test");
+        let err =
+            wl::share(&wpa, &mut Vec::new(), b"Home".to_vec(), OutputFormat::Text).unwrap_err();
+        assert_eq!(ErrorKind::Unsupported, err.kind());
+    }
+
+    #[test]
+    fn share_should_read_nmcli_secrets() {
+        let runner = FakeRunner::new()
+            .reply(
+                "nmcli",
+                &[
+                    "--show-secrets",
+                    "-g",
+                    PASSWD_ARGS,
+                    "connection",
+                    "show",
+                    "id",
+                    "Home",
+                ],
+                Reply::stdout("wpa-psk\np\\:ss\n"),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "--show-secrets",
+                    "-g",
+                    PASSWD_ARGS,
+                    "connection",
+                    "show",
+                    "id",
+                    "Cafe",
+                ],
+                Reply::stdout("\n\n"),
+            )
+            .reply(
+                "nmcli",
+                &[
+                    "--show-secrets",
+                    "-g",
+                    PASSWD_ARGS,
+                    "connection",
+                    "show",
+                    "id",
+                    "Office",
+                ],
+                Reply::stdout("wpa-psk\n\n"),
+            );
+        let nmcli = Nmcli::with_runner(runner);
+
+        assert_eq!(Some(b"p:ss".to_vec()), nmcli.get_passwd(b"Home").unwrap());
+        assert_eq!(None, nmcli.get_passwd(b"Cafe").unwrap());
+        assert!(matches!(
+            nmcli.get_passwd(b"Office"),
+            Err(NetworkAdapterError::CannotGetPasswd((_, 1)))
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::mpsc::{self, Receiver, Sender},
+        thread,
+        time::Duration,
+    };
+
+    use wl::{
+        AccessPoint, Error, ErrorKind, FakeWl, NetworkAdapterError, Nmcli, WatchError, Wl,
+        api::{OutputFormat, WatchArgs},
+        fake::Operation,
+        runner::{FakeRunner, Reply},
+    };
+
+    const WIFI_LIST_ARGS: &str = "IN-USE,BSSID,SSID-HEX,MODE,CHAN,FREQ,RATE,SIGNAL,SECURITY";
+
+    fn args(count: Option<usize>) -> WatchArgs {
+        WatchArgs {
+            count,
+            interval: 0.01,
+            thresholds: vec![25, 50, 75],
+        }
+    }
+
+    /// Collects the events of `watch`, and reports each of them once it is written.
+    struct Events {
+        out: Vec<u8>,
+        tx: Sender<()>,
+    }
+
+    impl Events {
+        fn new() -> (Self, Receiver<()>) {
+            let (tx, rx) = mpsc::channel();
+            (
+                Self {
+                    out: Vec::new(),
+                    tx,
+                },
+                rx,
+            )
+        }
+    }
+
+    impl io::Write for Events {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.out.extend_from_slice(buf);
+            for _ in buf.iter().filter(|b| **b == b'\n') {
+                let _ = self.tx.send(());
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Waits until `watch` reads the initial state of the host, which ends with the access points.
+    fn wait_for_start(fake: &FakeWl) {
+        while fake.call_count(Operation::GetAccessPoints) == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn watch_should_poll_the_changes_of_the_host() {
+        let fake = FakeWl::new()
+            .with_access_point(AccessPoint {
+                ssid: b"Home".to_vec(),
+                signal: 80,
+                ..Default::default()
+            })
+            .with_active_network(b"Home");
+
+        let (mut events, rx) = Events::new();
+        thread::scope(|s| {
+            // Each change is made once the previous one is reported, so none of them are merged in a single poll.
+            let fake = &fake;
+            s.spawn(move || {
+                wait_for_start(fake);
+                fake.set_signal(b"Home", 40);
+
+                rx.recv().unwrap();
+                fake.toggle_wifi().unwrap();
+
+                rx.iter().take(2).for_each(drop);
+                fake.toggle_wifi().unwrap();
+                fake.connect(b"Home", None, None, true).unwrap();
+            });
+
+            wl::watch(fake, &mut events, args(Some(5)), OutputFormat::Text).unwrap();
+        });
+
+        assert_eq!(
+            b"signal: Home 40 (below 50)\nwifi: disabled\ndisconnected: Home/wlan0\nwifi: enabled\nconnected: Home/wlan0\n"
+                .as_slice(),
+            events.out
+        );
+    }
+
+    #[test]
+    fn watch_should_write_json_events() {
+        let fake = FakeWl::new()
+            .with_access_point(AccessPoint {
+                ssid: b"Home".to_vec(),
+                signal: 20,
+                ..Default::default()
+            })
+            .with_active_network(b"Home");
+
+        let mut out = Vec::new();
+        thread::scope(|s| {
+            s.spawn(|| {
+                wait_for_start(&fake);
+                fake.set_signal(b"Home", 90);
+            });
+
+            wl::watch(&fake, &mut out, args(Some(1)), OutputFormat::Json).unwrap();
+        });
+
+        assert_eq!(
+            b"{\"event\":\"signal\",\"ssid\":\"Home\",\"signal\":90,\"threshold\":75,\"direction\":\"up\"}\n"
+                .as_slice(),
+            out
+        );
+    }
+
+    #[test]
+    fn watch_should_validate_its_arguments() {
+        let fake = FakeWl::new();
+
+        let mut invalid = args(None);
+        invalid.interval = 0.0;
+        let err = wl::watch(&fake, &mut Vec::new(), invalid, OutputFormat::Text).unwrap_err();
+        assert!(matches!(err, Error::Watch(WatchError::InvalidInterval(_))));
+
+        // An interval that overflows `Duration` is refused instead of panicking.
+        for interval in [1e300, f64::INFINITY, f64::NAN, -1.0] {
+            let mut invalid = args(None);
+            invalid.interval = interval;
+            let err = wl::watch(&fake, &mut Vec::new(), invalid, OutputFormat::Text).unwrap_err();
+            assert!(matches!(err, Error::Watch(WatchError::InvalidInterval(_))));
+        }
+
+        let mut invalid = args(None);
+        invalid.thresholds = vec![50, 101];
+        let err = wl::watch(&fake, &mut Vec::new(), invalid, OutputFormat::Text).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Watch(WatchError::InvalidThreshold(101))
+        ));
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+    }
+
+    fn nmcli_runner(monitor: &str) -> FakeRunner {
+        FakeRunner::new()
+            .reply("nmcli", &["monitor"], Reply::stdout(monitor))
+            .reply("nmcli", &["-g", "WIFI", "g"], Reply::stdout("enabled\n"))
+            .reply(
+                "nmcli",
+                &[
+                    "-g",
+                    "NAME,UUID,TYPE,DEVICE",
+                    "connection",
+                    "show",
+                    "--active",
+                ],
+                Reply::stdout(""),
+            )
+            .reply(
+                "nmcli",
+                &["-g", WIFI_LIST_ARGS, "d", "wifi", "list"],
+                Reply::stdout(""),
+            )
+    }
+
+    #[test]
+    fn watch_should_follow_nmcli_monitor() {
+        let nmcli = Nmcli::with_runner(nmcli_runner(
+            "wlp2s0: disconnected\nConnectivity is now 'none'\nwlp2s0: using connection 'Home'\nwlp2s0: connecting (prepare)\n",
+        ));
+
+        let mut out = Vec::new();
+        wl::watch(&nmcli, &mut out, args(Some(2)), OutputFormat::Text).unwrap();
+
+        assert_eq!(
+            b"device: wlp2s0 disconnected\ndevice: wlp2s0 connecting (prepare)\n".as_slice(),
+            out
+        );
+        assert_eq!("nmcli monitor", nmcli.runner().calls()[0]);
+    }
+
+    #[test]
+    fn watch_should_fail_when_nmcli_monitor_exits() {
+        let nmcli = Nmcli::with_runner(nmcli_runner("wlp2s0: connected\n"));
+
+        let mut out = Vec::new();
+        let err = wl::watch(&nmcli, &mut out, args(None), OutputFormat::Ndjson).unwrap_err();
+
+        assert_eq!(
+            b"{\"event\":\"device\",\"device\":\"wlp2s0\",\"state\":\"connected\"}\n".as_slice(),
+            out
+        );
+        assert!(matches!(
+            err,
+            Error::Backend(_, NetworkAdapterError::CannotWatch(_))
+        ));
+    }
+}
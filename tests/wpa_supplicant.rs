@@ -0,0 +1,407 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{self},
+        os::unix::net::UnixDatagram,
+        path::PathBuf,
+        process,
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use wl::{ExportedNetwork, NetworkPriority, Security, WifiStatus, Wl, WpaSupplicant};
+
+    /// A fake `wpa_supplicant` control interface.
+    ///
+    /// It replies to each command with the replies that are returned from `reply`,
+    /// and records the received commands.
+    struct FakeCtrl {
+        path: PathBuf,
+        commands: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FakeCtrl {
+        fn start(name: &str, reply: fn(&str) -> Vec<&'static str>) -> io::Result<Self> {
+            let dir = std::env::temp_dir().join(format!("wl-test-{}-{}", name, process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir)?;
+
+            let path = dir.join("wlan0");
+            let socket = UnixDatagram::bind(&path)?;
+            socket.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+            let commands = Arc::new(Mutex::new(Vec::new()));
+            let recorded = Arc::clone(&commands);
+
+            thread::spawn(move || {
+                let mut buf = [0u8; 4096];
+                while let Ok((len, addr)) = socket.recv_from(&mut buf) {
+                    let cmd = String::from_utf8_lossy(&buf[..len]).into_owned();
+                    let Some(addr) = addr.as_pathname() else {
+                        continue;
+                    };
+
+                    let replies = reply(&cmd);
+                    recorded.lock().unwrap().push(cmd);
+
+                    for msg in replies {
+                        let _ = socket.send_to(msg.as_bytes(), addr);
+                    }
+                }
+            });
+
+            Ok(Self { path, commands })
+        }
+
+        fn commands(&self) -> Vec<String> {
+            self.commands.lock().unwrap().clone()
+        }
+    }
+
+    #[test]
+    fn wpa_supplicant_should_list_networks() -> io::Result<()> {
+        let ctrl = FakeCtrl::start("list", |cmd| match cmd {
+            "LIST_NETWORKS" => vec![
+                "network id / ssid / bssid / flags\n0\tHome\tany\t[CURRENT]\n1\tCaf\\xc3\\xa9 \\\"A\\\"\tany\t[DISABLED]\n",
+            ],
+            _ => vec!["FAIL\n"],
+        })?;
+
+        let wpa = WpaSupplicant::with_ctrl_path(&ctrl.path);
+        let networks = wpa.get_networks(false).unwrap();
+
+        assert_eq!(2, networks.len());
+        assert_eq!(b"Home".as_slice(), networks[0].ssid);
+        assert_eq!(Some("wlan0"), networks[0].device.as_deref());
+        assert_eq!("Café \"A\"".as_bytes(), networks[1].ssid);
+        assert!(!networks[1].is_active());
+
+        let active_networks = wpa.get_networks(true).unwrap();
+        assert_eq!(1, active_networks.len());
+
+        assert!(wpa.is_known_ssid("Café \"A\"".as_bytes()).unwrap());
+        assert!(!wpa.is_known_ssid(b"Unknown").unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    fn wpa_supplicant_should_scan_and_wait_for_results() -> io::Result<()> {
+        let ctrl = FakeCtrl::start("scan", |cmd| match cmd {
+            "ATTACH" | "DETACH" => vec!["OK\n"],
+            "SCAN" => vec![
+                "OK\n",
+                "<2>CTRL-EVENT-SCAN-STARTED ",
+                "<2>CTRL-EVENT-SCAN-RESULTS ",
+            ],
+            "STATUS" => vec!["bssid=aa:bb:cc:dd:ee:01\nssid=Home\nwpa_state=COMPLETED\n"],
+            "SCAN_RESULTS" => vec![
+                "bssid / frequency / signal level / flags / ssid\naa:bb:cc:dd:ee:01\t5180\t-55\t[WPA2-PSK-CCMP][ESS]\tHome\naa:bb:cc:dd:ee:02\t2437\t-80\t[ESS]\tOpen\n",
+            ],
+            _ => vec!["FAIL\n"],
+        })?;
+
+        let wpa = WpaSupplicant::with_ctrl_path(&ctrl.path);
+        let aps = wpa.get_access_points(true).unwrap();
+
+        assert_eq!(
+            vec!["ATTACH", "SCAN", "DETACH", "STATUS", "SCAN_RESULTS"],
+            ctrl.commands()
+        );
+
+        assert_eq!(2, aps.len());
+        assert_eq!(b"Home".as_slice(), aps[0].ssid);
+        assert_eq!("AA:BB:CC:DD:EE:01", aps[0].bssid);
+        assert_eq!(36, aps[0].channel);
+        assert_eq!(90, aps[0].signal);
+        assert_eq!("WPA2", aps[0].security);
+        assert!(aps[0].in_use);
+
+        assert_eq!(6, aps[1].channel);
+        assert!(!aps[1].is_secured());
+        assert!(!aps[1].in_use);
+
+        Ok(())
+    }
+
+    #[test]
+    fn wpa_supplicant_should_connect_to_new_network() -> io::Result<()> {
+        let ctrl = FakeCtrl::start("connect", |cmd| match cmd {
+            "ADD_NETWORK" => vec!["3\n"],
+            "SELECT_NETWORK 3" => vec![
+                "OK\n",
+                "<2>CTRL-EVENT-CONNECTED - Connection to aa:bb:cc:dd:ee:01 completed",
+            ],
+            _ => vec!["OK\n"],
+        })?;
+
+        let wpa = WpaSupplicant::with_ctrl_path(&ctrl.path);
+        let result = wpa.connect(b"Home", Some(b"secret"), None, false).unwrap();
+
+        assert!(!result.is_empty());
+        assert_eq!(
+            vec![
+                "ADD_NETWORK",
+                "SET_NETWORK 3 ssid 486f6d65",
+                "SET_NETWORK 3 psk \"secret\"",
+                "ATTACH",
+                "SELECT_NETWORK 3",
+                "DETACH",
+                "SAVE_CONFIG",
+            ],
+            ctrl.commands()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wpa_supplicant_should_pin_and_unpin_bssid() -> io::Result<()> {
+        let ctrl = FakeCtrl::start("bssid", |cmd| match cmd {
+            "LIST_NETWORKS" => vec!["network id / ssid / bssid / flags\n2\tOffice\tany\t\n"],
+            "SELECT_NETWORK 2" => vec![
+                "OK\n",
+                "<2>CTRL-EVENT-CONNECTED - Connection to aa:bb:cc:dd:ee:02 completed",
+            ],
+            _ => vec!["OK\n"],
+        })?;
+
+        let wpa = WpaSupplicant::with_ctrl_path(&ctrl.path);
+        wpa.connect(b"Office", None, Some("AA:BB:CC:DD:EE:02"), true)
+            .unwrap();
+        wpa.clear_bssid(b"Office").unwrap();
+
+        assert_eq!(
+            vec![
+                "LIST_NETWORKS",
+                "SET_NETWORK 2 bssid AA:BB:CC:DD:EE:02",
+                "ATTACH",
+                "SELECT_NETWORK 2",
+                "DETACH",
+                "SAVE_CONFIG",
+                "LIST_NETWORKS",
+                "SET_NETWORK 2 bssid any",
+                "SAVE_CONFIG",
+            ],
+            ctrl.commands()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wpa_supplicant_should_get_and_set_priorities() -> io::Result<()> {
+        let ctrl = FakeCtrl::start("priority", |cmd| match cmd {
+            "LIST_NETWORKS" => vec![
+                "network id / ssid / bssid / flags\n0\tHome\tany\t[CURRENT]\n1\tOffice\tany\t[DISABLED]\n",
+            ],
+            "GET_NETWORK 0 priority" => vec!["5"],
+            "GET_NETWORK 1 priority" => vec!["0"],
+            _ => vec!["OK\n"],
+        })?;
+
+        let wpa = WpaSupplicant::with_ctrl_path(&ctrl.path);
+
+        assert_eq!(
+            vec![
+                NetworkPriority {
+                    ssid: b"Home".to_vec(),
+                    priority: 5,
+                    autoconnect: true,
+                },
+                NetworkPriority {
+                    ssid: b"Office".to_vec(),
+                    priority: 0,
+                    autoconnect: false,
+                },
+            ],
+            wpa.get_priorities().unwrap()
+        );
+
+        assert_eq!(
+            b"Network 'Office' priority is set to 10.\n".to_vec(),
+            wpa.set_priority(b"Office", 10).unwrap()
+        );
+
+        assert_eq!(
+            vec![
+                "LIST_NETWORKS",
+                "GET_NETWORK 0 priority",
+                "GET_NETWORK 1 priority",
+                "LIST_NETWORKS",
+                "SET_NETWORK 1 priority 10",
+                "SAVE_CONFIG",
+            ],
+            ctrl.commands()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wpa_supplicant_should_add_known_networks() -> io::Result<()> {
+        let ctrl = FakeCtrl::start("add-network", |cmd| match cmd {
+            "LIST_NETWORKS" => vec!["network id / ssid / bssid / flags\n0\tHome\tany\t\n"],
+            "ADD_NETWORK" => vec!["1\n"],
+            _ => vec!["OK\n"],
+        })?;
+
+        let wpa = WpaSupplicant::with_ctrl_path(&ctrl.path);
+        let mut network = ExportedNetwork {
+            ssid: b"Home".to_vec(),
+            security: Security::WpaPsk,
+            secret: None,
+            hidden: true,
+            priority: 5,
+            autoconnect: true,
+        };
+
+        // wpa_supplicant cannot ask for the password on connection.
+        assert!(wpa.add_network(&network, true).is_err());
+
+        network.secret = Some(b"secret".to_vec());
+        assert_eq!(
+            b"Network 'Home' is added.\n".to_vec(),
+            wpa.add_network(&network, true).unwrap()
+        );
+
+        assert_eq!(
+            vec![
+                "LIST_NETWORKS",
+                "REMOVE_NETWORK 0",
+                "ADD_NETWORK",
+                "SET_NETWORK 1 ssid 486f6d65",
+                "SET_NETWORK 1 scan_ssid 1",
+                "SET_NETWORK 1 key_mgmt WPA-PSK",
+                "SET_NETWORK 1 psk \"secret\"",
+                "SET_NETWORK 1 priority 5",
+                "ENABLE_NETWORK 1",
+                "SAVE_CONFIG",
+            ],
+            ctrl.commands()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wpa_supplicant_should_add_known_networks_with_psk_hash() -> io::Result<()> {
+        let ctrl = FakeCtrl::start("add-network-psk-hash", |cmd| match cmd {
+            "ADD_NETWORK" => vec!["1\n"],
+            _ => vec!["OK\n"],
+        })?;
+
+        let wpa = WpaSupplicant::with_ctrl_path(&ctrl.path);
+        let psk = "2c4ad6e5a3e1f1f6ab7d1d8e8d0e3a3c8a7b6f5e4d3c2b1a0f9e8d7c6b5a4f3e";
+        let network = ExportedNetwork {
+            ssid: b"Home".to_vec(),
+            security: Security::WpaPsk,
+            secret: Some(psk.as_bytes().to_vec()),
+            hidden: false,
+            priority: 0,
+            autoconnect: false,
+        };
+        wpa.add_network(&network, false).unwrap();
+
+        // The PSK hash is not quoted, so it is not taken as a passphrase.
+        assert!(
+            ctrl.commands()
+                .contains(&format!("SET_NETWORK 1 psk {}", psk))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wpa_supplicant_should_connect_to_hidden_network() -> io::Result<()> {
+        let ctrl = FakeCtrl::start("connect-hidden", |cmd| match cmd {
+            "ADD_NETWORK" => vec!["1\n"],
+            "SELECT_NETWORK 1" => vec![
+                "OK\n",
+                "<2>CTRL-EVENT-CONNECTED - Connection to aa:bb:cc:dd:ee:02 completed",
+            ],
+            _ => vec!["OK\n"],
+        })?;
+
+        let wpa = WpaSupplicant::with_ctrl_path(&ctrl.path);
+        let result = wpa
+            .connect_hidden(b"Attic", Security::Sae, Some(b"secret"), false)
+            .unwrap();
+
+        assert!(!result.is_empty());
+        assert_eq!(
+            vec![
+                "ADD_NETWORK",
+                "SET_NETWORK 1 ssid 4174746963",
+                "SET_NETWORK 1 scan_ssid 1",
+                "SET_NETWORK 1 key_mgmt SAE",
+                "SET_NETWORK 1 ieee80211w 2",
+                "SET_NETWORK 1 psk \"secret\"",
+                "ATTACH",
+                "SELECT_NETWORK 1",
+                "DETACH",
+                "SAVE_CONFIG",
+            ],
+            ctrl.commands()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wpa_supplicant_should_remove_new_network_when_connect_fails() -> io::Result<()> {
+        let ctrl = FakeCtrl::start("connect-fail", |cmd| match cmd {
+            "ADD_NETWORK" => vec!["0\n"],
+            "SELECT_NETWORK 0" => vec![
+                "OK\n",
+                "<3>CTRL-EVENT-SSID-TEMP-DISABLED id=0 ssid=\"Home\" auth_failures=1 duration=10 reason=WRONG_KEY",
+            ],
+            _ => vec!["OK\n"],
+        })?;
+
+        let wpa = WpaSupplicant::with_ctrl_path(&ctrl.path);
+        let result = wpa.connect(b"Home", Some(b"wrong-secret"), None, false);
+
+        assert!(result.is_err());
+        assert_eq!(
+            Some("REMOVE_NETWORK 0"),
+            ctrl.commands().last().map(|c| c.as_str())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wpa_supplicant_should_disconnect_and_forget() -> io::Result<()> {
+        let ctrl = FakeCtrl::start("disconnect", |cmd| match cmd {
+            "LIST_NETWORKS" => vec!["network id / ssid / bssid / flags\n5\tHome\tany\t[CURRENT]\n"],
+            "STATUS" => vec!["wpa_state=COMPLETED\n"],
+            _ => vec!["OK\n"],
+        })?;
+
+        let wpa = WpaSupplicant::with_ctrl_path(&ctrl.path);
+
+        wpa.disconnect(b"Home", false).unwrap();
+        wpa.disconnect(b"Home", true).unwrap();
+        assert!(wpa.disconnect(b"Unknown", false).is_err());
+
+        // Only forgetting a network is saved, so a disabled network is not persisted with `disabled=1`.
+        assert_eq!(
+            vec![
+                "LIST_NETWORKS",
+                "DISABLE_NETWORK 5",
+                "LIST_NETWORKS",
+                "REMOVE_NETWORK 5",
+                "SAVE_CONFIG",
+                "LIST_NETWORKS",
+            ],
+            ctrl.commands()
+        );
+
+        assert_eq!(WifiStatus::Enabled, wpa.get_wifi_status().unwrap());
+
+        Ok(())
+    }
+}